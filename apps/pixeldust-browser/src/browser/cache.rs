@@ -0,0 +1,356 @@
+use super::navigation::contains_cache_directive;
+use super::navigation::header_value;
+use super::navigation::parse_max_age;
+use super::*;
+use std::fmt;
+use std::fs;
+
+/// Storage backend for cached HTTP responses, keyed by final URL.
+pub(super) trait ResponseCache: fmt::Debug + Send {
+    fn lookup(&self, url: &str) -> CacheLookup;
+    fn store(&mut self, url: &str, entry: CachedResponse);
+    /// Applies the caching headers of a `304 Not Modified` revalidation.
+    fn refresh_metadata(&mut self, url: &str, response_headers: &[(String, String)]);
+    fn clear(&mut self);
+    /// Drops entries stored at or after `cutoff`.
+    fn clear_stored_since(&mut self, cutoff: Instant);
+    /// Returns a URL-sorted snapshot of cached entries.
+    fn entry_summaries(&self) -> Vec<CacheEntrySummary>;
+}
+
+impl CachedResponse {
+    fn is_fresh(&self) -> bool {
+        let Some(max_age) = self.max_age else {
+            return false;
+        };
+
+        self.stored_at.elapsed() < max_age
+    }
+}
+
+/// In-memory cache that forgets everything on restart.
+#[derive(Debug, Default)]
+pub(super) struct MemoryCache {
+    entries: HashMap<String, CachedResponse>,
+}
+
+impl MemoryCache {
+    /// Inserts `entry`, returning the URL evicted to stay within `MAX_CACHE_ENTRIES`.
+    fn insert(&mut self, url: &str, entry: CachedResponse) -> Option<String> {
+        let mut evicted = None;
+        if !self.entries.contains_key(url) && self.entries.len() >= MAX_CACHE_ENTRIES {
+            evicted = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.stored_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = &evicted {
+                self.entries.remove(oldest);
+            }
+        }
+        self.entries.insert(url.to_owned(), entry);
+        evicted
+    }
+
+    /// Returns whether an entry for `url` is still cached after the update.
+    fn refresh(&mut self, url: &str, response_headers: &[(String, String)]) -> bool {
+        let cache_control = header_value(response_headers, "cache-control");
+        if cache_control.is_some_and(|value| contains_cache_directive(value, "no-store")) {
+            self.entries.remove(url);
+            return false;
+        }
+
+        let Some(entry) = self.entries.get_mut(url) else {
+            return false;
+        };
+        if let Some(max_age) = cache_control.and_then(parse_max_age) {
+            entry.max_age = Some(max_age);
+        }
+        if let Some(etag) = header_value(response_headers, "etag") {
+            entry.etag = Some(etag.to_owned());
+        }
+        if let Some(last_modified) = header_value(response_headers, "last-modified") {
+            entry.last_modified = Some(last_modified.to_owned());
+        }
+        entry.stored_at = Instant::now();
+        true
+    }
+}
+
+impl ResponseCache for MemoryCache {
+    fn lookup(&self, url: &str) -> CacheLookup {
+        let Some(entry) = self.entries.get(url) else {
+            return CacheLookup::Miss;
+        };
+
+        if entry.is_fresh() {
+            return CacheLookup::Fresh(entry.response.clone());
+        }
+
+        if entry.etag.is_some() || entry.last_modified.is_some() {
+            return CacheLookup::Stale {
+                cached: entry.response.clone(),
+                etag: entry.etag.clone(),
+                last_modified: entry.last_modified.clone(),
+            };
+        }
+
+        CacheLookup::Miss
+    }
+
+    fn store(&mut self, url: &str, entry: CachedResponse) {
+        let _ = self.insert(url, entry);
+    }
+
+    fn refresh_metadata(&mut self, url: &str, response_headers: &[(String, String)]) {
+        let _ = self.refresh(url, response_headers);
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn clear_stored_since(&mut self, cutoff: Instant) {
+        self.entries.retain(|_, entry| entry.stored_at < cutoff);
+    }
+
+    fn entry_summaries(&self) -> Vec<CacheEntrySummary> {
+        let mut summaries = self
+            .entries
+            .iter()
+            .map(|(url, entry)| CacheEntrySummary {
+                url: url.clone(),
+                stored_at: entry.stored_at,
+                max_age: entry.max_age,
+                has_etag: entry.etag.is_some(),
+                is_fresh: entry.is_fresh(),
+            })
+            .collect::<Vec<_>>();
+        summaries.sort_by(|left, right| left.url.cmp(&right.url));
+        summaries
+    }
+}
+
+/// Cache that mirrors every entry to one file per URL so it survives restarts.
+#[derive(Debug)]
+pub(super) struct DiskCache {
+    root: PathBuf,
+    memory: MemoryCache,
+}
+
+impl DiskCache {
+    /// Opens `root`, loading every readable entry and discarding corrupt ones.
+    pub(super) fn open(root: PathBuf) -> Result<Self, String> {
+        fs::create_dir_all(&root).map_err(|error| {
+            format!(
+                "failed to create cache directory `{}`: {error}",
+                root.display()
+            )
+        })?;
+        let listing = fs::read_dir(&root).map_err(|error| {
+            format!(
+                "failed to read cache directory `{}`: {error}",
+                root.display()
+            )
+        })?;
+
+        let mut memory = MemoryCache::default();
+        for path in listing.flatten().map(|entry| entry.path()) {
+            if path
+                .extension()
+                .is_none_or(|extension| extension != "entry")
+            {
+                continue;
+            }
+            let decoded = fs::read(&path)
+                .ok()
+                .and_then(|bytes| decode_disk_entry(&bytes));
+            match decoded {
+                Some((url, entry)) => {
+                    let _ = memory.insert(&url, entry);
+                }
+                None => {
+                    let _ = fs::remove_file(&path);
+                }
+            }
+        }
+
+        Ok(Self { root, memory })
+    }
+
+    fn entry_path(&self, url: &str) -> PathBuf {
+        self.root
+            .join(format!("{:016x}.entry", fnv1a_64(url.as_bytes())))
+    }
+
+    fn persist(&self, url: &str) {
+        let Some(entry) = self.memory.entries.get(url) else {
+            return;
+        };
+        let _ = write_atomically(&self.entry_path(url), &encode_disk_entry(url, entry));
+    }
+}
+
+impl ResponseCache for DiskCache {
+    fn lookup(&self, url: &str) -> CacheLookup {
+        self.memory.lookup(url)
+    }
+
+    fn store(&mut self, url: &str, entry: CachedResponse) {
+        if let Some(evicted) = self.memory.insert(url, entry) {
+            let _ = fs::remove_file(self.entry_path(&evicted));
+        }
+        self.persist(url);
+    }
+
+    fn refresh_metadata(&mut self, url: &str, response_headers: &[(String, String)]) {
+        if self.memory.refresh(url, response_headers) {
+            self.persist(url);
+        } else {
+            let _ = fs::remove_file(self.entry_path(url));
+        }
+    }
+
+    fn clear(&mut self) {
+        for url in self.memory.entries.keys() {
+            let _ = fs::remove_file(self.entry_path(url));
+        }
+        self.memory.clear();
+    }
+
+    fn clear_stored_since(&mut self, cutoff: Instant) {
+        for (url, entry) in &self.memory.entries {
+            if entry.stored_at >= cutoff {
+                let _ = fs::remove_file(self.entry_path(url));
+            }
+        }
+        self.memory.clear_stored_since(cutoff);
+    }
+
+    fn entry_summaries(&self) -> Vec<CacheEntrySummary> {
+        self.memory.entry_summaries()
+    }
+}
+
+/// Picks the disk cache under the storage root unless storage is ephemeral or unavailable.
+pub(super) fn response_cache_for(storage: Option<&StorageManager>) -> Box<dyn ResponseCache> {
+    let disk = storage
+        .filter(|storage| !storage.config.ephemeral_mode)
+        .and_then(StorageManager::persistent_root)
+        .and_then(|root| DiskCache::open(root.join(HTTP_CACHE_DIRECTORY)).ok());
+    match disk {
+        Some(disk) => Box::new(disk),
+        None => Box::new(MemoryCache::default()),
+    }
+}
+
+/// Writes through a sibling temp file and renames it so readers never see a partial entry.
+fn write_atomically(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, bytes)?;
+    fs::rename(&temp, path)
+}
+
+fn fnv1a_64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+// Entries are a `key value` line per field, a blank line, then the raw body.
+fn encode_disk_entry(url: &str, entry: &CachedResponse) -> Vec<u8> {
+    let stored_at = SystemTime::now()
+        .checked_sub(entry.stored_at.elapsed())
+        .and_then(|stored| stored.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |since_epoch| since_epoch.as_secs());
+    let response = &entry.response;
+    let mut head = format!(
+        "url {url}\nfinal-url {}\nstored {stored_at}\nstatus {} {}\nversion {}\ncontent-type {}\n",
+        response.final_url,
+        response.status_code,
+        response.status_text,
+        response.http_version,
+        response.content_type
+    );
+    if let Some(max_age) = entry.max_age {
+        head.push_str(&format!("max-age {}\n", max_age.as_secs()));
+    }
+    if let Some(etag) = &entry.etag {
+        head.push_str(&format!("etag {etag}\n"));
+    }
+    if let Some(last_modified) = &entry.last_modified {
+        head.push_str(&format!("last-modified {last_modified}\n"));
+    }
+    for (name, value) in &response.headers {
+        head.push_str(&format!("header {name}: {value}\n"));
+    }
+    head.push('\n');
+
+    let mut bytes = head.into_bytes();
+    bytes.extend_from_slice(&response.body);
+    bytes
+}
+
+fn decode_disk_entry(bytes: &[u8]) -> Option<(String, CachedResponse)> {
+    let split = bytes.windows(2).position(|window| window == b"\n\n")?;
+    let head = std::str::from_utf8(&bytes[..split]).ok()?;
+    let body = bytes[split + 2..].to_vec();
+
+    let mut url = None;
+    let mut final_url = None;
+    let mut stored_secs = None;
+    let mut status = None;
+    let mut http_version = String::new();
+    let mut content_type = String::new();
+    let mut max_age = None;
+    let mut etag = None;
+    let mut last_modified = None;
+    let mut headers = Vec::new();
+    for line in head.lines() {
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        match key {
+            "url" => url = Some(value.to_owned()),
+            "final-url" => final_url = Some(value.to_owned()),
+            "stored" => stored_secs = value.parse::<u64>().ok(),
+            "status" => {
+                let (code, text) = value.split_once(' ').unwrap_or((value, ""));
+                status = Some((code.parse::<u16>().ok()?, text.to_owned()));
+            }
+            "version" => http_version = value.to_owned(),
+            "content-type" => content_type = value.to_owned(),
+            "max-age" => max_age = Some(Duration::from_secs(value.parse().ok()?)),
+            "etag" => etag = Some(value.to_owned()),
+            "last-modified" => last_modified = Some(value.to_owned()),
+            "header" => {
+                let (name, value) = value.split_once(": ")?;
+                headers.push((name.to_owned(), value.to_owned()));
+            }
+            _ => return None,
+        }
+    }
+
+    let stored_system = UNIX_EPOCH.checked_add(Duration::from_secs(stored_secs?))?;
+    let age = SystemTime::now()
+        .duration_since(stored_system)
+        .unwrap_or_default();
+    let stored_at = Instant::now().checked_sub(age).unwrap_or_else(Instant::now);
+    let (status_code, status_text) = status?;
+    let entry = CachedResponse {
+        response: FetchedResponse {
+            final_url: final_url?,
+            status_code,
+            status_text,
+            http_version,
+            headers,
+            content_type,
+            body,
+            warnings: Vec::new(),
+            tls: None,
+        },
+        etag,
+        last_modified,
+        max_age,
+        stored_at,
+    };
+    Some((url?, entry))
+}
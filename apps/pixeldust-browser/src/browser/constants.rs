@@ -1,23 +1,46 @@
 const DEFAULT_URL: &str = "https://www.google.com/";
+const BROWSER_WINDOW_TITLE: &str = "PixelDust Browser";
+const NEW_TAB_LABEL: &str = "New Tab";
 const MAX_BODY_PREVIEW_BYTES: usize = 128 * 1024;
+const DEFAULT_PREVIEW_WRAP_COLUMNS: usize = 100;
 const MAX_REDIRECTS: usize = 10;
 const MAX_SUBRESOURCE_REDIRECTS: usize = 5;
 const MAX_STYLESHEET_FETCHES: usize = 16;
 const MAX_SCRIPT_FETCHES: usize = 64;
 const MAX_IMAGE_FETCHES: usize = 32;
+const MAX_LAZY_IMAGE_FETCHES: usize = 64;
+const MAX_PRELOAD_FETCHES: usize = 8;
+const MAX_CONCURRENT_SUBRESOURCE_FETCHES: usize = 8;
+const MAX_SUBRESOURCE_FETCHES_PER_HOST: usize = 6;
+const SUBRESOURCE_REQUESTS_PER_SECOND_PER_HOST: u32 = 20;
+const SUBRESOURCE_REQUEST_BURST_PER_HOST: u32 = 12;
 const MAX_IMAGE_PIXELS: usize = 16 * 1024 * 1024;
+const MAX_IMAGE_WIDTH: u32 = 8_192;
+const MAX_IMAGE_HEIGHT: u32 = 8_192;
+const MAX_TOTAL_IMAGE_BYTES: usize = 256 * 1024 * 1024;
 const MAX_CACHE_ENTRIES: usize = 256;
+/// Heuristic freshness is this fraction (1/N) of the time since `Last-Modified`.
+const HEURISTIC_FRESHNESS_DIVISOR: u32 = 10;
+const MAX_HEURISTIC_FRESHNESS_SECS: u64 = 24 * 60 * 60;
+const HTTP_CACHE_DIRECTORY: &str = "http-cache";
+/// Hash-prefix list of phishing and malware URLs, one hex prefix per line.
+const URL_BLOCKLIST_FILE: &str = "url-blocklist.txt";
+const SAVED_PAGES_DIRECTORY: &str = "saved-pages";
 const MAX_DOM_EVENTS_PER_FRAME: usize = 16;
 const MAX_JS_ERROR_LOGS: usize = 64;
+const MAX_NAV_LOG_ENTRIES: usize = 128;
 const MAX_JS_ERROR_ORIGIN_CHARS: usize = 96;
 const MAX_JS_ERROR_MESSAGE_CHARS: usize = 240;
 const MAX_INLINE_EVENT_HANDLER_BYTES: usize = 16 * 1024;
 const MAX_PAGE_SCRIPT_BYTES: usize = 2 * 1024 * 1024;
 const MAX_PAGE_SCRIPT_HARD_BYTES: usize = 8 * 1024 * 1024;
+const MAX_PAGE_SCRIPTS: usize = 512;
 const MAX_PAGE_JS_REDIRECTS: usize = 3;
 const MAX_COOKIE_DOMAINS: usize = 256;
 const MAX_COOKIES_PER_DOMAIN: usize = 64;
+const COOKIE_STORAGE_PARTITION: &str = "__cookies";
 const NAVIGATION_THREAD_STACK_SIZE: usize = 32 * 1024 * 1024;
 const MAX_STATIC_FALLBACK_CHARS: usize = 2400;
 const RUNTIME_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const WORKER_STDERR_TAIL_LINES: usize = 20;
 const WORKER_IDLE_SLEEP: Duration = Duration::from_secs(30);
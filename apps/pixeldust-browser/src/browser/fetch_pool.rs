@@ -0,0 +1,210 @@
+use super::*;
+use std::collections::VecDeque;
+use std::sync::Condvar;
+
+/// Limits for the parallel subresource fetcher.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct FetchPoolConfig {
+    pub(super) max_concurrent: usize,
+    pub(super) max_per_host: usize,
+    /// Optional per-host request pacing on top of the connection limits.
+    pub(super) rate_limit: Option<HostRateLimit>,
+}
+
+impl Default for FetchPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: MAX_CONCURRENT_SUBRESOURCE_FETCHES,
+            max_per_host: MAX_SUBRESOURCE_FETCHES_PER_HOST,
+            rate_limit: Some(HostRateLimit {
+                requests_per_second: SUBRESOURCE_REQUESTS_PER_SECOND_PER_HOST,
+                burst: SUBRESOURCE_REQUEST_BURST_PER_HOST,
+            }),
+        }
+    }
+}
+
+/// Token-bucket settings: `burst` requests start immediately, then `requests_per_second`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct HostRateLimit {
+    pub(super) requests_per_second: u32,
+    pub(super) burst: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// Independent token buckets keyed by host.
+#[derive(Debug)]
+pub(super) struct HostRateLimiter {
+    limit: HostRateLimit,
+    buckets: HashMap<String, TokenBucket>,
+}
+
+impl HostRateLimiter {
+    pub(super) fn new(limit: HostRateLimit) -> Self {
+        Self {
+            limit,
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Spends a token for `host`, or returns how long until the next one is available.
+    pub(super) fn try_acquire(&mut self, host: &str, now: Instant) -> Result<(), Duration> {
+        let burst = f64::from(self.limit.burst.max(1));
+        let rate = f64::from(self.limit.requests_per_second.max(1));
+        let bucket = self.buckets.entry(host.to_owned()).or_insert(TokenBucket {
+            tokens: burst,
+            refilled_at: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.refilled_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * rate).min(burst);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        }
+    }
+}
+
+#[derive(Debug)]
+struct FetchQueue {
+    pending: VecDeque<usize>,
+    in_flight_by_host: HashMap<String, usize>,
+    rate_limiter: Option<HostRateLimiter>,
+}
+
+/// Runs `fetch` for each URL on a bounded set of worker threads.
+///
+/// Results are returned in `urls` order so rendering does not depend on completion order;
+/// `None` marks a fetch that never started because `cancel` was set.
+pub(super) fn fetch_in_parallel<T: Send>(
+    urls: &[String],
+    config: FetchPoolConfig,
+    cancel: &AtomicBool,
+    fetch: impl Fn(&str) -> T + Sync,
+) -> Vec<Option<T>> {
+    let mut results = (0..urls.len()).map(|_| None).collect::<Vec<_>>();
+    if urls.is_empty() {
+        return results;
+    }
+
+    let hosts = urls.iter().map(|url| host_key(url)).collect::<Vec<_>>();
+    let queue = Mutex::new(FetchQueue {
+        pending: (0..urls.len()).collect(),
+        in_flight_by_host: HashMap::new(),
+        rate_limiter: config.rate_limit.map(HostRateLimiter::new),
+    });
+    let slot_freed = Condvar::new();
+    let max_per_host = config.max_per_host.max(1);
+    let workers = config.max_concurrent.clamp(1, urls.len());
+    let (sender, receiver) = mpsc::channel();
+
+    let worker = |sender: mpsc::Sender<(usize, T)>| {
+        while let Some(index) = claim_next(&queue, &slot_freed, &hosts, max_per_host, cancel) {
+            let _ = sender.send((index, fetch(&urls[index])));
+            let mut guard = match queue.lock() {
+                Ok(guard) => guard,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            if let Some(count) = guard.in_flight_by_host.get_mut(&hosts[index]) {
+                *count = count.saturating_sub(1);
+            }
+            slot_freed.notify_all();
+        }
+    };
+
+    thread::scope(|scope| {
+        let mut spawned = 0_usize;
+        for _ in 0..workers {
+            let sender = sender.clone();
+            let spawn = thread::Builder::new()
+                .name("pixeldust-fetch".to_owned())
+                .spawn_scoped(scope, || worker(sender));
+            if spawn.is_err() {
+                break;
+            }
+            spawned = spawned.saturating_add(1);
+        }
+        if spawned == 0 {
+            worker(sender.clone());
+        }
+        drop(sender);
+
+        for (index, result) in receiver {
+            results[index] = Some(result);
+        }
+    });
+
+    results
+}
+
+/// Takes the first queued URL whose host is under its connection and rate limits, waiting
+/// for a slot.
+fn claim_next(
+    queue: &Mutex<FetchQueue>,
+    slot_freed: &Condvar,
+    hosts: &[String],
+    max_per_host: usize,
+    cancel: &AtomicBool,
+) -> Option<usize> {
+    let mut guard = match queue.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    loop {
+        if cancel.load(Ordering::Relaxed) || guard.pending.is_empty() {
+            return None;
+        }
+
+        let FetchQueue {
+            pending,
+            in_flight_by_host,
+            rate_limiter,
+        } = &mut *guard;
+        let now = Instant::now();
+        let mut wait = Duration::from_millis(50);
+        let ready = pending.iter().position(|index| {
+            let host = &hosts[*index];
+            if in_flight_by_host
+                .get(host)
+                .is_some_and(|count| *count >= max_per_host)
+            {
+                return false;
+            }
+            match rate_limiter
+                .as_mut()
+                .map(|limiter| limiter.try_acquire(host, now))
+            {
+                Some(Err(retry_after)) => {
+                    wait = wait.min(retry_after);
+                    false
+                }
+                Some(Ok(())) | None => true,
+            }
+        });
+        if let Some(index) = ready.and_then(|position| pending.remove(position)) {
+            *in_flight_by_host.entry(hosts[index].clone()).or_default() += 1;
+            return Some(index);
+        }
+
+        // Time out so cancellation and refilled tokens are noticed even if no fetch finishes.
+        guard = match slot_freed.wait_timeout(guard, wait.max(Duration::from_millis(1))) {
+            Ok((guard, _)) => guard,
+            Err(poisoned) => poisoned.into_inner().0,
+        };
+    }
+}
+
+fn host_key(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|parsed| parsed.host_str().map(str::to_ascii_lowercase))
+        .unwrap_or_else(|| url.to_owned())
+}
@@ -1,23 +1,39 @@
 use eframe::egui;
-use encoding_rs::Encoding;
 use image::GenericImageView;
 use pd_ipc::ProcessRole;
+use pd_js::DomMutation;
 use pd_js::JsExecutionReport;
 use pd_js::JsHostElement;
 use pd_js::JsHostEnvironment;
 use pd_js::JsRuntime;
 use pd_js::JsRuntimeConfig;
 use pd_js::ScriptSource;
+use pd_js::ScriptingDeterminism;
 use pd_net::Header;
+use pd_net::TlsSessionInfo;
 use pd_net::TrustStoreMode;
-use pd_net::client::Http11Client;
+use pd_net::UserAgentOverrides;
+use pd_net::charset::decode_text;
+use pd_net::client::SharedHttp11Client;
+use pd_net::transport::base64_encode;
+use pd_privacy::PrivacyPolicy;
+use pd_privacy::UrlParamCleaner;
+use pd_security::SecurityPolicy;
+use pd_storage::StorageConfig;
+use pd_storage::StorageManager;
 use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::sync::mpsc;
 use std::thread;
 use std::time::Duration;
 use std::time::Instant;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
 use url::Url;
 
 use crate::simple_html;
@@ -25,9 +41,18 @@ use crate::simple_html;
 include!("constants.rs");
 include!("types.rs");
 
+mod cache;
+mod fetch_pool;
 mod navigation;
+mod reputation;
 mod runtime;
 mod startup;
 mod ui;
 
+use cache::MemoryCache;
+use cache::ResponseCache;
+use navigation::AllowAllNavigations;
+use navigation::NavigationInterceptor;
+use navigation::UrlRuleInterceptor;
+use reputation::UrlReputation;
 pub(crate) use startup::run;
@@ -1,313 +1,1245 @@
+use super::fetch_pool::FetchPoolConfig;
+use super::fetch_pool::fetch_in_parallel;
+use super::reputation::requires_interstitial;
 use super::*;
 
+/// Loads `raw_url` by advancing a [`NavigationStep`] until it completes or fails,
+/// reporting each [`NavigationState`] it enters to `progress`.
 pub(super) fn execute_navigation(
     raw_url: &str,
-    trust_store: TrustStoreSelection,
-    ocsp_required: bool,
+    options: &NavigationOptions,
     cache: Arc<Mutex<HttpCache>>,
-) -> Result<PageView, String> {
-    let browser = pd_browser::Browser::new().map_err(|error| error.to_string())?;
-    let policy = browser
-        .network
-        .tls_policy
-        .clone()
-        .with_trust_store_mode(trust_store.as_policy_mode())
-        .with_ocsp_stapling_required(ocsp_required);
+    cancel: &AtomicBool,
+    progress: &dyn Fn(NavigationState),
+) -> Result<PageView, NavigationError> {
+    let mut step = NavigationStep::Preparing {
+        raw_url,
+        options,
+        cache,
+        cancel,
+    };
+    let mut reported = None;
+    loop {
+        let state = step.state();
+        if reported.as_ref() != Some(&state) {
+            progress(state.clone());
+            reported = Some(state);
+        }
+        step = match step {
+            NavigationStep::Complete(page) => return Ok(*page),
+            NavigationStep::Failed(error) => return Err(error),
+            step => step.advance().unwrap_or_else(NavigationStep::Failed),
+        };
+    }
+}
 
-    let mut client = browser
-        .network
-        .http11_client_with_tls_policy(policy.clone())
-        .map_err(|error| error.to_string())?;
-    let mut current_url = raw_url.to_owned();
-    let mut js_redirects_remaining = MAX_PAGE_JS_REDIRECTS;
+impl<'a> NavigationStep<'a> {
+    fn state(&self) -> NavigationState {
+        match self {
+            Self::Preparing { .. } => NavigationState::Preparing,
+            Self::Fetching { .. } => NavigationState::Fetching,
+            Self::Parsing { .. } => NavigationState::Parsing,
+            Self::LoadingSubresources(load) => NavigationState::LoadingSubresources {
+                done: load.done,
+                total: load.total,
+            },
+            Self::RunningScripts(_) => NavigationState::RunningScripts,
+            Self::Complete(_) => NavigationState::Complete,
+            Self::Failed(error) => NavigationState::Failed(error.to_string()),
+        }
+    }
 
-    loop {
-        let page = fetch_with_redirects(
-            &browser,
-            &mut client,
-            &policy,
-            &current_url,
-            MAX_REDIRECTS,
-            &cache,
-        )?;
-
-        let is_html = page.content_type.to_ascii_lowercase().contains("text/html")
-            || page
-                .content_type
-                .to_ascii_lowercase()
-                .contains("application/xhtml+xml");
-
-        let decoded_body = decode_text_response(&page.body, &page.content_type);
-        let body_preview = truncate_preview_text(&decoded_body, MAX_BODY_PREVIEW_BYTES);
-        let mut html_document = None;
-        let mut static_text_fallback = None;
-        let mut decoded_images = Vec::new();
-        let mut subresource_stats = SubresourceStats::default();
-        let mut js_execution = JsExecutionStats::default();
-        let mut renderer_draw_calls = None;
-        let mut js_redirect_target: Option<String> = None;
-
-        if is_html {
-            js_execution.enabled = true;
-            let mut document = simple_html::HtmlDocument::parse(&decoded_body);
-            let manifest = document.collect_subresources(&page.final_url);
-            subresource_stats.inline_style_tags = document.inline_style_tag_count();
-            let mut stylesheet_sources = String::new();
-            let mut script_sources = Vec::new();
-
-            for stylesheet_url in manifest.stylesheets.iter().take(MAX_STYLESHEET_FETCHES) {
-                if !allow_subresource_request(&browser, &page.final_url, stylesheet_url) {
-                    subresource_stats.blocked = subresource_stats.blocked.saturating_add(1);
-                    continue;
+    /// Does the current phase's work and returns the phase that follows it.
+    fn advance(self) -> Result<Self, NavigationError> {
+        match self {
+            Self::Preparing {
+                raw_url,
+                options,
+                cache,
+                cancel,
+            } => {
+                if is_internal_about_url(raw_url) {
+                    let page = internal_about_page(raw_url, &cache)?;
+                    return Ok(Self::Complete(Box::new(page)));
+                }
+                Ok(Self::Fetching {
+                    session: Box::new(NavigationSession::new(options, cache, cancel)?),
+                    url: raw_url.to_owned(),
+                })
+            }
+            Self::Fetching { mut session, url } => {
+                ensure_not_stopped(session.cancel)?;
+                let page = fetch_with_redirects(
+                    &session.browser,
+                    &mut session.client,
+                    &session.policy,
+                    &url,
+                    FetchTarget::Document(DocumentGuards::new(session.options)),
+                    &session.cache,
+                    session.options.cache_mode,
+                )?;
+                ensure_not_stopped(session.cancel)?;
+                for warning in &page.warnings {
+                    session
+                        .nav_log
+                        .warn("document", &page.final_url, warning.clone());
+                }
+                Ok(Self::Parsing {
+                    session,
+                    page: Box::new(page),
+                })
+            }
+            Self::Parsing { mut session, page } => {
+                let handler = document_content_handler(&page);
+                let mut rendered = RenderedDocument {
+                    content_kind: handler.kind,
+                    ..RenderedDocument::default()
+                };
+                (handler.render)(&mut session, &page, &mut rendered);
+                if rendered.content_kind == ContentKind::Html
+                    && let Some(document) = rendered.html_document.take()
+                {
+                    let load = HtmlLoad::new(*session, *page, rendered, document);
+                    return Ok(Self::LoadingSubresources(Box::new(load)));
+                }
+                settle_document(*session, *page, rendered)
+            }
+            Self::LoadingSubresources(mut load) => {
+                let Some(batch) = load.next_batch else {
+                    return load.finish();
+                };
+                load.next_batch = match batch {
+                    SubresourceBatch::Preloads => {
+                        load.load_preloads();
+                        Some(SubresourceBatch::Stylesheets)
+                    }
+                    SubresourceBatch::Stylesheets => {
+                        load.load_stylesheets();
+                        Some(SubresourceBatch::Scripts)
+                    }
+                    SubresourceBatch::Scripts => {
+                        load.fetch_scripts();
+                        Some(SubresourceBatch::Images)
+                    }
+                    SubresourceBatch::Images => {
+                        load.load_images();
+                        None
+                    }
+                };
+                load.finish_batch(batch);
+                if batch == SubresourceBatch::Scripts && !load.scripts.is_empty() {
+                    return Ok(Self::RunningScripts(load));
                 }
+                Ok(Self::LoadingSubresources(load))
+            }
+            Self::RunningScripts(mut load) => {
+                load.run_scripts();
+                Ok(Self::LoadingSubresources(load))
+            }
+            step @ (Self::Complete(_) | Self::Failed(_)) => Ok(step),
+        }
+    }
+}
 
-                let stylesheet = fetch_with_redirects(
-                    &browser,
-                    &mut client,
-                    &policy,
-                    stylesheet_url,
-                    MAX_SUBRESOURCE_REDIRECTS,
-                    &cache,
+/// Follows a script redirect the document asked for, or builds its [`PageView`].
+fn settle_document<'a>(
+    mut session: NavigationSession<'a>,
+    page: FetchedResponse,
+    mut rendered: RenderedDocument,
+) -> Result<NavigationStep<'a>, NavigationError> {
+    let js_redirect_target = match rendered.js_redirect_target.take() {
+        Some(next_url) => match DocumentGuards::new(session.options).admit(next_url.clone()) {
+            Ok(admitted) => Some(admitted),
+            Err(NavigationError::Failed(_)) => {
+                session.nav_log.warn(
+                    "document",
+                    &next_url,
+                    "script redirect blocked by interceptor",
                 );
-                let Ok(stylesheet) = stylesheet else {
-                    continue;
-                };
+                None
+            }
+            Err(error) => return Err(error),
+        },
+        None => None,
+    };
+    if let Some(next_url) = js_redirect_target
+        && session.js_redirects.follow(&page.final_url, &next_url)? == JsRedirectStep::Follow
+    {
+        session
+            .nav_log
+            .info("document", &next_url, "following script-initiated redirect");
+        return Ok(NavigationStep::Fetching {
+            session: Box::new(session),
+            url: next_url,
+        });
+    }
 
-                if !is_success_status(stylesheet.status_code) {
-                    continue;
-                }
+    let page = finish_page_view(&mut session, page, rendered);
+    Ok(NavigationStep::Complete(Box::new(page)))
+}
 
-                if !is_css_content_type(&stylesheet.content_type, &stylesheet.final_url) {
-                    continue;
+impl<'a> NavigationSession<'a> {
+    fn new(
+        options: &'a NavigationOptions,
+        cache: Arc<Mutex<HttpCache>>,
+        cancel: &'a AtomicBool,
+    ) -> Result<Self, String> {
+        let mut browser = pd_browser::Browser::new().map_err(|error| error.to_string())?;
+        browser.network.user_agent_overrides = Arc::clone(&options.user_agent_overrides);
+        let policy = browser
+            .network
+            .tls_policy
+            .clone()
+            .with_trust_store_mode(options.trust_store.as_policy_mode())
+            .with_ocsp_stapling_required(options.ocsp_required);
+        let client = browser
+            .network
+            .shared_http11_client_with_tls_policy(policy.clone())
+            .map_err(|error| error.to_string())?;
+        Ok(Self {
+            options,
+            browser,
+            client,
+            policy,
+            cache,
+            cancel,
+            nav_log: NavLog::default(),
+            navigation_start: Instant::now(),
+            js_redirects: JsRedirectTracker::new(MAX_PAGE_JS_REDIRECTS),
+        })
+    }
+
+    fn fetch_subresources(&self, urls: &[String]) -> Vec<Option<Result<FetchedResponse, String>>> {
+        fetch_subresources(
+            &self.browser,
+            &self.client,
+            &self.policy,
+            urls,
+            &self.cache,
+            self.options.cache_mode,
+            self.cancel,
+        )
+    }
+}
+
+/// Builds the [`PageView`] for the document a navigation settled on.
+fn finish_page_view(
+    session: &mut NavigationSession<'_>,
+    page: FetchedResponse,
+    mut rendered: RenderedDocument,
+) -> PageView {
+    let cookies = response_cookie_reports(&page.final_url, &page.headers, &session.browser.privacy);
+    let auth_challenge = (page.status_code == 401)
+        .then(|| basic_auth_challenge(&page.final_url, &page.headers))
+        .flatten();
+    let title = rendered
+        .html_document
+        .as_ref()
+        .and_then(|doc| doc.title.clone())
+        .or_else(|| extract_html_title(&rendered.body_preview));
+    rendered.paint_timings.load_complete = Some(session.navigation_start.elapsed());
+    let blocked_requests = std::mem::take(&mut session.nav_log.blocked);
+    let security = SecurityInfo::for_page(&page.final_url, page.tls, &blocked_requests);
+
+    PageView {
+        final_url: page.final_url,
+        status_code: page.status_code,
+        status_text: page.status_text,
+        http_version: page.http_version,
+        content_type: page.content_type,
+        headers: page.headers,
+        body_bytes: page.body.len(),
+        body_preview: rendered.body_preview,
+        content_kind: rendered.content_kind,
+        auth_challenge,
+        title,
+        favicon_url: rendered.favicon_url,
+        favicon: rendered.favicon,
+        html_document: rendered.html_document,
+        static_text_fallback: rendered.static_text_fallback,
+        decoded_images: rendered.decoded_images,
+        deferred_images: rendered.deferred_images,
+        loading_images: Vec::new(),
+        image_budget: rendered.image_budget,
+        subresource_stats: rendered.subresource_stats,
+        js_execution: rendered.js_execution,
+        renderer_draw_calls: rendered.renderer_draw_calls,
+        paint_timings: rendered.paint_timings,
+        blocked_requests,
+        cookies,
+        nav_log: std::mem::take(&mut session.nav_log),
+        security: Some(security),
+    }
+}
+
+/// Claims responses by content type and renders them into a [`RenderedDocument`].
+pub(super) struct ContentHandler {
+    pub(super) kind: ContentKind,
+    pub(super) matches: fn(&str) -> bool,
+    pub(super) render: fn(&mut NavigationSession<'_>, &FetchedResponse, &mut RenderedDocument),
+}
+
+/// Built-in handlers in priority order; unclaimed responses fall back to [`RAW_CONTENT_HANDLER`].
+pub(super) const CONTENT_HANDLERS: &[ContentHandler] = &[
+    ContentHandler {
+        kind: ContentKind::Html,
+        matches: |content_type| {
+            matches!(
+                content_mime(content_type).as_str(),
+                "text/html" | "application/xhtml+xml"
+            )
+        },
+        render: parse_html_document,
+    },
+    ContentHandler {
+        kind: ContentKind::Image,
+        matches: |content_type| content_mime(content_type).starts_with("image/"),
+        render: render_image_document,
+    },
+    ContentHandler {
+        kind: ContentKind::Json,
+        matches: is_json_content_type,
+        render: render_json_preview,
+    },
+    DOWNLOAD_CONTENT_HANDLER,
+];
+
+/// Also claims any response sent as a `Content-Disposition: attachment`.
+const DOWNLOAD_CONTENT_HANDLER: ContentHandler = ContentHandler {
+    kind: ContentKind::Download,
+    matches: |content_type| {
+        matches!(
+            content_mime(content_type).as_str(),
+            "application/octet-stream"
+                | "application/pdf"
+                | "application/zip"
+                | "application/gzip"
+                | "application/x-tar"
+                | "application/x-7z-compressed"
+        )
+    },
+    render: render_download_notice,
+};
+
+const RAW_CONTENT_HANDLER: ContentHandler = ContentHandler {
+    kind: ContentKind::Raw,
+    matches: |_| true,
+    render: render_raw_preview,
+};
+
+pub(super) fn select_content_handler<'a>(
+    handlers: &'a [ContentHandler],
+    content_type: &str,
+) -> &'a ContentHandler {
+    handlers
+        .iter()
+        .find(|handler| (handler.matches)(content_type))
+        .unwrap_or(&RAW_CONTENT_HANDLER)
+}
+
+fn document_content_handler(page: &FetchedResponse) -> &'static ContentHandler {
+    if content_disposition(page).is_some_and(|disposition| disposition.is_attachment()) {
+        &DOWNLOAD_CONTENT_HANDLER
+    } else {
+        select_content_handler(CONTENT_HANDLERS, &page.content_type)
+    }
+}
+
+fn content_disposition(page: &FetchedResponse) -> Option<pd_net::ContentDisposition> {
+    header_value(&page.headers, "content-disposition").and_then(pd_net::ContentDisposition::parse)
+}
+
+/// Decodes and parses an HTML page; its subresources and scripts load in later
+/// [`NavigationStep`]s.
+fn parse_html_document(
+    session: &mut NavigationSession<'_>,
+    page: &FetchedResponse,
+    out: &mut RenderedDocument,
+) {
+    let decoded_body = decode_text(&page.body, &page.content_type);
+    out.body_preview = truncate_preview_text(&decoded_body, MAX_BODY_PREVIEW_BYTES);
+    out.js_execution.enabled = session.options.javascript_enabled;
+    let document = simple_html::HtmlDocument::parse(&decoded_body);
+    if document.renderable_text_len() > 0 {
+        out.paint_timings.text_ready = Some(session.navigation_start.elapsed());
+    }
+    if document.truncated {
+        session.nav_log.warn(
+            "document",
+            &page.final_url,
+            "document truncated at the parser node limit",
+        );
+    }
+    out.html_document = Some(document);
+}
+
+impl<'a> HtmlLoad<'a> {
+    fn new(
+        session: NavigationSession<'a>,
+        page: FetchedResponse,
+        mut rendered: RenderedDocument,
+        document: simple_html::HtmlDocument,
+    ) -> Self {
+        let mut manifest = document.collect_subresources(&page.final_url);
+        manifest.apply_resource_hint_policy(session.options.honor_resource_hints);
+        rendered.subresource_stats.inline_style_tags = document.inline_style_tag_count();
+        let mut load = Self {
+            session,
+            page,
+            rendered,
+            document,
+            manifest,
+            next_batch: Some(SubresourceBatch::Preloads),
+            done: 0,
+            total: 0,
+            stylesheet_sources: String::new(),
+            scripts: Vec::new(),
+            total_scripts: 0,
+            skipped_scripts: 0,
+        };
+        load.total = [
+            SubresourceBatch::Preloads,
+            SubresourceBatch::Stylesheets,
+            SubresourceBatch::Scripts,
+            SubresourceBatch::Images,
+        ]
+        .into_iter()
+        .map(|batch| load.planned(batch))
+        .sum();
+        load
+    }
+
+    /// Fetches `batch` will make; blocked URLs count so the total is always reached.
+    fn planned(&self, batch: SubresourceBatch) -> usize {
+        let options = self.session.options;
+        match batch {
+            SubresourceBatch::Preloads => self.manifest.preloads.len().min(MAX_PRELOAD_FETCHES),
+            SubresourceBatch::Stylesheets => {
+                self.manifest.stylesheets.len().min(MAX_STYLESHEET_FETCHES)
+            }
+            SubresourceBatch::Scripts if options.javascript_enabled => {
+                self.manifest.scripts.len().min(MAX_SCRIPT_FETCHES)
+            }
+            SubresourceBatch::Images if options.load_images => {
+                self.manifest.images.len().min(MAX_IMAGE_FETCHES)
+            }
+            SubresourceBatch::Scripts | SubresourceBatch::Images => 0,
+        }
+    }
+
+    fn finish_batch(&mut self, batch: SubresourceBatch) {
+        self.done = (self.done + self.planned(batch)).min(self.total);
+    }
+
+    /// Hinted resources are only fetched to warm the HTTP cache.
+    fn load_preloads(&mut self) {
+        let Self {
+            session,
+            page,
+            rendered: out,
+            manifest,
+            ..
+        } = self;
+        let preload_urls = manifest
+            .preloads
+            .iter()
+            .take(MAX_PRELOAD_FETCHES)
+            .filter(|preload_url| {
+                let allowed = allow_logged_subresource(
+                    &session.browser,
+                    &mut session.nav_log,
+                    "preload",
+                    &page.final_url,
+                    preload_url,
+                );
+                if !allowed {
+                    out.subresource_stats.blocked = out.subresource_stats.blocked.saturating_add(1);
                 }
+                allowed
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        let preloads = session.fetch_subresources(&preload_urls);
+        for (preload_url, preload) in preload_urls.iter().zip(preloads) {
+            let Some(preload) = preload else {
+                continue;
+            };
+            let _ = successful_subresource(&mut session.nav_log, "preload", preload_url, preload);
+        }
+    }
 
-                let source = decode_text_response(&stylesheet.body, &stylesheet.content_type);
-                document.append_stylesheet_source(&source);
-                stylesheet_sources.push_str(&source);
-                stylesheet_sources.push('\n');
-                subresource_stats.stylesheets_loaded =
-                    subresource_stats.stylesheets_loaded.saturating_add(1);
+    fn load_stylesheets(&mut self) {
+        let Self {
+            session,
+            page,
+            rendered: out,
+            document,
+            manifest,
+            stylesheet_sources,
+            ..
+        } = self;
+        let nav_log = &mut session.nav_log;
+        let stylesheet_urls = manifest
+            .stylesheets
+            .iter()
+            .take(MAX_STYLESHEET_FETCHES)
+            .filter(|stylesheet_url| {
+                let allowed = allow_logged_subresource(
+                    &session.browser,
+                    nav_log,
+                    "stylesheet",
+                    &page.final_url,
+                    stylesheet_url,
+                );
+                if !allowed {
+                    out.subresource_stats.blocked = out.subresource_stats.blocked.saturating_add(1);
+                }
+                allowed
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        let stylesheets = session.fetch_subresources(&stylesheet_urls);
+        let nav_log = &mut session.nav_log;
+        for (stylesheet_url, stylesheet) in stylesheet_urls.iter().zip(stylesheets) {
+            let Some(stylesheet) = stylesheet else {
+                continue;
+            };
+            let Some(stylesheet) =
+                successful_subresource(nav_log, "stylesheet", stylesheet_url, stylesheet)
+            else {
+                continue;
+            };
+
+            if !accept_stylesheet_response(
+                &stylesheet.content_type,
+                &stylesheet.final_url,
+                &stylesheet.body,
+            ) {
+                nav_log.warn(
+                    "stylesheet",
+                    &stylesheet.final_url,
+                    format!("unexpected content type '{}'", stylesheet.content_type),
+                );
+                continue;
             }
 
+            let source = decode_text(&stylesheet.body, &stylesheet.content_type);
+            document.append_stylesheet_source(&source);
+            stylesheet_sources.push_str(&source);
+            stylesheet_sources.push('\n');
+            out.subresource_stats.stylesheets_loaded =
+                out.subresource_stats.stylesheets_loaded.saturating_add(1);
+        }
+
+        if session.options.renderer_baseline {
+            let decoded_body = decode_text(&page.body, &page.content_type);
             let pipeline_renderer = pd_renderer::RendererProcess::default();
-            let frame = pipeline_renderer.render_document(&decoded_body, &stylesheet_sources);
-            renderer_draw_calls = Some(frame.draw_calls);
-
-            subresource_stats.css_rules_total = document.css_rule_count();
-            let script_plan = document.collect_script_descriptors(&page.final_url);
-            let total_scripts = script_plan.len();
-            let overflow_scripts = total_scripts.saturating_sub(MAX_SCRIPT_FETCHES);
-            let mut budget_skipped_scripts = 0_usize;
-            let mut inline_index = 0_usize;
-
-            for descriptor in script_plan.into_iter().take(MAX_SCRIPT_FETCHES) {
-                match descriptor {
-                    simple_html::ScriptDescriptor::Inline { source } => {
-                        inline_index = inline_index.saturating_add(1);
-                        if source.trim().is_empty() {
-                            continue;
-                        }
-                        if !allow_page_script_source(&source) {
-                            budget_skipped_scripts = budget_skipped_scripts.saturating_add(1);
-                            continue;
-                        }
-                        script_sources.push(ScriptSource {
-                            origin: format!("inline-script:{inline_index}"),
-                            source,
-                        });
+            let frame = pipeline_renderer.render_document(&decoded_body, stylesheet_sources);
+            out.renderer_draw_calls = Some(frame.draw_calls);
+        }
+    }
+
+    /// Fetches the page's external scripts and queues every script that may run, in order.
+    fn fetch_scripts(&mut self) {
+        let Self {
+            session,
+            page,
+            rendered: out,
+            document,
+            scripts,
+            ..
+        } = self;
+        let options = session.options;
+        let nav_log = &mut session.nav_log;
+        out.subresource_stats.css_rules_total = document.css_rule_count();
+        let script_plan = if options.javascript_enabled {
+            document.collect_script_descriptors(&page.final_url)
+        } else {
+            nav_log.info("script", &page.final_url, "JavaScript is turned off");
+            Vec::new()
+        };
+        let total_scripts = script_plan.len();
+        let overflow_scripts = total_scripts.saturating_sub(MAX_SCRIPT_FETCHES);
+        let mut budget_skipped_scripts = 0_usize;
+        let mut inline_index = 0_usize;
+        let script_plan = script_plan
+            .into_iter()
+            .take(MAX_SCRIPT_FETCHES)
+            .collect::<Vec<_>>();
+
+        let mut script_urls = Vec::new();
+        for descriptor in &script_plan {
+            let simple_html::ScriptDescriptor::External { url } = descriptor else {
+                continue;
+            };
+            if !allow_third_party_script(options.block_third_party_scripts, &page.final_url, url) {
+                out.subresource_stats.blocked = out.subresource_stats.blocked.saturating_add(1);
+                nav_log.block("script", url, BlockReason::ThirdPartyScript);
+                continue;
+            }
+            if !allow_logged_subresource(&session.browser, nav_log, "script", &page.final_url, url)
+            {
+                out.subresource_stats.blocked = out.subresource_stats.blocked.saturating_add(1);
+                continue;
+            }
+            script_urls.push(url.clone());
+        }
+        let script_fetches = session.fetch_subresources(&script_urls);
+        let nav_log = &mut session.nav_log;
+        let mut fetched_scripts = script_urls.into_iter().zip(script_fetches).peekable();
+
+        for descriptor in script_plan {
+            match descriptor {
+                simple_html::ScriptDescriptor::Inline { source } => {
+                    inline_index = inline_index.saturating_add(1);
+                    if source.trim().is_empty() {
+                        continue;
                     }
-                    simple_html::ScriptDescriptor::External { url } => {
-                        if !allow_subresource_request(&browser, &page.final_url, &url) {
-                            subresource_stats.blocked = subresource_stats.blocked.saturating_add(1);
-                            continue;
-                        }
-
-                        let script = fetch_with_redirects(
-                            &browser,
-                            &mut client,
-                            &policy,
-                            &url,
-                            MAX_SUBRESOURCE_REDIRECTS,
-                            &cache,
+                    if !allow_page_script_source(&source) {
+                        budget_skipped_scripts = budget_skipped_scripts.saturating_add(1);
+                        nav_log.warn(
+                            "script",
+                            &format!("inline-script:{inline_index}"),
+                            "exceeds the page script size budget",
                         );
-                        let Ok(script) = script else {
-                            continue;
-                        };
-
-                        if is_success_status(script.status_code) {
-                            subresource_stats.scripts_loaded =
-                                subresource_stats.scripts_loaded.saturating_add(1);
-                        }
-
-                        if !is_success_status(script.status_code) {
-                            continue;
-                        }
-
-                        if !is_javascript_content_type(&script.content_type, &script.final_url) {
-                            continue;
-                        }
-
-                        let source = decode_text_response(&script.body, &script.content_type);
-                        if source.trim().is_empty() {
-                            continue;
-                        }
-                        if !allow_page_script_source(&source) {
-                            budget_skipped_scripts = budget_skipped_scripts.saturating_add(1);
-                            continue;
-                        }
-
-                        script_sources.push(ScriptSource {
-                            origin: format_script_origin(&script.final_url),
-                            source,
-                        });
+                        continue;
                     }
+                    scripts.push(ScriptSource {
+                        origin: format!("inline-script:{inline_index}"),
+                        source,
+                    });
                 }
-            }
+                simple_html::ScriptDescriptor::External { url } => {
+                    // Blocked scripts were never queued, so only a matching URL is ours.
+                    let Some((_, script)) =
+                        fetched_scripts.next_if(|(fetched_url, _)| *fetched_url == url)
+                    else {
+                        continue;
+                    };
+                    let Some(script) = script else {
+                        continue;
+                    };
+                    let Some(script) = successful_subresource(nav_log, "script", &url, script)
+                    else {
+                        continue;
+                    };
+                    out.subresource_stats.scripts_loaded =
+                        out.subresource_stats.scripts_loaded.saturating_add(1);
+
+                    if !accept_script_response(
+                        &script.content_type,
+                        &script.final_url,
+                        &script.body,
+                    ) {
+                        nav_log.warn(
+                            "script",
+                            &script.final_url,
+                            format!("unexpected content type '{}'", script.content_type),
+                        );
+                        continue;
+                    }
 
-            if !script_sources.is_empty() {
-                let host = JsHostEnvironment {
-                    page_url: page.final_url.clone(),
-                    document_title: document.title.clone().unwrap_or_default(),
-                    cookie_header: cookie_header_for_url(&cache, &page.final_url),
-                    elements_by_id: document
-                        .collect_id_elements(256)
-                        .into_iter()
-                        .map(|element| JsHostElement {
-                            id: element.id,
-                            tag_name: element.tag_name,
-                            text_content: element.text_content,
-                            attributes: element.attributes,
-                        })
-                        .collect(),
-                };
-                let js_runtime = JsRuntime::new(page_js_runtime_config());
-                let output = js_runtime.execute_scripts_with_host(&host, &script_sources);
-                js_execution = js_stats_from_report(true, output.report);
-                js_execution.scripts_seen = js_execution
-                    .scripts_seen
-                    .saturating_add(overflow_scripts)
-                    .saturating_add(budget_skipped_scripts);
-                js_execution.scripts_skipped = js_execution
-                    .scripts_skipped
-                    .saturating_add(overflow_scripts)
-                    .saturating_add(budget_skipped_scripts);
-
-                if let Some(cookie_snapshot) = output.document_cookie.as_deref() {
-                    merge_document_cookie_snapshot(&cache, &page.final_url, cookie_snapshot);
-                }
+                    let source = decode_text(&script.body, &script.content_type);
+                    if source.trim().is_empty() {
+                        nav_log.warn("script", &script.final_url, "empty script body");
+                        continue;
+                    }
+                    if !allow_page_script_source(&source) {
+                        budget_skipped_scripts = budget_skipped_scripts.saturating_add(1);
+                        nav_log.warn(
+                            "script",
+                            &script.final_url,
+                            "exceeds the page script size budget",
+                        );
+                        continue;
+                    }
 
-                if let Some(new_title) = output
-                    .document_title
-                    .map(|title| title.trim().to_owned())
-                    .filter(|title| !title.is_empty())
-                {
-                    document.title = Some(new_title);
+                    scripts.push(ScriptSource {
+                        origin: format_script_origin(&script.final_url),
+                        source,
+                    });
                 }
-
-                js_redirect_target = output
-                    .location_href
-                    .as_deref()
-                    .and_then(|href| resolve_js_location(&page.final_url, href))
-                    .filter(|next| !same_navigation_target(next, &page.final_url));
-            } else if overflow_scripts > 0 || budget_skipped_scripts > 0 {
-                js_execution.scripts_seen = total_scripts;
-                js_execution.scripts_skipped =
-                    overflow_scripts.saturating_add(budget_skipped_scripts);
             }
+        }
 
-            for image_url in manifest.images.iter().take(MAX_IMAGE_FETCHES) {
-                if !allow_subresource_request(&browser, &page.final_url, image_url) {
-                    subresource_stats.blocked = subresource_stats.blocked.saturating_add(1);
-                    continue;
-                }
+        self.total_scripts = total_scripts;
+        self.skipped_scripts = overflow_scripts.saturating_add(budget_skipped_scripts);
+        if self.scripts.is_empty() && self.skipped_scripts > 0 {
+            self.rendered.js_execution.scripts_seen = total_scripts;
+            self.rendered.js_execution.scripts_skipped = self.skipped_scripts;
+        }
+    }
+
+    /// Runs the queued scripts against the parsed document and applies what they changed.
+    fn run_scripts(&mut self) {
+        let Self {
+            session,
+            page,
+            rendered: out,
+            document,
+            scripts,
+            skipped_scripts,
+            ..
+        } = self;
+        let host = JsHostEnvironment {
+            page_url: page.final_url.clone(),
+            document_title: document.title.clone().unwrap_or_default(),
+            cookie_header: cookie_header_for_url(&session.cache, &page.final_url),
+            elements_by_id: document
+                .collect_id_elements(256)
+                .into_iter()
+                .map(|element| JsHostElement {
+                    id: element.id,
+                    tag_name: element.tag_name,
+                    text_content: element.text_content,
+                    attributes: element.attributes,
+                })
+                .collect(),
+            determinism: ScriptingDeterminism::default(),
+            sse_events: Vec::new(),
+            element_rects: Vec::new(),
+        };
+        let js_runtime = JsRuntime::new(page_js_runtime_config());
+        let output = js_runtime.execute_scripts_with_host(&host, scripts);
+        out.js_execution = js_stats_from_report(true, output.report);
+        out.js_execution.scripts_seen = out
+            .js_execution
+            .scripts_seen
+            .saturating_add(*skipped_scripts);
+        out.js_execution.scripts_skipped = out
+            .js_execution
+            .scripts_skipped
+            .saturating_add(*skipped_scripts);
+
+        if let Some(cookie_snapshot) = output.document_cookie.as_deref() {
+            merge_document_cookie_snapshot(&session.cache, &page.final_url, cookie_snapshot);
+        }
+
+        if let Some(new_title) = output
+            .document_title
+            .map(|title| title.trim().to_owned())
+            .filter(|title| !title.is_empty())
+        {
+            document.title = Some(new_title);
+        }
+        apply_dom_mutations(document, &output.mutations);
 
-                let image = fetch_with_redirects(
-                    &browser,
-                    &mut client,
-                    &policy,
+        out.js_redirect_target = output
+            .location_href
+            .as_deref()
+            .and_then(|href| resolve_js_location(&page.final_url, href))
+            .filter(|next| !same_navigation_target(next, &page.final_url));
+    }
+
+    fn load_images(&mut self) {
+        let Self {
+            session,
+            page,
+            rendered: out,
+            manifest,
+            ..
+        } = self;
+        let load_images = session.options.load_images;
+        let navigation_start = session.navigation_start;
+        let nav_log = &mut session.nav_log;
+        if load_images {
+            out.deferred_images = manifest
+                .lazy_images
+                .iter()
+                .take(MAX_LAZY_IMAGE_FETCHES)
+                .cloned()
+                .collect();
+        } else if !manifest.images.is_empty() || !manifest.lazy_images.is_empty() {
+            nav_log.info("image", &page.final_url, "image loading is turned off");
+        }
+
+        let mut blocked_images = 0usize;
+        let image_urls = manifest
+            .images
+            .iter()
+            .take(if load_images { MAX_IMAGE_FETCHES } else { 0 })
+            .filter(|image_url| {
+                let allowed = allow_logged_subresource(
+                    &session.browser,
+                    nav_log,
+                    "image",
+                    &page.final_url,
                     image_url,
-                    MAX_SUBRESOURCE_REDIRECTS,
-                    &cache,
                 );
-                let Ok(image) = image else {
-                    continue;
-                };
-
-                if !is_success_status(image.status_code) {
-                    continue;
+                if !allowed {
+                    blocked_images = blocked_images.saturating_add(1);
                 }
-
-                if let Some(decoded) =
-                    decode_image_asset(&image.final_url, &image.content_type, &image.body)
-                {
-                    decoded_images.push(decoded);
-                    subresource_stats.images_loaded =
-                        subresource_stats.images_loaded.saturating_add(1);
+                allowed
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut fetched_images = session.fetch_subresources(&image_urls).into_iter();
+        let nav_log = &mut session.nav_log;
+        out.decoded_images = load_page_images(load_images, &image_urls, |image_url| {
+            let image = fetched_images.next()??;
+            let image = successful_subresource(nav_log, "image", image_url, image)?;
+            match decode_budgeted_image(&out.image_budget, &image) {
+                BudgetedImage::Decoded(decoded) => {
+                    out.paint_timings
+                        .first_image_ready
+                        .get_or_insert_with(|| navigation_start.elapsed());
+                    Some(decoded)
                 }
-            }
-
-            if document.renderable_text_len() == 0 {
-                let fallback = document.static_text_fallback(MAX_STATIC_FALLBACK_CHARS);
-                if !fallback.is_empty() {
-                    static_text_fallback = Some(fallback);
+                BudgetedImage::OverBudget => {
+                    blocked_images = blocked_images.saturating_add(1);
+                    nav_log.warn("image", &image.final_url, "image memory budget exhausted");
+                    None
                 }
+                BudgetedImage::Undecodable => {
+                    nav_log.warn("image", &image.final_url, "image could not be decoded");
+                    None
+                }
+            }
+        });
+        out.subresource_stats.blocked =
+            out.subresource_stats.blocked.saturating_add(blocked_images);
+        out.subresource_stats.images_loaded = out.decoded_images.len();
+    }
+
+    /// Loads the favicon, lays out the fallbacks and settles the document.
+    fn finish(self) -> Result<NavigationStep<'a>, NavigationError> {
+        let Self {
+            mut session,
+            page,
+            rendered: mut out,
+            mut document,
+            total_scripts,
+            ..
+        } = self;
+        out.favicon_url = page_favicon_url(&document, &page.final_url);
+        if let Some(icon_url) = out
+            .favicon_url
+            .as_ref()
+            .filter(|_| session.options.load_images)
+            && allow_logged_subresource(
+                &session.browser,
+                &mut session.nav_log,
+                "favicon",
+                &page.final_url,
+                icon_url,
+            )
+        {
+            let fetched = session.fetch_subresources(std::slice::from_ref(icon_url));
+            out.favicon = fetched
+                .into_iter()
+                .next()
+                .flatten()
+                .and_then(|icon| {
+                    successful_subresource(&mut session.nav_log, "favicon", icon_url, icon)
+                })
+                .and_then(|icon| {
+                    decode_image_asset(
+                        &icon.final_url,
+                        &icon.content_type,
+                        &icon.body,
+                        ImageDecodeLimits::default(),
+                    )
+                })
+                .map(|mut icon| {
+                    icon.url.clone_from(icon_url);
+                    icon
+                });
+        }
+        // A page whose scripts all failed gets its `<noscript>` fallback, like a no-JS load.
+        document.set_js_enabled(
+            session.options.javascript_enabled
+                && (total_scripts == 0 || out.js_execution.scripts_executed > 0),
+        );
+
+        if document.renderable_text_len() == 0 {
+            let fallback = document.static_text_fallback(MAX_STATIC_FALLBACK_CHARS);
+            if !fallback.is_empty() {
+                out.static_text_fallback = Some(fallback);
             }
+        }
 
-            html_document = Some(document);
+        out.html_document = Some(document);
+        settle_document(session, page, out)
+    }
+}
+
+/// Shows a standalone image, or its raw bytes when it cannot be decoded.
+fn render_image_document(
+    session: &mut NavigationSession<'_>,
+    page: &FetchedResponse,
+    out: &mut RenderedDocument,
+) {
+    match decode_image_asset(
+        &page.final_url,
+        &page.content_type,
+        &page.body,
+        ImageDecodeLimits::default(),
+    ) {
+        Some(image) => {
+            out.paint_timings.first_image_ready = Some(session.navigation_start.elapsed());
+            out.html_document = Some(standalone_image_document(&image));
+            out.decoded_images.push(image);
+        }
+        None => {
+            session
+                .nav_log
+                .warn("document", &page.final_url, "image could not be decoded");
+            out.content_kind = ContentKind::Raw;
+            render_raw_preview(session, page, out);
         }
+    }
+}
 
-        if let Some(next_url) = js_redirect_target {
-            if js_redirects_remaining > 0 {
-                js_redirects_remaining = js_redirects_remaining.saturating_sub(1);
-                current_url = next_url;
-                continue;
-            }
+/// Pretty-prints a JSON body, falling back to the raw preview when it does not parse.
+fn render_json_preview(
+    session: &mut NavigationSession<'_>,
+    page: &FetchedResponse,
+    out: &mut RenderedDocument,
+) {
+    let decoded_body = decode_text(&page.body, &page.content_type);
+    let Some(pretty) = pretty_print_json(&decoded_body) else {
+        out.content_kind = ContentKind::Raw;
+        render_raw_preview(session, page, out);
+        return;
+    };
+    out.body_preview = truncate_preview_text(&pretty, MAX_BODY_PREVIEW_BYTES);
+    out.paint_timings.text_ready = Some(session.navigation_start.elapsed());
+}
 
-            if js_execution.errors.len() < MAX_JS_ERROR_LOGS {
-                js_execution.errors.push(format_js_error(
-                    "runtime",
-                    &format!("js redirect limit reached while navigating to {next_url}"),
-                ));
-            }
+fn render_download_notice(
+    _session: &mut NavigationSession<'_>,
+    page: &FetchedResponse,
+    out: &mut RenderedDocument,
+) {
+    let file_name = content_disposition(page)
+        .and_then(|disposition| disposition.filename)
+        .unwrap_or_else(|| url_file_name(&page.final_url));
+    out.body_preview = format!(
+        "{file_name} ({} bytes) is a download and is not displayed.",
+        page.body.len()
+    );
+}
+
+fn render_raw_preview(
+    session: &mut NavigationSession<'_>,
+    page: &FetchedResponse,
+    out: &mut RenderedDocument,
+) {
+    let decoded_body = decode_text(&page.body, &page.content_type);
+    out.body_preview = truncate_preview_text(&decoded_body, MAX_BODY_PREVIEW_BYTES);
+    if !out.body_preview.is_empty() {
+        out.paint_timings.text_ready = Some(session.navigation_start.elapsed());
+    }
+}
+
+fn content_mime(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// Last path segment of `url`, or the whole URL when it has none.
+fn url_file_name(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|parsed| {
+            parsed
+                .path_segments()
+                .and_then(|mut segments| segments.next_back().map(str::to_owned))
+        })
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| url.to_owned())
+}
+
+/// Wraps a directly navigated image in a document so the HTML renderer can show it.
+fn standalone_image_document(image: &DecodedImageAsset) -> simple_html::HtmlDocument {
+    simple_html::HtmlDocument::parse(&format!(
+        "<html><head><title>{} ({}\u{d7}{})</title></head><body><img src=\"{}\"></body></html>",
+        escape_html_text(&url_file_name(&image.url)),
+        image.width,
+        image.height,
+        escape_html_text(&image.url),
+    ))
+}
+
+fn is_internal_about_url(url: &str) -> bool {
+    url.get(..6)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("about:"))
+}
+
+fn internal_about_page(url: &str, cache: &Arc<Mutex<HttpCache>>) -> Result<PageView, String> {
+    let normalized = url.trim().to_ascii_lowercase();
+    let html = match normalized.as_str() {
+        "about:blank" => String::new(),
+        "about:version" => about_version_html(),
+        "about:cache" => about_cache_html(cache),
+        _ => return Err(format!("unknown internal page '{normalized}'")),
+    };
+
+    let body_preview = truncate_preview_text(&html, MAX_BODY_PREVIEW_BYTES);
+    let html_document = simple_html::HtmlDocument::parse(&html);
+    Ok(PageView {
+        final_url: normalized,
+        status_code: 200,
+        status_text: "OK".to_owned(),
+        http_version: "internal".to_owned(),
+        content_type: "text/html; charset=utf-8".to_owned(),
+        headers: Vec::new(),
+        body_bytes: html.len(),
+        body_preview,
+        content_kind: ContentKind::Html,
+        auth_challenge: None,
+        title: html_document.title.clone(),
+        favicon_url: None,
+        favicon: None,
+        html_document: Some(html_document),
+        static_text_fallback: None,
+        decoded_images: Vec::new(),
+        deferred_images: Vec::new(),
+        loading_images: Vec::new(),
+        image_budget: Arc::default(),
+        subresource_stats: SubresourceStats::default(),
+        js_execution: JsExecutionStats::default(),
+        renderer_draw_calls: None,
+        paint_timings: PaintTimings::default(),
+        blocked_requests: Vec::new(),
+        cookies: Vec::new(),
+        nav_log: NavLog::default(),
+        security: None,
+    })
+}
+
+fn about_version_html() -> String {
+    format!(
+        "<html><head><title>About PixelDust</title></head><body>\
+         <h1>PixelDust Browser</h1>\
+         <p>Version {}</p>\
+         <ul><li>Target: {} / {}</li><li>JavaScript: Boa</li><li>UI: egui</li></ul>\
+         </body></html>",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+    )
+}
+
+fn about_cache_html(cache: &Arc<Mutex<HttpCache>>) -> String {
+    let summaries = {
+        let guard = match cache.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        guard.responses.entry_summaries()
+    };
+
+    let mut html = String::from("<html><head><title>Cache</title></head><body><h1>HTTP cache</h1>");
+    html.push_str(&format!("<p>{} entries</p><ul>", summaries.len()));
+    for summary in summaries {
+        let max_age = summary
+            .max_age
+            .map(|max_age| format!(", max-age {}s", max_age.as_secs()))
+            .unwrap_or_default();
+        html.push_str(&format!(
+            "<li>{} ({}{}{}, stored {}s ago)</li>",
+            escape_html_text(&summary.url),
+            if summary.is_fresh { "fresh" } else { "stale" },
+            max_age,
+            if summary.has_etag { ", etag" } else { "" },
+            summary.stored_at.elapsed().as_secs()
+        ));
+    }
+    html.push_str("</ul></body></html>");
+    html
+}
+
+fn escape_html_text(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for ch in input.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(ch),
         }
+    }
+    out
+}
 
-        let title = html_document
-            .as_ref()
-            .and_then(|doc| doc.title.clone())
-            .or_else(|| extract_html_title(&body_preview));
-
-        return Ok(PageView {
-            final_url: page.final_url,
-            status_code: page.status_code,
-            http_version: page.http_version,
-            content_type: page.content_type,
-            headers: page.headers,
-            body_bytes: page.body.len(),
-            body_preview,
-            title,
-            html_document,
-            static_text_fallback,
-            decoded_images,
-            subresource_stats,
-            js_execution,
-            renderer_draw_calls,
-        });
+/// Fetches and decodes a page's eager images, or nothing when images are turned off.
+pub(super) fn load_page_images(
+    load_images: bool,
+    image_urls: &[String],
+    mut fetch: impl FnMut(&str) -> Option<DecodedImageAsset>,
+) -> Vec<DecodedImageAsset> {
+    if !load_images {
+        return Vec::new();
+    }
+
+    image_urls
+        .iter()
+        .take(MAX_IMAGE_FETCHES)
+        .filter_map(|url| fetch(url))
+        .collect()
+}
+
+/// Fetches requested lazy images, ignoring URLs that are not in the page's deferred list.
+pub(super) fn fetch_requested_images(
+    deferred: &[String],
+    requested: &[String],
+    mut fetch: impl FnMut(&str) -> Option<DecodedImageAsset>,
+) -> Vec<DecodedImageAsset> {
+    let mut fetched = Vec::new();
+    let mut seen = Vec::new();
+    for url in requested {
+        if !deferred.contains(url) || seen.contains(&url) {
+            continue;
+        }
+        seen.push(url);
+        if let Some(image) = fetch(url) {
+            fetched.push(image);
+        }
+    }
+    fetched
+}
+
+/// Fetches one lazy image; refused requests land in `nav_log` like eager ones.
+pub(super) fn fetch_deferred_image(
+    page_url: &str,
+    image_url: &str,
+    options: &NavigationOptions,
+    cache: &Arc<Mutex<HttpCache>>,
+    image_budget: &Mutex<ImageMemoryBudget>,
+    nav_log: &mut NavLog,
+) -> Option<DecodedImageAsset> {
+    let mut browser = pd_browser::Browser::new().ok()?;
+    browser.network.user_agent_overrides = Arc::clone(&options.user_agent_overrides);
+    if !allow_logged_subresource(&browser, nav_log, "image", page_url, image_url) {
+        return None;
+    }
+
+    let policy = browser
+        .network
+        .tls_policy
+        .clone()
+        .with_trust_store_mode(options.trust_store.as_policy_mode())
+        .with_ocsp_stapling_required(options.ocsp_required);
+    let mut client = browser
+        .network
+        .shared_http11_client_with_tls_policy(policy.clone())
+        .ok()?;
+    let image = fetch_with_redirects(
+        &browser,
+        &mut client,
+        &policy,
+        image_url,
+        FetchTarget::Subresource,
+        cache,
+        CacheMode::Normal,
+    )
+    .ok()?;
+    if !is_success_status(image.status_code) {
+        return None;
     }
+
+    match decode_budgeted_image(image_budget, &image) {
+        BudgetedImage::Decoded(decoded) => Some(decoded),
+        BudgetedImage::OverBudget | BudgetedImage::Undecodable => None,
+    }
+}
+
+/// Saves or, with `None`, clears the `User-Agent` override for `host` and its subdomains.
+///
+/// Without `storage` the change only lasts for this session.
+pub(super) fn save_user_agent_override(
+    overrides: &mut Arc<UserAgentOverrides>,
+    storage: Option<&StorageManager>,
+    host: &str,
+    user_agent: Option<&str>,
+) -> Result<(), String> {
+    let overrides = Arc::make_mut(overrides);
+    match user_agent {
+        Some(user_agent) => overrides.set(storage, host, user_agent),
+        None => overrides.remove(storage, host),
+    }
+    .map_err(|error| error.to_string())
+}
+
+/// Time until the first text or image could be painted; `None` when nothing renderable loaded.
+pub(super) fn first_contentful_paint(timings: &PaintTimings) -> Option<Duration> {
+    match (timings.text_ready, timings.first_image_ready) {
+        (Some(text), Some(image)) => Some(text.min(image)),
+        (text, image) => text.or(image),
+    }
+}
+
+fn ensure_not_stopped(cancel: &AtomicBool) -> Result<(), String> {
+    if cancel.load(Ordering::Relaxed) {
+        return Err("navigation stopped".to_owned());
+    }
+    Ok(())
+}
+
+/// Aborts the in-flight navigation, returning its request id if one was running.
+pub(super) fn stop_navigation(
+    inflight_request_id: &mut Option<u64>,
+    cancel: &AtomicBool,
+) -> Option<u64> {
+    let stopped = inflight_request_id.take()?;
+    cancel.store(true, Ordering::Relaxed);
+    Some(stopped)
+}
+
+/// Whether a finished navigation is still the one the UI is waiting for.
+pub(super) fn is_current_navigation(inflight_request_id: Option<u64>, request_id: u64) -> bool {
+    inflight_request_id == Some(request_id)
+}
+
+/// Fetches subresources on the shared pool in `urls` order; every fetch clones `client`, so
+/// they all draw keep-alive connections from the document's connection pool.
+fn fetch_subresources(
+    browser: &pd_browser::Browser,
+    client: &SharedHttp11Client,
+    policy: &pd_net::tls::StrictTlsPolicy,
+    urls: &[String],
+    cache: &Arc<Mutex<HttpCache>>,
+    cache_mode: CacheMode,
+    cancel: &AtomicBool,
+) -> Vec<Option<Result<FetchedResponse, String>>> {
+    fetch_in_parallel(urls, FetchPoolConfig::default(), cancel, |url| {
+        fetch_with_redirects(
+            browser,
+            &mut client.clone(),
+            policy,
+            url,
+            FetchTarget::Subresource,
+            cache,
+            cache_mode,
+        )
+        .map_err(|error| error.to_string())
+    })
 }
 
 fn fetch_with_redirects(
     browser: &pd_browser::Browser,
-    client: &mut Http11Client,
+    client: &mut SharedHttp11Client,
     policy: &pd_net::tls::StrictTlsPolicy,
     raw_url: &str,
-    max_redirects: usize,
+    target: FetchTarget<'_>,
     cache: &Arc<Mutex<HttpCache>>,
-) -> Result<FetchedResponse, String> {
+    cache_mode: CacheMode,
+) -> Result<FetchedResponse, NavigationError> {
+    let max_redirects = target.max_redirects();
     let mut current_url = raw_url.to_owned();
     let mut redirects_followed = 0_usize;
 
     loop {
-        let cached = lookup_cache(cache, &current_url);
+        let cached = match cache_mode {
+            CacheMode::Normal => lookup_cache(cache, &current_url),
+            CacheMode::Bypass => CacheLookup::Miss,
+        };
         if let CacheLookup::Fresh(response) = cached {
             return Ok(response);
         }
@@ -318,6 +1250,10 @@ fn fetch_with_redirects(
             .prepare_get_with_tls_policy(&current_url, &request_policy)
             .map_err(|error| error.to_string())?;
         attach_cookie_header(cache, &current_url, &mut prepared.request.headers)?;
+        attach_authorization_header(cache, &current_url, &mut prepared.request.headers);
+        if cache_mode == CacheMode::Bypass {
+            add_no_cache_request_headers(&mut prepared.request.headers)?;
+        }
 
         if let CacheLookup::Stale {
             etag,
@@ -341,7 +1277,7 @@ fn fetch_with_redirects(
             .map(|header| (header.name.clone(), header.value.clone()))
             .collect();
         let status_code = response.status.as_u16();
-        store_response_cookies(cache, &current_url, &headers);
+        store_response_cookies(cache, &current_url, &headers, &browser.privacy);
 
         if status_code == 304 {
             if let CacheLookup::Stale { cached, .. } = cached {
@@ -358,12 +1294,13 @@ fn fetch_with_redirects(
 
             if let Some(location) = location {
                 if redirects_followed >= max_redirects {
-                    return Err(format!(
+                    return Err(NavigationError::Failed(format!(
                         "Too many redirects (>{max_redirects}) while loading {raw_url}"
-                    ));
+                    )));
                 }
 
-                current_url = resolve_redirect_url(&current_url, &location)?;
+                current_url =
+                    target.admit_redirect(resolve_redirect_url(&current_url, &location)?)?;
                 redirects_followed = redirects_followed.saturating_add(1);
                 continue;
             }
@@ -378,10 +1315,13 @@ fn fetch_with_redirects(
         let fetched = FetchedResponse {
             final_url: current_url,
             status_code,
+            status_text: response.status_text,
             http_version: response.version.as_str().to_owned(),
             headers,
             content_type,
             body: response.body,
+            warnings: response.warnings.iter().map(ToString::to_string).collect(),
+            tls: response.tls,
         };
 
         maybe_store_cache_entry(cache, &fetched);
@@ -408,24 +1348,7 @@ fn lookup_cache(cache: &Arc<Mutex<HttpCache>>, url: &str) -> CacheLookup {
         Ok(guard) => guard,
         Err(poisoned) => poisoned.into_inner(),
     };
-
-    let Some(entry) = guard.entries.get(url) else {
-        return CacheLookup::Miss;
-    };
-
-    if entry.is_fresh() {
-        return CacheLookup::Fresh(entry.response.clone());
-    }
-
-    if entry.etag.is_some() || entry.last_modified.is_some() {
-        return CacheLookup::Stale {
-            cached: entry.response.clone(),
-            etag: entry.etag.clone(),
-            last_modified: entry.last_modified.clone(),
-        };
-    }
-
-    CacheLookup::Miss
+    guard.responses.lookup(url)
 }
 
 fn add_conditional_request_headers(
@@ -442,8 +1365,15 @@ fn add_conditional_request_headers(
     Ok(())
 }
 
+/// Asks the origin and any intermediaries for a response that was not served from cache.
+fn add_no_cache_request_headers(headers: &mut Vec<Header>) -> Result<(), String> {
+    headers.push(Header::new("Cache-Control", "no-cache").map_err(|error| error.to_string())?);
+    headers.push(Header::new("Pragma", "no-cache").map_err(|error| error.to_string())?);
+    Ok(())
+}
+
 fn maybe_store_cache_entry(cache: &Arc<Mutex<HttpCache>>, response: &FetchedResponse) {
-    if !is_success_status(response.status_code) {
+    if !is_success_status(response.status_code) || !response.warnings.is_empty() {
         return;
     }
 
@@ -452,32 +1382,29 @@ fn maybe_store_cache_entry(cache: &Arc<Mutex<HttpCache>>, response: &FetchedResp
         return;
     }
 
-    let max_age = parse_max_age(cache_control);
-    let etag = header_value(&response.headers, "etag").map(ToOwned::to_owned);
-    let last_modified = header_value(&response.headers, "last-modified").map(ToOwned::to_owned);
-
-    if max_age.is_none() && etag.is_none() && last_modified.is_none() {
-        return;
-    }
-
     let mut guard = match cache.lock() {
         Ok(guard) => guard,
         Err(poisoned) => poisoned.into_inner(),
     };
-
-    if guard.entries.len() >= MAX_CACHE_ENTRIES {
-        let oldest = guard
-            .entries
-            .iter()
-            .min_by_key(|(_, entry)| entry.stored_at)
-            .map(|(key, _)| key.clone());
-        if let Some(oldest_key) = oldest {
-            guard.entries.remove(&oldest_key);
+    let max_age = match parse_max_age(cache_control) {
+        Some(max_age) => Some(max_age),
+        None if !guard.heuristic_freshness
+            || contains_cache_directive(cache_control, "no-cache")
+            || header_value(&response.headers, "expires").is_some() =>
+        {
+            None
         }
+        None => heuristic_freshness(&response.headers, SystemTime::now()),
+    };
+    let etag = header_value(&response.headers, "etag").map(ToOwned::to_owned);
+    let last_modified = header_value(&response.headers, "last-modified").map(ToOwned::to_owned);
+
+    if max_age.is_none() && etag.is_none() && last_modified.is_none() {
+        return;
     }
 
-    guard.entries.insert(
-        response.final_url.clone(),
+    guard.responses.store(
+        &response.final_url,
         CachedResponse {
             response: response.clone(),
             etag,
@@ -497,54 +1424,37 @@ fn refresh_cached_metadata(
         Ok(guard) => guard,
         Err(poisoned) => poisoned.into_inner(),
     };
-
-    let mut remove_entry = false;
-    if let Some(cache_control) = header_value(response_headers, "cache-control") {
-        if contains_cache_directive(cache_control, "no-store") {
-            remove_entry = true;
-        }
-    }
-
-    if remove_entry {
-        guard.entries.remove(url);
-        return;
-    }
-
-    let Some(entry) = guard.entries.get_mut(url) else {
-        return;
-    };
-
-    if let Some(cache_control) = header_value(response_headers, "cache-control") {
-        if let Some(max_age) = parse_max_age(cache_control) {
-            entry.max_age = Some(max_age);
-        }
-    }
-
-    if let Some(etag) = header_value(response_headers, "etag") {
-        entry.etag = Some(etag.to_owned());
-    }
-    if let Some(last_modified) = header_value(response_headers, "last-modified") {
-        entry.last_modified = Some(last_modified.to_owned());
-    }
-
-    entry.stored_at = Instant::now();
+    guard.responses.refresh_metadata(url, response_headers);
 }
 
-fn header_value<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+pub(super) fn header_value<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
     headers
         .iter()
         .find(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
         .map(|(_, value)| value.as_str())
 }
 
-fn contains_cache_directive(value: &str, directive: &str) -> bool {
+pub(super) fn contains_cache_directive(value: &str, directive: &str) -> bool {
     value
         .split(',')
         .map(str::trim)
         .any(|token| token.eq_ignore_ascii_case(directive))
 }
 
-fn parse_max_age(cache_control: &str) -> Option<Duration> {
+/// Freshness for a response with `Last-Modified` but no explicit lifetime (RFC 7234 §4.2.2).
+pub(super) fn heuristic_freshness(
+    headers: &[(String, String)],
+    now: SystemTime,
+) -> Option<Duration> {
+    let last_modified = parse_http_date(header_value(headers, "last-modified")?)?;
+    let date = header_value(headers, "date")
+        .and_then(parse_http_date)
+        .unwrap_or(now);
+    let age = date.duration_since(last_modified).ok()?;
+    Some((age / HEURISTIC_FRESHNESS_DIVISOR).min(Duration::from_secs(MAX_HEURISTIC_FRESHNESS_SECS)))
+}
+
+pub(super) fn parse_max_age(cache_control: &str) -> Option<Duration> {
     for directive in cache_control.split(',').map(str::trim) {
         let Some((name, value)) = directive.split_once('=') else {
             continue;
@@ -563,13 +1473,219 @@ fn parse_max_age(cache_control: &str) -> Option<Duration> {
     None
 }
 
-impl CachedResponse {
-    fn is_fresh(&self) -> bool {
-        let Some(max_age) = self.max_age else {
-            return false;
+impl SecurityInfo {
+    /// HTTPS pages are secure, with warnings once mixed content was blocked; others are not.
+    pub(super) fn for_page(
+        final_url: &str,
+        tls: Option<TlsSessionInfo>,
+        blocked_requests: &[BlockedRequest],
+    ) -> Self {
+        let mixed_content_blocked = blocked_requests
+            .iter()
+            .filter(|request| request.reason == BlockReason::MixedContent)
+            .count();
+        let state = if !Url::parse(final_url).is_ok_and(|url| url.scheme() == "https") {
+            SecurityState::Insecure
+        } else if mixed_content_blocked > 0 {
+            SecurityState::SecureWithWarnings
+        } else {
+            SecurityState::Secure
+        };
+        Self {
+            state,
+            tls,
+            mixed_content_blocked,
+        }
+    }
+}
+
+impl NavigationState {
+    pub(super) fn label(&self) -> String {
+        match self {
+            Self::Preparing => "Preparing".to_owned(),
+            Self::Fetching => "Fetching document".to_owned(),
+            Self::Parsing => "Parsing".to_owned(),
+            Self::LoadingSubresources { done, total } => {
+                format!("Loading subresources ({done}/{total})")
+            }
+            Self::RunningScripts => "Running scripts".to_owned(),
+            Self::Complete => "Complete".to_owned(),
+            Self::Failed(error) => format!("Failed: {error}"),
+        }
+    }
+}
+
+impl JsRedirectTracker {
+    fn new(limit: usize) -> Self {
+        Self {
+            visited: Vec::new(),
+            remaining: limit,
+        }
+    }
+
+    /// Accepts a redirect from `from` to `target` unless it revisits a page or exceeds the limit.
+    fn follow(&mut self, from: &str, target: &str) -> Result<JsRedirectStep, String> {
+        let from = without_fragment(from);
+        let target_key = without_fragment(target);
+        if target_key == from {
+            return Ok(JsRedirectStep::SameDocument);
+        }
+        if !self.visited.iter().any(|visited| visited == from) {
+            self.visited.push(from.to_owned());
+        }
+        if self.visited.iter().any(|visited| visited == target_key) {
+            return Err(format!(
+                "js redirect loop detected: {target} was already visited"
+            ));
+        }
+        if self.remaining == 0 {
+            return Err(format!(
+                "js redirect limit reached while navigating to {target}"
+            ));
+        }
+        self.remaining -= 1;
+        self.visited.push(target_key.to_owned());
+        Ok(JsRedirectStep::Follow)
+    }
+}
+
+fn without_fragment(url: &str) -> &str {
+    url.split_once('#').map_or(url, |(before, _)| before)
+}
+
+impl NavLog {
+    fn push(&mut self, level: NavLogLevel, resource: &'static str, url: &str, reason: String) {
+        if self.entries.len() >= MAX_NAV_LOG_ENTRIES {
+            self.dropped = self.dropped.saturating_add(1);
+            return;
+        }
+        self.entries.push(NavLogEntry {
+            level,
+            resource,
+            url: url.to_owned(),
+            reason,
+        });
+    }
+
+    fn info(&mut self, resource: &'static str, url: &str, reason: impl Into<String>) {
+        self.push(NavLogLevel::Info, resource, url, reason.into());
+    }
+
+    fn warn(&mut self, resource: &'static str, url: &str, reason: impl Into<String>) {
+        self.push(NavLogLevel::Warning, resource, url, reason.into());
+    }
+
+    /// Logs a refused request and records it once per URL and reason.
+    fn block(&mut self, resource: &'static str, url: &str, reason: BlockReason) {
+        self.warn(resource, url, format!("blocked: {}", reason.label()));
+        let seen = self
+            .blocked
+            .iter()
+            .any(|blocked| blocked.url == url && blocked.reason == reason);
+        if !seen && self.blocked.len() < MAX_NAV_LOG_ENTRIES {
+            self.blocked.push(BlockedRequest {
+                url: url.to_owned(),
+                reason,
+            });
+        }
+    }
+
+    /// Adds entries logged after the page loaded, such as lazy image fetches.
+    pub(super) fn append_entries(&mut self, other: NavLog) {
+        for entry in other.entries {
+            self.push(entry.level, entry.resource, &entry.url, entry.reason);
+        }
+        self.dropped = self.dropped.saturating_add(other.dropped);
+    }
+
+    pub(super) fn warning_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.level == NavLogLevel::Warning)
+            .count()
+    }
+}
+
+impl NavLogEntry {
+    pub(super) fn summary(&self) -> String {
+        let level = match self.level {
+            NavLogLevel::Info => "info",
+            NavLogLevel::Warning => "warn",
         };
+        format!("[{level}] {} {}: {}", self.resource, self.url, self.reason)
+    }
+}
+
+/// Applies the subresource policy, logging a warning when the request is blocked.
+fn allow_logged_subresource(
+    browser: &pd_browser::Browser,
+    log: &mut NavLog,
+    resource: &'static str,
+    document_url: &str,
+    candidate_url: &str,
+) -> bool {
+    match subresource_block_reason(browser, document_url, candidate_url) {
+        Some(reason) => {
+            log.block(resource, candidate_url, reason);
+            false
+        }
+        None => true,
+    }
+}
+
+/// Blocked requests per reason, in [`BlockReason::ALL`] order, omitting empty reasons.
+pub(super) fn blocked_request_counts(blocked: &[BlockedRequest]) -> Vec<(BlockReason, usize)> {
+    BlockReason::ALL
+        .into_iter()
+        .map(|reason| {
+            let count = blocked
+                .iter()
+                .filter(|request| request.reason == reason)
+                .count();
+            (reason, count)
+        })
+        .filter(|(_, count)| *count > 0)
+        .collect()
+}
+
+/// Keeps a subresource response only if it was fetched with a success status.
+fn successful_subresource(
+    log: &mut NavLog,
+    resource: &'static str,
+    url: &str,
+    response: Result<FetchedResponse, String>,
+) -> Option<FetchedResponse> {
+    match response {
+        Ok(response) if is_success_status(response.status_code) => {
+            for warning in &response.warnings {
+                log.warn(resource, &response.final_url, warning.clone());
+            }
+            Some(response)
+        }
+        Ok(response) => {
+            log.warn(
+                resource,
+                &response.final_url,
+                format!(
+                    "HTTP status {}",
+                    status_label(response.status_code, &response.status_text)
+                ),
+            );
+            None
+        }
+        Err(error) => {
+            log.warn(resource, url, format!("fetch failed: {error}"));
+            None
+        }
+    }
+}
 
-        self.stored_at.elapsed() < max_age
+/// Status code followed by the server's reason phrase, when it sent one.
+pub(super) fn status_label(status: u16, status_text: &str) -> String {
+    if status_text.is_empty() {
+        status.to_string()
+    } else {
+        format!("{status} {status_text}")
     }
 }
 
@@ -577,47 +1693,80 @@ fn is_success_status(status: u16) -> bool {
     (200..=299).contains(&status)
 }
 
-fn allow_subresource_request(
+/// Why the subresource policy refuses `candidate_url`, or `None` when it may be fetched.
+fn subresource_block_reason(
     browser: &pd_browser::Browser,
     document_url: &str,
     candidate_url: &str,
-) -> bool {
+) -> Option<BlockReason> {
     let Ok(candidate) = Url::parse(candidate_url) else {
-        return false;
+        return Some(BlockReason::UnsupportedUrl);
     };
     if !matches!(candidate.scheme(), "http" | "https") {
-        return false;
+        return Some(BlockReason::UnsupportedUrl);
     }
 
     let Some(host) = candidate.host_str() else {
-        return false;
+        return Some(BlockReason::UnsupportedUrl);
     };
 
     if browser.privacy.should_block_host(host) {
-        return false;
+        return Some(BlockReason::Tracker);
     }
 
     if !browser.security.enforce_site_isolation {
-        return true;
+        return None;
     }
 
     if same_origin(document_url, candidate_url) {
-        return true;
+        return None;
     }
 
     // Keep downgrade protections while allowing cross-origin HTTPS subresources
     // (required by modern pages that split assets across dedicated hosts/CDNs).
     let Ok(document) = Url::parse(document_url) else {
-        return false;
+        return Some(BlockReason::CrossOrigin);
     };
     if !matches!(document.scheme(), "http" | "https") {
-        return false;
+        return Some(BlockReason::CrossOrigin);
     }
     if document.scheme() == "https" && candidate.scheme() != "https" {
-        return false;
+        return Some(BlockReason::MixedContent);
     }
 
-    true
+    None
+}
+
+/// Same-origin scripts always pass; with blocking on, others must share the registrable domain.
+fn allow_third_party_script(
+    block_third_party_scripts: bool,
+    document_url: &str,
+    script_url: &str,
+) -> bool {
+    if !block_third_party_scripts || same_origin(document_url, script_url) {
+        return true;
+    }
+
+    let host = |url: &str| {
+        Url::parse(url)
+            .ok()
+            .and_then(|parsed| parsed.host_str().map(registrable_domain))
+    };
+    match (host(document_url), host(script_url)) {
+        (Some(document), Some(script)) => document == script,
+        _ => false,
+    }
+}
+
+/// Public-suffix-lite approximation: the last two labels of a DNS name, or the whole IP literal.
+fn registrable_domain(host: &str) -> String {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    if host.starts_with('[') || host.parse::<std::net::Ipv4Addr>().is_ok() {
+        return host;
+    }
+
+    let labels = host.split('.').collect::<Vec<_>>();
+    labels[labels.len().saturating_sub(2)..].join(".")
 }
 
 fn same_origin(left: &str, right: &str) -> bool {
@@ -654,6 +1803,74 @@ fn is_javascript_content_type(content_type: &str, final_url: &str) -> bool {
     url_lower.contains(".js") || url_lower.contains(".mjs")
 }
 
+/// Decides whether a `<link rel=stylesheet>` response can be used as CSS.
+fn accept_stylesheet_response(content_type: &str, final_url: &str, body: &[u8]) -> bool {
+    accept_contextual_response(content_type, body, || {
+        is_css_content_type(content_type, final_url)
+    })
+}
+
+/// Decides whether a `<script src>` response can be executed as JavaScript.
+fn accept_script_response(content_type: &str, final_url: &str, body: &[u8]) -> bool {
+    accept_contextual_response(content_type, body, || {
+        is_javascript_content_type(content_type, final_url)
+    })
+}
+
+// The requesting element already says what the resource should be, so a missing
+// or generic type is trusted; sniffing only rejects HTML error pages.
+fn accept_contextual_response(
+    content_type: &str,
+    body: &[u8],
+    declared_match: impl FnOnce() -> bool,
+) -> bool {
+    if body_looks_like_html(body) {
+        return false;
+    }
+
+    let essence = content_type_essence(content_type);
+    if essence == "text/html" || essence == "application/xhtml+xml" {
+        return false;
+    }
+
+    is_generic_content_type(&essence) || declared_match()
+}
+
+fn content_type_essence(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+fn is_generic_content_type(essence: &str) -> bool {
+    matches!(
+        essence,
+        "" | "text/plain"
+            | "application/octet-stream"
+            | "binary/octet-stream"
+            | "application/unknown"
+            | "unknown/unknown"
+    )
+}
+
+fn body_looks_like_html(body: &[u8]) -> bool {
+    let body = body.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(body);
+    let start = body
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .unwrap_or(body.len());
+    let prefix = &body[start..body.len().min(start.saturating_add(16))];
+
+    [b"<!doctype html".as_slice(), b"<html", b"<head", b"<body"]
+        .iter()
+        .any(|marker| {
+            prefix.len() >= marker.len() && prefix[..marker.len()].eq_ignore_ascii_case(marker)
+        })
+}
+
 fn format_script_origin(input: &str) -> String {
     let trimmed = input.trim();
     if trimmed.is_empty() {
@@ -663,12 +1880,22 @@ fn format_script_origin(input: &str) -> String {
     if let Ok(mut url) = Url::parse(trimmed) {
         url.set_query(None);
         url.set_fragment(None);
-        return clamp_log_text(&url.to_string(), MAX_JS_ERROR_ORIGIN_CHARS);
+        return clamp_log_text(url.as_str(), MAX_JS_ERROR_ORIGIN_CHARS);
     }
 
     clamp_log_text(trimmed, MAX_JS_ERROR_ORIGIN_CHARS)
 }
 
+/// Counts a repeat of a logged error, or appends a new entry while under `MAX_JS_ERROR_LOGS`.
+fn record_js_error(errors: &mut Vec<JsErrorEntry>, origin: &str, message: &str) {
+    let text = format_js_error(origin, message);
+    if let Some(entry) = errors.iter_mut().find(|entry| entry.text == text) {
+        entry.count = entry.count.saturating_add(1);
+    } else if errors.len() < MAX_JS_ERROR_LOGS {
+        errors.push(JsErrorEntry { text, count: 1 });
+    }
+}
+
 fn format_js_error(origin: &str, message: &str) -> String {
     let clean_origin = format_script_origin(origin);
     let clean_message = normalize_log_whitespace(message);
@@ -699,17 +1926,19 @@ fn clamp_log_text(input: &str, max_chars: usize) -> String {
 }
 
 fn js_stats_from_report(enabled: bool, report: JsExecutionReport) -> JsExecutionStats {
-    let errors = report
-        .errors
-        .into_iter()
-        .map(|error| format_js_error(&error.origin, &error.message))
-        .collect::<Vec<_>>();
+    let mut errors = Vec::new();
+    for error in &report.errors {
+        record_js_error(&mut errors, &error.origin, &error.message);
+    }
 
     JsExecutionStats {
         enabled,
         scripts_seen: report.scripts_seen,
         scripts_executed: report.scripts_executed,
         scripts_failed: report.scripts_failed,
+        syntax_errors: report.syntax_errors,
+        runtime_errors: report.runtime_errors,
+        resource_limit_errors: report.resource_limit_errors,
         scripts_skipped: report.scripts_skipped,
         event_dispatches: 0,
         event_failures: 0,
@@ -720,51 +1949,48 @@ fn js_stats_from_report(enabled: bool, report: JsExecutionReport) -> JsExecution
 pub(super) fn dispatch_dom_events(
     page: &mut PageView,
     events: &[simple_html::DomEventRequest],
-) -> Option<String> {
+) -> DomDispatchOutcome {
     if events.is_empty() {
-        return None;
+        return DomDispatchOutcome::default();
     }
-    let document = page.html_document.as_ref()?;
+    let Some(document) = page.html_document.as_ref() else {
+        return DomDispatchOutcome::default();
+    };
 
     let mut event_scripts = Vec::new();
+    let mut default_action_origins = Vec::new();
     for (index, event) in events.iter().take(MAX_DOM_EVENTS_PER_FRAME).enumerate() {
         if event.inline_handler.len() > MAX_INLINE_EVENT_HANDLER_BYTES {
             page.js_execution.event_failures = page.js_execution.event_failures.saturating_add(1);
-            if page.js_execution.errors.len() < MAX_JS_ERROR_LOGS {
-                page.js_execution.errors.push(format_js_error(
-                    &format!(
-                        "dom-event:{}:{}",
-                        match event.kind {
-                            simple_html::DomEventKind::Click => "click",
-                            simple_html::DomEventKind::Input => "input",
-                            simple_html::DomEventKind::Submit => "submit",
-                        },
-                        index + 1
-                    ),
-                    &format!(
-                        "inline handler too large ({} bytes)",
-                        event.inline_handler.len()
-                    ),
-                ));
-            }
+            record_js_error(
+                &mut page.js_execution.errors,
+                &format!("dom-event:{}:{}", event.kind.event_type(), index + 1),
+                &format!(
+                    "inline handler too large ({} bytes)",
+                    event.inline_handler.len()
+                ),
+            );
             continue;
         }
 
-        let event_type = match event.kind {
-            simple_html::DomEventKind::Click => "click",
-            simple_html::DomEventKind::Input => "input",
-            simple_html::DomEventKind::Submit => "submit",
-        };
+        let event_type = event.kind.event_type();
         let target_id = event.target_id.as_deref().unwrap_or("");
         let script = build_inline_event_script(event_type, target_id, &event.inline_handler);
+        let origin = format!("dom-event:{}:{}", event_type, index + 1);
+        if matches!(
+            event.kind,
+            simple_html::DomEventKind::Click | simple_html::DomEventKind::Submit
+        ) {
+            default_action_origins.push(origin.clone());
+        }
         event_scripts.push(ScriptSource {
-            origin: format!("dom-event:{}:{}", event_type, index + 1),
+            origin,
             source: script,
         });
     }
 
     if event_scripts.is_empty() {
-        return None;
+        return DomDispatchOutcome::default();
     }
 
     let host = JsHostEnvironment {
@@ -781,6 +2007,9 @@ pub(super) fn dispatch_dom_events(
                 attributes: element.attributes,
             })
             .collect(),
+        determinism: ScriptingDeterminism::default(),
+        sse_events: Vec::new(),
+        element_rects: Vec::new(),
     };
 
     let runtime = JsRuntime::new(event_js_runtime_config());
@@ -795,12 +2024,7 @@ pub(super) fn dispatch_dom_events(
         .saturating_add(output.report.scripts_failed);
 
     for error in output.report.errors {
-        if page.js_execution.errors.len() >= MAX_JS_ERROR_LOGS {
-            break;
-        }
-        page.js_execution
-            .errors
-            .push(format_js_error(&error.origin, &error.message));
+        record_js_error(&mut page.js_execution.errors, &error.origin, &error.message);
     }
 
     if let Some(new_title) = output
@@ -813,11 +2037,88 @@ pub(super) fn dispatch_dom_events(
             doc.title = Some(new_title);
         }
     }
+    if let Some(doc) = page.html_document.as_mut() {
+        apply_dom_mutations(doc, &output.mutations);
+        page.favicon_url = page_favicon_url(doc, &page.final_url);
+    }
+
+    DomDispatchOutcome {
+        navigate_to: output
+            .location_href
+            .as_deref()
+            .and_then(|href| resolve_js_location(&page.final_url, href)),
+        default_prevented: output
+            .default_prevented
+            .iter()
+            .any(|origin| default_action_origins.contains(origin)),
+    }
+}
+
+/// Applies script-made DOM changes so the next frame renders them.
+fn apply_dom_mutations(document: &mut simple_html::HtmlDocument, mutations: &[DomMutation]) {
+    for mutation in mutations {
+        match mutation {
+            DomMutation::InlineStyle {
+                element_id,
+                property,
+                value,
+            } => {
+                document.set_inline_style_property(element_id, property, value);
+            }
+            DomMutation::Attribute {
+                element_id,
+                name,
+                value,
+            } => {
+                document.set_element_attribute(element_id, name, value);
+            }
+            DomMutation::HeadLink { rel, href } => document.append_head_link(rel, href),
+        }
+    }
+}
 
-    output
-        .location_href
+/// Label for the active tab: the document title, else the host, else the raw URL.
+pub(super) fn tab_label(page: Option<&PageView>) -> String {
+    let Some(page) = page else {
+        return NEW_TAB_LABEL.to_owned();
+    };
+    if let Some(title) = page
+        .title
         .as_deref()
-        .and_then(|href| resolve_js_location(&page.final_url, href))
+        .map(str::trim)
+        .filter(|title| !title.is_empty())
+    {
+        return title.to_owned();
+    }
+    Url::parse(&page.final_url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_owned))
+        .unwrap_or_else(|| page.final_url.clone())
+}
+
+/// Updates the stored tab label from the page; returns whether it changed.
+/// Absolute URL of the document's current `<link rel=icon>`, if it is http(s).
+fn page_favicon_url(document: &simple_html::HtmlDocument, base_url: &str) -> Option<String> {
+    document
+        .favicon_href()
+        .and_then(|href| resolve_js_location(base_url, href))
+}
+
+/// Decoded icon for the tab strip, once it matches the page's current favicon URL.
+pub(super) fn tab_favicon(page: Option<&PageView>) -> Option<&DecodedImageAsset> {
+    let page = page?;
+    page.favicon
+        .as_ref()
+        .filter(|icon| page.favicon_url.as_deref() == Some(icon.url.as_str()))
+}
+
+pub(super) fn refresh_tab_title(tab_title: &mut String, page: Option<&PageView>) -> bool {
+    let label = tab_label(page);
+    if *tab_title == label {
+        return false;
+    }
+    *tab_title = label;
+    true
 }
 
 fn allow_page_script_source(source: &str) -> bool {
@@ -835,22 +2136,26 @@ fn allow_page_script_source(source: &str) -> bool {
 fn page_js_runtime_config() -> JsRuntimeConfig {
     JsRuntimeConfig {
         max_scripts: 128,
+        max_scripts_hard: Some(MAX_PAGE_SCRIPTS),
         max_script_bytes: MAX_PAGE_SCRIPT_BYTES,
         max_error_messages: 64,
         recursion_limit: 96,
         stack_size_limit: 2048,
         loop_iteration_limit: 500_000,
+        max_timer_runs: 2048,
     }
 }
 
 fn event_js_runtime_config() -> JsRuntimeConfig {
     JsRuntimeConfig {
         max_scripts: MAX_DOM_EVENTS_PER_FRAME,
+        max_scripts_hard: None,
         max_script_bytes: MAX_INLINE_EVENT_HANDLER_BYTES + 1024,
         max_error_messages: 24,
         recursion_limit: 32,
         stack_size_limit: 512,
         loop_iteration_limit: 25_000,
+        max_timer_runs: 256,
     }
 }
 
@@ -865,11 +2170,18 @@ fn build_inline_event_script(event_type: &str, target_id: &str, handler: &str) -
   const __pd_event = {{
     type: {event_type:?},
     target: __pd_target,
-    currentTarget: __pd_target
+    currentTarget: __pd_target,
+    cancelable: true,
+    defaultPrevented: false,
+    preventDefault: function() {{ __pd_prevent_default(this); }},
+    stopPropagation: function() {{}},
+    stopImmediatePropagation: function() {{}}
   }};
   const __pd_handler_src = {handler_literal};
   const __pd_handler = Function("event", __pd_handler_src);
-  __pd_handler.call(__pd_target || document, __pd_event);
+  if (__pd_handler.call(__pd_target || document, __pd_event) === false) {{
+    __pd_event.preventDefault();
+  }}
 }})();
 "#
     )
@@ -942,11 +2254,15 @@ fn cookie_header_for_url(cache: &Arc<Mutex<HttpCache>>, request_url: &str) -> St
             .then_with(|| left.as_str().cmp(right.as_str()))
     });
 
+    let now = SystemTime::now();
     let mut selected = HashMap::<String, String>::new();
     for (_, cookies) in domain_entries {
-        for (name, value) in cookies {
+        for (name, cookie) in cookies {
+            if !cookie_is_live(cookie, now) {
+                continue;
+            }
             if !selected.contains_key(name) {
-                selected.insert(name.clone(), value.clone());
+                selected.insert(name.clone(), cookie.value.clone());
             }
         }
     }
@@ -974,7 +2290,115 @@ fn attach_cookie_header(
     if let Ok(cookie_header) = Header::new("Cookie", &cookie) {
         headers.push(cookie_header);
     }
-    Ok(())
+    Ok(())
+}
+
+fn attach_authorization_header(
+    cache: &Arc<Mutex<HttpCache>>,
+    request_url: &str,
+    headers: &mut Vec<Header>,
+) {
+    let Some(value) = basic_authorization_for_url(cache, request_url) else {
+        return;
+    };
+
+    headers.retain(|header| !header.name.eq_ignore_ascii_case("authorization"));
+    if let Ok(header) = Header::new("Authorization", &value) {
+        headers.push(header);
+    }
+}
+
+fn origin_key(url: &str) -> Option<String> {
+    let origin = Url::parse(url).ok()?.origin();
+    origin.is_tuple().then(|| origin.ascii_serialization())
+}
+
+/// Remembers credentials for the origin of `url` until the app exits.
+pub(super) fn store_basic_credentials(
+    cache: &Arc<Mutex<HttpCache>>,
+    url: &str,
+    username: &str,
+    password: &str,
+) {
+    let Some(origin) = origin_key(url) else {
+        return;
+    };
+    let mut guard = match cache.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    guard.credentials.insert(
+        origin,
+        BasicCredentials {
+            username: username.to_owned(),
+            password: password.to_owned(),
+        },
+    );
+}
+
+fn basic_authorization_for_url(cache: &Arc<Mutex<HttpCache>>, url: &str) -> Option<String> {
+    let origin = origin_key(url)?;
+    let guard = match cache.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let credentials = guard.credentials.get(&origin)?;
+    Some(basic_authorization_value(
+        &credentials.username,
+        &credentials.password,
+    ))
+}
+
+fn basic_authorization_value(username: &str, password: &str) -> String {
+    format!(
+        "Basic {}",
+        base64_encode(format!("{username}:{password}").as_bytes())
+    )
+}
+
+/// Finds a Basic challenge among the `WWW-Authenticate` headers of a `401` response.
+fn basic_auth_challenge(url: &str, headers: &[(String, String)]) -> Option<AuthChallenge> {
+    let origin = origin_key(url)?;
+    headers
+        .iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case("www-authenticate"))
+        .find_map(|(_, value)| parse_basic_realm(value))
+        .map(|realm| AuthChallenge { origin, realm })
+}
+
+/// Returns the realm of a `Basic` challenge in `value`, or an empty realm if none is given.
+fn parse_basic_realm(value: &str) -> Option<String> {
+    let lower = value.to_ascii_lowercase();
+    let scheme_start = lower
+        .match_indices("basic")
+        .map(|(index, _)| index)
+        .find(|index| {
+            let before = lower[..*index].trim_end();
+            let after = lower[index + "basic".len()..].chars().next();
+            (before.is_empty() || before.ends_with(','))
+                && after.is_none_or(|ch| ch.is_ascii_whitespace() || ch == ',')
+        })?;
+
+    let params = &value[scheme_start + "basic".len()..];
+    let Some(realm_start) = params.to_ascii_lowercase().find("realm=") else {
+        return Some(String::new());
+    };
+    let realm = &params[realm_start + "realm=".len()..];
+    let Some(quoted) = realm.strip_prefix('"') else {
+        let end = realm.find([',', ' ']).unwrap_or(realm.len());
+        return Some(realm[..end].to_owned());
+    };
+
+    let mut out = String::new();
+    let mut chars = quoted.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => break,
+            '\\' => out.extend(chars.next()),
+            _ => out.push(ch),
+        }
+    }
+    Some(out)
 }
 
 fn merge_document_cookie_snapshot(
@@ -1010,14 +2434,28 @@ fn merge_document_cookie_snapshot(
         if name.is_empty() {
             continue;
         }
-        upsert_cookie(&mut guard, &host, name, value.trim());
+        let expires_at = guard
+            .cookies
+            .get(&host)
+            .and_then(|cookies| cookies.get(name))
+            .and_then(|cookie| cookie.expires_at);
+        upsert_cookie(
+            &mut guard,
+            &host,
+            name,
+            value.trim(),
+            expires_at,
+            Some(SystemTime::now()),
+        );
     }
 }
 
+/// Stores `Set-Cookie` headers; `session_only` drops expiry so nothing is persisted.
 fn store_response_cookies(
     cache: &Arc<Mutex<HttpCache>>,
     request_url: &str,
     response_headers: &[(String, String)],
+    privacy: &PrivacyPolicy,
 ) {
     let Ok(parsed_url) = Url::parse(request_url) else {
         return;
@@ -1031,7 +2469,9 @@ fn store_response_cookies(
         if !name.eq_ignore_ascii_case("set-cookie") {
             continue;
         }
-        if let Some(cookie) = parse_set_cookie_header(value, &default_domain) {
+        if let Some(cookie) = parse_set_cookie_header(value, &default_domain)
+            && set_cookie_rejection(&cookie, &parsed_url, privacy).is_none()
+        {
             parsed_cookies.push(cookie);
         }
     }
@@ -1056,7 +2496,14 @@ fn store_response_cookies(
             continue;
         }
 
-        upsert_cookie(&mut guard, &cookie.domain, &cookie.name, &cookie.value);
+        upsert_cookie(
+            &mut guard,
+            &cookie.domain,
+            &cookie.name,
+            &cookie.value,
+            cookie.expires_at.filter(|_| !privacy.session_cookies_only),
+            Some(SystemTime::now()),
+        );
     }
 }
 
@@ -1066,6 +2513,11 @@ struct ParsedSetCookie {
     name: String,
     value: String,
     delete: bool,
+    expires_at: Option<SystemTime>,
+    path: Option<String>,
+    secure: bool,
+    http_only: bool,
+    same_site: Option<String>,
 }
 
 fn parse_set_cookie_header(input: &str, default_domain: &str) -> Option<ParsedSetCookie> {
@@ -1079,6 +2531,12 @@ fn parse_set_cookie_header(input: &str, default_domain: &str) -> Option<ParsedSe
 
     let mut domain = default_domain.to_owned();
     let mut delete = value.trim().is_empty();
+    let mut max_age: Option<i64> = None;
+    let mut expires: Option<SystemTime> = None;
+    let mut path = None;
+    let mut secure = false;
+    let mut http_only = false;
+    let mut same_site = None;
 
     for raw_attr in segments {
         let attr = raw_attr.trim();
@@ -1098,14 +2556,36 @@ fn parse_set_cookie_header(input: &str, default_domain: &str) -> Option<ParsedSe
             continue;
         }
 
-        if attr_name.eq_ignore_ascii_case("max-age")
-            && attr_value
-                .parse::<i64>()
-                .ok()
-                .is_some_and(|value| value <= 0)
-        {
+        if attr_name.eq_ignore_ascii_case("max-age") {
+            max_age = attr_value.parse::<i64>().ok();
+            continue;
+        }
+
+        if attr_name.eq_ignore_ascii_case("expires") {
+            expires = parse_http_date(attr_value);
+        } else if attr_name.eq_ignore_ascii_case("path") {
+            path = Some(attr_value.to_owned()).filter(|value| value.starts_with('/'));
+        } else if attr_name.eq_ignore_ascii_case("secure") {
+            secure = true;
+        } else if attr_name.eq_ignore_ascii_case("httponly") {
+            http_only = true;
+        } else if attr_name.eq_ignore_ascii_case("samesite") && !attr_value.is_empty() {
+            same_site = Some(attr_value.to_owned());
+        }
+    }
+
+    // Max-Age takes precedence over Expires when both are present.
+    let now = SystemTime::now();
+    let expires_at = match max_age {
+        Some(seconds) if seconds <= 0 => {
             delete = true;
+            None
         }
+        Some(seconds) => now.checked_add(Duration::from_secs(seconds.unsigned_abs())),
+        None => expires,
+    };
+    if max_age.is_none() && expires_at.is_some_and(|at| at <= now) {
+        delete = true;
     }
 
     Some(ParsedSetCookie {
@@ -1113,9 +2593,351 @@ fn parse_set_cookie_header(input: &str, default_domain: &str) -> Option<ParsedSe
         name: name.to_owned(),
         value: value.trim().to_owned(),
         delete,
+        expires_at,
+        path,
+        secure,
+        http_only,
+        same_site,
     })
 }
 
+impl CookieReport {
+    pub(super) fn status_label(&self) -> String {
+        match self.status {
+            CookieStatus::Stored => "stored".to_owned(),
+            CookieStatus::Session => "stored for this session".to_owned(),
+            CookieStatus::Deleted => "deleted".to_owned(),
+            CookieStatus::Rejected(reason) => format!("rejected: {reason}"),
+        }
+    }
+
+    /// Attributes in `Set-Cookie` order, e.g. `Domain=example.com; Path=/; Secure`.
+    pub(super) fn attributes_summary(&self, now: SystemTime) -> String {
+        let mut parts = vec![format!("Domain={}", self.domain)];
+        if let Some(path) = &self.path {
+            parts.push(format!("Path={path}"));
+        }
+        match self.expires_at.map(|at| at.duration_since(now)) {
+            Some(Ok(remaining)) => parts.push(format!("expires in {}s", remaining.as_secs())),
+            Some(Err(_)) => parts.push("expired".to_owned()),
+            None => parts.push("session".to_owned()),
+        }
+        if self.secure {
+            parts.push("Secure".to_owned());
+        }
+        if self.http_only {
+            parts.push("HttpOnly".to_owned());
+        }
+        if let Some(same_site) = &self.same_site {
+            parts.push(format!("SameSite={same_site}"));
+        }
+        parts.join("; ")
+    }
+}
+
+/// Why the privacy policy keeps a `Set-Cookie` from `request_url` out of the jar, if it does.
+///
+/// `Path`, `Secure`, `HttpOnly` and `SameSite` are reported but not stored or enforced.
+fn set_cookie_rejection(
+    cookie: &ParsedSetCookie,
+    request_url: &Url,
+    privacy: &PrivacyPolicy,
+) -> Option<&'static str> {
+    let host = request_url.host_str().and_then(normalize_cookie_domain)?;
+    if privacy.block_third_party_cookies
+        && registrable_domain(&host) != registrable_domain(&cookie.domain)
+    {
+        return Some("third-party cookie blocked by privacy policy");
+    }
+    None
+}
+
+/// Decomposes the response's `Set-Cookie` headers with what the cookie jar did with each.
+fn response_cookie_reports(
+    request_url: &str,
+    response_headers: &[(String, String)],
+    privacy: &PrivacyPolicy,
+) -> Vec<CookieReport> {
+    let session_only = privacy.session_cookies_only;
+    let Ok(parsed_url) = Url::parse(request_url) else {
+        return Vec::new();
+    };
+    let Some(default_domain) = parsed_url.host_str().and_then(normalize_cookie_domain) else {
+        return Vec::new();
+    };
+
+    response_headers
+        .iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case("set-cookie"))
+        .filter_map(|(_, value)| parse_set_cookie_header(value, &default_domain))
+        .map(|cookie| {
+            let status = match set_cookie_rejection(&cookie, &parsed_url, privacy) {
+                Some(reason) => CookieStatus::Rejected(reason),
+                None if cookie.delete => CookieStatus::Deleted,
+                None if session_only || cookie.expires_at.is_none() => CookieStatus::Session,
+                None => CookieStatus::Stored,
+            };
+            CookieReport {
+                name: cookie.name,
+                value: cookie.value,
+                domain: cookie.domain,
+                path: cookie.path,
+                expires_at: cookie.expires_at.filter(|_| !session_only),
+                secure: cookie.secure,
+                http_only: cookie.http_only,
+                same_site: cookie.same_site,
+                status,
+            }
+        })
+        .collect()
+}
+
+/// Parses the HTTP date forms servers send (IMF-fixdate and RFC 850 variants).
+fn parse_http_date(input: &str) -> Option<SystemTime> {
+    let mut day = None;
+    let mut month = None;
+    let mut year = None;
+    let mut time = None;
+
+    for token in input
+        .split(|ch: char| ch.is_whitespace() || ch == '-' || ch == ',')
+        .filter(|token| !token.is_empty())
+    {
+        if time.is_none() && token.contains(':') {
+            let mut parts = token.split(':').map(|part| part.parse::<u64>().ok());
+            let (Some(Some(hour)), Some(Some(minute)), Some(Some(second))) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                return None;
+            };
+            time = Some((hour, minute, second));
+        } else if let Ok(number) = token.parse::<i64>() {
+            if day.is_none() && token.len() <= 2 && month.is_none() {
+                day = Some(number);
+            } else if year.is_none() {
+                year = Some(match number {
+                    0..=69 => number + 2000,
+                    70..=99 => number + 1900,
+                    _ => number,
+                });
+            }
+        } else if month.is_none() {
+            let lower = token.to_ascii_lowercase();
+            month = [
+                "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+            ]
+            .iter()
+            .position(|name| lower.starts_with(name))
+            .map(|index| index as i64 + 1);
+        }
+    }
+
+    let (day, month, year, (hour, minute, second)) = (day?, month?, year?, time?);
+    if !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 || year < 1970 {
+        return None;
+    }
+
+    // Days since the Unix epoch for a proleptic Gregorian date.
+    let shifted_year = if month <= 2 { year - 1 } else { year };
+    let era = shifted_year.div_euclid(400);
+    let year_of_era = shifted_year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = u64::try_from(era * 146_097 + day_of_era - 719_468).ok()?;
+
+    UNIX_EPOCH.checked_add(Duration::from_secs(
+        days * 86_400 + hour * 3_600 + minute * 60 + second,
+    ))
+}
+
+fn cookie_is_live(cookie: &StoredCookie, now: SystemTime) -> bool {
+    cookie.expires_at.is_none_or(|at| at > now)
+}
+
+/// Writes persistent cookies into the storage partition; session cookies stay in memory.
+pub(super) fn save_cookies(
+    cache: &Arc<Mutex<HttpCache>>,
+    manager: &StorageManager,
+) -> Result<usize, String> {
+    if manager.config.ephemeral_mode {
+        return Ok(0);
+    }
+
+    let now = SystemTime::now();
+    let mut records = HashMap::<String, String>::new();
+    let mut saved = 0;
+    {
+        let guard = match cache.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        for (domain, cookies) in &guard.cookies {
+            let mut lines = Vec::new();
+            for (name, cookie) in cookies {
+                let Some(expires_at) = cookie.expires_at.filter(|at| *at > now) else {
+                    continue;
+                };
+                let expires_secs = expires_at
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |elapsed| elapsed.as_secs());
+                lines.push(format!("{expires_secs}\t{name}\t{}", cookie.value));
+            }
+            if !lines.is_empty() {
+                lines.sort();
+                saved += lines.len();
+                records.insert(domain.clone(), lines.join("\n"));
+            }
+        }
+    }
+
+    let stored_domains = manager
+        .partition_keys(COOKIE_STORAGE_PARTITION)
+        .map_err(|error| error.to_string())?;
+    for domain in stored_domains {
+        if !records.contains_key(&domain) {
+            manager
+                .remove_partition_value(COOKIE_STORAGE_PARTITION, &domain)
+                .map_err(|error| error.to_string())?;
+        }
+    }
+    for (domain, record) in records {
+        manager
+            .set_partition_value(COOKIE_STORAGE_PARTITION, &domain, &record)
+            .map_err(|error| error.to_string())?;
+    }
+    Ok(saved)
+}
+
+/// Restores persisted cookies into the jar, purging any that expired while stored.
+pub(super) fn load_cookies(
+    cache: &Arc<Mutex<HttpCache>>,
+    manager: &StorageManager,
+) -> Result<usize, String> {
+    if manager.config.ephemeral_mode {
+        return Ok(0);
+    }
+
+    let now = SystemTime::now();
+    let stored_domains = manager
+        .partition_keys(COOKIE_STORAGE_PARTITION)
+        .map_err(|error| error.to_string())?;
+    let mut guard = match cache.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+
+    let mut loaded = 0;
+    for domain in stored_domains {
+        let record = manager
+            .get_partition_value(COOKIE_STORAGE_PARTITION, &domain)
+            .map_err(|error| error.to_string())?
+            .unwrap_or_default();
+        let mut kept = Vec::new();
+        for line in record.lines() {
+            let mut fields = line.splitn(3, '\t');
+            let (Some(expires_secs), Some(name), Some(value)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            let Some(expires_at) = expires_secs
+                .parse::<u64>()
+                .ok()
+                .and_then(|secs| UNIX_EPOCH.checked_add(Duration::from_secs(secs)))
+                .filter(|at| *at > now)
+            else {
+                continue;
+            };
+            upsert_cookie(&mut guard, &domain, name, value, Some(expires_at), None);
+            kept.push(line);
+            loaded += 1;
+        }
+
+        let stored_lines = record.lines().count();
+        if kept.is_empty() {
+            manager
+                .remove_partition_value(COOKIE_STORAGE_PARTITION, &domain)
+                .map_err(|error| error.to_string())?;
+        } else if kept.len() < stored_lines {
+            manager
+                .set_partition_value(COOKIE_STORAGE_PARTITION, &domain, &kept.join("\n"))
+                .map_err(|error| error.to_string())?;
+        }
+    }
+    Ok(loaded)
+}
+
+/// Drops history entries visited within `range`.
+pub(super) fn clear_history_range(history: &mut Vec<HistoryEntry>, range: ClearRange) {
+    match range
+        .window()
+        .and_then(|window| SystemTime::now().checked_sub(window))
+    {
+        Some(cutoff) => history.retain(|entry| entry.visited_at < cutoff),
+        None => history.clear(),
+    }
+}
+
+/// Clears the selected cookie, cache, and site-storage data; history lives in the UI.
+///
+/// Cookies restored from disk have no set time, so ranged clears keep them. Clearing cookies
+/// also forgets Basic credentials.
+pub(super) fn clear_stored_browsing_data(
+    cache: &Arc<Mutex<HttpCache>>,
+    storage: Option<&StorageManager>,
+    options: ClearOptions,
+) -> Result<(), String> {
+    let window = options.range.window();
+    {
+        let mut guard = match cache.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if options.cache {
+            // A window reaching past the clock's origin covers every entry.
+            match window.and_then(|window| Instant::now().checked_sub(window)) {
+                Some(cutoff) => guard.responses.clear_stored_since(cutoff),
+                None => guard.responses.clear(),
+            }
+        }
+        if options.cookies {
+            match window.and_then(|window| SystemTime::now().checked_sub(window)) {
+                Some(cutoff) => {
+                    for cookies in guard.cookies.values_mut() {
+                        cookies.retain(|_, cookie| cookie.set_at.is_none_or(|at| at < cutoff));
+                    }
+                    guard.cookies.retain(|_, cookies| !cookies.is_empty());
+                }
+                None => guard.cookies.clear(),
+            }
+            guard.credentials.clear();
+        }
+    }
+
+    let Some(storage) = storage
+        .filter(|storage| !storage.config.ephemeral_mode && storage.persistent_root().is_some())
+    else {
+        return Ok(());
+    };
+    if options.cookies {
+        save_cookies(cache, storage)?;
+    }
+    if options.storage {
+        let cookie_partition = storage.partition_id(COOKIE_STORAGE_PARTITION);
+        for partition in storage
+            .list_partitions()
+            .map_err(|error| error.to_string())?
+        {
+            if partition != cookie_partition {
+                storage
+                    .clear_partition(&partition)
+                    .map_err(|error| error.to_string())?;
+            }
+        }
+    }
+    Ok(())
+}
+
 fn normalize_cookie_domain(input: &str) -> Option<String> {
     let normalized = input.trim().trim_start_matches('.').to_ascii_lowercase();
     if normalized.is_empty() || normalized.chars().any(char::is_whitespace) {
@@ -1132,7 +2954,14 @@ fn cookie_domain_matches(host: &str, domain: &str) -> bool {
             && host.as_bytes()[host.len() - domain.len() - 1] == b'.')
 }
 
-fn upsert_cookie(cache: &mut HttpCache, domain: &str, name: &str, value: &str) {
+fn upsert_cookie(
+    cache: &mut HttpCache,
+    domain: &str,
+    name: &str,
+    value: &str,
+    expires_at: Option<SystemTime>,
+    set_at: Option<SystemTime>,
+) {
     if !cache.cookies.contains_key(domain)
         && cache.cookies.len() >= MAX_COOKIE_DOMAINS
         && let Some(evicted) = cache.cookies.keys().next().cloned()
@@ -1148,120 +2977,152 @@ fn upsert_cookie(cache: &mut HttpCache, domain: &str, name: &str, value: &str) {
         cookies.remove(&evicted);
     }
 
-    cookies.insert(name.to_owned(), value.to_owned());
+    cookies.insert(
+        name.to_owned(),
+        StoredCookie {
+            value: value.to_owned(),
+            expires_at,
+            set_at,
+        },
+    );
 }
 
-fn decode_text_response(body: &[u8], content_type: &str) -> String {
-    let charset = detect_response_charset(body, content_type);
-    if let Some(label) = charset {
-        if let Some(encoding) = Encoding::for_label(label.as_bytes()) {
-            let (decoded, _, _) = encoding.decode(body);
-            return decoded.into_owned();
-        }
+fn truncate_preview_text(input: &str, max_bytes: usize) -> String {
+    if input.len() <= max_bytes {
+        return input.to_owned();
     }
 
-    String::from_utf8_lossy(body).to_string()
-}
-
-fn detect_response_charset(body: &[u8], content_type: &str) -> Option<String> {
-    let is_html = content_type.to_ascii_lowercase().contains("text/html")
-        || content_type
-            .to_ascii_lowercase()
-            .contains("application/xhtml+xml");
-
-    if is_html {
-        if let Some(meta_charset) = parse_charset_from_html_prefix(body) {
-            return Some(meta_charset);
-        }
+    let mut end = max_bytes.min(input.len());
+    while end > 0 && !input.is_char_boundary(end) {
+        end = end.saturating_sub(1);
     }
-
-    parse_charset_from_content_type(content_type)
+    input[..end].to_owned()
 }
 
-fn parse_charset_from_content_type(content_type: &str) -> Option<String> {
-    for part in content_type.split(';').skip(1) {
-        let Some((name, value)) = part.split_once('=') else {
-            continue;
-        };
-        if !name.trim().eq_ignore_ascii_case("charset") {
-            continue;
-        }
-
-        let label = value.trim().trim_matches('"').trim_matches('\'');
-        if !label.is_empty() {
-            return Some(label.to_owned());
-        }
-    }
-
-    None
+fn is_json_content_type(content_type: &str) -> bool {
+    let mime = content_mime(content_type);
+    mime == "application/json" || mime == "text/json" || mime.ends_with("+json")
 }
 
-fn parse_charset_from_html_prefix(body: &[u8]) -> Option<String> {
-    let prefix_len = body.len().min(8192);
-    let prefix = String::from_utf8_lossy(&body[..prefix_len]);
-    let lower = prefix.to_ascii_lowercase();
-    let mut search_start = 0_usize;
+/// Re-serializes a JSON body with two-space indentation, or `None` if it does not parse.
+pub(super) fn pretty_print_json(body: &str) -> Option<String> {
+    let value = serde_json::from_str::<serde_json::Value>(body).ok()?;
+    serde_json::to_string_pretty(&value).ok()
+}
 
-    while let Some(relative) = lower[search_start..].find("charset=") {
-        let charset_start = search_start + relative + "charset=".len();
-        let remainder = &prefix[charset_start..];
-        if let Some(label) = parse_charset_label(remainder) {
-            return Some(label);
-        }
-        search_start = charset_start;
-    }
+/// Writes the page as one HTML file with fetched stylesheets and decoded images inlined.
+pub(super) fn save_page_offline(page: &PageView, directory: &Path) -> Result<PathBuf, String> {
+    let document = page
+        .html_document
+        .as_ref()
+        .ok_or_else(|| "only HTML pages can be saved".to_owned())?;
+    let image_data_urls = page
+        .decoded_images
+        .iter()
+        .filter_map(|image| Some((image.url.clone(), png_data_url(image)?)))
+        .collect::<HashMap<_, _>>();
+    let html = document.to_offline_html(&page.final_url, &image_data_urls);
+
+    std::fs::create_dir_all(directory).map_err(|error| {
+        format!(
+            "failed to create `{}` for saved pages: {error}",
+            directory.display()
+        )
+    })?;
+    let host = Url::parse(&page.final_url)
+        .ok()
+        .and_then(|url| url.host_str().map(ToOwned::to_owned))
+        .unwrap_or_else(|| "page".to_owned());
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since_epoch| since_epoch.as_secs());
+    let file_name = format!("{host}-{stamp}.html")
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || matches!(ch, '.' | '-') {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    let path = directory.join(file_name);
+    std::fs::write(&path, html)
+        .map_err(|error| format!("failed to write `{}`: {error}", path.display()))?;
+    Ok(path)
+}
 
-    None
+fn png_data_url(image: &DecodedImageAsset) -> Option<String> {
+    let width = u32::try_from(image.width).ok()?;
+    let height = u32::try_from(image.height).ok()?;
+    let buffer = image::RgbaImage::from_raw(width, height, image.rgba.clone())?;
+    let mut png = Vec::new();
+    buffer
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .ok()?;
+    Some(format!("data:image/png;base64,{}", base64_encode(&png)))
 }
 
-fn parse_charset_label(input: &str) -> Option<String> {
-    let trimmed = input.trim_start();
-    if trimmed.is_empty() {
-        return None;
+impl ImageDecodeLimits {
+    fn allows(&self, width: u32, height: u32) -> bool {
+        let pixels = usize::try_from(width)
+            .ok()
+            .zip(usize::try_from(height).ok())
+            .and_then(|(width, height)| width.checked_mul(height));
+        width <= self.max_width
+            && height <= self.max_height
+            && pixels.is_some_and(|pixels| pixels > 0 && pixels <= self.max_pixels)
     }
+}
 
-    let mut chars = trimmed.chars();
-    let first = chars.next()?;
-
-    if first == '"' || first == '\'' {
-        let rest = &trimmed[first.len_utf8()..];
-        let end = rest.find(first)?;
-        let label = rest[..end].trim();
-        return if label.is_empty() {
-            None
-        } else {
-            Some(label.to_owned())
-        };
+impl ImageMemoryBudget {
+    fn is_exhausted(&self) -> bool {
+        self.used_bytes >= self.max_bytes
     }
 
-    let end = trimmed
-        .find(|ch: char| ch.is_whitespace() || matches!(ch, '"' | '\'' | ';' | '>' | '/'))
-        .unwrap_or(trimmed.len());
-    let label = trimmed[..end].trim();
-    if label.is_empty() {
-        None
-    } else {
-        Some(label.to_owned())
+    fn record(&mut self, image: &DecodedImageAsset) {
+        self.used_bytes = self.used_bytes.saturating_add(image.rgba.len());
     }
 }
 
-fn truncate_preview_text(input: &str, max_bytes: usize) -> String {
-    if input.len() <= max_bytes {
-        return input.to_owned();
-    }
-
-    let mut end = max_bytes.min(input.len());
-    while end > 0 && !input.is_char_boundary(end) {
-        end = end.saturating_sub(1);
+/// Decodes `image` and charges it to `budget`, or skips it once the budget is spent.
+///
+/// The lock is held across the decode so concurrent lazy loads cannot overshoot the budget.
+pub(super) fn decode_budgeted_image(
+    budget: &Mutex<ImageMemoryBudget>,
+    image: &FetchedResponse,
+) -> BudgetedImage {
+    let mut budget = match budget.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if budget.is_exhausted() {
+        return BudgetedImage::OverBudget;
+    }
+    match decode_image_asset(
+        &image.final_url,
+        &image.content_type,
+        &image.body,
+        ImageDecodeLimits::default(),
+    ) {
+        Some(decoded) => {
+            budget.record(&decoded);
+            BudgetedImage::Decoded(decoded)
+        }
+        None => BudgetedImage::Undecodable,
     }
-    input[..end].to_owned()
 }
 
-fn decode_image_asset(url: &str, content_type: &str, body: &[u8]) -> Option<DecodedImageAsset> {
+fn decode_image_asset(
+    url: &str,
+    content_type: &str,
+    body: &[u8],
+    limits: ImageDecodeLimits,
+) -> Option<DecodedImageAsset> {
     let content_type = content_type.to_ascii_lowercase();
     let lower_url = url.to_ascii_lowercase();
     if is_svg_image_candidate(&content_type, &lower_url, body) {
-        if let Some((width, height, rgba)) = decode_svg_image(body) {
+        if let Some((width, height, rgba)) = decode_svg_image(body, limits) {
             return Some(DecodedImageAsset {
                 url: url.to_owned(),
                 width,
@@ -1293,20 +3154,28 @@ fn decode_image_asset(url: &str, content_type: &str, body: &[u8]) -> Option<Deco
         return None;
     }
 
-    let decode_with_limits = |format: Option<image::ImageFormat>| -> Option<image::DynamicImage> {
+    let open_reader = |format: Option<image::ImageFormat>| {
         let mut reader = image::ImageReader::new(std::io::Cursor::new(body));
         if let Some(format) = format {
             reader.set_format(format);
+            Some(reader)
         } else {
-            reader = reader.with_guessed_format().ok()?;
+            reader.with_guessed_format().ok()
+        }
+    };
+    let decode_with_limits = |format: Option<image::ImageFormat>| -> Option<image::DynamicImage> {
+        // Reject oversized shapes from the header alone, before any pixel buffer is allocated.
+        let (width, height) = open_reader(format)?.into_dimensions().ok()?;
+        if !limits.allows(width, height) {
+            return None;
         }
 
-        // Prevent pathological image payloads from requesting huge allocations.
-        let mut limits = image::Limits::default();
-        limits.max_image_width = Some(8_192);
-        limits.max_image_height = Some(8_192);
-        limits.max_alloc = Some((MAX_IMAGE_PIXELS.saturating_mul(4)) as u64);
-        reader.limits(limits);
+        let mut reader = open_reader(format)?;
+        let mut decoder_limits = image::Limits::default();
+        decoder_limits.max_image_width = Some(limits.max_width);
+        decoder_limits.max_image_height = Some(limits.max_height);
+        decoder_limits.max_alloc = Some((limits.max_pixels.saturating_mul(4)) as u64);
+        reader.limits(decoder_limits);
         reader.decode().ok()
     };
 
@@ -1316,12 +3185,11 @@ fn decode_image_asset(url: &str, content_type: &str, body: &[u8]) -> Option<Deco
         decode_with_limits(None)?
     };
     let (width, height) = decoded.dimensions();
-    let width_usize = usize::try_from(width).ok()?;
-    let height_usize = usize::try_from(height).ok()?;
-    let pixels = width_usize.checked_mul(height_usize)?;
-    if pixels == 0 || pixels > MAX_IMAGE_PIXELS {
+    if !limits.allows(width, height) {
         return None;
     }
+    let width_usize = usize::try_from(width).ok()?;
+    let height_usize = usize::try_from(height).ok()?;
 
     let rgba = decoded.to_rgba8().into_raw();
 
@@ -1381,18 +3249,17 @@ fn looks_like_svg_document(body: &[u8]) -> bool {
     false
 }
 
-fn decode_svg_image(body: &[u8]) -> Option<(usize, usize, Vec<u8>)> {
+fn decode_svg_image(body: &[u8], limits: ImageDecodeLimits) -> Option<(usize, usize, Vec<u8>)> {
     let options = resvg::usvg::Options::default();
     let tree = resvg::usvg::Tree::from_data(body, &options).ok()?;
     let size = tree.size().to_int_size();
     let width_u32 = size.width();
     let height_u32 = size.height();
-    let width = usize::try_from(width_u32).ok()?;
-    let height = usize::try_from(height_u32).ok()?;
-    let pixels = width.checked_mul(height)?;
-    if pixels == 0 || pixels > MAX_IMAGE_PIXELS {
+    if !limits.allows(width_u32, height_u32) {
         return None;
     }
+    let width = usize::try_from(width_u32).ok()?;
+    let height = usize::try_from(height_u32).ok()?;
 
     let mut pixmap = resvg::tiny_skia::Pixmap::new(width_u32, height_u32)?;
     let mut pixmap_mut = pixmap.as_mut();
@@ -1405,12 +3272,151 @@ fn decode_svg_image(body: &[u8]) -> Option<(usize, usize, Vec<u8>)> {
     Some((width, height, rgba))
 }
 
+/// Lets embedders and test harnesses observe, veto or reroute top-level navigations.
+pub(super) trait NavigationInterceptor: Send + Sync {
+    fn before_navigate(&self, _url: &str) -> NavigationDecision {
+        NavigationDecision::Allow
+    }
+}
+
+/// Interceptor installed by default; every navigation proceeds.
+pub(super) struct AllowAllNavigations;
+
+impl NavigationInterceptor for AllowAllNavigations {}
+
+/// Interceptor built from the `--block-url` and `--redirect-url` startup flags. Each rule
+/// matches a URL prefix, and the first matching rule decides.
+#[derive(Debug, Default)]
+pub(super) struct UrlRuleInterceptor {
+    rules: Vec<(String, NavigationDecision)>,
+}
+
+impl UrlRuleInterceptor {
+    /// Reads `--block-url <prefix>` and `--redirect-url <prefix>=<target>` from `args`,
+    /// ignoring other arguments. Returns `None` when no rule was given.
+    pub(super) fn from_args(
+        args: impl IntoIterator<Item = String>,
+    ) -> Result<Option<Self>, String> {
+        let mut rules = Vec::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg != "--block-url" && arg != "--redirect-url" {
+                continue;
+            }
+
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing URL rule after {arg}"))?;
+            if arg == "--block-url" {
+                rules.push((value, NavigationDecision::Block));
+                continue;
+            }
+            let (prefix, target) = value
+                .split_once('=')
+                .filter(|(prefix, target)| !prefix.is_empty() && !target.is_empty())
+                .ok_or_else(|| {
+                    format!("invalid --redirect-url rule `{value}` (expected: <prefix>=<target>)")
+                })?;
+            rules.push((
+                prefix.to_owned(),
+                NavigationDecision::Redirect(target.to_owned()),
+            ));
+        }
+
+        Ok((!rules.is_empty()).then_some(Self { rules }))
+    }
+}
+
+impl NavigationInterceptor for UrlRuleInterceptor {
+    fn before_navigate(&self, url: &str) -> NavigationDecision {
+        self.rules
+            .iter()
+            .find(|(prefix, _)| url.starts_with(prefix.as_str()))
+            .map_or(NavigationDecision::Allow, |(_, decision)| decision.clone())
+    }
+}
+
+/// Returns the URL to fetch after consulting `interceptor`, or `None` when it is blocked.
+pub(super) fn intercept_navigation(
+    interceptor: &dyn NavigationInterceptor,
+    url: String,
+) -> Option<String> {
+    match interceptor.before_navigate(&url) {
+        NavigationDecision::Allow => Some(url),
+        NavigationDecision::Block => None,
+        NavigationDecision::Redirect(target) => Some(normalize_input_url(target)),
+    }
+}
+
+impl FetchTarget<'_> {
+    fn max_redirects(self) -> usize {
+        match self {
+            Self::Document(_) => MAX_REDIRECTS,
+            Self::Subresource => MAX_SUBRESOURCE_REDIRECTS,
+        }
+    }
+
+    /// The URL to request for a redirect to `url`, or an error when the hop is refused.
+    fn admit_redirect(self, url: String) -> Result<String, NavigationError> {
+        match self {
+            Self::Document(guards) => guards.admit(url),
+            Self::Subresource => Ok(url),
+        }
+    }
+}
+
+impl DocumentGuards<'_> {
+    fn new(options: &NavigationOptions) -> DocumentGuards<'_> {
+        DocumentGuards {
+            interceptor: options.interceptor.as_ref(),
+            reputation: &options.reputation,
+            param_cleaner: options.url_param_cleaner.as_deref(),
+        }
+    }
+
+    /// The URL to load in place of `url`, or why the document may not go there.
+    fn admit(self, url: String) -> Result<String, NavigationError> {
+        let url = match self.param_cleaner {
+            Some(cleaner) => cleaner.clean(&url),
+            None => url,
+        };
+        let Some(url) = intercept_navigation(self.interceptor, url.clone()) else {
+            return Err(NavigationError::Failed(format!(
+                "redirect to {url} was blocked"
+            )));
+        };
+        if requires_interstitial(self.reputation, &url, &mut None) {
+            return Err(NavigationError::Blocklisted(url));
+        }
+        Ok(url)
+    }
+}
+
+impl From<String> for NavigationError {
+    fn from(error: String) -> Self {
+        Self::Failed(error)
+    }
+}
+
+impl std::fmt::Display for NavigationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Failed(error) => f.write_str(error),
+            Self::Blocklisted(url) => write!(f, "{url} is on the URL blocklist"),
+        }
+    }
+}
+
 pub(super) fn normalize_input_url(input: String) -> String {
     let trimmed = input.trim();
     if trimmed.is_empty() {
         return DEFAULT_URL.to_owned();
     }
 
+    if is_internal_about_url(trimmed) {
+        return trimmed.to_ascii_lowercase();
+    }
+
     let candidate = if trimmed.contains("://") {
         trimmed.to_owned()
     } else {
@@ -1529,5 +3535,92 @@ fn resolve_redirect_url(base_url: &str, location: &str) -> Result<String, String
     }
 }
 
+/// A named counter read from one of a page view's stats structs.
+#[cfg(test)]
+type StatCounter<T> = (&'static str, fn(&T) -> usize);
+
+#[cfg(test)]
+impl PageView {
+    /// Lists differences in title, status, subresource and script stats, and draw calls.
+    fn diff(&self, other: &PageView) -> Vec<PageDiff> {
+        use PageDiffCategory::{Renderer, Scripts, Status, Subresources, Title};
+
+        let subresource_counts: [StatCounter<SubresourceStats>; 6] = [
+            ("stylesheets_loaded", |stats| stats.stylesheets_loaded),
+            ("inline_style_tags", |stats| stats.inline_style_tags),
+            ("css_rules_total", |stats| stats.css_rules_total),
+            ("scripts_loaded", |stats| stats.scripts_loaded),
+            ("images_loaded", |stats| stats.images_loaded),
+            ("blocked", |stats| stats.blocked),
+        ];
+        let script_counts: [StatCounter<JsExecutionStats>; 9] = [
+            ("scripts_seen", |stats| stats.scripts_seen),
+            ("scripts_executed", |stats| stats.scripts_executed),
+            ("scripts_failed", |stats| stats.scripts_failed),
+            ("syntax_errors", |stats| stats.syntax_errors),
+            ("runtime_errors", |stats| stats.runtime_errors),
+            ("resource_limit_errors", |stats| stats.resource_limit_errors),
+            ("scripts_skipped", |stats| stats.scripts_skipped),
+            ("event_dispatches", |stats| stats.event_dispatches),
+            ("event_failures", |stats| stats.event_failures),
+        ];
+
+        let mut out = Vec::new();
+        push_page_diff(&mut out, Title, "title", &self.title, &other.title);
+        let (before, after) = (self.status_code, other.status_code);
+        push_page_diff(&mut out, Status, "status_code", &before, &after);
+        let (before, after) = (&self.status_text, &other.status_text);
+        push_page_diff(&mut out, Status, "status_text", before, after);
+        let (before, after) = (&self.subresource_stats, &other.subresource_stats);
+        for (field, count) in subresource_counts {
+            push_page_diff(&mut out, Subresources, field, &count(before), &count(after));
+        }
+        let (before, after) = (&self.js_execution, &other.js_execution);
+        push_page_diff(
+            &mut out,
+            Scripts,
+            "enabled",
+            &before.enabled,
+            &after.enabled,
+        );
+        for (field, count) in script_counts {
+            push_page_diff(&mut out, Scripts, field, &count(before), &count(after));
+        }
+        push_page_diff(&mut out, Scripts, "errors", &before.errors, &after.errors);
+        let (before, after) = (self.renderer_draw_calls, other.renderer_draw_calls);
+        push_page_diff(&mut out, Renderer, "draw_calls", &before, &after);
+        out
+    }
+}
+
+#[cfg(test)]
+fn push_page_diff<T: PartialEq + std::fmt::Debug>(
+    out: &mut Vec<PageDiff>,
+    category: PageDiffCategory,
+    field: &'static str,
+    before: &T,
+    after: &T,
+) {
+    if before != after {
+        out.push(PageDiff {
+            category,
+            field,
+            before: format!("{before:?}"),
+            after: format!("{after:?}"),
+        });
+    }
+}
+
+#[cfg(test)]
+impl std::fmt::Display for PageDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} {}: {} -> {}",
+            self.category, self.field, self.before, self.after
+        )
+    }
+}
+
 #[cfg(test)]
 include!("tests.rs");
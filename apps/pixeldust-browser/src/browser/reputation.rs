@@ -0,0 +1,118 @@
+use super::*;
+use sha2::Digest;
+use sha2::Sha256;
+use std::collections::HashSet;
+
+/// Safe-browsing style blocklist of 4-byte SHA-256 prefixes of URL lookup expressions.
+///
+/// A prefix hit is treated as a match; there is no full-hash confirmation round trip.
+#[derive(Debug, Clone, Default)]
+pub(super) struct UrlReputation {
+    prefixes: HashSet<[u8; 4]>,
+}
+
+impl UrlReputation {
+    /// Parses one hex-encoded hash prefix per line; blank lines and `#` comments are skipped.
+    pub(super) fn from_prefix_list(text: &str) -> Self {
+        let prefixes = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(parse_hex_prefix)
+            .collect();
+        Self { prefixes }
+    }
+
+    /// Reads [`URL_BLOCKLIST_FILE`] from the profile; a missing file yields an empty list.
+    pub(super) fn load(storage: Option<&StorageManager>) -> Result<Self, String> {
+        let Some(root) = storage.and_then(StorageManager::persistent_root) else {
+            return Ok(Self::default());
+        };
+        match std::fs::read_to_string(root.join(URL_BLOCKLIST_FILE)) {
+            Ok(text) => Ok(Self::from_prefix_list(&text)),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error.to_string()),
+        }
+    }
+
+    pub(super) fn is_listed(&self, url: &str) -> bool {
+        !self.prefixes.is_empty()
+            && lookup_expressions(url)
+                .iter()
+                .any(|expression| self.prefixes.contains(&hash_prefix(expression)))
+    }
+}
+
+/// Whether navigating to `url` must stop at the warning interstitial.
+///
+/// A matching `proceed_once` grant is consumed so the bypass covers a single navigation.
+pub(super) fn requires_interstitial(
+    reputation: &UrlReputation,
+    url: &str,
+    proceed_once: &mut Option<String>,
+) -> bool {
+    if !reputation.is_listed(url) {
+        return false;
+    }
+    if proceed_once.as_deref() == Some(url) {
+        *proceed_once = None;
+        return false;
+    }
+    true
+}
+
+pub(super) fn hash_prefix(expression: &str) -> [u8; 4] {
+    let digest = Sha256::digest(expression.as_bytes());
+    [digest[0], digest[1], digest[2], digest[3]]
+}
+
+fn parse_hex_prefix(line: &str) -> Option<[u8; 4]> {
+    let hex = line.get(..8)?;
+    let mut prefix = [0; 4];
+    for (index, byte) in prefix.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(hex.get(index * 2..index * 2 + 2)?, 16).ok()?;
+    }
+    Some(prefix)
+}
+
+/// Host-suffix and path-prefix combinations checked for `url`, most specific first.
+fn lookup_expressions(url: &str) -> Vec<String> {
+    let Ok(parsed) = Url::parse(url) else {
+        return Vec::new();
+    };
+    let Some(host) = parsed.host_str() else {
+        return Vec::new();
+    };
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+
+    let mut hosts = vec![host.clone()];
+    if matches!(parsed.host(), Some(url::Host::Domain(_))) {
+        let labels = host.split('.').collect::<Vec<_>>();
+        let first = labels.len().saturating_sub(5).max(1);
+        for start in first..labels.len().saturating_sub(1) {
+            hosts.push(labels[start..].join("."));
+        }
+    }
+
+    let path = parsed.path();
+    let mut paths = Vec::new();
+    if let Some(query) = parsed.query() {
+        paths.push(format!("{path}?{query}"));
+    }
+    paths.push(path.to_owned());
+    paths.push("/".to_owned());
+    for (index, _) in path.match_indices('/').skip(1).take(3) {
+        paths.push(path[..=index].to_owned());
+    }
+
+    let mut expressions = Vec::with_capacity(hosts.len() * paths.len());
+    for host in &hosts {
+        for path in &paths {
+            let expression = format!("{host}{path}");
+            if !expressions.contains(&expression) {
+                expressions.push(expression);
+            }
+        }
+    }
+    expressions
+}
@@ -1,3 +1,5 @@
+use super::WORKER_STDERR_TAIL_LINES;
+
 pub(super) fn bootstrap_runtime() -> (Option<pd_browser::BrowserRuntime>, Option<String>) {
     let browser = match pd_browser::Browser::new() {
         Ok(browser) => browser,
@@ -14,7 +16,8 @@ pub(super) fn bootstrap_runtime() -> (Option<pd_browser::BrowserRuntime>, Option
         }
     };
 
-    let config = pd_browser::RuntimeLaunchConfig::new(executable);
+    let mut config = pd_browser::RuntimeLaunchConfig::new(executable);
+    config.stderr_tail_lines = WORKER_STDERR_TAIL_LINES;
     match browser.boot_with_runtime(&config) {
         Ok(runtime) => (Some(runtime), None),
         Err(error) => (None, Some(error.to_string())),
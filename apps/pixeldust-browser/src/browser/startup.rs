@@ -19,9 +19,17 @@ pub(crate) fn run() -> Result<(), eframe::Error> {
         }
     }
 
+    let interceptor = match UrlRuleInterceptor::from_args(std::env::args().skip(1)) {
+        Ok(interceptor) => interceptor,
+        Err(error) => {
+            eprintln!("PixelDust startup error: {error}");
+            return Ok(());
+        }
+    };
+
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
-            .with_title("PixelDust Browser")
+            .with_title(BROWSER_WINDOW_TITLE)
             .with_inner_size([1320.0, 840.0])
             .with_min_inner_size([960.0, 640.0]),
         ..Default::default()
@@ -30,9 +38,13 @@ pub(crate) fn run() -> Result<(), eframe::Error> {
     eframe::run_native(
         "PixelDust Browser",
         native_options,
-        Box::new(|cc| {
+        Box::new(move |cc| {
             install_platform_fonts(&cc.egui_ctx);
-            Ok(Box::new(BrowserUiApp::default()))
+            let mut app = BrowserUiApp::default();
+            if let Some(interceptor) = interceptor {
+                app.set_navigation_interceptor(Arc::new(interceptor));
+            }
+            Ok(Box::new(app))
         }),
     )
 }
@@ -67,6 +79,7 @@ fn run_worker(role: ProcessRole) {
 }
 
 fn install_platform_fonts(ctx: &egui::Context) {
+    #[cfg_attr(not(target_os = "windows"), allow(unused_mut))]
     let mut fonts = egui::FontDefinitions::default();
 
     #[cfg(target_os = "windows")]
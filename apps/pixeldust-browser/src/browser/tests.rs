@@ -1,34 +1,37 @@
 #[cfg(test)]
 mod tests {
+    use super::cache::DiskCache;
+    use super::fetch_pool::{HostRateLimit, HostRateLimiter};
+    use super::reputation::{UrlReputation, hash_prefix, requires_interstitial};
     use super::{
-        allow_page_script_source, allow_subresource_request, cookie_domain_matches,
-        decode_text_response, effective_tls_policy_for_request, format_js_error,
-        format_script_origin, is_local_network_host, is_local_network_url, normalize_input_url,
-        parse_charset_from_content_type, parse_charset_from_html_prefix, parse_set_cookie_header,
-        same_navigation_target, same_origin, truncate_preview_text,
+        AllowAllNavigations, Arc, AtomicBool, BlockReason, BlockedRequest, BudgetedImage,
+        CONTENT_HANDLERS, COOKIE_STORAGE_PARTITION, CacheLookup, CacheMode, CachedResponse,
+        ClearOptions, ClearRange, ColorScheme, ContentKind, CookieStatus, DecodedImageAsset,
+        Duration, FetchPoolConfig, FetchedResponse, HistoryEntry, HttpCache, ImageDecodeLimits,
+        ImageMemoryBudget, Instant, JsRedirectStep, JsRedirectTracker, MAX_JS_ERROR_LOGS,
+        MAX_PAGE_JS_REDIRECTS, MemoryCache, Mutex, NavLog, NavLogLevel, NavigationDecision,
+        NavigationError, NavigationInterceptor, NavigationOptions, NavigationSession,
+        NavigationState, Ordering, PageDiffCategory, PageView, PaintTimings, RenderedDocument,
+        ResponseCache, SecurityInfo, SecurityState, StoredCookie, SystemTime, TlsSessionInfo,
+        UNIX_EPOCH, UrlParamCleaner, UrlRuleInterceptor, UserAgentOverrides,
+        accept_script_response, accept_stylesheet_response, allow_logged_subresource,
+        allow_page_script_source, allow_third_party_script, attach_authorization_header,
+        basic_auth_challenge, basic_authorization_value, blocked_request_counts,
+        build_inline_event_script, clear_history_range, clear_stored_browsing_data,
+        cookie_domain_matches, cookie_header_for_url, decode_budgeted_image, decode_image_asset,
+        dispatch_dom_events, effective_tls_policy_for_request, execute_navigation,
+        fetch_deferred_image, fetch_in_parallel, fetch_requested_images, first_contentful_paint,
+        format_js_error, format_script_origin, heuristic_freshness, intercept_navigation,
+        internal_about_page, is_current_navigation, is_local_network_host, is_local_network_url,
+        load_cookies, load_page_images, lookup_cache, maybe_store_cache_entry, normalize_input_url,
+        parse_basic_realm, parse_http_date, parse_set_cookie_header, pretty_print_json,
+        record_js_error, refresh_tab_title, registrable_domain, response_cookie_reports,
+        same_navigation_target, same_origin, save_cookies, save_page_offline,
+        save_user_agent_override, select_content_handler, stop_navigation, store_basic_credentials,
+        store_response_cookies, successful_subresource, tab_favicon, truncate_preview_text,
     };
     use pd_browser::Browser;
-
-    #[test]
-    fn parses_charset_from_content_type_header() {
-        let content_type = "text/html; charset=ISO-8859-1";
-        let parsed = parse_charset_from_content_type(content_type);
-        assert_eq!(parsed.as_deref(), Some("ISO-8859-1"));
-    }
-
-    #[test]
-    fn prefers_meta_charset_for_html() {
-        let html = "<html><head><meta charset=\"UTF-8\"></head><body>hello</body></html>";
-        let parsed = parse_charset_from_html_prefix(html.as_bytes());
-        assert_eq!(parsed.as_deref(), Some("UTF-8"));
-    }
-
-    #[test]
-    fn decodes_html_using_meta_charset_before_header_charset() {
-        let html = b"<html><head><meta charset=\"UTF-8\"></head><body>\xE2\x82\xAC</body></html>";
-        let decoded = decode_text_response(html, "text/html; charset=ISO-8859-1");
-        assert!(decoded.contains("\u{20AC}"));
-    }
+    use pd_privacy::PrivacyPolicy;
 
     #[test]
     fn truncates_preview_without_breaking_utf8() {
@@ -127,7 +130,7 @@ mod tests {
             "www.google.com",
         );
         assert!(parsed.is_some());
-        let parsed = parsed.expect("cookie should parse");
+        let parsed = parsed.unwrap_or_else(|| panic!("cookie should parse"));
         assert_eq!(parsed.domain, "google.com");
         assert_eq!(parsed.name, "sid");
         assert_eq!(parsed.value, "abc");
@@ -159,8 +162,10 @@ mod tests {
     #[test]
     fn subresource_policy_allows_cross_origin_https_assets() {
         let browser = Browser::new().unwrap_or_else(|_| unreachable!());
-        assert!(allow_subresource_request(
+        assert!(allow_logged_subresource(
             &browser,
+            &mut NavLog::default(),
+            "script",
             "https://www.google.com/",
             "https://www.gstatic.com/myscript.js"
         ));
@@ -169,8 +174,10 @@ mod tests {
     #[test]
     fn subresource_policy_blocks_https_to_http_downgrade() {
         let browser = Browser::new().unwrap_or_else(|_| unreachable!());
-        assert!(!allow_subresource_request(
+        assert!(!allow_logged_subresource(
             &browser,
+            &mut NavLog::default(),
+            "script",
             "https://www.example.com/",
             "http://cdn.example.com/app.js"
         ));
@@ -196,4 +203,2436 @@ mod tests {
         assert!(!message.contains('\n'));
         assert!(!message.contains("?huge=true"));
     }
+
+    fn navigate(
+        url: &str,
+        options: &NavigationOptions,
+        cache: Arc<Mutex<HttpCache>>,
+    ) -> Result<PageView, NavigationError> {
+        execute_navigation(url, options, cache, &AtomicBool::new(false), &|_| {})
+    }
+
+    fn navigate_with_options(
+        url: &str,
+        options: NavigationOptions,
+    ) -> Result<PageView, NavigationError> {
+        navigate(url, &options, Arc::new(Mutex::new(HttpCache::default())))
+    }
+
+    #[test]
+    fn about_blank_is_served_without_network() {
+        let cache = Arc::new(Mutex::new(HttpCache::default()));
+        assert_eq!(
+            normalize_input_url(" About:Blank ".to_owned()),
+            "about:blank"
+        );
+
+        let page = match navigate("about:blank", &NavigationOptions::default(), cache) {
+            Ok(page) => page,
+            Err(error) => panic!("{error}"),
+        };
+        assert_eq!(page.final_url, "about:blank");
+        assert_eq!(page.status_code, 200);
+        assert_eq!(page.body_bytes, 0);
+        assert!(page.headers.is_empty());
+        assert!(page.title.is_none());
+    }
+
+    #[test]
+    fn about_version_reports_package_version() {
+        let cache = Arc::new(Mutex::new(HttpCache::default()));
+        let page = match navigate("about:version", &NavigationOptions::default(), cache) {
+            Ok(page) => page,
+            Err(error) => panic!("{error}"),
+        };
+        assert!(page.body_preview.contains(env!("CARGO_PKG_VERSION")));
+        assert_eq!(page.title.as_deref(), Some("About PixelDust"));
+    }
+
+    #[test]
+    fn about_cache_lists_cached_urls() {
+        let url = "https://example.com/app.css?v=<1>";
+        let mut cache = HttpCache::default();
+        cache.responses.store(
+            url,
+            CachedResponse {
+                response: FetchedResponse {
+                    final_url: url.to_owned(),
+                    status_code: 200,
+                    status_text: "OK".to_owned(),
+                    http_version: "HTTP/1.1".to_owned(),
+                    headers: Vec::new(),
+                    content_type: "text/css".to_owned(),
+                    body: b"body{}".to_vec(),
+                    warnings: Vec::new(),
+                    tls: None,
+                },
+                etag: None,
+                last_modified: None,
+                max_age: Some(Duration::from_secs(60)),
+                stored_at: Instant::now(),
+            },
+        );
+
+        let page = match navigate(
+            "about:cache",
+            &NavigationOptions::default(),
+            Arc::new(Mutex::new(cache)),
+        ) {
+            Ok(page) => page,
+            Err(error) => panic!("{error}"),
+        };
+        assert!(
+            page.body_preview
+                .contains("https://example.com/app.css?v=&lt;1&gt; (fresh, max-age 60s")
+        );
+        assert!(
+            navigate(
+                "about:nonexistent",
+                &NavigationOptions::default(),
+                Arc::new(Mutex::new(HttpCache::default())),
+            )
+            .is_err()
+        );
+    }
+
+    fn cached_entry(url: &str, max_age: Option<Duration>, stored_at: Instant) -> CachedResponse {
+        CachedResponse {
+            response: FetchedResponse {
+                final_url: url.to_owned(),
+                status_code: 200,
+                status_text: "OK".to_owned(),
+                http_version: "HTTP/1.1".to_owned(),
+                headers: Vec::new(),
+                content_type: "text/plain".to_owned(),
+                body: Vec::new(),
+                warnings: Vec::new(),
+                tls: None,
+            },
+            etag: Some("\"v1\"".to_owned()),
+            last_modified: None,
+            max_age,
+            stored_at,
+        }
+    }
+
+    #[test]
+    fn cache_summaries_report_freshness_and_expiry() {
+        let now = Instant::now();
+        let past = now
+            .checked_sub(Duration::from_secs(120))
+            .unwrap_or_else(|| unreachable!());
+        let mut cache = HttpCache::default();
+        cache.responses.store(
+            "https://example.com/b",
+            cached_entry("https://example.com/b", Some(Duration::from_secs(60)), past),
+        );
+        cache.responses.store(
+            "https://example.com/a",
+            cached_entry("https://example.com/a", Some(Duration::from_secs(60)), now),
+        );
+
+        let summaries = cache.responses.entry_summaries();
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].url, "https://example.com/a");
+        assert!(summaries[0].is_fresh);
+        assert!(summaries[0].has_etag);
+        assert_eq!(summaries[0].max_age, Some(Duration::from_secs(60)));
+        assert_eq!(summaries[1].url, "https://example.com/b");
+        assert!(!summaries[1].is_fresh);
+        assert_eq!(summaries[1].stored_at, past);
+
+        cache.responses.clear();
+        assert!(cache.responses.entry_summaries().is_empty());
+        assert!(matches!(
+            cache.responses.lookup("https://example.com/a"),
+            CacheLookup::Miss
+        ));
+    }
+
+    fn exercise_response_cache(cache: &mut dyn ResponseCache) {
+        let fresh_url = "https://example.com/fresh.css";
+        let stale_url = "https://example.com/stale.css";
+        let past = Instant::now()
+            .checked_sub(Duration::from_secs(120))
+            .unwrap_or_else(|| unreachable!());
+        cache.store(
+            fresh_url,
+            cached_entry(fresh_url, Some(Duration::from_secs(60)), Instant::now()),
+        );
+        cache.store(
+            stale_url,
+            cached_entry(stale_url, Some(Duration::from_secs(60)), past),
+        );
+
+        match cache.lookup(fresh_url) {
+            CacheLookup::Fresh(response) => assert_eq!(response.final_url, fresh_url),
+            other => panic!("expected fresh entry, got {other:?}"),
+        }
+        match cache.lookup(stale_url) {
+            CacheLookup::Stale { etag, .. } => assert_eq!(etag.as_deref(), Some("\"v1\"")),
+            other => panic!("expected stale entry, got {other:?}"),
+        }
+
+        cache.refresh_metadata(
+            stale_url,
+            &[
+                ("Cache-Control".to_owned(), "max-age=300".to_owned()),
+                ("ETag".to_owned(), "\"v2\"".to_owned()),
+            ],
+        );
+        assert!(matches!(cache.lookup(stale_url), CacheLookup::Fresh(_)));
+        let summaries = cache.entry_summaries();
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[1].url, stale_url);
+        assert_eq!(summaries[1].max_age, Some(Duration::from_secs(300)));
+
+        cache.refresh_metadata(
+            stale_url,
+            &[("Cache-Control".to_owned(), "no-store".to_owned())],
+        );
+        assert!(matches!(cache.lookup(stale_url), CacheLookup::Miss));
+    }
+
+    #[test]
+    fn memory_cache_passes_shared_harness() {
+        exercise_response_cache(&mut MemoryCache::default());
+    }
+
+    #[test]
+    fn disk_cache_passes_shared_harness_and_survives_reopen() {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|value| value.as_nanos())
+            .unwrap_or_default();
+        let root = std::env::temp_dir().join(format!("pixeldust-http-cache-test-{stamp}"));
+        let mut cache = match DiskCache::open(root.clone()) {
+            Ok(cache) => cache,
+            Err(error) => panic!("{error}"),
+        };
+        exercise_response_cache(&mut cache);
+
+        let mut reopened = match DiskCache::open(root.clone()) {
+            Ok(cache) => cache,
+            Err(error) => panic!("{error}"),
+        };
+        match reopened.lookup("https://example.com/fresh.css") {
+            CacheLookup::Fresh(response) => assert_eq!(response.status_text, "OK"),
+            other => panic!("expected persisted fresh entry, got {other:?}"),
+        }
+        assert!(matches!(
+            reopened.lookup("https://example.com/stale.css"),
+            CacheLookup::Miss
+        ));
+
+        reopened.clear();
+        let emptied = match DiskCache::open(root.clone()) {
+            Ok(cache) => cache,
+            Err(error) => panic!("{error}"),
+        };
+        assert!(emptied.entry_summaries().is_empty());
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn fetches_only_requested_deferred_images_once() {
+        let deferred = vec![
+            "https://example.com/a.png".to_owned(),
+            "https://example.com/b.png".to_owned(),
+        ];
+        let requested = vec![
+            "https://example.com/b.png".to_owned(),
+            "https://example.com/other.png".to_owned(),
+            "https://example.com/b.png".to_owned(),
+        ];
+        let mut fetched = Vec::new();
+        let images = fetch_requested_images(&deferred, &requested, |url| {
+            fetched.push(url.to_owned());
+            Some(DecodedImageAsset {
+                url: url.to_owned(),
+                width: 1,
+                height: 1,
+                rgba: vec![0; 4],
+            })
+        });
+
+        assert_eq!(fetched, vec!["https://example.com/b.png".to_owned()]);
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].url, "https://example.com/b.png");
+    }
+
+    #[test]
+    fn blocked_lazy_images_are_logged_like_eager_ones() {
+        let mut nav_log = NavLog::default();
+        let image = fetch_deferred_image(
+            "https://example.com/",
+            "http://cdn.example.com/lazy.png",
+            &NavigationOptions::default(),
+            &Arc::new(Mutex::new(HttpCache::default())),
+            &Mutex::new(ImageMemoryBudget::default()),
+            &mut nav_log,
+        );
+
+        assert!(image.is_none());
+        assert_eq!(
+            nav_log.blocked,
+            vec![BlockedRequest {
+                url: "http://cdn.example.com/lazy.png".to_owned(),
+                reason: BlockReason::MixedContent,
+            }]
+        );
+        assert_eq!(nav_log.warning_count(), 1);
+    }
+
+    #[test]
+    fn skips_image_fetches_when_images_are_disabled() {
+        let urls = vec![
+            "https://example.com/a.png".to_owned(),
+            "https://example.com/b.png".to_owned(),
+        ];
+        let mut fetched = Vec::new();
+        let images = load_page_images(false, &urls, |url| {
+            fetched.push(url.to_owned());
+            None
+        });
+        assert!(images.is_empty());
+        assert!(fetched.is_empty());
+
+        let images = load_page_images(true, &urls, |url| {
+            fetched.push(url.to_owned());
+            Some(DecodedImageAsset {
+                url: url.to_owned(),
+                width: 1,
+                height: 1,
+                rgba: vec![0; 4],
+            })
+        });
+        assert_eq!(images.len(), 2);
+        assert_eq!(fetched, urls);
+    }
+
+    fn tracked_fetch(
+        in_flight: &std::sync::atomic::AtomicUsize,
+        peak: &std::sync::atomic::AtomicUsize,
+        url: &str,
+    ) -> String {
+        let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        peak.fetch_max(now, Ordering::SeqCst);
+        std::thread::sleep(Duration::from_millis(5));
+        in_flight.fetch_sub(1, Ordering::SeqCst);
+        url.to_owned()
+    }
+
+    #[test]
+    fn fetch_pool_completes_in_order_with_bounded_concurrency() {
+        let urls = (0..12)
+            .map(|index| format!("https://h{}.example/{index}.png", index % 4))
+            .collect::<Vec<_>>();
+        let in_flight = std::sync::atomic::AtomicUsize::new(0);
+        let peak = std::sync::atomic::AtomicUsize::new(0);
+        let config = FetchPoolConfig {
+            max_concurrent: 3,
+            max_per_host: 3,
+            rate_limit: None,
+        };
+
+        let results = fetch_in_parallel(&urls, config, &AtomicBool::new(false), |url| {
+            tracked_fetch(&in_flight, &peak, url)
+        });
+        assert_eq!(results, urls.iter().cloned().map(Some).collect::<Vec<_>>());
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+    }
+
+    #[test]
+    fn fetch_pool_respects_per_host_limit() {
+        let urls = (0..6)
+            .map(|index| format!("https://one.example/{index}.css"))
+            .collect::<Vec<_>>();
+        let in_flight = std::sync::atomic::AtomicUsize::new(0);
+        let peak = std::sync::atomic::AtomicUsize::new(0);
+        let config = FetchPoolConfig {
+            max_concurrent: 4,
+            max_per_host: 1,
+            rate_limit: None,
+        };
+
+        let results = fetch_in_parallel(&urls, config, &AtomicBool::new(false), |url| {
+            tracked_fetch(&in_flight, &peak, url)
+        });
+        assert!(results.iter().all(Option::is_some));
+        assert_eq!(peak.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn fetch_pool_cancellation_skips_pending_fetches() {
+        let urls = (0..5)
+            .map(|index| format!("https://example.com/{index}.js"))
+            .collect::<Vec<_>>();
+        let cancel = AtomicBool::new(false);
+        let config = FetchPoolConfig {
+            max_concurrent: 1,
+            max_per_host: 1,
+            rate_limit: None,
+        };
+
+        let results = fetch_in_parallel(&urls, config, &cancel, |url| {
+            cancel.store(true, Ordering::SeqCst);
+            url.to_owned()
+        });
+        assert_eq!(results[0].as_deref(), Some("https://example.com/0.js"));
+        assert!(results[1..].iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn nav_log_records_blocked_and_failed_subresources() {
+        let browser = Browser::new().unwrap_or_else(|_| unreachable!());
+        let mut log = NavLog::default();
+        assert!(!allow_logged_subresource(
+            &browser,
+            &mut log,
+            "stylesheet",
+            "https://www.example.com/",
+            "http://cdn.example.com/site.css",
+        ));
+        assert!(
+            successful_subresource(
+                &mut log,
+                "script",
+                "https://www.example.com/app.js",
+                Err("connection refused".to_owned()),
+            )
+            .is_none()
+        );
+
+        assert_eq!(log.entries.len(), 2);
+        assert_eq!(log.warning_count(), 2);
+        assert_eq!(log.entries[0].level, NavLogLevel::Warning);
+        assert_eq!(log.entries[0].resource, "stylesheet");
+        assert_eq!(log.entries[0].url, "http://cdn.example.com/site.css");
+        assert_eq!(log.entries[0].reason, "blocked: Mixed content");
+        assert_eq!(log.entries[1].resource, "script");
+        assert_eq!(log.entries[1].url, "https://www.example.com/app.js");
+        assert_eq!(log.entries[1].reason, "fetch failed: connection refused");
+        assert_eq!(
+            log.entries[1].summary(),
+            "[warn] script https://www.example.com/app.js: fetch failed: connection refused"
+        );
+    }
+
+    #[test]
+    fn script_context_accepts_generic_types_but_rejects_html() {
+        assert!(accept_script_response(
+            "text/plain; charset=utf-8",
+            "https://example.com/bundle",
+            b"window.ready = true;",
+        ));
+        assert!(accept_script_response(
+            "",
+            "https://example.com/bundle",
+            b"var x = 1;"
+        ));
+        assert!(!accept_script_response(
+            "application/javascript",
+            "https://example.com/app.js",
+            b"\n  <!DOCTYPE html><html><body>Not Found</body></html>",
+        ));
+        assert!(!accept_script_response(
+            "text/html",
+            "https://example.com/app.js",
+            b"Service unavailable",
+        ));
+        assert!(!accept_script_response(
+            "image/png",
+            "https://example.com/pixel",
+            b"\x89PNG"
+        ));
+    }
+
+    #[test]
+    fn stylesheet_context_accepts_octet_stream_css() {
+        assert!(accept_stylesheet_response(
+            "application/octet-stream",
+            "https://example.com/theme",
+            b"body { color: red; }",
+        ));
+        assert!(!accept_stylesheet_response(
+            "text/plain",
+            "https://example.com/theme.css",
+            b"<html><body>login required</body></html>",
+        ));
+    }
+
+    #[test]
+    fn inline_event_script_uses_matching_event_type() {
+        let script = build_inline_event_script(
+            crate::simple_html::DomEventKind::Change.event_type(),
+            "qty",
+            "updateTotal(event.type)",
+        );
+        assert!(script.contains("type: \"change\""));
+        assert!(script.contains("\"qty\""));
+    }
+
+    #[test]
+    fn tab_title_follows_navigation_and_event_title_changes() {
+        let cache = Arc::new(Mutex::new(HttpCache::default()));
+        let mut tab_title = String::from("New Tab");
+        assert!(!refresh_tab_title(&mut tab_title, None));
+
+        let mut page = match navigate("about:version", &NavigationOptions::default(), cache) {
+            Ok(page) => page,
+            Err(error) => panic!("{error}"),
+        };
+        assert!(refresh_tab_title(&mut tab_title, Some(&page)));
+        assert_eq!(tab_title, "About PixelDust");
+        assert!(!refresh_tab_title(&mut tab_title, Some(&page)));
+
+        page.html_document = Some(crate::simple_html::HtmlDocument::parse(
+            "<button id=\"rename\">Rename</button>",
+        ));
+        let events = [crate::simple_html::DomEventRequest {
+            kind: crate::simple_html::DomEventKind::Click,
+            target_id: Some("rename".to_owned()),
+            inline_handler: "document.title = 'Renamed';".to_owned(),
+        }];
+        let outcome = dispatch_dom_events(&mut page, &events);
+        assert!(outcome.navigate_to.is_none());
+        assert!(refresh_tab_title(&mut tab_title, Some(&page)));
+        assert_eq!(tab_title, "Renamed");
+    }
+
+    #[test]
+    fn submit_handlers_can_cancel_the_default_action() {
+        let mut page = match navigate(
+            "about:blank",
+            &NavigationOptions::default(),
+            Arc::new(Mutex::new(HttpCache::default())),
+        ) {
+            Ok(page) => page,
+            Err(error) => panic!("{error}"),
+        };
+        page.html_document = Some(crate::simple_html::HtmlDocument::parse(
+            "<form id=\"search\"><input name=\"q\"></form>",
+        ));
+        let mut submit_prevented = |handler: &str| {
+            let submit = crate::simple_html::DomEventRequest {
+                kind: crate::simple_html::DomEventKind::Submit,
+                target_id: Some("search".to_owned()),
+                inline_handler: handler.to_owned(),
+            };
+            dispatch_dom_events(&mut page, &[submit]).default_prevented
+        };
+
+        assert!(submit_prevented("event.preventDefault();"));
+        assert!(submit_prevented("return false;"));
+        assert!(!submit_prevented("document.title = 'sent';"));
+
+        let keydown = crate::simple_html::DomEventRequest {
+            kind: crate::simple_html::DomEventKind::KeyDown,
+            target_id: Some("search".to_owned()),
+            inline_handler: "event.preventDefault();".to_owned(),
+        };
+        let submit = crate::simple_html::DomEventRequest {
+            kind: crate::simple_html::DomEventKind::Submit,
+            target_id: Some("search".to_owned()),
+            inline_handler: "document.title = 'sent';".to_owned(),
+        };
+        assert!(!dispatch_dom_events(&mut page, &[keydown, submit]).default_prevented);
+    }
+
+    #[test]
+    fn json_bodies_are_pretty_printed_with_stable_indentation() {
+        let pretty = pretty_print_json(r#"{"b":[1,true,null],"a":{"name":"pd"}}"#);
+        assert_eq!(
+            pretty.as_deref(),
+            Some(
+                "{\n  \"b\": [\n    1,\n    true,\n    null\n  ],\n  \"a\": {\n    \"name\": \"pd\"\n  }\n}"
+            )
+        );
+    }
+
+    /// Runs the registry handler for `content_type` over `body`, as a navigation would.
+    fn render_with_handler(content_type: &str, body: &str) -> (ContentKind, String) {
+        let options = NavigationOptions::default();
+        let cancel = AtomicBool::new(false);
+        let mut session = match NavigationSession::new(
+            &options,
+            Arc::new(Mutex::new(HttpCache::default())),
+            &cancel,
+        ) {
+            Ok(session) => session,
+            Err(error) => panic!("{error}"),
+        };
+        let page = FetchedResponse {
+            content_type: content_type.to_owned(),
+            body: body.as_bytes().to_vec(),
+            ..response_with_headers("https://example.com/data", &[])
+        };
+        let handler = select_content_handler(CONTENT_HANDLERS, content_type);
+        let mut rendered = RenderedDocument {
+            content_kind: handler.kind,
+            ..RenderedDocument::default()
+        };
+        (handler.render)(&mut session, &page, &mut rendered);
+        (rendered.content_kind, rendered.body_preview)
+    }
+
+    #[test]
+    fn invalid_json_falls_back_to_the_raw_preview() {
+        assert_eq!(pretty_print_json("{\"unterminated\": "), None);
+
+        assert_eq!(
+            render_with_handler("application/json; charset=utf-8", "{broken"),
+            (ContentKind::Raw, "{broken".to_owned())
+        );
+        assert_eq!(
+            render_with_handler("application/problem+json", "[1,2]"),
+            (ContentKind::Json, "[\n  1,\n  2\n]".to_owned())
+        );
+        assert_eq!(
+            render_with_handler("text/plain", "[1,2]"),
+            (ContentKind::Raw, "[1,2]".to_owned())
+        );
+    }
+
+    fn encode_test_png(width: u32, height: u32) -> Vec<u8> {
+        let mut png = std::io::Cursor::new(Vec::new());
+        image::RgbaImage::new(width, height)
+            .write_to(&mut png, image::ImageFormat::Png)
+            .unwrap_or_else(|_| unreachable!());
+        png.into_inner()
+    }
+
+    fn fetched_png(url: &str, width: u32, height: u32) -> FetchedResponse {
+        FetchedResponse {
+            final_url: url.to_owned(),
+            status_code: 200,
+            status_text: "OK".to_owned(),
+            http_version: "HTTP/1.1".to_owned(),
+            headers: Vec::new(),
+            content_type: "image/png".to_owned(),
+            body: encode_test_png(width, height),
+            warnings: Vec::new(),
+            tls: None,
+        }
+    }
+
+    #[test]
+    fn image_memory_budget_is_shared_by_eager_and_lazy_images() {
+        let urls = (0..4)
+            .map(|index| format!("https://example.com/{index}.png"))
+            .collect::<Vec<_>>();
+        let budget = Mutex::new(ImageMemoryBudget {
+            max_bytes: 2 * 4 * 4 * 4,
+            used_bytes: 0,
+        });
+        let mut over_budget = 0;
+        let images = load_page_images(true, &urls, |url| {
+            match decode_budgeted_image(&budget, &fetched_png(url, 4, 4)) {
+                BudgetedImage::Decoded(decoded) => Some(decoded),
+                BudgetedImage::OverBudget => {
+                    over_budget += 1;
+                    None
+                }
+                BudgetedImage::Undecodable => None,
+            }
+        });
+
+        assert_eq!(
+            images
+                .iter()
+                .map(|image| image.url.as_str())
+                .collect::<Vec<_>>(),
+            vec!["https://example.com/0.png", "https://example.com/1.png"]
+        );
+        assert_eq!(over_budget, 2);
+        assert!(matches!(
+            decode_budgeted_image(&budget, &fetched_png("https://example.com/lazy.png", 1, 1)),
+            BudgetedImage::OverBudget
+        ));
+    }
+
+    #[test]
+    fn image_memory_budget_counts_decoded_rgba_bytes() {
+        let budget = Mutex::new(ImageMemoryBudget {
+            max_bytes: 100,
+            used_bytes: 0,
+        });
+        let image = fetched_png("https://example.com/wide.png", 5, 3);
+
+        assert!(matches!(
+            decode_budgeted_image(&budget, &image),
+            BudgetedImage::Decoded(_)
+        ));
+        assert_eq!(
+            budget.lock().map(|budget| budget.used_bytes).ok(),
+            Some(5 * 3 * 4)
+        );
+        assert!(matches!(
+            decode_budgeted_image(&budget, &image),
+            BudgetedImage::Decoded(_)
+        ));
+        assert!(matches!(
+            decode_budgeted_image(&budget, &image),
+            BudgetedImage::OverBudget
+        ));
+    }
+
+    #[test]
+    fn image_decoding_enforces_dimension_caps_and_pixel_cap_independently() {
+        let limits = ImageDecodeLimits {
+            max_width: 16,
+            max_height: 16,
+            max_pixels: 100,
+        };
+        let decode = |width, height| {
+            decode_image_asset(
+                "https://example.com/test.png",
+                "image/png",
+                &encode_test_png(width, height),
+                limits,
+            )
+        };
+
+        let within = decode(8, 8);
+        assert_eq!(
+            within.map(|image| (image.width, image.height)),
+            Some((8, 8))
+        );
+        assert!(decode(20, 2).is_none(), "wider than max_width");
+        assert!(decode(2, 20).is_none(), "taller than max_height");
+        assert!(
+            decode(12, 12).is_none(),
+            "within both dimensions but over max_pixels"
+        );
+    }
+
+    #[test]
+    fn saved_page_inlines_decoded_images() {
+        let mut page = match navigate(
+            "about:blank",
+            &NavigationOptions::default(),
+            Arc::new(Mutex::new(HttpCache::default())),
+        ) {
+            Ok(page) => page,
+            Err(error) => panic!("{error}"),
+        };
+        page.final_url = "https://example.com/post".to_owned();
+        page.html_document = Some(crate::simple_html::HtmlDocument::parse(
+            "<html><body><p>Offline</p><img src=\"/dot.png\"></body></html>",
+        ));
+        page.decoded_images = vec![DecodedImageAsset {
+            url: "https://example.com/dot.png".to_owned(),
+            width: 1,
+            height: 1,
+            rgba: vec![255, 0, 0, 255],
+        }];
+
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|value| value.as_nanos())
+            .unwrap_or_default();
+        let directory = std::env::temp_dir().join(format!("pixeldust-saved-page-test-{stamp}"));
+        let path = match save_page_offline(&page, &directory) {
+            Ok(path) => path,
+            Err(error) => panic!("{error}"),
+        };
+        let saved = std::fs::read_to_string(&path).unwrap_or_else(|_| unreachable!());
+        assert!(path.starts_with(&directory));
+        assert!(saved.contains("<p>Offline</p>"));
+        assert!(saved.contains("<img src=\"data:image/png;base64,iVBORw0KGgo"));
+        let _ = std::fs::remove_dir_all(directory);
+    }
+
+    fn temp_cookie_storage() -> (pd_storage::StorageManager, std::path::PathBuf) {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|value| value.as_nanos())
+            .unwrap_or_default();
+        let root = std::env::temp_dir().join(format!("pixeldust-cookie-test-{stamp}"));
+        let storage = pd_storage::StorageManager::new(
+            pd_storage::StorageConfig::default(),
+            pd_privacy::PrivacyPolicy::default(),
+            pd_security::SecurityPolicy::default(),
+        )
+        .with_persistent_root(root.clone())
+        .with_dedicated_partition(COOKIE_STORAGE_PARTITION);
+        (storage, root)
+    }
+
+    fn only(range: ClearRange) -> ClearOptions {
+        ClearOptions {
+            cookies: false,
+            cache: false,
+            history: false,
+            storage: false,
+            range,
+        }
+    }
+
+    #[test]
+    fn clearing_cookies_empties_the_jar_but_keeps_the_cache() {
+        let cache = Arc::new(Mutex::new(HttpCache::default()));
+        store_response_cookies(
+            &cache,
+            "https://example.com/",
+            &[("Set-Cookie".to_owned(), "sid=abc".to_owned())],
+            &PrivacyPolicy::default(),
+        );
+        let url = "https://example.com/app.css";
+        if let Ok(mut guard) = cache.lock() {
+            guard.responses.store(
+                url,
+                cached_entry(url, Some(Duration::from_secs(60)), Instant::now()),
+            );
+        }
+
+        let options = ClearOptions {
+            cookies: true,
+            ..only(ClearRange::AllTime)
+        };
+        assert_eq!(clear_stored_browsing_data(&cache, None, options), Ok(()));
+
+        assert_eq!(cookie_header_for_url(&cache, "https://example.com/"), "");
+        let guard = cache.lock().unwrap_or_else(|_| unreachable!());
+        assert!(matches!(guard.responses.lookup(url), CacheLookup::Fresh(_)));
+    }
+
+    #[test]
+    fn clearing_everything_empties_cookies_cache_and_site_storage() {
+        let (storage, root) = temp_cookie_storage();
+        let cache = Arc::new(Mutex::new(HttpCache::default()));
+        store_response_cookies(
+            &cache,
+            "https://example.com/",
+            &[("Set-Cookie".to_owned(), "sid=abc; Max-Age=3600".to_owned())],
+            &PrivacyPolicy::default(),
+        );
+        assert_eq!(save_cookies(&cache, &storage), Ok(1));
+        assert!(
+            storage
+                .set_partition_value("example.com", "theme", "dark")
+                .is_ok()
+        );
+        let url = "https://example.com/";
+        if let Ok(mut guard) = cache.lock() {
+            guard
+                .responses
+                .store(url, cached_entry(url, None, Instant::now()));
+        }
+
+        let options = ClearOptions {
+            cookies: true,
+            cache: true,
+            history: true,
+            storage: true,
+            range: ClearRange::AllTime,
+        };
+        assert_eq!(
+            clear_stored_browsing_data(&cache, Some(&storage), options),
+            Ok(())
+        );
+
+        assert_eq!(cookie_header_for_url(&cache, url), "");
+        let guard = cache.lock().unwrap_or_else(|_| unreachable!());
+        assert!(guard.responses.entry_summaries().is_empty());
+        assert_eq!(storage.list_partitions(), Ok(Vec::new()));
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn unpartitioned_site_storage_clears_apart_from_cookies() {
+        let (mut storage, root) = temp_cookie_storage();
+        storage.config.partition_by_top_level_site = false;
+        let cache = Arc::new(Mutex::new(HttpCache::default()));
+        store_response_cookies(
+            &cache,
+            "https://example.com/",
+            &[("Set-Cookie".to_owned(), "sid=abc; Max-Age=3600".to_owned())],
+            &PrivacyPolicy::default(),
+        );
+        assert_eq!(save_cookies(&cache, &storage), Ok(1));
+        assert!(
+            storage
+                .set_partition_value("example.com", "theme", "dark")
+                .is_ok()
+        );
+
+        let cookies_only = ClearOptions {
+            cookies: true,
+            cache: false,
+            history: false,
+            storage: false,
+            range: ClearRange::AllTime,
+        };
+        assert_eq!(
+            clear_stored_browsing_data(&cache, Some(&storage), cookies_only),
+            Ok(())
+        );
+        assert_eq!(
+            storage.get_partition_value("example.com", "theme"),
+            Ok(Some("dark".to_owned()))
+        );
+
+        store_response_cookies(
+            &cache,
+            "https://example.com/",
+            &[("Set-Cookie".to_owned(), "sid=abc; Max-Age=3600".to_owned())],
+            &PrivacyPolicy::default(),
+        );
+        assert_eq!(save_cookies(&cache, &storage), Ok(1));
+        let storage_only = ClearOptions {
+            cookies: false,
+            storage: true,
+            ..cookies_only
+        };
+        assert_eq!(
+            clear_stored_browsing_data(&cache, Some(&storage), storage_only),
+            Ok(())
+        );
+        assert_eq!(
+            storage.get_partition_value("example.com", "theme"),
+            Ok(None)
+        );
+        assert_eq!(
+            storage.partition_keys(COOKIE_STORAGE_PARTITION),
+            Ok(vec!["example.com".to_owned()])
+        );
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn user_agent_override_without_storage_lasts_for_the_session() {
+        let mut overrides = Arc::new(UserAgentOverrides::default());
+        let in_flight = Arc::clone(&overrides);
+        if let Err(error) =
+            save_user_agent_override(&mut overrides, None, "Example.com", Some("CompatAgent/1.0"))
+        {
+            panic!("{error}");
+        }
+        assert_eq!(
+            overrides.for_host("www.example.com"),
+            Some("CompatAgent/1.0")
+        );
+        assert_eq!(in_flight.for_host("www.example.com"), None);
+
+        if let Err(error) = save_user_agent_override(&mut overrides, None, "example.com", None) {
+            panic!("{error}");
+        }
+        assert_eq!(overrides.for_host("example.com"), None);
+    }
+
+    #[test]
+    fn ranged_history_clear_keeps_older_visits() {
+        let now = SystemTime::now();
+        let visit = |url: &str, age_secs: u64| HistoryEntry {
+            url: url.to_owned(),
+            visited_at: now - Duration::from_secs(age_secs),
+        };
+        let mut history = vec![
+            visit("https://old.example/", 3 * 60 * 60),
+            visit("https://recent.example/", 60),
+        ];
+
+        clear_history_range(&mut history, ClearRange::LastHour);
+        assert_eq!(
+            history
+                .iter()
+                .map(|entry| entry.url.as_str())
+                .collect::<Vec<_>>(),
+            vec!["https://old.example/"]
+        );
+
+        clear_history_range(&mut history, ClearRange::AllTime);
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn ranged_clear_only_removes_recent_cookies_and_cache_entries() {
+        let two_hours = Duration::from_secs(2 * 60 * 60);
+        let old_instant = Instant::now()
+            .checked_sub(two_hours)
+            .unwrap_or_else(|| unreachable!());
+        let old_time = SystemTime::now()
+            .checked_sub(two_hours)
+            .unwrap_or_else(|| unreachable!());
+        let cache = Arc::new(Mutex::new(HttpCache::default()));
+        store_response_cookies(
+            &cache,
+            "https://example.com/",
+            &[("Set-Cookie".to_owned(), "recent=1".to_owned())],
+            &PrivacyPolicy::default(),
+        );
+        if let Ok(mut guard) = cache.lock() {
+            let cookies = guard.cookies.entry("example.com".to_owned()).or_default();
+            cookies.insert(
+                "old".to_owned(),
+                StoredCookie {
+                    value: "1".to_owned(),
+                    expires_at: None,
+                    set_at: Some(old_time),
+                },
+            );
+            cookies.insert(
+                "restored".to_owned(),
+                StoredCookie {
+                    value: "1".to_owned(),
+                    expires_at: None,
+                    set_at: None,
+                },
+            );
+            guard.responses.store(
+                "https://example.com/old.css",
+                cached_entry("https://example.com/old.css", None, old_instant),
+            );
+            guard.responses.store(
+                "https://example.com/new.css",
+                cached_entry("https://example.com/new.css", None, Instant::now()),
+            );
+        }
+
+        let options = ClearOptions {
+            cookies: true,
+            cache: true,
+            ..only(ClearRange::LastHour)
+        };
+        assert_eq!(clear_stored_browsing_data(&cache, None, options), Ok(()));
+
+        assert_eq!(
+            cookie_header_for_url(&cache, "https://example.com/"),
+            "old=1; restored=1"
+        );
+        let guard = cache.lock().unwrap_or_else(|_| unreachable!());
+        let remaining = guard
+            .responses
+            .entry_summaries()
+            .into_iter()
+            .map(|summary| summary.url)
+            .collect::<Vec<_>>();
+        assert_eq!(remaining, ["https://example.com/old.css"]);
+    }
+
+    #[test]
+    fn parses_basic_challenge_realms() {
+        assert_eq!(
+            parse_basic_realm(r#"Basic realm="x""#).as_deref(),
+            Some("x")
+        );
+        assert_eq!(
+            parse_basic_realm(
+                r#"Bearer realm="api", Basic realm="Staff \"only\"", charset="UTF-8""#
+            )
+            .as_deref(),
+            Some("Staff \"only\"")
+        );
+        assert_eq!(
+            parse_basic_realm("basic realm=intranet").as_deref(),
+            Some("intranet")
+        );
+        assert_eq!(parse_basic_realm("Basic").as_deref(), Some(""));
+        assert_eq!(parse_basic_realm(r#"Bearer realm="basic""#), None);
+
+        let challenge = basic_auth_challenge(
+            "https://example.com/private/page",
+            &[(
+                "WWW-Authenticate".to_owned(),
+                r#"Basic realm="x""#.to_owned(),
+            )],
+        );
+        assert_eq!(
+            challenge.map(|challenge| (challenge.origin, challenge.realm)),
+            Some(("https://example.com".to_owned(), "x".to_owned()))
+        );
+    }
+
+    #[test]
+    fn basic_authorization_header_is_base64_encoded() {
+        assert_eq!(
+            basic_authorization_value("Aladdin", "open sesame"),
+            "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ=="
+        );
+        assert_eq!(basic_authorization_value("user", ""), "Basic dXNlcjo=");
+    }
+
+    #[test]
+    fn basic_credentials_are_scoped_per_origin() {
+        let cache = Arc::new(Mutex::new(HttpCache::default()));
+        store_basic_credentials(&cache, "https://example.com", "Aladdin", "open sesame");
+
+        let authorization_for = |url: &str| {
+            let mut headers = Vec::new();
+            attach_authorization_header(&cache, url, &mut headers);
+            headers.into_iter().map(|header| header.value).next()
+        };
+        assert_eq!(
+            authorization_for("https://example.com/a/b?c").as_deref(),
+            Some("Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==")
+        );
+        assert_eq!(
+            authorization_for("https://example.com:443/").as_deref(),
+            Some("Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==")
+        );
+        assert_eq!(authorization_for("http://example.com/"), None);
+        assert_eq!(authorization_for("https://example.com:8443/"), None);
+        assert_eq!(authorization_for("https://sub.example.com/"), None);
+    }
+
+    #[test]
+    fn parses_cookie_expires_dates() {
+        let expected = UNIX_EPOCH.checked_add(Duration::from_secs(1_445_412_480));
+        assert_eq!(parse_http_date("Wed, 21 Oct 2015 07:28:00 GMT"), expected);
+        assert_eq!(
+            parse_http_date("Wednesday, 21-Oct-15 07:28:00 GMT"),
+            expected
+        );
+        assert_eq!(parse_http_date("not a date"), None);
+    }
+
+    #[test]
+    fn saves_persistent_cookies_and_restores_them() {
+        let (storage, root) = temp_cookie_storage();
+        let cache = Arc::new(Mutex::new(HttpCache::default()));
+        store_response_cookies(
+            &cache,
+            "https://example.com/",
+            &[
+                ("Set-Cookie".to_owned(), "sid=abc; Max-Age=3600".to_owned()),
+                ("Set-Cookie".to_owned(), "theme=dark".to_owned()),
+            ],
+            &PrivacyPolicy::default(),
+        );
+        assert_eq!(
+            cookie_header_for_url(&cache, "https://example.com/"),
+            "sid=abc; theme=dark"
+        );
+        assert_eq!(save_cookies(&cache, &storage), Ok(1));
+
+        let restored = Arc::new(Mutex::new(HttpCache::default()));
+        assert_eq!(load_cookies(&restored, &storage), Ok(1));
+        assert_eq!(
+            cookie_header_for_url(&restored, "https://example.com/"),
+            "sid=abc"
+        );
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn session_only_cookies_drop_expiry_and_skip_persistence() {
+        let (storage, root) = temp_cookie_storage();
+        let headers = [("Set-Cookie".to_owned(), "sid=abc; Max-Age=3600".to_owned())];
+        let expiry = |cache: &Arc<Mutex<HttpCache>>| {
+            let guard = cache.lock().unwrap_or_else(|_| unreachable!());
+            guard
+                .cookies
+                .get("example.com")
+                .and_then(|cookies| cookies.get("sid"))
+                .map(|cookie| cookie.expires_at)
+        };
+
+        let session = Arc::new(Mutex::new(HttpCache::default()));
+        store_response_cookies(
+            &session,
+            "https://example.com/",
+            &headers,
+            &session_only_privacy(),
+        );
+        assert_eq!(expiry(&session), Some(None));
+        assert_eq!(
+            cookie_header_for_url(&session, "https://example.com/"),
+            "sid=abc"
+        );
+        assert_eq!(save_cookies(&session, &storage), Ok(0));
+
+        let persistent = Arc::new(Mutex::new(HttpCache::default()));
+        store_response_cookies(
+            &persistent,
+            "https://example.com/",
+            &headers,
+            &PrivacyPolicy::default(),
+        );
+        assert!(expiry(&persistent).is_some_and(|expires_at| expires_at.is_some()));
+        assert_eq!(save_cookies(&persistent, &storage), Ok(1));
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn purges_expired_cookies_on_load() {
+        let (storage, root) = temp_cookie_storage();
+        let future = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|value| value.as_secs() + 3_600)
+            .unwrap_or_default();
+        let record = format!("1\told\tgone\n{future}\tfresh\tkept");
+        assert!(
+            storage
+                .set_partition_value(COOKIE_STORAGE_PARTITION, "example.com", &record)
+                .is_ok()
+        );
+
+        let cache = Arc::new(Mutex::new(HttpCache::default()));
+        assert_eq!(load_cookies(&cache, &storage), Ok(1));
+        assert_eq!(
+            cookie_header_for_url(&cache, "https://example.com/"),
+            "fresh=kept"
+        );
+        assert_eq!(
+            storage.get_partition_value(COOKIE_STORAGE_PARTITION, "example.com"),
+            Ok(Some(format!("{future}\tfresh\tkept")))
+        );
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn private_mode_skips_cookie_persistence() {
+        let (mut storage, root) = temp_cookie_storage();
+        storage.config.ephemeral_mode = true;
+        let cache = Arc::new(Mutex::new(HttpCache::default()));
+        store_response_cookies(
+            &cache,
+            "https://example.com/",
+            &[("Set-Cookie".to_owned(), "sid=abc; Max-Age=3600".to_owned())],
+            &PrivacyPolicy::default(),
+        );
+
+        assert_eq!(save_cookies(&cache, &storage), Ok(0));
+        assert!(!root.exists());
+    }
+
+    #[test]
+    fn third_party_script_gate_compares_registrable_domains() {
+        let page = "https://www.example.com/index.html";
+        assert!(allow_third_party_script(
+            true,
+            page,
+            "https://static.example.com/app.js"
+        ));
+        assert!(!allow_third_party_script(
+            true,
+            page,
+            "https://cdn.tracker.net/t.js"
+        ));
+        assert!(allow_third_party_script(
+            false,
+            page,
+            "https://cdn.tracker.net/t.js"
+        ));
+        assert!(allow_third_party_script(
+            true,
+            "http://10.0.0.5:8080/",
+            "http://10.0.0.5:8080/app.js"
+        ));
+        assert!(!allow_third_party_script(
+            true,
+            "http://10.0.0.5/",
+            "http://11.0.0.5/app.js"
+        ));
+
+        assert_eq!(registrable_domain("a.b.Example.COM."), "example.com");
+        assert_eq!(registrable_domain("localhost"), "localhost");
+        assert_eq!(registrable_domain("192.168.1.20"), "192.168.1.20");
+    }
+
+    fn blank_page_view() -> PageView {
+        let cache = Arc::new(Mutex::new(HttpCache::default()));
+        internal_about_page("about:blank", &cache).unwrap_or_else(|_| unreachable!())
+    }
+
+    #[test]
+    fn identical_page_views_have_no_diff() {
+        let page = blank_page_view();
+        assert!(page.diff(&page.clone()).is_empty());
+    }
+
+    #[test]
+    fn page_view_diff_reports_title_change() {
+        let before = blank_page_view();
+        let mut after = before.clone();
+        after.title = Some("Renamed".to_owned());
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff[0].category, PageDiffCategory::Title);
+        assert_eq!(
+            diff[0].to_string(),
+            "Title title: None -> Some(\"Renamed\")"
+        );
+    }
+
+    #[test]
+    fn page_view_diff_categorizes_subresource_counts() {
+        let before = blank_page_view();
+        let mut after = before.clone();
+        after.subresource_stats.images_loaded = 3;
+        after.subresource_stats.blocked = 1;
+
+        let diff = before.diff(&after);
+        assert_eq!(
+            diff.iter()
+                .map(|entry| (entry.category, entry.field))
+                .collect::<Vec<_>>(),
+            vec![
+                (PageDiffCategory::Subresources, "images_loaded"),
+                (PageDiffCategory::Subresources, "blocked"),
+            ]
+        );
+        assert_eq!(diff[0].after, "3");
+    }
+
+    struct ScriptedInterceptor;
+
+    impl NavigationInterceptor for ScriptedInterceptor {
+        fn before_navigate(&self, url: &str) -> NavigationDecision {
+            if url.contains("blocked") {
+                NavigationDecision::Block
+            } else if url.contains("old.example") {
+                NavigationDecision::Redirect("new.example/landing".to_owned())
+            } else {
+                NavigationDecision::Allow
+            }
+        }
+    }
+
+    #[test]
+    fn default_navigation_interceptor_allows_everything() {
+        for url in [
+            "https://example.com/",
+            "about:blank",
+            "https://blocked.example/",
+        ] {
+            assert_eq!(
+                intercept_navigation(&AllowAllNavigations, url.to_owned()),
+                Some(url.to_owned())
+            );
+        }
+    }
+
+    #[test]
+    fn blocking_interceptor_prevents_navigation() {
+        assert_eq!(
+            intercept_navigation(&ScriptedInterceptor, "https://blocked.example/".to_owned()),
+            None
+        );
+        assert_eq!(
+            intercept_navigation(&ScriptedInterceptor, "https://example.com/".to_owned()),
+            Some("https://example.com/".to_owned())
+        );
+    }
+
+    #[test]
+    fn redirecting_interceptor_changes_the_target() {
+        assert_eq!(
+            intercept_navigation(&ScriptedInterceptor, "https://old.example/".to_owned()),
+            Some("https://new.example/landing".to_owned())
+        );
+    }
+
+    #[test]
+    fn url_rule_interceptor_reads_block_and_redirect_flags() {
+        let args = [
+            "--block-url",
+            "https://ads.example/",
+            "--pd-verbose",
+            "--redirect-url",
+            "http://old.example/=https://new.example/landing",
+        ]
+        .map(str::to_owned);
+        let interceptor = match UrlRuleInterceptor::from_args(args) {
+            Ok(Some(interceptor)) => interceptor,
+            other => panic!("{other:?}"),
+        };
+
+        assert_eq!(
+            intercept_navigation(&interceptor, "https://ads.example/banner".to_owned()),
+            None
+        );
+        assert_eq!(
+            intercept_navigation(&interceptor, "http://old.example/page".to_owned()),
+            Some("https://new.example/landing".to_owned())
+        );
+        assert_eq!(
+            intercept_navigation(&interceptor, "https://example.com/".to_owned()),
+            Some("https://example.com/".to_owned())
+        );
+    }
+
+    #[test]
+    fn url_rule_interceptor_rejects_malformed_flags() {
+        assert!(matches!(
+            UrlRuleInterceptor::from_args(["--other".to_owned()]),
+            Ok(None)
+        ));
+        assert!(UrlRuleInterceptor::from_args(["--block-url".to_owned()]).is_err());
+        assert!(
+            UrlRuleInterceptor::from_args(["--redirect-url", "old.example"].map(str::to_owned))
+                .is_err()
+        );
+    }
+
+    /// Answers one connection per canned response, returning each request line in order.
+    fn serve_http_responses(responses: Vec<String>) -> (u16, std::thread::JoinHandle<Vec<String>>) {
+        serve_http(responses, |head| {
+            head.lines().next().unwrap_or_default().to_owned()
+        })
+    }
+
+    /// Like [`serve_http_responses`], but returns each full request head, headers included.
+    fn serve_http_request_heads(
+        responses: Vec<String>,
+    ) -> (u16, std::thread::JoinHandle<Vec<String>>) {
+        serve_http(responses, str::to_owned)
+    }
+
+    fn serve_http(
+        responses: Vec<String>,
+        record: fn(&str) -> String,
+    ) -> (u16, std::thread::JoinHandle<Vec<String>>) {
+        use std::io::{Read, Write};
+
+        let listener =
+            std::net::TcpListener::bind("127.0.0.1:0").unwrap_or_else(|_| unreachable!());
+        let port = listener
+            .local_addr()
+            .unwrap_or_else(|_| unreachable!())
+            .port();
+        let server = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for response in responses {
+                let Ok((mut stream, _)) = listener.accept() else {
+                    break;
+                };
+                let mut head = Vec::new();
+                let mut byte = [0_u8; 1];
+                while !head.ends_with(b"\r\n\r\n") && stream.read_exact(&mut byte).is_ok() {
+                    head.push(byte[0]);
+                }
+                requests.push(record(&String::from_utf8_lossy(&head)));
+                let _ = stream.write_all(response.as_bytes());
+            }
+            requests
+        });
+        (port, server)
+    }
+
+    fn text_response(content_type: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+             Connection: close\r\n\r\n{body}",
+            body.len()
+        )
+    }
+
+    fn html_response(body: &str) -> String {
+        text_response("text/html", body)
+    }
+
+    fn redirect_response(location: &str) -> String {
+        format!(
+            "HTTP/1.1 302 Found\r\nLocation: {location}\r\nContent-Length: 0\r\n\
+             Connection: close\r\n\r\n"
+        )
+    }
+
+    #[test]
+    fn interceptor_is_consulted_on_every_http_redirect_hop() {
+        let (port, server) = serve_http_responses(vec![
+            redirect_response("/hop"),
+            redirect_response("/blocked/next"),
+        ]);
+
+        let result = navigate_with_options(
+            &format!("http://127.0.0.1:{port}/start"),
+            NavigationOptions {
+                interceptor: Arc::new(ScriptedInterceptor),
+                ..NavigationOptions::default()
+            },
+        );
+        let requests = server.join().unwrap_or_default();
+
+        let error = result
+            .err()
+            .map(|error| error.to_string())
+            .unwrap_or_default();
+        assert!(error.contains("/blocked/next was blocked"), "{error}");
+        assert_eq!(
+            requests,
+            vec![
+                "GET /start HTTP/1.1".to_owned(),
+                "GET /hop HTTP/1.1".to_owned()
+            ]
+        );
+    }
+
+    #[test]
+    fn interceptor_is_consulted_on_script_redirects() {
+        let (port, server) = serve_http_responses(vec![html_response(
+            "<p>Stay</p><script>location.href = '/blocked/next';</script>",
+        )]);
+        let url = format!("http://127.0.0.1:{port}/");
+
+        let options = NavigationOptions {
+            interceptor: Arc::new(ScriptedInterceptor),
+            ..NavigationOptions::default()
+        };
+        let page = navigate_with_options(&url, options).unwrap_or_else(|error| panic!("{error}"));
+        let requests = server.join().unwrap_or_default();
+
+        assert_eq!(page.final_url, url);
+        assert_eq!(requests, vec!["GET / HTTP/1.1".to_owned()]);
+        assert!(page.nav_log.entries.iter().any(|entry| {
+            entry.url.ends_with("/blocked/next") && entry.reason.contains("blocked by interceptor")
+        }));
+    }
+
+    #[test]
+    fn repeated_js_errors_collapse_into_one_counted_entry() {
+        let mut errors = Vec::new();
+        for _ in 0..3 {
+            record_js_error(&mut errors, "inline-script-1", "x is not defined");
+        }
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].count, 3);
+        assert_eq!(errors[0].text, "inline-script-1: x is not defined");
+    }
+
+    #[test]
+    fn distinct_js_errors_stay_separate_in_first_seen_order() {
+        let mut errors = Vec::new();
+        record_js_error(&mut errors, "a.js", "first");
+        record_js_error(&mut errors, "b.js", "first");
+        record_js_error(&mut errors, "a.js", "second");
+        record_js_error(&mut errors, "a.js", "first");
+        assert_eq!(
+            errors
+                .iter()
+                .map(|entry| (entry.text.as_str(), entry.count))
+                .collect::<Vec<_>>(),
+            vec![("a.js: first", 2), ("b.js: first", 1), ("a.js: second", 1)]
+        );
+    }
+
+    #[test]
+    fn js_error_log_caps_unique_entries() {
+        let mut errors = Vec::new();
+        for index in 0..(MAX_JS_ERROR_LOGS + 10) {
+            record_js_error(&mut errors, "loop.js", &format!("error {index}"));
+        }
+        record_js_error(&mut errors, "loop.js", "error 0");
+        assert_eq!(errors.len(), MAX_JS_ERROR_LOGS);
+        assert_eq!(errors[0].count, 2);
+    }
+
+    #[test]
+    fn color_scheme_resolves_dark_preference_and_auto_follows_host_theme() {
+        assert!(!ColorScheme::Light.prefers_dark(true));
+        assert!(ColorScheme::Dark.prefers_dark(false));
+        assert!(ColorScheme::Auto.prefers_dark(true));
+        assert!(!ColorScheme::Auto.prefers_dark(false));
+        assert_eq!(ColorScheme::default(), ColorScheme::Light);
+    }
+
+    #[test]
+    fn stop_cancels_the_inflight_navigation_and_leaves_loading_state() {
+        let cancel = AtomicBool::new(false);
+        let mut inflight = Some(7);
+
+        assert_eq!(stop_navigation(&mut inflight, &cancel), Some(7));
+        assert_eq!(inflight, None);
+        assert!(cancel.load(Ordering::Relaxed));
+        assert_eq!(stop_navigation(&mut inflight, &cancel), None);
+    }
+
+    #[test]
+    fn late_result_for_a_stopped_navigation_is_discarded() {
+        let cancel = AtomicBool::new(false);
+        let mut inflight = Some(3);
+        stop_navigation(&mut inflight, &cancel);
+
+        assert!(!is_current_navigation(inflight, 3));
+        assert!(is_current_navigation(Some(4), 4));
+        assert!(!is_current_navigation(Some(4), 3));
+    }
+
+    #[test]
+    fn stopped_navigation_returns_before_fetching_the_document() {
+        let cache = Arc::new(Mutex::new(HttpCache::default()));
+        let result = execute_navigation(
+            "http://127.0.0.1:9/never-fetched",
+            &NavigationOptions::default(),
+            cache,
+            &AtomicBool::new(true),
+            &|_| {},
+        );
+        assert_eq!(
+            result.err(),
+            Some(NavigationError::Failed("navigation stopped".to_owned()))
+        );
+    }
+
+    #[test]
+    fn html_handler_claims_html_responses() {
+        for content_type in [
+            "text/html",
+            "text/html; charset=utf-8",
+            "application/xhtml+xml",
+        ] {
+            assert_eq!(
+                select_content_handler(CONTENT_HANDLERS, content_type).kind,
+                ContentKind::Html
+            );
+        }
+    }
+
+    #[test]
+    fn json_image_and_download_handlers_claim_their_types() {
+        let kind = |content_type| select_content_handler(CONTENT_HANDLERS, content_type).kind;
+        assert_eq!(kind("application/json"), ContentKind::Json);
+        assert_eq!(
+            kind("application/ld+json; charset=utf-8"),
+            ContentKind::Json
+        );
+        assert_eq!(kind("image/png"), ContentKind::Image);
+        assert_eq!(kind("application/octet-stream"), ContentKind::Download);
+    }
+
+    #[test]
+    fn unclaimed_content_types_fall_back_to_raw_preview() {
+        let kind = |content_type| select_content_handler(CONTENT_HANDLERS, content_type).kind;
+        assert_eq!(kind("text/x-unknown"), ContentKind::Raw);
+        assert_eq!(kind(""), ContentKind::Raw);
+        assert_eq!(
+            select_content_handler(&[], "text/html").kind,
+            ContentKind::Raw
+        );
+    }
+
+    #[test]
+    fn first_contentful_paint_uses_earliest_content_not_total_load() {
+        let timings = PaintTimings {
+            text_ready: Some(Duration::from_millis(120)),
+            first_image_ready: Some(Duration::from_millis(80)),
+            load_complete: Some(Duration::from_millis(900)),
+        };
+        assert_eq!(
+            first_contentful_paint(&timings),
+            Some(Duration::from_millis(80))
+        );
+    }
+
+    #[test]
+    fn text_only_page_paints_at_parse_completion() {
+        let timings = PaintTimings {
+            text_ready: Some(Duration::from_millis(40)),
+            first_image_ready: None,
+            load_complete: Some(Duration::from_millis(300)),
+        };
+        assert_eq!(
+            first_contentful_paint(&timings),
+            Some(Duration::from_millis(40))
+        );
+        assert_eq!(first_contentful_paint(&PaintTimings::default()), None);
+    }
+
+    fn response_with_headers(url: &str, headers: &[(&str, &str)]) -> FetchedResponse {
+        FetchedResponse {
+            final_url: url.to_owned(),
+            status_code: 200,
+            status_text: "OK".to_owned(),
+            http_version: "HTTP/1.1".to_owned(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| ((*name).to_owned(), (*value).to_owned()))
+                .collect(),
+            content_type: "text/css".to_owned(),
+            body: b"body{}".to_vec(),
+            warnings: Vec::new(),
+            tls: None,
+        }
+    }
+
+    fn stored_max_age(cache: &Arc<Mutex<HttpCache>>, url: &str) -> Option<Duration> {
+        let guard = cache.lock().unwrap_or_else(|_| unreachable!());
+        guard
+            .responses
+            .entry_summaries()
+            .into_iter()
+            .find(|summary| summary.url == url)
+            .and_then(|summary| summary.max_age)
+    }
+
+    #[test]
+    fn last_modified_only_responses_get_heuristic_freshness() {
+        let url = "https://example.com/heuristic.css";
+        let response = response_with_headers(
+            url,
+            &[
+                ("Date", "Wed, 21 Oct 2015 07:28:00 GMT"),
+                ("Last-Modified", "Fri, 16 Oct 2015 07:28:00 GMT"),
+            ],
+        );
+        let twelve_hours = Duration::from_secs(12 * 60 * 60);
+        assert_eq!(
+            heuristic_freshness(&response.headers, SystemTime::now()),
+            Some(twelve_hours)
+        );
+
+        let cache = Arc::new(Mutex::new(HttpCache::default()));
+        maybe_store_cache_entry(&cache, &response);
+        assert_eq!(stored_max_age(&cache, url), None);
+        assert!(matches!(
+            lookup_cache(&cache, url),
+            CacheLookup::Stale { .. }
+        ));
+
+        let cache = Arc::new(Mutex::new(HttpCache {
+            heuristic_freshness: true,
+            ..HttpCache::default()
+        }));
+        maybe_store_cache_entry(&cache, &response);
+        assert_eq!(stored_max_age(&cache, url), Some(twelve_hours));
+        assert!(matches!(lookup_cache(&cache, url), CacheLookup::Fresh(_)));
+
+        let ancient = response_with_headers(
+            url,
+            &[
+                ("Date", "Wed, 21 Oct 2015 07:28:00 GMT"),
+                ("Last-Modified", "Thu, 01 Jan 2009 00:00:00 GMT"),
+            ],
+        );
+        assert_eq!(
+            heuristic_freshness(&ancient.headers, SystemTime::now()),
+            Some(Duration::from_secs(24 * 60 * 60))
+        );
+    }
+
+    #[test]
+    fn no_store_responses_are_never_cached() {
+        let url = "https://example.com/private.css";
+        let cache = Arc::new(Mutex::new(HttpCache {
+            heuristic_freshness: true,
+            ..HttpCache::default()
+        }));
+        maybe_store_cache_entry(
+            &cache,
+            &response_with_headers(
+                url,
+                &[
+                    ("Cache-Control", "no-store"),
+                    ("Last-Modified", "Thu, 01 Jan 2009 00:00:00 GMT"),
+                    ("ETag", "\"v1\""),
+                ],
+            ),
+        );
+        assert!(matches!(lookup_cache(&cache, url), CacheLookup::Miss));
+    }
+
+    #[test]
+    fn explicit_max_age_wins_over_heuristic_freshness() {
+        let url = "https://example.com/explicit.css";
+        let cache = Arc::new(Mutex::new(HttpCache {
+            heuristic_freshness: true,
+            ..HttpCache::default()
+        }));
+        maybe_store_cache_entry(
+            &cache,
+            &response_with_headers(
+                url,
+                &[
+                    ("Cache-Control", "public, max-age=60"),
+                    ("Last-Modified", "Thu, 01 Jan 2009 00:00:00 GMT"),
+                ],
+            ),
+        );
+        assert_eq!(stored_max_age(&cache, url), Some(Duration::from_secs(60)));
+
+        // `no-cache` forces revalidation, so no heuristic lifetime is assumed.
+        let revalidate = "https://example.com/revalidate.css";
+        maybe_store_cache_entry(
+            &cache,
+            &response_with_headers(
+                revalidate,
+                &[
+                    ("Cache-Control", "no-cache"),
+                    ("Last-Modified", "Thu, 01 Jan 2009 00:00:00 GMT"),
+                ],
+            ),
+        );
+        assert_eq!(stored_max_age(&cache, revalidate), None);
+        assert!(matches!(
+            lookup_cache(&cache, revalidate),
+            CacheLookup::Stale { .. }
+        ));
+    }
+
+    #[test]
+    fn blocked_requests_are_recorded_with_distinct_reasons_and_counted() {
+        let browser = Browser::new().unwrap_or_else(|_| unreachable!());
+        let mut log = NavLog::default();
+        let document = "https://news.example.com/";
+        let tracker = "https://stats.doubleclick.net/pixel.gif";
+        let insecure = "http://cdn.example.com/app.js";
+
+        assert!(!allow_logged_subresource(
+            &browser, &mut log, "image", document, tracker
+        ));
+        assert!(!allow_logged_subresource(
+            &browser, &mut log, "image", document, tracker
+        ));
+        assert!(!allow_logged_subresource(
+            &browser, &mut log, "script", document, insecure
+        ));
+        assert!(allow_logged_subresource(
+            &browser,
+            &mut log,
+            "script",
+            document,
+            "https://cdn.example.com/app.js"
+        ));
+
+        assert_eq!(
+            log.blocked,
+            vec![
+                BlockedRequest {
+                    url: tracker.to_owned(),
+                    reason: BlockReason::Tracker,
+                },
+                BlockedRequest {
+                    url: insecure.to_owned(),
+                    reason: BlockReason::MixedContent,
+                },
+            ]
+        );
+        assert_eq!(log.warning_count(), 3);
+
+        log.blocked.push(BlockedRequest {
+            url: "https://ads.example.net/tag.js".to_owned(),
+            reason: BlockReason::Tracker,
+        });
+        assert_eq!(
+            blocked_request_counts(&log.blocked),
+            vec![(BlockReason::Tracker, 2), (BlockReason::MixedContent, 1)]
+        );
+        assert!(blocked_request_counts(&[]).is_empty());
+    }
+
+    #[test]
+    fn host_rate_limiter_allows_burst_then_paces_requests() {
+        let mut limiter = HostRateLimiter::new(HostRateLimit {
+            requests_per_second: 4,
+            burst: 3,
+        });
+        let start = Instant::now();
+        for _ in 0..3 {
+            assert_eq!(limiter.try_acquire("cdn.example", start), Ok(()));
+        }
+        assert_eq!(
+            limiter.try_acquire("cdn.example", start),
+            Err(Duration::from_millis(250))
+        );
+
+        let later = start + Duration::from_millis(125);
+        let Err(wait) = limiter.try_acquire("cdn.example", later) else {
+            panic!("half a token should not be enough");
+        };
+        assert!(wait > Duration::from_millis(124) && wait <= Duration::from_millis(125));
+
+        let refilled = start + Duration::from_millis(250);
+        assert_eq!(limiter.try_acquire("cdn.example", refilled), Ok(()));
+        assert!(limiter.try_acquire("cdn.example", refilled).is_err());
+
+        // Idle time refills only up to the burst size.
+        let idle = start + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert_eq!(limiter.try_acquire("cdn.example", idle), Ok(()));
+        }
+        assert!(limiter.try_acquire("cdn.example", idle).is_err());
+    }
+
+    #[test]
+    fn host_rate_limiter_keeps_independent_buckets_per_host() {
+        let mut limiter = HostRateLimiter::new(HostRateLimit {
+            requests_per_second: 1,
+            burst: 1,
+        });
+        let now = Instant::now();
+        assert_eq!(limiter.try_acquire("a.example", now), Ok(()));
+        assert!(limiter.try_acquire("a.example", now).is_err());
+        assert_eq!(limiter.try_acquire("b.example", now), Ok(()));
+        assert!(limiter.try_acquire("b.example", now).is_err());
+    }
+
+    #[test]
+    fn hard_reload_bypasses_fresh_cache_entries_and_sends_no_cache() {
+        let (port, server) = serve_http_request_heads(vec![
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nCache-Control: max-age=60\r\n\
+             Content-Length: 10\r\nConnection: close\r\n\r\nfresh copy"
+                .to_owned(),
+        ]);
+        let url = format!("http://127.0.0.1:{port}/page.txt");
+        let cache = Arc::new(Mutex::new(HttpCache::default()));
+        {
+            let mut entry = cached_entry(&url, Some(Duration::from_secs(60)), Instant::now());
+            entry.response.body = b"cached copy".to_vec();
+            let mut guard = cache.lock().unwrap_or_else(|_| unreachable!());
+            guard.responses.store(&url, entry);
+        }
+        let navigate = |cache_mode| {
+            navigate(
+                &url,
+                &NavigationOptions {
+                    load_images: false,
+                    javascript_enabled: false,
+                    cache_mode,
+                    ..NavigationOptions::default()
+                },
+                Arc::clone(&cache),
+            )
+            .unwrap_or_else(|error| panic!("{error}"))
+        };
+
+        // A normal reload that reached the server would be answered with the fresh copy.
+        let page = navigate(CacheMode::Normal);
+        assert!(page.body_preview.contains("cached copy"));
+
+        let page = navigate(CacheMode::Bypass);
+        let heads = server.join().unwrap_or_default();
+        assert!(page.body_preview.contains("fresh copy"));
+        assert_eq!(heads.len(), 1);
+        let head = heads[0].to_ascii_lowercase();
+        assert!(head.contains("\r\ncache-control: no-cache\r\n"));
+        assert!(head.contains("\r\npragma: no-cache\r\n"));
+
+        // The fresh response replaced the cached copy.
+        let CacheLookup::Fresh(stored) = lookup_cache(&cache, &url) else {
+            panic!("hard reload should refresh the cache entry");
+        };
+        assert_eq!(stored.body, b"fresh copy");
+    }
+
+    fn session_only_privacy() -> PrivacyPolicy {
+        PrivacyPolicy {
+            session_cookies_only: true,
+            ..PrivacyPolicy::default()
+        }
+    }
+
+    #[test]
+    fn cookie_reports_list_stored_and_rejected_cookies_with_attributes() {
+        let headers = [
+            ("Content-Type".to_owned(), "text/html".to_owned()),
+            (
+                "Set-Cookie".to_owned(),
+                "sid=abc; Path=/app; Max-Age=3600; Secure; HttpOnly; SameSite=Lax".to_owned(),
+            ),
+            (
+                "Set-Cookie".to_owned(),
+                "track=1; Domain=tracker.example.net; Path=/".to_owned(),
+            ),
+            ("set-cookie".to_owned(), "old=; Max-Age=0".to_owned()),
+        ];
+        let reports = response_cookie_reports(
+            "https://www.example.com/",
+            &headers,
+            &PrivacyPolicy::default(),
+        );
+        assert_eq!(reports.len(), 3);
+
+        let stored = &reports[0];
+        assert_eq!(
+            (stored.name.as_str(), stored.value.as_str()),
+            ("sid", "abc")
+        );
+        assert_eq!(stored.status, CookieStatus::Stored);
+        assert_eq!(stored.status_label(), "stored");
+        let now = SystemTime::now();
+        let summary = stored.attributes_summary(now);
+        assert!(summary.starts_with("Domain=www.example.com; Path=/app; expires in 3"));
+        assert!(summary.ends_with("; Secure; HttpOnly; SameSite=Lax"));
+
+        let rejected = &reports[1];
+        assert_eq!(rejected.domain, "tracker.example.net");
+        assert_eq!(
+            rejected.status,
+            CookieStatus::Rejected("third-party cookie blocked by privacy policy")
+        );
+        assert_eq!(
+            rejected.status_label(),
+            "rejected: third-party cookie blocked by privacy policy"
+        );
+        assert_eq!(
+            rejected.attributes_summary(now),
+            "Domain=tracker.example.net; Path=/; session"
+        );
+        assert_eq!(reports[2].status, CookieStatus::Deleted);
+
+        // The jar agrees with the report: only the first-party cookie was stored.
+        let cache = Arc::new(Mutex::new(HttpCache::default()));
+        store_response_cookies(
+            &cache,
+            "https://www.example.com/app",
+            &headers,
+            &PrivacyPolicy::default(),
+        );
+        assert_eq!(
+            cookie_header_for_url(&cache, "https://www.example.com/app"),
+            "sid=abc"
+        );
+        assert_eq!(
+            cookie_header_for_url(&cache, "https://tracker.example.net/"),
+            ""
+        );
+
+        let session = response_cookie_reports(
+            "https://www.example.com/",
+            &headers[..2],
+            &session_only_privacy(),
+        );
+        assert_eq!(session[0].status, CookieStatus::Session);
+        assert_eq!(session[0].expires_at, None);
+
+        // With third-party blocking off the jar keeps the cross-site cookie too.
+        let permissive = PrivacyPolicy {
+            block_third_party_cookies: false,
+            ..PrivacyPolicy::default()
+        };
+        let reports = response_cookie_reports("https://www.example.com/", &headers, &permissive);
+        assert_eq!(reports[1].status, CookieStatus::Session);
+    }
+
+    #[test]
+    fn js_redirect_tracker_aborts_when_a_target_repeats() {
+        let mut tracker = JsRedirectTracker::new(MAX_PAGE_JS_REDIRECTS);
+        assert_eq!(
+            tracker.follow("https://a.example/", "https://b.example/"),
+            Ok(JsRedirectStep::Follow)
+        );
+        let error = tracker
+            .follow("https://b.example/", "https://a.example/#again")
+            .err()
+            .unwrap_or_default();
+        assert!(error.contains("redirect loop detected"), "{error}");
+    }
+
+    #[test]
+    fn js_redirect_tracker_treats_hash_only_changes_as_same_document() {
+        let mut tracker = JsRedirectTracker::new(1);
+        for _ in 0..3 {
+            assert_eq!(
+                tracker.follow("https://a.example/page", "https://a.example/page#section"),
+                Ok(JsRedirectStep::SameDocument)
+            );
+        }
+        assert_eq!(
+            tracker.follow("https://a.example/page#section", "https://b.example/"),
+            Ok(JsRedirectStep::Follow)
+        );
+    }
+
+    #[test]
+    fn js_redirect_loop_aborts_the_navigation() {
+        let (port, server) = serve_http_responses(vec![
+            html_response("<script>location.href = '/b';</script>"),
+            html_response("<script>location.href = '/';</script>"),
+        ]);
+        let url = format!("http://127.0.0.1:{port}/");
+
+        let error = navigate_with_options(&url, NavigationOptions::default())
+            .err()
+            .map(|error| error.to_string())
+            .unwrap_or_default();
+        let requests = server.join().unwrap_or_default();
+
+        assert!(error.contains("redirect loop detected"), "{error}");
+        assert_eq!(
+            requests,
+            vec!["GET / HTTP/1.1".to_owned(), "GET /b HTTP/1.1".to_owned()]
+        );
+    }
+
+    #[test]
+    fn js_redirect_tracker_allows_linear_chain_within_limit() {
+        let mut tracker = JsRedirectTracker::new(MAX_PAGE_JS_REDIRECTS);
+        assert_eq!(
+            tracker.follow("https://a.example/", "https://b.example/"),
+            Ok(JsRedirectStep::Follow)
+        );
+        assert_eq!(
+            tracker.follow("https://b.example/", "https://c.example/"),
+            Ok(JsRedirectStep::Follow)
+        );
+    }
+
+    #[test]
+    fn js_redirect_tracker_still_enforces_count_limit() {
+        let mut tracker = JsRedirectTracker::new(2);
+        assert_eq!(
+            tracker.follow("https://example.com/0", "https://example.com/1"),
+            Ok(JsRedirectStep::Follow)
+        );
+        assert_eq!(
+            tracker.follow("https://example.com/1", "https://example.com/2"),
+            Ok(JsRedirectStep::Follow)
+        );
+        let error = tracker
+            .follow("https://example.com/2", "https://example.com/3")
+            .err()
+            .unwrap_or_default();
+        assert!(error.contains("redirect limit reached"), "{error}");
+    }
+
+    fn reputation_listing(expressions: &[&str]) -> UrlReputation {
+        let list = expressions
+            .iter()
+            .map(|expression| {
+                hash_prefix(expression)
+                    .iter()
+                    .map(|byte| format!("{byte:02x}"))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        UrlReputation::from_prefix_list(&format!("# test list\n{list}\n"))
+    }
+
+    #[test]
+    fn listed_url_requires_reputation_interstitial() {
+        let reputation = reputation_listing(&["evil.example/", "phish.example/login/"]);
+        let mut proceed_once = None;
+
+        assert!(requires_interstitial(
+            &reputation,
+            "https://www.evil.example/account?id=1",
+            &mut proceed_once
+        ));
+        assert!(requires_interstitial(
+            &reputation,
+            "https://phish.example/login/form.html",
+            &mut proceed_once
+        ));
+    }
+
+    #[test]
+    fn unlisted_url_skips_reputation_interstitial() {
+        let reputation = reputation_listing(&["evil.example/"]);
+        let mut proceed_once = None;
+
+        assert!(!requires_interstitial(
+            &reputation,
+            "https://example.com/evil.example/",
+            &mut proceed_once
+        ));
+        assert!(!requires_interstitial(
+            &reputation,
+            "https://phish.example/",
+            &mut proceed_once
+        ));
+    }
+
+    #[test]
+    fn proceed_anyway_bypasses_reputation_block_once() {
+        let reputation = reputation_listing(&["evil.example/"]);
+        let url = "https://evil.example/";
+        let mut proceed_once = Some(url.to_owned());
+
+        assert!(!requires_interstitial(&reputation, url, &mut proceed_once));
+        assert_eq!(proceed_once, None);
+        assert!(requires_interstitial(&reputation, url, &mut proceed_once));
+    }
+
+    #[test]
+    fn http_redirect_to_listed_url_stops_at_the_interstitial() {
+        let (port, server) = serve_http_responses(vec![redirect_response("/listed/page")]);
+
+        let result = navigate_with_options(
+            &format!("http://127.0.0.1:{port}/start"),
+            NavigationOptions {
+                reputation: Arc::new(reputation_listing(&["127.0.0.1/listed/"])),
+                ..NavigationOptions::default()
+            },
+        );
+        let requests = server.join().unwrap_or_default();
+
+        assert_eq!(
+            result.err(),
+            Some(NavigationError::Blocklisted(format!(
+                "http://127.0.0.1:{port}/listed/page"
+            )))
+        );
+        assert_eq!(requests, vec!["GET /start HTTP/1.1".to_owned()]);
+    }
+
+    #[test]
+    fn script_redirect_to_listed_url_stops_at_the_interstitial() {
+        let (port, server) = serve_http_responses(vec![html_response(
+            "<script>location.href = '/listed/page';</script>",
+        )]);
+
+        let result = navigate_with_options(
+            &format!("http://127.0.0.1:{port}/"),
+            NavigationOptions {
+                reputation: Arc::new(reputation_listing(&["127.0.0.1/listed/"])),
+                ..NavigationOptions::default()
+            },
+        );
+        let requests = server.join().unwrap_or_default();
+
+        assert_eq!(
+            result.err(),
+            Some(NavigationError::Blocklisted(format!(
+                "http://127.0.0.1:{port}/listed/page"
+            )))
+        );
+        assert_eq!(requests, vec!["GET / HTTP/1.1".to_owned()]);
+    }
+
+    #[test]
+    fn http_redirect_target_is_stripped_of_tracking_params() {
+        let (port, server) = serve_http_responses(vec![
+            redirect_response("/next?utm_source=mail&id=1"),
+            html_response("<p>next</p>"),
+        ]);
+
+        let result = navigate_with_options(
+            &format!("http://127.0.0.1:{port}/start"),
+            NavigationOptions {
+                url_param_cleaner: Some(Arc::new(UrlParamCleaner::default())),
+                ..NavigationOptions::default()
+            },
+        );
+        let requests = server.join().unwrap_or_default();
+
+        assert!(result.is_ok());
+        assert_eq!(
+            requests,
+            vec![
+                "GET /start HTTP/1.1".to_owned(),
+                "GET /next?id=1 HTTP/1.1".to_owned()
+            ]
+        );
+    }
+
+    #[test]
+    fn script_redirect_target_is_stripped_of_tracking_params() {
+        let (port, server) = serve_http_responses(vec![
+            html_response("<script>location.href = '/next?fbclid=abc&id=1';</script>"),
+            html_response("<p>next</p>"),
+        ]);
+
+        let result = navigate_with_options(
+            &format!("http://127.0.0.1:{port}/"),
+            NavigationOptions {
+                url_param_cleaner: Some(Arc::new(UrlParamCleaner::default())),
+                ..NavigationOptions::default()
+            },
+        );
+        let requests = server.join().unwrap_or_default();
+
+        assert!(result.is_ok());
+        assert_eq!(
+            requests,
+            vec![
+                "GET / HTTP/1.1".to_owned(),
+                "GET /next?id=1 HTTP/1.1".to_owned()
+            ]
+        );
+    }
+
+    fn navigate_recording_states(url: &str) -> (Result<PageView, String>, Vec<NavigationState>) {
+        let states = Mutex::new(Vec::new());
+        let result = execute_navigation(
+            url,
+            &NavigationOptions::default(),
+            Arc::new(Mutex::new(HttpCache::default())),
+            &AtomicBool::new(false),
+            &|state| {
+                if let Ok(mut states) = states.lock() {
+                    states.push(state);
+                }
+            },
+        )
+        .map_err(|error| error.to_string());
+        (result, states.into_inner().unwrap_or_default())
+    }
+
+    #[test]
+    fn scripted_navigation_reports_phases_in_order() {
+        // The stylesheet, script and image batches run one after another, so requests arrive
+        // in this order.
+        let (port, server) = serve_http_responses(vec![
+            html_response(
+                "<html><head><link rel=\"stylesheet\" href=\"/site.css\"></head>\
+                 <body><p>Hello</p><img src=\"/logo.png\" alt=\"Logo\">\
+                 <script>document.title = 'ran';</script></body></html>",
+            ),
+            text_response("text/css", "body { color: red; }"),
+            text_response("image/png", "not a png"),
+        ]);
+
+        let (result, states) = navigate_recording_states(&format!("http://127.0.0.1:{port}/"));
+        let requests = server.join().unwrap_or_default();
+        assert_eq!(
+            requests,
+            vec![
+                "GET / HTTP/1.1".to_owned(),
+                "GET /site.css HTTP/1.1".to_owned(),
+                "GET /logo.png HTTP/1.1".to_owned()
+            ]
+        );
+        let page = result.unwrap_or_else(|error| panic!("{error}"));
+        assert_eq!(page.title.as_deref(), Some("ran"));
+        assert_eq!(
+            states,
+            vec![
+                NavigationState::Preparing,
+                NavigationState::Fetching,
+                NavigationState::Parsing,
+                NavigationState::LoadingSubresources { done: 0, total: 2 },
+                NavigationState::LoadingSubresources { done: 1, total: 2 },
+                NavigationState::RunningScripts,
+                NavigationState::LoadingSubresources { done: 1, total: 2 },
+                NavigationState::LoadingSubresources { done: 2, total: 2 },
+                NavigationState::Complete,
+            ]
+        );
+    }
+
+    #[test]
+    fn failed_navigation_reports_failed_with_the_error() {
+        let (result, states) = navigate_recording_states("http://127.0.0.1:9/unreachable");
+        let error = result.err().unwrap_or_default();
+        assert!(!error.is_empty());
+        assert_eq!(states.first(), Some(&NavigationState::Preparing));
+        assert_eq!(states.last(), Some(&NavigationState::Failed(error)));
+        assert!(!states.contains(&NavigationState::Complete));
+    }
+
+    fn tls13_session() -> TlsSessionInfo {
+        TlsSessionInfo {
+            version: pd_net::TlsVersion::V1_3,
+            trust_anchor: pd_net::TrustAnchorSource::WebPki,
+            ocsp_stapled: true,
+            certificate_subject: Some("example.com".to_owned()),
+        }
+    }
+
+    #[test]
+    fn https_page_without_mixed_content_is_secure() {
+        let info = SecurityInfo::for_page("https://example.com/", Some(tls13_session()), &[]);
+        assert_eq!(info.state, SecurityState::Secure);
+        assert_eq!(info.mixed_content_blocked, 0);
+        assert_eq!(
+            info.tls.map(|tls| tls.version),
+            Some(pd_net::TlsVersion::V1_3)
+        );
+    }
+
+    #[test]
+    fn https_page_with_blocked_mixed_content_is_secure_with_warnings() {
+        let blocked = [
+            BlockedRequest {
+                url: "http://example.com/app.js".to_owned(),
+                reason: BlockReason::MixedContent,
+            },
+            BlockedRequest {
+                url: "https://tracker.example/pixel.gif".to_owned(),
+                reason: BlockReason::Tracker,
+            },
+        ];
+        let info = SecurityInfo::for_page("https://example.com/", Some(tls13_session()), &blocked);
+        assert_eq!(info.state, SecurityState::SecureWithWarnings);
+        assert_eq!(info.mixed_content_blocked, 1);
+    }
+
+    #[test]
+    fn plain_http_page_is_insecure() {
+        let info = SecurityInfo::for_page("http://example.com/", None, &[]);
+        assert_eq!(info.state, SecurityState::Insecure);
+        assert_eq!(info.tls, None);
+    }
+
+    #[test]
+    fn renderer_baseline_pass_only_runs_when_enabled() {
+        let url = "https://example.com/baseline";
+        let navigate = |renderer_baseline: bool| {
+            let mut entry = cached_entry(url, Some(Duration::from_secs(60)), Instant::now());
+            entry.response.content_type = "text/html".to_owned();
+            entry.response.body = b"<title>Baseline</title><p>Hello <b>there</b></p>".to_vec();
+            let mut cache = HttpCache::default();
+            cache.responses.store(url, entry);
+            match navigate(
+                url,
+                &NavigationOptions {
+                    load_images: false,
+                    javascript_enabled: false,
+                    renderer_baseline,
+                    ..NavigationOptions::default()
+                },
+                Arc::new(Mutex::new(cache)),
+            ) {
+                Ok(page) => page,
+                Err(error) => panic!("{error}"),
+            }
+        };
+
+        let skipped = navigate(false);
+        let baseline = navigate(true);
+        assert_eq!(skipped.renderer_draw_calls, None);
+        assert!(baseline.renderer_draw_calls.is_some());
+        assert_eq!(skipped.title, baseline.title);
+        assert_eq!(skipped.body_preview, baseline.body_preview);
+        assert_eq!(
+            skipped.html_document.map(|doc| doc.renderable_text_len()),
+            baseline.html_document.map(|doc| doc.renderable_text_len())
+        );
+    }
+
+    #[test]
+    fn script_favicon_changes_reach_the_tab_strip() {
+        let mut page = blank_page_view();
+        page.final_url = "https://mail.example/inbox".to_owned();
+        page.html_document = Some(crate::simple_html::HtmlDocument::parse(
+            "<head><link id=\"icon\" rel=\"shortcut icon\" href=\"/read.png\"></head>\
+             <body><button id=\"mark\">Mark unread</button></body>",
+        ));
+        page.favicon_url = Some("https://mail.example/read.png".to_owned());
+        page.favicon = Some(DecodedImageAsset {
+            url: "https://mail.example/read.png".to_owned(),
+            width: 1,
+            height: 1,
+            rgba: vec![0; 4],
+        });
+        assert!(tab_favicon(Some(&page)).is_some());
+
+        let click = |handler: &str| crate::simple_html::DomEventRequest {
+            kind: crate::simple_html::DomEventKind::Click,
+            target_id: Some("mark".to_owned()),
+            inline_handler: handler.to_owned(),
+        };
+        dispatch_dom_events(
+            &mut page,
+            &[click(
+                "document.getElementById('icon').setAttribute('href', '/unread.png');",
+            )],
+        );
+        assert_eq!(
+            page.favicon_url.as_deref(),
+            Some("https://mail.example/unread.png")
+        );
+        // The icon decoded at load time no longer matches, so the strip falls back.
+        assert!(tab_favicon(Some(&page)).is_none());
+
+        dispatch_dom_events(
+            &mut page,
+            &[click(
+                "var link = document.createElement('link'); link.rel = 'icon'; \
+                 link.href = 'https://cdn.example/badge.ico'; document.head.appendChild(link);",
+            )],
+        );
+        assert_eq!(
+            page.favicon_url.as_deref(),
+            Some("https://cdn.example/badge.ico")
+        );
+    }
 }
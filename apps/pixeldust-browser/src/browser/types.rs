@@ -4,6 +4,143 @@ enum TrustStoreSelection {
     WebPkiAndOs,
 }
 
+/// How far back a browsing-data clear reaches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ClearRange {
+    LastHour,
+    LastDay,
+    AllTime,
+}
+
+impl ClearRange {
+    const ALL: [Self; 3] = [Self::LastHour, Self::LastDay, Self::AllTime];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::LastHour => "Last hour",
+            Self::LastDay => "Last 24 hours",
+            Self::AllTime => "All time",
+        }
+    }
+
+    /// Age of the oldest data to remove, or `None` to remove everything.
+    fn window(self) -> Option<Duration> {
+        match self {
+            Self::LastHour => Some(Duration::from_secs(60 * 60)),
+            Self::LastDay => Some(Duration::from_secs(24 * 60 * 60)),
+            Self::AllTime => None,
+        }
+    }
+}
+
+/// Whether a navigation may answer from the HTTP cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum CacheMode {
+    #[default]
+    Normal,
+    /// Hard reload: skip cached responses and ask intermediaries for fresh ones.
+    Bypass,
+}
+
+/// Settings a navigation is started with, captured from the toolbar when the load begins.
+#[derive(Clone)]
+struct NavigationOptions {
+    trust_store: TrustStoreSelection,
+    ocsp_required: bool,
+    load_images: bool,
+    honor_resource_hints: bool,
+    block_third_party_scripts: bool,
+    javascript_enabled: bool,
+    renderer_baseline: bool,
+    cache_mode: CacheMode,
+    /// Consulted again on every HTTP and script redirect the document follows.
+    interceptor: Arc<dyn NavigationInterceptor>,
+    /// Loaded once at startup so navigations never re-read them from disk.
+    user_agent_overrides: Arc<UserAgentOverrides>,
+    /// Consulted on redirect hops; the typed URL is checked by the UI before dispatch.
+    reputation: Arc<UrlReputation>,
+    /// Set when tracking-parameter stripping is on; applied to redirect targets as well.
+    url_param_cleaner: Option<Arc<UrlParamCleaner>>,
+}
+
+impl Default for NavigationOptions {
+    fn default() -> Self {
+        Self {
+            trust_store: TrustStoreSelection::WebPkiOnly,
+            ocsp_required: true,
+            load_images: true,
+            honor_resource_hints: false,
+            block_third_party_scripts: false,
+            javascript_enabled: true,
+            renderer_baseline: false,
+            cache_mode: CacheMode::Normal,
+            interceptor: Arc::new(AllowAllNavigations),
+            user_agent_overrides: Arc::default(),
+            reputation: Arc::default(),
+            url_param_cleaner: None,
+        }
+    }
+}
+
+/// Default page colors and `prefers-color-scheme` value used when rendering pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum ColorScheme {
+    #[default]
+    Light,
+    Dark,
+    /// Follow the host app's egui theme.
+    Auto,
+}
+
+impl ColorScheme {
+    const ALL: [Self; 3] = [Self::Light, Self::Dark, Self::Auto];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Light => "Light",
+            Self::Dark => "Dark",
+            Self::Auto => "Auto",
+        }
+    }
+
+    fn prefers_dark(self, host_dark_mode: bool) -> bool {
+        match self {
+            Self::Light => false,
+            Self::Dark => true,
+            Self::Auto => host_dark_mode,
+        }
+    }
+}
+
+/// Categories removed by a browsing-data clear. Site storage has no timestamps, so it is
+/// cleared in full whatever the range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ClearOptions {
+    cookies: bool,
+    cache: bool,
+    history: bool,
+    storage: bool,
+    range: ClearRange,
+}
+
+impl Default for ClearOptions {
+    fn default() -> Self {
+        Self {
+            cookies: true,
+            cache: true,
+            history: true,
+            storage: false,
+            range: ClearRange::LastHour,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HistoryEntry {
+    url: String,
+    visited_at: SystemTime,
+}
+
 impl TrustStoreSelection {
     fn label(self) -> &'static str {
         match self {
@@ -20,22 +157,210 @@ impl TrustStoreSelection {
     }
 }
 
+/// How a fetched document is presented, picked by the content handler registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum ContentKind {
+    Html,
+    Image,
+    Json,
+    Download,
+    /// Fallback for anything no handler claims: a raw text preview.
+    #[default]
+    Raw,
+}
+
+/// Network stack, settings and log shared by every document a navigation fetches.
+struct NavigationSession<'a> {
+    options: &'a NavigationOptions,
+    browser: pd_browser::Browser,
+    client: SharedHttp11Client,
+    policy: pd_net::tls::StrictTlsPolicy,
+    cache: Arc<Mutex<HttpCache>>,
+    cancel: &'a AtomicBool,
+    nav_log: NavLog,
+    navigation_start: Instant,
+    js_redirects: JsRedirectTracker,
+}
+
+/// A navigation's current phase together with the data its next phase works on.
+///
+/// Each [`NavigationStep::advance`] does one phase's work; [`NavigationStep::state`] is what
+/// the UI is told.
+enum NavigationStep<'a> {
+    Preparing {
+        raw_url: &'a str,
+        options: &'a NavigationOptions,
+        cache: Arc<Mutex<HttpCache>>,
+        cancel: &'a AtomicBool,
+    },
+    Fetching {
+        session: Box<NavigationSession<'a>>,
+        url: String,
+    },
+    Parsing {
+        session: Box<NavigationSession<'a>>,
+        page: Box<FetchedResponse>,
+    },
+    LoadingSubresources(Box<HtmlLoad<'a>>),
+    RunningScripts(Box<HtmlLoad<'a>>),
+    Complete(Box<PageView>),
+    Failed(NavigationError),
+}
+
+/// A parsed HTML page whose subresources and scripts are still loading.
+struct HtmlLoad<'a> {
+    session: NavigationSession<'a>,
+    page: FetchedResponse,
+    rendered: RenderedDocument,
+    document: simple_html::HtmlDocument,
+    manifest: simple_html::SubresourceManifest,
+    /// `None` once every batch has settled and the document can be finished.
+    next_batch: Option<SubresourceBatch>,
+    /// Planned fetches settled so far, blocked ones included, out of `total`.
+    done: usize,
+    total: usize,
+    stylesheet_sources: String,
+    scripts: Vec<ScriptSource>,
+    total_scripts: usize,
+    /// Scripts over the fetch limit or the size budget.
+    skipped_scripts: usize,
+}
+
+/// Subresources fetched together, in load order; scripts run before images load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SubresourceBatch {
+    Preloads,
+    Stylesheets,
+    Scripts,
+    Images,
+}
+
+/// What a [`ContentHandler`] rendered for one fetched document.
+#[derive(Default)]
+struct RenderedDocument {
+    content_kind: ContentKind,
+    body_preview: String,
+    html_document: Option<simple_html::HtmlDocument>,
+    static_text_fallback: Option<String>,
+    decoded_images: Vec<DecodedImageAsset>,
+    deferred_images: Vec<String>,
+    image_budget: Arc<Mutex<ImageMemoryBudget>>,
+    subresource_stats: SubresourceStats,
+    js_execution: JsExecutionStats,
+    renderer_draw_calls: Option<usize>,
+    /// Where the page's scripts asked to navigate next, if anywhere.
+    js_redirect_target: Option<String>,
+    paint_timings: PaintTimings,
+    favicon_url: Option<String>,
+    favicon: Option<DecodedImageAsset>,
+}
+
 #[derive(Debug, Clone)]
 struct PageView {
     final_url: String,
     status_code: u16,
+    status_text: String,
     http_version: String,
     content_type: String,
     headers: Vec<(String, String)>,
     body_bytes: usize,
     body_preview: String,
+    /// `Json` only when `body_preview` holds pretty-printed JSON.
+    content_kind: ContentKind,
+    /// Set when the server answered `401` with a Basic challenge the user can answer.
+    auth_challenge: Option<AuthChallenge>,
     title: Option<String>,
+    /// Resolved `<link rel=icon>` URL; follows script changes after load.
+    favicon_url: Option<String>,
+    /// Icon fetched during navigation, keyed by the URL it was loaded from.
+    favicon: Option<DecodedImageAsset>,
     html_document: Option<simple_html::HtmlDocument>,
     static_text_fallback: Option<String>,
     decoded_images: Vec<DecodedImageAsset>,
+    deferred_images: Vec<String>,
+    /// Lazy images handed to an image job whose result has not arrived yet.
+    loading_images: Vec<String>,
+    /// Decoded-image memory charged so far; lazy images draw on the same budget.
+    image_budget: Arc<Mutex<ImageMemoryBudget>>,
     subresource_stats: SubresourceStats,
     js_execution: JsExecutionStats,
     renderer_draw_calls: Option<usize>,
+    paint_timings: PaintTimings,
+    blocked_requests: Vec<BlockedRequest>,
+    /// `Set-Cookie` headers of the final document response.
+    cookies: Vec<CookieReport>,
+    nav_log: NavLog,
+    /// Lock-icon details; `None` for internal `about:` pages.
+    security: Option<SecurityInfo>,
+}
+
+/// Lock-icon state of the current page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SecurityState {
+    Secure,
+    /// HTTPS page whose insecure subresources were blocked.
+    SecureWithWarnings,
+    Insecure,
+}
+
+impl SecurityState {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Secure => "Connection is secure",
+            Self::SecureWithWarnings => "Secure, but insecure content was blocked",
+            Self::Insecure => "Connection is not secure",
+        }
+    }
+
+    fn icon(self) -> &'static str {
+        match self {
+            Self::Secure => "🔒",
+            Self::SecureWithWarnings => "⚠",
+            Self::Insecure => "🔓",
+        }
+    }
+}
+
+/// Connection details shown in the lock-icon popover.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SecurityInfo {
+    state: SecurityState,
+    tls: Option<TlsSessionInfo>,
+    mixed_content_blocked: usize,
+}
+
+/// Part of a [`PageView`] that a [`PageDiff`] entry describes.
+#[cfg(test)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PageDiffCategory {
+    Title,
+    Status,
+    Subresources,
+    Scripts,
+    Renderer,
+}
+
+/// One difference between two page views, for snapshot-style navigation tests.
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PageDiff {
+    category: PageDiffCategory,
+    field: &'static str,
+    before: String,
+    after: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AuthChallenge {
+    origin: String,
+    realm: String,
+}
+
+/// Session-only username and password for one origin's Basic challenges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BasicCredentials {
+    username: String,
+    password: String,
 }
 
 #[derive(Debug, Clone)]
@@ -46,6 +371,59 @@ struct DecodedImageAsset {
     rgba: Vec<u8>,
 }
 
+/// Size caps checked against an image's header before it is fully decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ImageDecodeLimits {
+    max_width: u32,
+    max_height: u32,
+    max_pixels: usize,
+}
+
+impl Default for ImageDecodeLimits {
+    fn default() -> Self {
+        Self {
+            max_width: MAX_IMAGE_WIDTH,
+            max_height: MAX_IMAGE_HEIGHT,
+            max_pixels: MAX_IMAGE_PIXELS,
+        }
+    }
+}
+
+/// Running total of decoded RGBA bytes for one navigation's images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ImageMemoryBudget {
+    max_bytes: usize,
+    used_bytes: usize,
+}
+
+impl Default for ImageMemoryBudget {
+    fn default() -> Self {
+        Self {
+            max_bytes: MAX_TOTAL_IMAGE_BYTES,
+            used_bytes: 0,
+        }
+    }
+}
+
+/// Outcome of decoding one fetched image against its page's [`ImageMemoryBudget`].
+#[derive(Debug)]
+enum BudgetedImage {
+    Decoded(DecodedImageAsset),
+    OverBudget,
+    Undecodable,
+}
+
+/// Navigation milestones as offsets from navigation start.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct PaintTimings {
+    /// The main document was parsed and had renderable text.
+    text_ready: Option<Duration>,
+    /// The first image finished decoding.
+    first_image_ready: Option<Duration>,
+    /// Every subresource had finished.
+    load_complete: Option<Duration>,
+}
+
 #[derive(Debug, Clone, Default)]
 struct SubresourceStats {
     stylesheets_loaded: usize,
@@ -56,26 +434,115 @@ struct SubresourceStats {
     blocked: usize,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NavLogLevel {
+    Info,
+    Warning,
+}
+
+/// Why the subresource gate refused a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BlockReason {
+    Tracker,
+    MixedContent,
+    CrossOrigin,
+    ThirdPartyScript,
+    UnsupportedUrl,
+}
+
+impl BlockReason {
+    const ALL: [Self; 5] = [
+        Self::Tracker,
+        Self::MixedContent,
+        Self::CrossOrigin,
+        Self::ThirdPartyScript,
+        Self::UnsupportedUrl,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Tracker => "Tracker",
+            Self::MixedContent => "Mixed content",
+            Self::CrossOrigin => "Cross-origin",
+            Self::ThirdPartyScript => "Third-party script",
+            Self::UnsupportedUrl => "Unsupported URL",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BlockedRequest {
+    url: String,
+    reason: BlockReason,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct NavLogEntry {
+    level: NavLogLevel,
+    resource: &'static str,
+    url: String,
+    reason: String,
+}
+
+/// How a script-initiated location change is handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JsRedirectStep {
+    /// Load the target as a new document.
+    Follow,
+    /// Only the fragment changed, so the current document stays.
+    SameDocument,
+}
+
+/// Script-initiated redirects followed during one navigation.
+#[derive(Debug, Clone)]
+struct JsRedirectTracker {
+    visited: Vec<String>,
+    remaining: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+struct NavLog {
+    entries: Vec<NavLogEntry>,
+    dropped: usize,
+    /// Requests refused by the subresource gate, handed to [`PageView::blocked_requests`].
+    blocked: Vec<BlockedRequest>,
+}
+
 #[derive(Debug, Clone, Default)]
 struct JsExecutionStats {
     enabled: bool,
     scripts_seen: usize,
     scripts_executed: usize,
     scripts_failed: usize,
+    syntax_errors: usize,
+    runtime_errors: usize,
+    resource_limit_errors: usize,
     scripts_skipped: usize,
     event_dispatches: usize,
     event_failures: usize,
-    errors: Vec<String>,
+    errors: Vec<JsErrorEntry>,
+}
+
+/// One distinct JavaScript error line and how many times it was reported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct JsErrorEntry {
+    text: String,
+    count: usize,
 }
 
 #[derive(Debug, Clone)]
 struct FetchedResponse {
     final_url: String,
     status_code: u16,
+    status_text: String,
     http_version: String,
     headers: Vec<(String, String)>,
     content_type: String,
     body: Vec<u8>,
+    /// Transport warnings such as a body cut short of its `Content-Length`.
+    warnings: Vec<String>,
+    /// TLS handshake details; `None` for plain HTTP or entries restored from the disk cache.
+    tls: Option<TlsSessionInfo>,
 }
 
 #[derive(Debug, Clone)]
@@ -87,10 +554,67 @@ struct CachedResponse {
     stored_at: Instant,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CacheEntrySummary {
+    url: String,
+    stored_at: Instant,
+    max_age: Option<Duration>,
+    has_etag: bool,
+    is_fresh: bool,
+}
+
+#[derive(Debug)]
 struct HttpCache {
-    entries: HashMap<String, CachedResponse>,
-    cookies: HashMap<String, HashMap<String, String>>,
+    responses: Box<dyn ResponseCache>,
+    cookies: HashMap<String, HashMap<String, StoredCookie>>,
+    /// Basic credentials keyed by origin; never persisted.
+    credentials: HashMap<String, BasicCredentials>,
+    /// Opt-in: gives `Last-Modified`-only responses an RFC 7234 heuristic lifetime.
+    heuristic_freshness: bool,
+}
+
+impl Default for HttpCache {
+    fn default() -> Self {
+        Self {
+            responses: Box::new(MemoryCache::default()),
+            cookies: HashMap::new(),
+            credentials: HashMap::new(),
+            heuristic_freshness: false,
+        }
+    }
+}
+
+/// Cookie value plus its expiry; `None` marks a session cookie.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StoredCookie {
+    value: String,
+    expires_at: Option<SystemTime>,
+    /// When this session set the cookie; `None` for cookies restored from disk.
+    set_at: Option<SystemTime>,
+}
+
+/// What the cookie jar did with one `Set-Cookie` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CookieStatus {
+    Stored,
+    /// Stored without an expiry, so it is dropped when the browser exits.
+    Session,
+    Deleted,
+    Rejected(&'static str),
+}
+
+/// A parsed `Set-Cookie` header shown in Navigation Details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CookieReport {
+    name: String,
+    value: String,
+    domain: String,
+    path: Option<String>,
+    expires_at: Option<SystemTime>,
+    secure: bool,
+    http_only: bool,
+    same_site: Option<String>,
+    status: CookieStatus,
 }
 
 #[derive(Debug, Clone)]
@@ -104,12 +628,87 @@ enum CacheLookup {
     Miss,
 }
 
+/// What the page's event handlers asked for after one frame of DOM events.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct DomDispatchOutcome {
+    navigate_to: Option<String>,
+    /// A click or submit handler cancelled the default action, such as following a link or
+    /// submitting a form; cancelling other events leaves it alone.
+    default_prevented: bool,
+}
+
+/// What a [`NavigationInterceptor`] wants done with a navigation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum NavigationDecision {
+    Allow,
+    Block,
+    Redirect(String),
+}
+
+/// Which redirects [`fetch_with_redirects`] may follow, and how many.
+#[derive(Clone, Copy)]
+enum FetchTarget<'a> {
+    /// A top-level document; each redirect hop goes back through its guards.
+    Document(DocumentGuards<'a>),
+    Subresource,
+}
+
+/// Checks a document applies to every URL it is redirected to, as for the typed URL.
+#[derive(Clone, Copy)]
+struct DocumentGuards<'a> {
+    interceptor: &'a dyn NavigationInterceptor,
+    reputation: &'a UrlReputation,
+    param_cleaner: Option<&'a UrlParamCleaner>,
+}
+
+/// Why a navigation ended without a page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum NavigationError {
+    Failed(String),
+    /// A redirect led to a blocklisted URL; the UI shows the interstitial for it.
+    Blocklisted(String),
+}
+
+/// Phase of an in-flight navigation, reported to the UI as it advances.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum NavigationState {
+    Preparing,
+    Fetching,
+    Parsing,
+    /// `done` of `total` planned preloads, stylesheets, images and scripts are settled.
+    LoadingSubresources {
+        done: usize,
+        total: usize,
+    },
+    RunningScripts,
+    Complete,
+    Failed(String),
+}
+
+/// Message sent from a navigation worker to the UI thread.
+#[derive(Debug)]
+enum NavigationEvent {
+    Progress {
+        request_id: u64,
+        state: NavigationState,
+    },
+    Finished(Box<NavigationResult>),
+}
+
 #[derive(Debug)]
 struct NavigationResult {
     request_id: u64,
     url: String,
     add_to_history: bool,
-    result: Result<PageView, String>,
+    result: Result<PageView, NavigationError>,
+}
+
+#[derive(Debug)]
+struct ImageLoadResult {
+    page_url: String,
+    requested: Vec<String>,
+    images: Vec<DecodedImageAsset>,
+    nav_log: NavLog,
 }
 
 #[derive(Debug, Clone)]
@@ -124,23 +723,66 @@ struct BrowserUiApp {
     address_input: String,
     current_url: Option<String>,
     page_view: Option<PageView>,
+    tab_title: String,
     status_line: String,
     last_error: Option<String>,
     trust_store: TrustStoreSelection,
     ocsp_required: bool,
-    history: Vec<String>,
+    load_images: bool,
+    honor_resource_hints: bool,
+    block_third_party_scripts: bool,
+    javascript_enabled: bool,
+    /// Debug toggle: also run the baseline renderer to report its draw-call count.
+    renderer_baseline: bool,
+    /// Strips tracking query parameters from navigation targets before they are requested.
+    strip_tracking_params: bool,
+    /// Mirrors `HttpCache::heuristic_freshness` for the settings toggle.
+    heuristic_caching: bool,
+    url_param_cleaner: Arc<UrlParamCleaner>,
+    history: Vec<HistoryEntry>,
     history_index: Option<usize>,
     next_request_id: u64,
     inflight_request_id: Option<u64>,
-    nav_receiver: Option<mpsc::Receiver<NavigationResult>>,
+    nav_receiver: Option<mpsc::Receiver<NavigationEvent>>,
+    /// Latest phase reported by the in-flight navigation.
+    nav_progress: Option<NavigationState>,
+    /// Set when a newer navigation supersedes the in-flight one.
+    nav_cancel: Arc<AtomicBool>,
     show_navigation_details: bool,
+    print_preview: bool,
+    reduce_motion: bool,
+    color_scheme: ColorScheme,
+    /// Page font sizes are clamped to `min_font_size..=max_font_size` pixels.
+    min_font_size: f32,
+    max_font_size: f32,
+    /// Draft `User-Agent` for the current site, edited in the toolbar menu.
+    user_agent_input: String,
+    user_agent_overrides: Arc<UserAgentOverrides>,
+    clear_options: ClearOptions,
+    auth_username: String,
+    auth_password: String,
+    wrap_preview: bool,
+    preview_wrap_columns: usize,
+    navigation_interceptor: Arc<dyn NavigationInterceptor>,
+    url_reputation: Arc<UrlReputation>,
+    /// Listed URL whose navigation is held at the warning interstitial.
+    reputation_warning: Option<String>,
+    /// One-shot "proceed anyway" grant for a listed URL.
+    reputation_proceed_once: Option<String>,
     image_textures: HashMap<String, egui::TextureHandle>,
+    pending_image_requests: Vec<String>,
+    image_loads_inflight: usize,
+    image_sender: mpsc::Sender<ImageLoadResult>,
+    image_receiver: mpsc::Receiver<ImageLoadResult>,
     form_state: HashMap<String, String>,
     cache: Arc<Mutex<HttpCache>>,
+    cookie_storage: Option<StorageManager>,
     runtime: Option<pd_browser::BrowserRuntime>,
     runtime_workers: Vec<RuntimeWorkerStatus>,
     runtime_restarts: usize,
     runtime_last_error: Option<String>,
+    /// Exit code and stderr tail of the most recently restarted worker.
+    runtime_last_crash: Option<String>,
     runtime_last_poll: Instant,
 }
 
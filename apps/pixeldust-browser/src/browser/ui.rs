@@ -1,42 +1,170 @@
+use super::cache::response_cache_for;
+use super::navigation::blocked_request_counts;
+use super::navigation::clear_history_range;
+use super::navigation::clear_stored_browsing_data;
 use super::navigation::dispatch_dom_events;
 use super::navigation::execute_navigation;
+use super::navigation::fetch_deferred_image;
+use super::navigation::fetch_requested_images;
+use super::navigation::first_contentful_paint;
+use super::navigation::intercept_navigation;
+use super::navigation::is_current_navigation;
+use super::navigation::load_cookies;
 use super::navigation::normalize_input_url;
+use super::navigation::refresh_tab_title;
+use super::navigation::save_cookies;
+use super::navigation::save_page_offline;
+use super::navigation::save_user_agent_override;
+use super::navigation::status_label;
+use super::navigation::stop_navigation;
+use super::navigation::store_basic_credentials;
+use super::navigation::tab_favicon;
+use super::reputation::requires_interstitial;
 use super::runtime::bootstrap_runtime;
 use super::*;
 
 impl Default for BrowserUiApp {
     fn default() -> Self {
         let (runtime, runtime_last_error) = bootstrap_runtime();
+        let (image_sender, image_receiver) = mpsc::channel();
+        let cookie_storage = Some(
+            StorageManager::new(
+                StorageConfig::default(),
+                PrivacyPolicy::default(),
+                SecurityPolicy::default(),
+            )
+            .with_persistent_root(pd_browser::default_storage_root())
+            .with_dedicated_partition(COOKIE_STORAGE_PARTITION),
+        );
+        let cache = Arc::new(Mutex::new(HttpCache {
+            responses: response_cache_for(cookie_storage.as_ref()),
+            ..HttpCache::default()
+        }));
+        let last_error = cookie_storage.as_ref().and_then(|storage| {
+            load_cookies(&cache, storage)
+                .err()
+                .map(|error| format!("Failed to restore cookies: {error}"))
+        });
+        let (url_reputation, last_error) = match UrlReputation::load(cookie_storage.as_ref()) {
+            Ok(reputation) => (reputation, last_error),
+            Err(error) => (
+                UrlReputation::default(),
+                last_error.or(Some(format!("Failed to load URL blocklist: {error}"))),
+            ),
+        };
+        let (user_agent_overrides, last_error) = match cookie_storage
+            .as_ref()
+            .map_or(Ok(UserAgentOverrides::default()), UserAgentOverrides::load)
+        {
+            Ok(overrides) => (overrides, last_error),
+            Err(error) => (
+                UserAgentOverrides::default(),
+                last_error.or(Some(format!(
+                    "Failed to load user-agent overrides: {error}"
+                ))),
+            ),
+        };
 
         Self {
             address_input: DEFAULT_URL.to_owned(),
             current_url: None,
             page_view: None,
+            tab_title: NEW_TAB_LABEL.to_owned(),
             status_line: "Ready".to_owned(),
-            last_error: None,
+            last_error,
             trust_store: TrustStoreSelection::WebPkiOnly,
             ocsp_required: true,
+            load_images: true,
+            honor_resource_hints: false,
+            block_third_party_scripts: false,
+            javascript_enabled: true,
+            renderer_baseline: false,
+            strip_tracking_params: false,
+            heuristic_caching: false,
+            url_param_cleaner: Arc::default(),
             history: Vec::new(),
             history_index: None,
             next_request_id: 1,
             inflight_request_id: None,
             nav_receiver: None,
+            nav_progress: None,
+            nav_cancel: Arc::new(AtomicBool::new(false)),
             show_navigation_details: false,
+            print_preview: false,
+            reduce_motion: false,
+            color_scheme: ColorScheme::default(),
+            min_font_size: simple_html::DEFAULT_MIN_FONT_SIZE,
+            max_font_size: simple_html::DEFAULT_MAX_FONT_SIZE,
+            user_agent_input: String::new(),
+            user_agent_overrides: Arc::new(user_agent_overrides),
+            clear_options: ClearOptions::default(),
+            auth_username: String::new(),
+            auth_password: String::new(),
+            wrap_preview: true,
+            preview_wrap_columns: DEFAULT_PREVIEW_WRAP_COLUMNS,
+            navigation_interceptor: Arc::new(AllowAllNavigations),
+            url_reputation: Arc::new(url_reputation),
+            reputation_warning: None,
+            reputation_proceed_once: None,
             image_textures: HashMap::new(),
+            pending_image_requests: Vec::new(),
+            image_loads_inflight: 0,
+            image_sender,
+            image_receiver,
             form_state: HashMap::new(),
-            cache: Arc::new(Mutex::new(HttpCache::default())),
+            cache,
+            cookie_storage,
             runtime,
             runtime_workers: Vec::new(),
             runtime_restarts: 0,
             runtime_last_error,
+            runtime_last_crash: None,
             runtime_last_poll: Instant::now(),
         }
     }
 }
 
 impl BrowserUiApp {
+    /// Replaces the interceptor consulted before each navigation and on its redirects.
+    pub(super) fn set_navigation_interceptor(
+        &mut self,
+        interceptor: Arc<dyn NavigationInterceptor>,
+    ) {
+        self.navigation_interceptor = interceptor;
+    }
+
     fn navigate(&mut self, raw_url: String, add_to_history: bool) {
-        let normalized_url = normalize_input_url(raw_url);
+        self.navigate_with_cache_mode(raw_url, add_to_history, CacheMode::Normal);
+    }
+
+    fn navigate_with_cache_mode(
+        &mut self,
+        raw_url: String,
+        add_to_history: bool,
+        cache_mode: CacheMode,
+    ) {
+        let mut requested_url = normalize_input_url(raw_url);
+        if self.strip_tracking_params {
+            requested_url = self.url_param_cleaner.clean(&requested_url);
+        }
+        let Some(normalized_url) =
+            intercept_navigation(self.navigation_interceptor.as_ref(), requested_url.clone())
+        else {
+            self.status_line = "Navigation blocked".to_owned();
+            self.last_error = Some(format!("navigation to {requested_url} was blocked"));
+            return;
+        };
+        if requires_interstitial(
+            &self.url_reputation,
+            &normalized_url,
+            &mut self.reputation_proceed_once,
+        ) {
+            self.address_input = normalized_url.clone();
+            self.status_line = "Deceptive site ahead".to_owned();
+            self.reputation_warning = Some(normalized_url);
+            return;
+        }
+        self.reputation_warning = None;
         self.address_input = normalized_url.clone();
         self.status_line = format!("Loading {}...", normalized_url);
         self.last_error = None;
@@ -45,20 +173,27 @@ impl BrowserUiApp {
         self.next_request_id = self.next_request_id.saturating_add(1);
         self.inflight_request_id = Some(request_id);
 
-        let trust_store = self.trust_store;
-        let ocsp_required = self.ocsp_required;
+        let options = self.navigation_options(cache_mode);
         let cache = Arc::clone(&self.cache);
+        self.nav_cancel.store(true, Ordering::Relaxed);
+        self.nav_cancel = Arc::new(AtomicBool::new(false));
+        let cancel = Arc::clone(&self.nav_cancel);
         let (tx, rx) = mpsc::channel();
         self.nav_receiver = Some(rx);
+        self.nav_progress = None;
 
         let nav_job = move || {
-            let result = execute_navigation(&normalized_url, trust_store, ocsp_required, cache);
-            let _ = tx.send(NavigationResult {
+            let progress_tx = tx.clone();
+            let progress = move |state| {
+                let _ = progress_tx.send(NavigationEvent::Progress { request_id, state });
+            };
+            let result = execute_navigation(&normalized_url, &options, cache, &cancel, &progress);
+            let _ = tx.send(NavigationEvent::Finished(Box::new(NavigationResult {
                 request_id,
                 url: normalized_url,
                 add_to_history,
                 result,
-            });
+            })));
         };
 
         if thread::Builder::new()
@@ -74,6 +209,19 @@ impl BrowserUiApp {
         }
     }
 
+    /// Cancels the in-flight navigation and keeps the current page on screen.
+    fn stop(&mut self) {
+        if stop_navigation(&mut self.inflight_request_id, &self.nav_cancel).is_none() {
+            return;
+        }
+        self.nav_receiver = None;
+        self.nav_progress = None;
+        self.status_line = "Stopped".to_owned();
+        if let Some(url) = &self.current_url {
+            self.address_input = url.clone();
+        }
+    }
+
     fn poll_navigation(&mut self) {
         loop {
             let message = self
@@ -81,23 +229,33 @@ impl BrowserUiApp {
                 .as_ref()
                 .and_then(|receiver| receiver.try_recv().ok());
 
-            let Some(message) = message else {
-                break;
+            let message = match message {
+                Some(NavigationEvent::Progress { request_id, state }) => {
+                    if is_current_navigation(self.inflight_request_id, request_id) {
+                        self.nav_progress = Some(state);
+                    }
+                    continue;
+                }
+                Some(NavigationEvent::Finished(message)) => *message,
+                None => break,
             };
 
-            if Some(message.request_id) != self.inflight_request_id {
+            if !is_current_navigation(self.inflight_request_id, message.request_id) {
                 continue;
             }
 
             self.inflight_request_id = None;
             self.nav_receiver = None;
+            self.nav_progress = None;
 
             match message.result {
                 Ok(page) => {
                     self.current_url = Some(page.final_url.clone());
                     self.status_line = format!(
                         "Loaded {} (status {}, {} bytes)",
-                        page.final_url, page.status_code, page.body_bytes
+                        page.final_url,
+                        status_label(page.status_code, &page.status_text),
+                        page.body_bytes
                     );
 
                     if message.add_to_history {
@@ -105,11 +263,17 @@ impl BrowserUiApp {
                     }
 
                     self.image_textures.clear();
+                    self.pending_image_requests.clear();
                     self.form_state.clear();
                     self.page_view = Some(page);
                     self.last_error = None;
                 }
-                Err(error) => {
+                Err(NavigationError::Blocklisted(url)) => {
+                    self.address_input = url.clone();
+                    self.status_line = "Deceptive site ahead".to_owned();
+                    self.reputation_warning = Some(url);
+                }
+                Err(NavigationError::Failed(error)) => {
                     self.status_line = "Navigation failed".to_owned();
                     self.last_error = Some(error);
                 }
@@ -117,6 +281,115 @@ impl BrowserUiApp {
         }
     }
 
+    /// Queues a lazy image for fetching once its placeholder nears the viewport.
+    fn request_image(&mut self, url: String) {
+        let Some(page) = self.page_view.as_ref() else {
+            return;
+        };
+        if page.deferred_images.contains(&url) && !self.pending_image_requests.contains(&url) {
+            self.pending_image_requests.push(url);
+        }
+    }
+
+    fn navigation_options(&self, cache_mode: CacheMode) -> NavigationOptions {
+        NavigationOptions {
+            trust_store: self.trust_store,
+            ocsp_required: self.ocsp_required,
+            load_images: self.load_images,
+            honor_resource_hints: self.honor_resource_hints,
+            block_third_party_scripts: self.block_third_party_scripts,
+            javascript_enabled: self.javascript_enabled,
+            renderer_baseline: self.renderer_baseline,
+            cache_mode,
+            interceptor: Arc::clone(&self.navigation_interceptor),
+            user_agent_overrides: Arc::clone(&self.user_agent_overrides),
+            reputation: Arc::clone(&self.url_reputation),
+            url_param_cleaner: self
+                .strip_tracking_params
+                .then(|| Arc::clone(&self.url_param_cleaner)),
+        }
+    }
+
+    fn flush_image_requests(&mut self) {
+        if self.pending_image_requests.is_empty() {
+            return;
+        }
+        let requested = std::mem::take(&mut self.pending_image_requests);
+        let options = self.navigation_options(CacheMode::Normal);
+        let Some(page) = self.page_view.as_mut() else {
+            return;
+        };
+
+        let deferred = page.deferred_images.clone();
+        page.deferred_images.retain(|url| !requested.contains(url));
+        page.loading_images.extend(requested.iter().cloned());
+        let page_url = page.final_url.clone();
+        let image_budget = Arc::clone(&page.image_budget);
+        let cache = Arc::clone(&self.cache);
+        let sender = self.image_sender.clone();
+
+        let image_job = move || {
+            let mut nav_log = NavLog::default();
+            let images = fetch_requested_images(&deferred, &requested, |url| {
+                fetch_deferred_image(
+                    &page_url,
+                    url,
+                    &options,
+                    &cache,
+                    &image_budget,
+                    &mut nav_log,
+                )
+            });
+            let _ = sender.send(ImageLoadResult {
+                page_url,
+                requested,
+                images,
+                nav_log,
+            });
+        };
+
+        if thread::Builder::new()
+            .name("pixeldust-img".to_owned())
+            .stack_size(NAVIGATION_THREAD_STACK_SIZE)
+            .spawn(image_job)
+            .is_ok()
+        {
+            self.image_loads_inflight = self.image_loads_inflight.saturating_add(1);
+        }
+    }
+
+    fn poll_image_loads(&mut self) {
+        while let Ok(result) = self.image_receiver.try_recv() {
+            self.image_loads_inflight = self.image_loads_inflight.saturating_sub(1);
+            let Some(page) = self.page_view.as_mut() else {
+                continue;
+            };
+            if page.final_url != result.page_url {
+                continue;
+            }
+
+            page.loading_images
+                .retain(|url| !result.requested.contains(url));
+            let mut nav_log = result.nav_log;
+            if !nav_log.blocked.is_empty() {
+                page.blocked_requests.append(&mut nav_log.blocked);
+                if let Some(security) = page.security.as_mut() {
+                    *security = SecurityInfo::for_page(
+                        &page.final_url,
+                        security.tls.clone(),
+                        &page.blocked_requests,
+                    );
+                }
+            }
+            page.nav_log.append_entries(nav_log);
+            page.subresource_stats.images_loaded = page
+                .subresource_stats
+                .images_loaded
+                .saturating_add(result.images.len());
+            page.decoded_images.extend(result.images);
+        }
+    }
+
     fn poll_runtime(&mut self) {
         if self.runtime_last_poll.elapsed() < RUNTIME_POLL_INTERVAL {
             return;
@@ -130,6 +403,17 @@ impl BrowserUiApp {
         match runtime.restart_exited_workers() {
             Ok(restarts) => {
                 self.runtime_restarts = self.runtime_restarts.saturating_add(restarts.len());
+                if let Some(restart) = restarts.last() {
+                    let exit = restart
+                        .exit_code
+                        .map_or_else(|| "signal".to_owned(), |code| code.to_string());
+                    let mut report = format!("{} worker exited ({exit})", restart.role.as_str());
+                    if let Some(tail) = &restart.crash_tail {
+                        report.push('\n');
+                        report.push_str(tail);
+                    }
+                    self.runtime_last_crash = Some(report);
+                }
             }
             Err(error) => {
                 self.runtime_last_error = Some(error.to_string());
@@ -164,18 +448,51 @@ impl BrowserUiApp {
         }
     }
 
+    fn apply_user_agent_override(&mut self, host: &str, save: bool) {
+        let user_agent = save.then_some(self.user_agent_input.as_str());
+        match save_user_agent_override(
+            &mut self.user_agent_overrides,
+            self.cookie_storage.as_ref(),
+            host,
+            user_agent,
+        ) {
+            Ok(()) if save => self.status_line = format!("User agent for {host} saved"),
+            Ok(()) => {
+                self.user_agent_input.clear();
+                self.status_line = format!("User agent for {host} reset");
+            }
+            Err(error) => self.last_error = Some(format!("User agent not saved: {error}")),
+        }
+    }
+
+    /// Saves persistent cookies once on shutdown; private-mode storage skips the write.
+    fn persist_cookies(&mut self) {
+        if let Some(storage) = self.cookie_storage.take()
+            && let Err(error) = save_cookies(&self.cache, &storage)
+        {
+            eprintln!("PixelDust cookie save failed: {error}");
+        }
+    }
+
     fn push_history(&mut self, url: String) {
         if let Some(index) = self.history_index {
             let keep_to = index.saturating_add(1);
             self.history.truncate(keep_to);
         }
 
-        if self.history.last().is_some_and(|existing| existing == &url) {
+        if self
+            .history
+            .last()
+            .is_some_and(|existing| existing.url == url)
+        {
             self.history_index = Some(self.history.len().saturating_sub(1));
             return;
         }
 
-        self.history.push(url);
+        self.history.push(HistoryEntry {
+            url,
+            visited_at: SystemTime::now(),
+        });
         self.history_index = Some(self.history.len().saturating_sub(1));
     }
 
@@ -198,8 +515,8 @@ impl BrowserUiApp {
 
         let next_index = index - 1;
         self.history_index = Some(next_index);
-        if let Some(url) = self.history.get(next_index).cloned() {
-            self.navigate(url, false);
+        if let Some(entry) = self.history.get(next_index).cloned() {
+            self.navigate(entry.url, false);
         }
     }
 
@@ -214,16 +531,158 @@ impl BrowserUiApp {
         }
 
         self.history_index = Some(next_index);
-        if let Some(url) = self.history.get(next_index).cloned() {
-            self.navigate(url, false);
+        if let Some(entry) = self.history.get(next_index).cloned() {
+            self.navigate(entry.url, false);
         }
     }
 
     fn reload(&mut self) {
+        self.reload_with_cache_mode(CacheMode::Normal);
+    }
+
+    /// Reloads without answering from the HTTP cache; fresh responses still update it.
+    fn hard_reload(&mut self) {
+        self.reload_with_cache_mode(CacheMode::Bypass);
+    }
+
+    fn reload_with_cache_mode(&mut self, cache_mode: CacheMode) {
         if let Some(current) = self.current_url.clone() {
-            self.navigate(current, false);
+            self.navigate_with_cache_mode(current, false, cache_mode);
         } else {
-            self.navigate(self.address_input.clone(), true);
+            self.navigate_with_cache_mode(self.address_input.clone(), true, cache_mode);
+        }
+    }
+
+    fn clear_browsing_data(&mut self, options: ClearOptions) {
+        if options.history {
+            clear_history_range(&mut self.history, options.range);
+            self.history_index = self.history.len().checked_sub(1);
+        }
+
+        match clear_stored_browsing_data(&self.cache, self.cookie_storage.as_ref(), options) {
+            Ok(()) => {
+                self.status_line = format!(
+                    "Cleared browsing data ({})",
+                    options.range.label().to_ascii_lowercase()
+                );
+            }
+            Err(error) => {
+                self.last_error = Some(format!("Failed to clear browsing data: {error}"));
+            }
+        }
+    }
+
+    fn render_auth_prompt(&mut self, ui: &mut egui::Ui) {
+        let Some(challenge) = self
+            .page_view
+            .as_ref()
+            .and_then(|page| page.auth_challenge.clone())
+        else {
+            return;
+        };
+
+        let mut submitted = false;
+        egui::Frame::group(ui.style()).show(ui, |ui| {
+            let realm = if challenge.realm.is_empty() {
+                String::new()
+            } else {
+                format!(" for \"{}\"", challenge.realm)
+            };
+            ui.label(format!("{} requires a sign-in{realm}.", challenge.origin));
+            ui.horizontal(|ui| {
+                ui.label("Username");
+                ui.text_edit_singleline(&mut self.auth_username);
+                ui.label("Password");
+                let password =
+                    ui.add(egui::TextEdit::singleline(&mut self.auth_password).password(true));
+                let pressed_enter =
+                    password.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter));
+                submitted = ui.button("Sign in").clicked() || pressed_enter;
+            });
+        });
+        ui.separator();
+
+        if submitted && !self.is_loading() {
+            store_basic_credentials(
+                &self.cache,
+                &challenge.origin,
+                &self.auth_username,
+                &self.auth_password,
+            );
+            self.auth_password.clear();
+            self.reload();
+        }
+    }
+
+    /// Shows the blocklist interstitial in place of the page; returns whether it is active.
+    fn render_reputation_warning(
+        &mut self,
+        ui: &mut egui::Ui,
+        navigate_to: &mut Option<String>,
+    ) -> bool {
+        let Some(url) = self.reputation_warning.clone() else {
+            return false;
+        };
+
+        let mut go_back = false;
+        let mut proceed = false;
+        egui::Frame::group(ui.style())
+            .fill(egui::Color32::from_rgb(120, 20, 20))
+            .show(ui, |ui| {
+                ui.set_width(ui.available_width());
+                ui.heading(
+                    egui::RichText::new("Deceptive or dangerous site ahead")
+                        .color(egui::Color32::WHITE),
+                );
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{url} is on the blocklist of phishing and malware sites. \
+                         Visiting it may expose your passwords or install harmful software."
+                    ))
+                    .color(egui::Color32::WHITE),
+                );
+                ui.horizontal(|ui| {
+                    go_back = ui.button("Go back to safety").clicked();
+                    proceed = ui.button("Proceed anyway (unsafe)").clicked();
+                });
+            });
+
+        if go_back {
+            self.reputation_warning = None;
+            self.status_line = "Ready".to_owned();
+            if let Some(current) = &self.current_url {
+                self.address_input = current.clone();
+            }
+        } else if proceed {
+            self.reputation_proceed_once = Some(url.clone());
+            *navigate_to = Some(url);
+        }
+        true
+    }
+
+    fn can_save_page(&self) -> bool {
+        self.page_view
+            .as_ref()
+            .is_some_and(|page| page.html_document.is_some())
+    }
+
+    /// Saves next to other profile data, or under the temp dir when there is no profile.
+    fn save_page(&mut self) {
+        let Some(page) = self.page_view.as_ref() else {
+            return;
+        };
+        let directory = self
+            .cookie_storage
+            .as_ref()
+            .and_then(StorageManager::persistent_root)
+            .map_or_else(std::env::temp_dir, Path::to_path_buf)
+            .join(SAVED_PAGES_DIRECTORY);
+        match save_page_offline(page, &directory) {
+            Ok(path) => {
+                self.status_line = format!("Saved page to {}", path.display());
+                self.last_error = None;
+            }
+            Err(error) => self.last_error = Some(format!("Failed to save page: {error}")),
         }
     }
 
@@ -231,9 +690,72 @@ impl BrowserUiApp {
         self.inflight_request_id.is_some()
     }
 
+    /// Mirrors title changes from navigations and script events onto the window title.
+    fn sync_tab_title(&mut self, ctx: &egui::Context) {
+        if refresh_tab_title(&mut self.tab_title, self.page_view.as_ref()) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(format!(
+                "{} - {BROWSER_WINDOW_TITLE}",
+                self.tab_title
+            )));
+        }
+    }
+
+    /// Shows the active tab's favicon and title, both of which scripts may change after load.
+    fn render_tab_strip(&mut self, ui: &mut egui::Ui) {
+        match tab_favicon(self.page_view.as_ref()) {
+            Some(icon) => {
+                let texture = self
+                    .image_textures
+                    .entry(icon.url.clone())
+                    .or_insert_with(|| {
+                        ui.ctx().load_texture(
+                            format!("img:{}", icon.url),
+                            egui::ColorImage::from_rgba_unmultiplied(
+                                [icon.width, icon.height],
+                                &icon.rgba,
+                            ),
+                            egui::TextureOptions::LINEAR,
+                        )
+                    });
+                ui.add(egui::Image::new((texture.id(), egui::vec2(16.0, 16.0))));
+            }
+            None => {
+                ui.label("\u{1f310}");
+            }
+        }
+        let label = ui.strong(&self.tab_title);
+        if let Some(url) = self
+            .page_view
+            .as_ref()
+            .and_then(|page| page.favicon_url.as_deref())
+        {
+            label.on_hover_text(format!("Icon: {url}"));
+        }
+    }
+
+    /// Drops egui's widget animations while reduced motion is requested.
+    fn apply_motion_preference(&self, ctx: &egui::Context) {
+        let animation_time = if self.reduce_motion {
+            0.0
+        } else {
+            egui::Style::default().animation_time
+        };
+        if ctx.style().animation_time != animation_time {
+            ctx.style_mut(|style| style.animation_time = animation_time);
+        }
+    }
+
     fn render_viewport(&mut self, ui: &mut egui::Ui, navigate_to: &mut Option<String>) {
         let image_textures = &mut self.image_textures;
         let form_state = &mut self.form_state;
+        let print_preview = self.print_preview;
+        let reduce_motion = self.reduce_motion;
+        let font_size_limits = (self.min_font_size, self.max_font_size);
+        let prefers_dark = self.color_scheme.prefers_dark(ui.visuals().dark_mode);
+        let javascript_enabled = self.javascript_enabled;
+        let wrap_preview = &mut self.wrap_preview;
+        let preview_wrap_columns = &mut self.preview_wrap_columns;
+        let mut image_requests = Vec::new();
         match self.page_view.as_mut() {
             Some(page) => {
                 if let Some(title) = &page.title {
@@ -269,7 +791,11 @@ impl BrowserUiApp {
                         egui::Color32::from_rgb(200, 65, 65),
                         format!(
                             "JavaScript reported {} error(s). Press F12 for details.",
-                            page.js_execution.errors.len()
+                            page.js_execution
+                                .errors
+                                .iter()
+                                .map(|entry| entry.count)
+                                .sum::<usize>()
                         ),
                     );
                 }
@@ -278,7 +804,11 @@ impl BrowserUiApp {
                 }
                 ui.separator();
 
-                if let Some(doc) = page.html_document.as_ref() {
+                if let Some(doc) = page.html_document.as_mut() {
+                    doc.set_reduce_motion(reduce_motion);
+                    doc.set_prefers_dark(prefers_dark);
+                    doc.set_font_size_limits(font_size_limits.0, font_size_limits.1);
+                    let doc = &*doc;
                     let mut action = simple_html::RenderAction::default();
                     egui::ScrollArea::vertical()
                         .id_salt("viewport_html_scroll")
@@ -312,10 +842,22 @@ impl BrowserUiApp {
                                 }
                             }
 
+                            let pending_images: Vec<String> = page
+                                .deferred_images
+                                .iter()
+                                .chain(&page.loading_images)
+                                .cloned()
+                                .collect();
                             let resources = simple_html::RenderResources {
                                 images: &render_images,
+                                pending_images: &pending_images,
+                            };
+                            let render = if print_preview {
+                                simple_html::render_for_print
+                            } else {
+                                simple_html::render_document
                             };
-                            simple_html::render_document(
+                            render(
                                 ui,
                                 doc,
                                 &page.final_url,
@@ -324,10 +866,16 @@ impl BrowserUiApp {
                                 form_state,
                             );
                         });
-                    if action.navigate_to.is_some() {
+                    image_requests = std::mem::take(&mut action.image_requests);
+                    let outcome = if javascript_enabled {
+                        dispatch_dom_events(page, &action.dom_events)
+                    } else {
+                        DomDispatchOutcome::default()
+                    };
+                    if action.navigate_to.is_some() && !outcome.default_prevented {
                         *navigate_to = action.navigate_to;
                     }
-                    if let Some(js_nav) = dispatch_dom_events(page, &action.dom_events) {
+                    if let Some(js_nav) = outcome.navigate_to {
                         *navigate_to = Some(js_nav);
                     }
                     if let Some(fallback_text) = page.static_text_fallback.as_ref() {
@@ -367,17 +915,46 @@ impl BrowserUiApp {
                                     .color(egui::Color32::from_rgb(226, 226, 226)),
                             );
                         });
+                } else if page.content_kind == ContentKind::Download {
+                    ui.label(page.body_preview.as_str());
+                } else if page.content_kind == ContentKind::Json {
+                    ui.label("JSON response, pretty-printed.");
+                    egui::ScrollArea::both()
+                        .id_salt("viewport_json_scroll")
+                        .auto_shrink([false, false])
+                        .show(ui, |ui| {
+                            ui.add(egui::Label::new(json_layout_job(&page.body_preview)).extend());
+                        });
                 } else {
-                    ui.label("Non-HTML response, showing raw preview.");
-                    egui::ScrollArea::vertical()
+                    ui.horizontal(|ui| {
+                        ui.label("Non-HTML response, showing raw preview.");
+                        ui.checkbox(wrap_preview, "Wrap lines");
+                        ui.add_enabled(
+                            *wrap_preview,
+                            egui::DragValue::new(preview_wrap_columns)
+                                .range(20..=400)
+                                .suffix(" columns"),
+                        );
+                    });
+                    let preview = egui::Label::new(
+                        egui::RichText::new(page.body_preview.as_str())
+                            .monospace()
+                            .size(12.0),
+                    );
+                    egui::ScrollArea::both()
                         .id_salt("viewport_preview_scroll")
                         .auto_shrink([false, false])
                         .show(ui, |ui| {
-                            ui.label(
-                                egui::RichText::new(page.body_preview.as_str())
-                                    .monospace()
-                                    .size(12.0),
-                            );
+                            if *wrap_preview {
+                                // Soft wrap at the column width; the text itself is untouched,
+                                // so copying out of the preview gives the original lines.
+                                let font = egui::FontId::monospace(12.0);
+                                let column_width = ui.fonts(|fonts| fonts.glyph_width(&font, 'x'));
+                                ui.set_max_width(column_width * *preview_wrap_columns as f32);
+                                ui.add(preview.wrap_mode(egui::TextWrapMode::Wrap));
+                            } else {
+                                ui.add(preview.extend());
+                            }
                         });
                 }
             }
@@ -385,6 +962,10 @@ impl BrowserUiApp {
                 ui.label("No page loaded yet.");
             }
         }
+
+        for url in image_requests {
+            self.request_image(url);
+        }
     }
 
     fn render_navigation_details(&self, ui: &mut egui::Ui) {
@@ -417,13 +998,58 @@ impl BrowserUiApp {
                 format!("Runtime error: {error}"),
             );
         }
+        if let Some(crash) = &self.runtime_last_crash {
+            ui.collapsing("Last worker crash", |ui| {
+                ui.monospace(crash);
+            });
+        }
         ui.separator();
 
         if let Some(page) = &self.page_view {
             ui.label(format!("URL: {}", page.final_url));
-            ui.label(format!("Status: {}", page.status_code));
+            ui.label(format!(
+                "Status: {}",
+                status_label(page.status_code, &page.status_text)
+            ));
             ui.label(format!("HTTP Version: {}", page.http_version));
             ui.label(format!("Body Bytes: {}", page.body_bytes));
+            if let Some(doc) = page.html_document.as_ref() {
+                let meta = doc.meta_tags();
+                if let Some(description) = &meta.description {
+                    ui.label(format!("Description: {description}"));
+                }
+                if let Some(og_title) = &meta.og_title {
+                    ui.label(format!("OpenGraph title: {og_title}"));
+                }
+                if let Some(og_image) = &meta.og_image {
+                    let resolved = Url::parse(&page.final_url)
+                        .and_then(|base| base.join(og_image))
+                        .map_or_else(|_| og_image.clone(), |url| url.to_string());
+                    ui.label(format!("OpenGraph image: {resolved}"));
+                }
+                if let Some(color) = meta.theme_color {
+                    ui.horizontal(|ui| {
+                        ui.label("Theme color:");
+                        let (rect, _) =
+                            ui.allocate_exact_size(egui::vec2(14.0, 14.0), egui::Sense::hover());
+                        ui.painter().rect_filled(rect, 2.0, color);
+                        ui.monospace(format!(
+                            "#{:02x}{:02x}{:02x}",
+                            color.r(),
+                            color.g(),
+                            color.b()
+                        ));
+                    });
+                }
+            }
+            let millis = |duration: Option<Duration>| {
+                duration.map_or_else(|| "-".to_owned(), |d| format!("{} ms", d.as_millis()))
+            };
+            ui.label(format!(
+                "First contentful paint: {}, load: {}",
+                millis(first_contentful_paint(&page.paint_timings)),
+                millis(page.paint_timings.load_complete)
+            ));
             ui.label(format!(
                 "JavaScript: seen {}, ran {}, failed {}, skipped {}, events {}, event-failures {}",
                 page.js_execution.scripts_seen,
@@ -433,6 +1059,12 @@ impl BrowserUiApp {
                 page.js_execution.event_dispatches,
                 page.js_execution.event_failures
             ));
+            ui.label(format!(
+                "JavaScript failures: syntax {}, runtime {}, resource limit {}",
+                page.js_execution.syntax_errors,
+                page.js_execution.runtime_errors,
+                page.js_execution.resource_limit_errors
+            ));
             if let Some(draw_calls) = page.renderer_draw_calls {
                 ui.label(format!("Renderer baseline draw calls: {draw_calls}"));
             }
@@ -444,10 +1076,101 @@ impl BrowserUiApp {
                     .max_height(220.0)
                     .show(ui, |ui| {
                         for (index, error) in page.js_execution.errors.iter().enumerate() {
-                            ui.monospace(format!("{}. {}", index + 1, error));
+                            if error.count > 1 {
+                                ui.monospace(format!(
+                                    "{}. {} (x{})",
+                                    index + 1,
+                                    error.text,
+                                    error.count
+                                ));
+                            } else {
+                                ui.monospace(format!("{}. {}", index + 1, error.text));
+                            }
+                        }
+                    });
+            }
+            if !page.nav_log.entries.is_empty() {
+                ui.separator();
+                ui.label(format!(
+                    "Subresource Log ({} warning(s))",
+                    page.nav_log.warning_count()
+                ));
+                egui::ScrollArea::vertical()
+                    .id_salt("nav_log_scroll")
+                    .max_height(220.0)
+                    .show(ui, |ui| {
+                        for entry in &page.nav_log.entries {
+                            let line = entry.summary();
+                            if entry.level == NavLogLevel::Warning {
+                                ui.colored_label(egui::Color32::from_rgb(200, 140, 40), line);
+                            } else {
+                                ui.monospace(line);
+                            }
+                        }
+                        if page.nav_log.dropped > 0 {
+                            ui.label(format!("... {} more entries dropped", page.nav_log.dropped));
+                        }
+                    });
+            }
+            if !page.cookies.is_empty() {
+                ui.separator();
+                egui::CollapsingHeader::new(format!("Cookies ({})", page.cookies.len()))
+                    .id_salt("response_cookies")
+                    .show(ui, |ui| {
+                        let now = SystemTime::now();
+                        for cookie in &page.cookies {
+                            ui.horizontal_wrapped(|ui| {
+                                ui.monospace(format!("{}={}", cookie.name, cookie.value));
+                                let status = cookie.status_label();
+                                if matches!(cookie.status, CookieStatus::Rejected(_)) {
+                                    ui.colored_label(egui::Color32::from_rgb(200, 65, 65), status);
+                                } else {
+                                    ui.label(status);
+                                }
+                            });
+                            ui.small(cookie.attributes_summary(now));
                         }
                     });
             }
+            if !page.blocked_requests.is_empty() {
+                ui.separator();
+                egui::CollapsingHeader::new(format!(
+                    "Protections ({} blocked)",
+                    page.blocked_requests.len()
+                ))
+                .id_salt("protections")
+                .default_open(true)
+                .show(ui, |ui| {
+                    for (reason, count) in blocked_request_counts(&page.blocked_requests) {
+                        ui.label(format!("{}: {count}", reason.label()));
+                    }
+                    egui::ScrollArea::vertical()
+                        .id_salt("protections_scroll")
+                        .max_height(160.0)
+                        .show(ui, |ui| {
+                            for blocked in &page.blocked_requests {
+                                ui.monospace(format!(
+                                    "[{}] {}",
+                                    blocked.reason.label(),
+                                    blocked.url
+                                ));
+                            }
+                        });
+                });
+            }
+            if let Some(doc) = page.html_document.as_ref() {
+                ui.separator();
+                egui::CollapsingHeader::new("Resources")
+                    .id_salt("resource_manifest")
+                    .show(ui, |ui| {
+                        egui::ScrollArea::vertical()
+                            .id_salt("resource_manifest_scroll")
+                            .max_height(220.0)
+                            .show(ui, |ui| {
+                                ui.monospace(doc.resource_manifest_json(&page.final_url));
+                            });
+                    });
+            }
             ui.separator();
             ui.label("Response Headers");
             egui::ScrollArea::vertical()
@@ -466,6 +1189,7 @@ impl BrowserUiApp {
 
 impl Drop for BrowserUiApp {
     fn drop(&mut self) {
+        self.persist_cookies();
         self.shutdown_runtime();
     }
 }
@@ -473,17 +1197,28 @@ impl Drop for BrowserUiApp {
 impl eframe::App for BrowserUiApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         self.poll_navigation();
+        self.poll_image_loads();
         self.poll_runtime();
         if ctx.input(|input| input.key_pressed(egui::Key::F12)) {
             self.show_navigation_details = !self.show_navigation_details;
         }
-        if self.is_loading() {
+        if ctx.input_mut(|input| {
+            input.consume_key(
+                egui::Modifiers::COMMAND | egui::Modifiers::SHIFT,
+                egui::Key::R,
+            )
+        }) {
+            self.hard_reload();
+        }
+        self.apply_motion_preference(ctx);
+        if self.is_loading() || self.image_loads_inflight > 0 {
             ctx.request_repaint_after(Duration::from_millis(50));
         } else if self.runtime.is_some() {
             ctx.request_repaint_after(RUNTIME_POLL_INTERVAL);
         }
 
         egui::TopBottomPanel::top("toolbar_panel").show(ctx, |ui| {
+            ui.horizontal(|ui| self.render_tab_strip(ui));
             ui.horizontal(|ui| {
                 if ui
                     .add_enabled(self.can_go_back(), egui::Button::new("Back"))
@@ -497,8 +1232,77 @@ impl eframe::App for BrowserUiApp {
                 {
                     self.navigate_forward();
                 }
-                if ui.button("Reload").clicked() {
-                    self.reload();
+                let reload = ui
+                    .button("Reload")
+                    .on_hover_text("Shift-click or Ctrl+Shift+R to reload ignoring the cache");
+                if reload.clicked() {
+                    if ui.input(|input| input.modifiers.shift) {
+                        self.hard_reload();
+                    } else {
+                        self.reload();
+                    }
+                }
+                if ui
+                    .add_enabled(self.is_loading(), egui::Button::new("Stop"))
+                    .clicked()
+                {
+                    self.stop();
+                }
+                ui.toggle_value(&mut self.print_preview, "Print Preview");
+                ui.menu_button("Clear data", |ui| {
+                    ui.checkbox(&mut self.clear_options.cookies, "Cookies");
+                    ui.checkbox(&mut self.clear_options.cache, "Cached responses");
+                    ui.checkbox(&mut self.clear_options.history, "History");
+                    ui.checkbox(&mut self.clear_options.storage, "Site storage");
+                    ui.separator();
+                    for range in ClearRange::ALL {
+                        ui.radio_value(&mut self.clear_options.range, range, range.label());
+                    }
+                    ui.separator();
+                    if ui.button("Clear now").clicked() {
+                        self.clear_browsing_data(self.clear_options);
+                        ui.close_menu();
+                    }
+                });
+                let site_host = self
+                    .current_url
+                    .as_deref()
+                    .and_then(|url| Url::parse(url).ok())
+                    .and_then(|url| url.host_str().map(str::to_owned));
+                ui.add_enabled_ui(site_host.is_some(), |ui| {
+                    ui.menu_button("User agent", |ui| {
+                        let host = site_host.unwrap_or_default();
+                        ui.label(format!("Override for {host} and its subdomains"));
+                        ui.text_edit_singleline(&mut self.user_agent_input);
+                        ui.horizontal(|ui| {
+                            if ui.button("Save").clicked() {
+                                self.apply_user_agent_override(&host, true);
+                                ui.close_menu();
+                            }
+                            if ui.button("Reset").clicked() {
+                                self.apply_user_agent_override(&host, false);
+                                ui.close_menu();
+                            }
+                        });
+                    });
+                });
+                if ui
+                    .add_enabled(self.can_save_page(), egui::Button::new("Save page"))
+                    .clicked()
+                {
+                    self.save_page();
+                }
+
+                if let Some(security) = self
+                    .page_view
+                    .as_ref()
+                    .and_then(|page| page.security.clone())
+                {
+                    ui.menu_button(security.state.icon(), |ui| {
+                        render_security_info(ui, &security);
+                    })
+                    .response
+                    .on_hover_text(security.state.label());
                 }
 
                 let width = (ui.available_width() - 110.0).max(200.0);
@@ -536,6 +1340,50 @@ impl eframe::App for BrowserUiApp {
                 ui.selectable_value(&mut self.ocsp_required, true, "Required");
                 ui.selectable_value(&mut self.ocsp_required, false, "Optional");
 
+                ui.separator();
+                ui.checkbox(&mut self.load_images, "Images");
+                ui.checkbox(&mut self.honor_resource_hints, "Resource hints");
+                ui.checkbox(
+                    &mut self.block_third_party_scripts,
+                    "Block third-party scripts",
+                );
+                ui.checkbox(&mut self.javascript_enabled, "JavaScript");
+                ui.checkbox(&mut self.strip_tracking_params, "Strip tracking parameters");
+                if ui
+                    .checkbox(&mut self.heuristic_caching, "Heuristic caching")
+                    .on_hover_text("Cache responses that only send Last-Modified for a while")
+                    .changed()
+                {
+                    let mut guard = match self.cache.lock() {
+                        Ok(guard) => guard,
+                        Err(poisoned) => poisoned.into_inner(),
+                    };
+                    guard.heuristic_freshness = self.heuristic_caching;
+                }
+                ui.checkbox(&mut self.renderer_baseline, "Renderer baseline pass")
+                    .on_hover_text("Re-render through the baseline pipeline to count draw calls");
+                ui.checkbox(&mut self.reduce_motion, "Reduce motion");
+                egui::ComboBox::from_id_salt("color_scheme")
+                    .selected_text(self.color_scheme.label())
+                    .show_ui(ui, |ui| {
+                        for scheme in ColorScheme::ALL {
+                            ui.selectable_value(&mut self.color_scheme, scheme, scheme.label());
+                        }
+                    });
+                ui.label("Font size");
+                ui.add(
+                    egui::DragValue::new(&mut self.min_font_size)
+                        .range(1.0..=self.max_font_size)
+                        .prefix("min ")
+                        .suffix(" px"),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut self.max_font_size)
+                        .range(self.min_font_size..=512.0)
+                        .prefix("max ")
+                        .suffix(" px"),
+                );
+
                 ui.separator();
                 if let Some(url) = &self.current_url {
                     ui.label(format!("Current: {url}"));
@@ -546,7 +1394,11 @@ impl eframe::App for BrowserUiApp {
                 if self.is_loading() {
                     ui.separator();
                     ui.spinner();
-                    ui.label("Loading");
+                    ui.label(
+                        self.nav_progress
+                            .as_ref()
+                            .map_or_else(|| "Loading".to_owned(), NavigationState::label),
+                    );
                 }
 
                 if !self.runtime_workers.is_empty() {
@@ -590,7 +1442,11 @@ impl eframe::App for BrowserUiApp {
 
         egui::CentralPanel::default().show(ctx, |ui| {
             let mut navigate_to: Option<String> = None;
-            self.render_viewport(ui, &mut navigate_to);
+            self.render_auth_prompt(ui);
+            if !self.render_reputation_warning(ui, &mut navigate_to) {
+                self.render_viewport(ui, &mut navigate_to);
+            }
+            self.flush_image_requests();
 
             if let Some(url) = navigate_to {
                 if !self.is_loading() {
@@ -598,6 +1454,7 @@ impl eframe::App for BrowserUiApp {
                 }
             }
         });
+        self.sync_tab_title(ctx);
 
         if self.show_navigation_details {
             egui::Window::new("Navigation Details")
@@ -611,6 +1468,105 @@ impl eframe::App for BrowserUiApp {
     }
 
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.persist_cookies();
         self.shutdown_runtime();
     }
 }
+
+/// Lock-icon popover: page security state and the TLS session behind it.
+fn render_security_info(ui: &mut egui::Ui, security: &SecurityInfo) {
+    ui.strong(security.state.label());
+    ui.separator();
+    match &security.tls {
+        Some(tls) => {
+            ui.label(format!("Protocol: {}", tls.version.as_str()));
+            ui.label(format!("Verified by: {}", tls.trust_anchor.as_str()));
+            ui.label(if tls.ocsp_stapled {
+                "OCSP response stapled (not validated)"
+            } else {
+                "OCSP: no stapled response"
+            });
+            ui.label(format!(
+                "Issued to: {}",
+                tls.certificate_subject.as_deref().unwrap_or("unknown")
+            ));
+        }
+        None if security.state == SecurityState::Insecure => {
+            ui.label("Data sent to this site is not encrypted.");
+        }
+        None => {
+            ui.label("Loaded from cache; TLS details are unavailable.");
+        }
+    }
+    if security.mixed_content_blocked > 0 {
+        ui.colored_label(
+            egui::Color32::from_rgb(220, 160, 40),
+            format!(
+                "Blocked {} insecure subresource(s)",
+                security.mixed_content_blocked
+            ),
+        );
+    }
+}
+
+/// Colors keys, strings, numbers, and literals in pretty-printed JSON.
+fn json_layout_job(text: &str) -> egui::text::LayoutJob {
+    let font = egui::FontId::monospace(12.0);
+    let bytes = text.as_bytes();
+    let mut job = egui::text::LayoutJob::default();
+    let mut idx = 0_usize;
+    while idx < bytes.len() {
+        let start = idx;
+        let color = match bytes[idx] {
+            b'"' => {
+                idx += 1;
+                while idx < bytes.len() {
+                    match bytes[idx] {
+                        b'\\' => idx += 2,
+                        b'"' => {
+                            idx += 1;
+                            break;
+                        }
+                        _ => idx += 1,
+                    }
+                }
+                idx = idx.min(bytes.len());
+                let is_key = text[idx..].trim_start_matches(' ').starts_with(':');
+                if is_key {
+                    egui::Color32::from_rgb(156, 220, 254)
+                } else {
+                    egui::Color32::from_rgb(206, 145, 120)
+                }
+            }
+            b'-' | b'0'..=b'9' => {
+                while idx < bytes.len()
+                    && matches!(bytes[idx], b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E')
+                {
+                    idx += 1;
+                }
+                egui::Color32::from_rgb(181, 206, 168)
+            }
+            b't' | b'f' | b'n' => {
+                while idx < bytes.len() && bytes[idx].is_ascii_alphabetic() {
+                    idx += 1;
+                }
+                egui::Color32::from_rgb(86, 156, 214)
+            }
+            _ => {
+                idx += 1;
+                while idx < bytes.len()
+                    && !matches!(bytes[idx], b'"' | b'-' | b'0'..=b'9' | b't' | b'f' | b'n')
+                {
+                    idx += 1;
+                }
+                egui::Color32::from_rgb(212, 212, 212)
+            }
+        };
+        job.append(
+            &text[start..idx],
+            0.0,
+            egui::TextFormat::simple(font.clone(), color),
+        );
+    }
+    job
+}
@@ -8,7 +8,16 @@ use url::Url;
 pub struct HtmlDocument {
     pub root: HtmlElement,
     pub title: Option<String>,
+    /// Set when the tokenizer stopped at its node limit.
+    pub truncated: bool,
     styles: StyleSheet,
+    print_styles: StyleSheet,
+    style_sources: Vec<String>,
+    reduce_motion: bool,
+    prefers_dark: bool,
+    js_enabled: bool,
+    min_font_size: f32,
+    max_font_size: f32,
 }
 
 #[derive(Debug, Clone)]
@@ -18,6 +27,26 @@ pub struct HtmlElement {
     pub children: Vec<HtmlNode>,
 }
 
+impl HtmlElement {
+    /// Parses the `style` attribute into ordered `(name, value)` pairs with lowercase names.
+    pub fn inline_style_declarations(&self) -> Vec<(String, String)> {
+        let Some(style) = attr(self, "style") else {
+            return Vec::new();
+        };
+
+        split_css_top_level(style, ';')
+            .into_iter()
+            .filter_map(|chunk| {
+                let chunk = chunk.trim();
+                let colon = find_css_top_level_colon(chunk)?;
+                let name = chunk[..colon].trim().to_ascii_lowercase();
+                let value = chunk[(colon + 1)..].trim();
+                (!name.is_empty() && !value.is_empty()).then(|| (name, value.to_owned()))
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum HtmlNode {
     Element(HtmlElement),
@@ -41,13 +70,58 @@ enum Token {
 pub struct RenderAction {
     pub navigate_to: Option<String>,
     pub dom_events: Vec<DomEventRequest>,
+    /// Lazy images whose placeholders scrolled near the viewport this frame.
+    pub image_requests: Vec<String>,
+}
+
+/// Media type used to pick which `@media` blocks apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MediaType {
+    #[default]
+    Screen,
+    Print,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DomEventKind {
     Click,
     Input,
+    Change,
     Submit,
+    Focus,
+    Blur,
+    MouseOver,
+    KeyDown,
+}
+
+impl DomEventKind {
+    /// DOM `event.type` string.
+    pub fn event_type(self) -> &'static str {
+        match self {
+            Self::Click => "click",
+            Self::Input => "input",
+            Self::Change => "change",
+            Self::Submit => "submit",
+            Self::Focus => "focus",
+            Self::Blur => "blur",
+            Self::MouseOver => "mouseover",
+            Self::KeyDown => "keydown",
+        }
+    }
+
+    /// Inline handler attribute, e.g. `onchange`.
+    pub fn handler_attribute(self) -> &'static str {
+        match self {
+            Self::Click => "onclick",
+            Self::Input => "oninput",
+            Self::Change => "onchange",
+            Self::Submit => "onsubmit",
+            Self::Focus => "onfocus",
+            Self::Blur => "onblur",
+            Self::MouseOver => "onmouseover",
+            Self::KeyDown => "onkeydown",
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -57,7 +131,7 @@ pub struct DomEventRequest {
     pub inline_handler: String,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct RenderImage {
     pub texture_id: egui::TextureId,
     pub size: egui::Vec2,
@@ -66,16 +140,40 @@ pub struct RenderImage {
 #[derive(Debug)]
 pub struct RenderResources<'a> {
     pub images: &'a HashMap<String, RenderImage>,
+    /// Lazy images not yet requested or still in flight; other missing images have failed.
+    pub pending_images: &'a [String],
+}
+
+/// Page metadata declared by `<meta>` tags in the document head.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetaInfo {
+    pub description: Option<String>,
+    pub og_title: Option<String>,
+    /// Raw `og:image` content; callers resolve it against the page URL.
+    pub og_image: Option<String>,
+    pub theme_color: Option<egui::Color32>,
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct SubresourceManifest {
     pub stylesheets: Vec<String>,
     pub images: Vec<String>,
-    #[allow(dead_code)]
+    /// `<img loading="lazy">` sources that are fetched only once they near the viewport.
+    pub lazy_images: Vec<String>,
+    /// `rel="preload"`/`prefetch`/`modulepreload` targets; `nofollow` links are never listed.
+    pub preloads: Vec<String>,
     pub scripts: Vec<String>,
 }
 
+impl SubresourceManifest {
+    /// Drops resource-hint fetches unless the user opted in to honoring them.
+    pub fn apply_resource_hint_policy(&mut self, honor_resource_hints: bool) {
+        if !honor_resource_hints {
+            self.preloads.clear();
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ScriptDescriptor {
     External { url: String },
@@ -95,6 +193,18 @@ struct StyleSheet {
     rules: Vec<CssRule>,
 }
 
+impl StyleSheet {
+    /// Source order one past the last declaration in the sheet.
+    fn next_source_order(&self) -> usize {
+        self.rules
+            .iter()
+            .flat_map(|rule| &rule.declarations)
+            .map(|declaration| declaration.source_order.saturating_add(1))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
 #[derive(Debug, Clone)]
 struct CssRule {
     sel: Selector,
@@ -217,6 +327,22 @@ enum TextAlign {
     Justify,
 }
 
+/// Inline base direction from the HTML `dir` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextDirection {
+    Ltr,
+    Rtl,
+}
+
+impl TextDirection {
+    fn start_align(self) -> TextAlign {
+        match self {
+            Self::Ltr => TextAlign::Left,
+            Self::Rtl => TextAlign::Right,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum TextTransform {
     None,
@@ -555,9 +681,7 @@ fn canonical_element_tag(tag: &str) -> &str {
 #[cfg_attr(not(test), allow(dead_code))]
 fn is_mdn_reference_element(tag: &str) -> bool {
     let tag = canonical_element_tag(tag);
-    MDN_REFERENCE_ELEMENTS
-        .iter()
-        .any(|candidate| *candidate == tag)
+    MDN_REFERENCE_ELEMENTS.contains(&tag)
 }
 
 #[cfg_attr(not(test), allow(dead_code))]
@@ -639,6 +763,14 @@ fn is_non_rendered_element_tag(tag: &str) -> bool {
     )
 }
 
+/// `<noscript>` content only renders when scripting is disabled.
+fn is_skipped_render_tag(tag: &str, js_enabled: bool) -> bool {
+    if canonical_element_tag(tag) == "noscript" {
+        return js_enabled;
+    }
+    is_non_rendered_element_tag(tag)
+}
+
 fn is_static_fallback_ignored_tag(tag: &str) -> bool {
     matches!(
         canonical_element_tag(tag),
@@ -726,6 +858,7 @@ struct StyleProps {
     visibility_hidden: Option<bool>,
     opacity: Option<f32>,
     text_align: Option<TextAlign>,
+    direction: Option<TextDirection>,
     font_family: Option<FontFamilyChoice>,
     color: Option<egui::Color32>,
     bg: Option<egui::Color32>,
@@ -774,6 +907,7 @@ impl StyleProps {
             && self.visibility_hidden.is_none()
             && self.opacity.is_none()
             && self.text_align.is_none()
+            && self.direction.is_none()
             && self.font_family.is_none()
             && self.color.is_none()
             && self.bg.is_none()
@@ -826,9 +960,29 @@ impl StyleProps {
     }
 }
 
+/// User preferences that `@media` queries are evaluated against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct MediaEnvironment {
+    media: MediaType,
+    reduce_motion: bool,
+    prefers_dark: bool,
+}
+
+impl From<MediaType> for MediaEnvironment {
+    fn from(media: MediaType) -> Self {
+        Self {
+            media,
+            ..Self::default()
+        }
+    }
+}
+
+/// Cascade rank compared field by field: importance, then inline origin,
+/// then selector specificity, then source order.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 struct CascadePriority {
     important: bool,
+    inline: bool,
     specificity: u16,
     source_order: usize,
 }
@@ -897,7 +1051,27 @@ struct Ctx<'a> {
     form_state: &'a mut HashMap<String, String>,
     form_stack: Vec<FormRuntime>,
     form_fields: HashMap<String, HashMap<String, String>>,
+    form_rules: HashMap<String, HashMap<String, FormFieldRules>>,
     ancestor_stack: Vec<SelectorSubject>,
+    positioned: Vec<PositionedPaint<'a>>,
+    viewport: egui::Rect,
+    placing_positioned: bool,
+    js_enabled: bool,
+    min_font_size: f32,
+    max_font_size: f32,
+}
+
+/// An absolutely or fixed positioned element deferred until normal flow is painted.
+#[derive(Debug, Clone)]
+struct PositionedPaint<'a> {
+    element: &'a HtmlElement,
+    inherited: StyleProps,
+    ancestors: Vec<SelectorSubject>,
+    /// Computed style, kept for resolving the insets once the containing block is laid out.
+    style: StyleProps,
+    /// `None` until the nearest positioned ancestor (or the page) has been painted.
+    rect: Option<egui::Rect>,
+    z_index: i32,
 }
 
 #[derive(Debug, Clone)]
@@ -907,53 +1081,313 @@ struct FormRuntime {
     method: String,
     form_id: Option<String>,
     onsubmit: Option<String>,
+    novalidate: bool,
+    /// Enabled controls in document order with their `tabindex`.
+    focus_targets: Vec<(egui::Id, Option<i32>)>,
 }
 
+/// Upper bound on element nodes kept from a single document. Matches
+/// `pd_html::DEFAULT_MAX_NODES` so both parsers truncate a page at the same tag.
+const MAX_DOCUMENT_NODES: usize = 500_000;
+
+/// Deepest element nesting kept before further elements are flattened into the last ancestor.
+const MAX_TREE_DEPTH: usize = 256;
+
+/// Default bounds for computed font sizes, in CSS pixels before zoom.
+pub const DEFAULT_MIN_FONT_SIZE: f32 = 10.0;
+pub const DEFAULT_MAX_FONT_SIZE: f32 = 96.0;
+
 impl HtmlDocument {
     pub fn parse(source: &str) -> Self {
-        let tokens = tokenize(source);
-        let root = build_tree(tokens);
-        let styles = extract_styles(&root);
+        Self::parse_with_max_nodes(source, MAX_DOCUMENT_NODES)
+    }
+
+    /// Parses at most `max_nodes` elements, keeping the head of oversized documents.
+    pub fn parse_with_max_nodes(source: &str, max_nodes: usize) -> Self {
+        Self::parse_with_limits(source, max_nodes, MAX_TREE_DEPTH)
+    }
+
+    /// Like [`Self::parse_with_max_nodes`], but also caps element nesting at `max_tree_depth`.
+    pub fn parse_with_limits(source: &str, max_nodes: usize, max_tree_depth: usize) -> Self {
+        let (tokens, nodes_truncated) = tokenize(source, max_nodes);
+        let (root, depth_truncated) = build_tree(tokens, max_tree_depth);
+        let truncated = nodes_truncated || depth_truncated;
+        let css = extract_style_source(&root);
         let title = find_title(&root);
-        Self {
+        let mut document = Self {
             root,
             title,
-            styles,
-        }
+            truncated,
+            styles: StyleSheet::default(),
+            print_styles: StyleSheet::default(),
+            style_sources: Vec::new(),
+            reduce_motion: false,
+            prefers_dark: false,
+            js_enabled: true,
+            min_font_size: DEFAULT_MIN_FONT_SIZE,
+            max_font_size: DEFAULT_MAX_FONT_SIZE,
+        };
+        document.append_stylesheet_source(&css);
+        document
     }
 
+    /// Appends rules that cascade after every rule already in the document.
     pub fn append_stylesheet_source(&mut self, source: &str) {
-        self.styles.rules.extend(parse_css_rules(source));
+        self.style_sources.push(source.to_owned());
+        self.extend_stylesheets(source);
+    }
+
+    /// Re-evaluates every stylesheet when the reduced-motion preference changes.
+    pub fn set_reduce_motion(&mut self, reduce_motion: bool) {
+        if self.reduce_motion == reduce_motion {
+            return;
+        }
+        self.reduce_motion = reduce_motion;
+        self.rebuild_stylesheets();
+    }
+
+    /// Switches to dark default colors and re-evaluates `prefers-color-scheme` queries.
+    pub fn set_prefers_dark(&mut self, prefers_dark: bool) {
+        if self.prefers_dark == prefers_dark {
+            return;
+        }
+        self.prefers_dark = prefers_dark;
+        self.rebuild_stylesheets();
+    }
+
+    fn rebuild_stylesheets(&mut self) {
+        self.styles = StyleSheet::default();
+        self.print_styles = StyleSheet::default();
+        for source in std::mem::take(&mut self.style_sources) {
+            self.extend_stylesheets(&source);
+            self.style_sources.push(source);
+        }
+    }
+
+    /// Renders `<noscript>` content when scripting is off or never ran.
+    pub fn set_js_enabled(&mut self, js_enabled: bool) {
+        self.js_enabled = js_enabled;
+    }
+
+    /// Keeps page-specified font sizes between `min` and `max` pixels.
+    pub fn set_font_size_limits(&mut self, min: f32, max: f32) {
+        self.min_font_size = min;
+        self.max_font_size = max;
+    }
+
+    fn extend_stylesheets(&mut self, source: &str) {
+        let screen = MediaEnvironment {
+            media: MediaType::Screen,
+            reduce_motion: self.reduce_motion,
+            prefers_dark: self.prefers_dark,
+        };
+        let screen_order = self.styles.next_source_order();
+        self.styles
+            .rules
+            .extend(parse_css_rules_from_order(source, screen, screen_order));
+        let print = MediaEnvironment {
+            media: MediaType::Print,
+            prefers_dark: false,
+            ..screen
+        };
+        let print_order = self.print_styles.next_source_order();
+        self.print_styles
+            .rules
+            .extend(parse_css_rules_from_order(source, print, print_order));
+    }
+
+    /// Reads the description, OpenGraph title/image and `theme-color` from `<head>`.
+    pub fn meta_tags(&self) -> MetaInfo {
+        let head = find_first_element(&self.root.children, "head")
+            .map_or(self.root.children.as_slice(), |head| {
+                head.children.as_slice()
+            });
+        let mut meta = MetaInfo::default();
+        collect_meta_tags(head, &mut meta);
+        meta
     }
 
     pub fn collect_subresources(&self, base_url: &str) -> SubresourceManifest {
         let mut stylesheets = HashSet::new();
         let mut images = HashSet::new();
+        let mut lazy_images = HashSet::new();
+        let mut preloads = HashSet::new();
         let mut scripts = HashSet::new();
 
-        collect_subresources_from_nodes(
-            &self.root.children,
-            base_url,
-            &mut stylesheets,
-            &mut images,
-            &mut scripts,
-        );
+        let mut resources = Vec::new();
+        discover_resources(&self.root.children, base_url, &mut resources);
+        for resource in resources {
+            match resource {
+                DiscoveredResource::Image {
+                    url,
+                    lazy,
+                    primary: true,
+                    ..
+                } => {
+                    if lazy {
+                        lazy_images.insert(url);
+                    } else {
+                        images.insert(url);
+                    }
+                }
+                DiscoveredResource::Stylesheet(url) => {
+                    stylesheets.insert(url);
+                }
+                DiscoveredResource::ExternalScript(url) => {
+                    scripts.insert(url);
+                }
+                DiscoveredResource::Preload { url, .. } => {
+                    preloads.insert(url);
+                }
+                _ => {}
+            }
+        }
+        lazy_images.retain(|url| !images.contains(url));
+        preloads.retain(|url| !stylesheets.contains(url) && !scripts.contains(url));
 
         let mut stylesheets = stylesheets.into_iter().collect::<Vec<_>>();
         let mut images = images.into_iter().collect::<Vec<_>>();
+        let mut lazy_images = lazy_images.into_iter().collect::<Vec<_>>();
+        let mut preloads = preloads.into_iter().collect::<Vec<_>>();
         let mut scripts = scripts.into_iter().collect::<Vec<_>>();
 
         stylesheets.sort();
         images.sort();
+        lazy_images.sort();
+        preloads.sort();
         scripts.sort();
 
         SubresourceManifest {
             stylesheets,
             images,
+            lazy_images,
+            preloads,
             scripts,
         }
     }
 
+    /// Serializes every discovered subresource, grouped by category, with resolved URLs.
+    pub fn resource_manifest_json(&self, base_url: &str) -> String {
+        let mut resources = Vec::new();
+        discover_resources(&self.root.children, base_url, &mut resources);
+
+        let mut manifest = ResourceManifestEntries::default();
+        for resource in &resources {
+            match resource {
+                DiscoveredResource::Image {
+                    kind,
+                    url,
+                    lazy,
+                    descriptor,
+                    ..
+                } => {
+                    let mut fields = vec![("type", *kind), ("url", url.as_str())];
+                    if matches!(*kind, "img" | "source") {
+                        fields.push(("loading", if *lazy { "lazy" } else { "eager" }));
+                    }
+                    if let Some(descriptor) = descriptor {
+                        fields.push(("descriptor", descriptor));
+                    }
+                    manifest.images.push(resource_entry_json(&fields));
+                }
+                DiscoveredResource::Stylesheet(url) => {
+                    manifest
+                        .stylesheets
+                        .push(resource_entry_json(&[("url", url)]));
+                }
+                DiscoveredResource::ExternalScript(url) => {
+                    manifest
+                        .scripts
+                        .push(resource_entry_json(&[("type", "external"), ("url", url)]));
+                }
+                DiscoveredResource::InlineScript { bytes } => {
+                    manifest
+                        .scripts
+                        .push(format!("{{\"type\":\"inline\",\"bytes\":{bytes}}}"));
+                }
+                DiscoveredResource::Icon { rel, url } => {
+                    manifest
+                        .icons
+                        .push(resource_entry_json(&[("rel", rel), ("url", url)]));
+                }
+                DiscoveredResource::Preconnect { rel, url } => {
+                    manifest
+                        .preconnects
+                        .push(resource_entry_json(&[("rel", rel), ("url", url)]));
+                }
+                DiscoveredResource::Preload { rel, url, kind } => {
+                    manifest.preloads.push(resource_entry_json(&[
+                        ("rel", rel),
+                        ("url", url),
+                        ("as", kind),
+                    ]));
+                }
+            }
+        }
+
+        let categories = [
+            ("stylesheets", &manifest.stylesheets),
+            ("scripts", &manifest.scripts),
+            ("images", &manifest.images),
+            ("icons", &manifest.icons),
+            ("preconnects", &manifest.preconnects),
+            ("preloads", &manifest.preloads),
+        ];
+        let mut out = String::from("{");
+        for (index, (name, entries)) in categories.iter().enumerate() {
+            if index > 0 {
+                out.push(',');
+            }
+            push_json_string(&mut out, name);
+            out.push_str(":[");
+            out.push_str(&entries.join(","));
+            out.push(']');
+        }
+        out.push('}');
+        out
+    }
+
+    /// Serializes the parsed tree back to markup, escaping text and attribute values.
+    pub fn to_html(&self) -> String {
+        let mut out = String::from("<!DOCTYPE html>");
+        serialize_nodes(&self.root.children, false, &mut out);
+        out
+    }
+
+    /// Serializes a copy that renders offline: fetched stylesheets are inlined into `<head>`,
+    /// images found in `image_data_urls` (keyed by resolved URL) are swapped for data URLs,
+    /// scripts are dropped and relative URLs are made absolute against `base_url`.
+    pub fn to_offline_html(
+        &self,
+        base_url: &str,
+        image_data_urls: &HashMap<String, String>,
+    ) -> String {
+        let mut offline = self.clone();
+        let root = &mut offline.root;
+        inline_offline_resources(&mut root.children, base_url, image_data_urls);
+
+        // The first source is the document's own `<style>` text, which is still in the tree.
+        let fetched_css = self
+            .style_sources
+            .iter()
+            .skip(1)
+            .cloned()
+            .collect::<Vec<_>>();
+        if !fetched_css.is_empty() {
+            let style = HtmlNode::Element(HtmlElement {
+                tag: "style".to_owned(),
+                attrs: Vec::new(),
+                children: vec![HtmlNode::Text(fetched_css.join("\n"))],
+            });
+            match find_first_element_mut(&mut root.children, "head") {
+                Some(head) => head.children.push(style),
+                None => root.children.insert(0, style),
+            }
+        }
+
+        offline.to_html()
+    }
+
     pub fn css_rule_count(&self) -> usize {
         self.styles.rules.len()
     }
@@ -977,6 +1411,67 @@ impl HtmlDocument {
         out
     }
 
+    /// Sets or replaces one attribute on the element with `id`.
+    pub fn set_element_attribute(&mut self, id: &str, name: &str, value: &str) -> bool {
+        let Some(el) = find_element_by_id_mut(&mut self.root.children, id) else {
+            return false;
+        };
+
+        let name = name.trim().to_ascii_lowercase();
+        match el.attrs.iter_mut().find(|(existing, _)| *existing == name) {
+            Some((_, existing)) => value.clone_into(existing),
+            None => el.attrs.push((name, value.to_owned())),
+        }
+        true
+    }
+
+    /// Appends `<link rel href>` to `<head>`, or to the document root when there is none.
+    pub fn append_head_link(&mut self, rel: &str, href: &str) {
+        let link = HtmlNode::Element(HtmlElement {
+            tag: "link".to_owned(),
+            attrs: vec![
+                ("rel".to_owned(), rel.to_owned()),
+                ("href".to_owned(), href.to_owned()),
+            ],
+            children: Vec::new(),
+        });
+        match find_first_element_mut(&mut self.root.children, "head") {
+            Some(head) => head.children.push(link),
+            None => self.root.children.push(link),
+        }
+    }
+
+    /// `href` of the last `<link>` whose `rel` includes `icon`, as written in the markup.
+    pub fn favicon_href(&self) -> Option<&str> {
+        last_icon_link_href(&self.root.children)
+    }
+
+    /// Sets or, for an empty `value`, removes one declaration in the element's `style` attribute.
+    pub fn set_inline_style_property(&mut self, id: &str, property: &str, value: &str) -> bool {
+        let Some(el) = find_element_by_id_mut(&mut self.root.children, id) else {
+            return false;
+        };
+
+        let property = property.trim().to_ascii_lowercase();
+        let mut declarations = el
+            .inline_style_declarations()
+            .into_iter()
+            .filter(|(name, _)| *name != property)
+            .map(|(name, value)| format!("{name}: {value}"))
+            .collect::<Vec<_>>();
+        let value = value.trim();
+        if !value.is_empty() {
+            declarations.push(format!("{property}: {value}"));
+        }
+
+        let style = declarations.join("; ");
+        match el.attrs.iter_mut().find(|(name, _)| name == "style") {
+            Some((_, existing)) => *existing = style,
+            None => el.attrs.push(("style".to_owned(), style)),
+        }
+        true
+    }
+
     #[cfg(test)]
     pub fn visible_text_len(&self) -> usize {
         let text = if let Some(body) = find_first_element(&self.root.children, "body") {
@@ -1026,6 +1521,37 @@ impl HtmlDocument {
 
         collapsed.chars().take(max_chars).collect()
     }
+
+    /// Serializes the perceivable element tree as compact JSON (`role`, `name`, `children`).
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn to_accessibility_json(&self) -> String {
+        let inherited = StyleProps::default();
+        let mut ancestors = Vec::new();
+        let mut children = Vec::new();
+        if let Some(body) = find_first_element(&self.root.children, "body") {
+            let body_style = style_for(body, &self.styles, &inherited, &ancestors);
+            if !style_suppresses_rendering(&body_style) {
+                ancestors.push(selector_subject(body));
+                collect_accessibility_nodes(
+                    &body.children,
+                    &self.styles,
+                    &body_style,
+                    &mut ancestors,
+                    &mut children,
+                );
+            }
+        } else {
+            collect_accessibility_nodes(
+                &self.root.children,
+                &self.styles,
+                &inherited,
+                &mut ancestors,
+                &mut children,
+            );
+        }
+
+        accessibility_node_json("document", self.title.as_deref().unwrap_or(""), &children)
+    }
 }
 
 pub fn render_document(
@@ -1036,28 +1562,132 @@ pub fn render_document(
     action: &mut RenderAction,
     form_state: &mut HashMap<String, String>,
 ) {
-    // Match browser defaults regardless of host app theme.
+    render_document_with_styles(
+        ui,
+        doc,
+        MediaType::Screen,
+        base_url,
+        resources,
+        action,
+        form_state,
+    );
+}
+
+/// Default `(background, text)` colors for pages that do not set their own.
+fn default_page_colors(prefers_dark: bool) -> (egui::Color32, egui::Color32) {
+    if prefers_dark {
+        (
+            egui::Color32::from_rgb(18, 18, 18),
+            egui::Color32::from_gray(230),
+        )
+    } else {
+        (egui::Color32::WHITE, egui::Color32::BLACK)
+    }
+}
+
+const PRINT_PAGE_WIDTH: f32 = 680.0;
+const PRINT_PAGE_HEIGHT: f32 = 962.0;
+
+/// Renders the document with `@media print` rules onto fixed-size white pages.
+pub fn render_for_print(
+    ui: &mut egui::Ui,
+    doc: &HtmlDocument,
+    base_url: &str,
+    resources: &RenderResources<'_>,
+    action: &mut RenderAction,
+    form_state: &mut HashMap<String, String>,
+) {
     ui.painter()
-        .rect_filled(ui.max_rect(), 0.0, egui::Color32::WHITE);
+        .rect_filled(ui.max_rect(), 0.0, egui::Color32::from_gray(96));
+
+    let sheet = ui.vertical(|ui| {
+        ui.set_width(PRINT_PAGE_WIDTH);
+        let top = ui.cursor().top();
+        render_document_with_styles(
+            ui,
+            doc,
+            MediaType::Print,
+            base_url,
+            resources,
+            action,
+            form_state,
+        );
+        let content_height = (ui.cursor().top() - top).max(1.0);
+        let pages = (content_height / PRINT_PAGE_HEIGHT).ceil().max(1.0);
+        ui.add_space((pages * PRINT_PAGE_HEIGHT - content_height).max(0.0));
+        pages as usize
+    });
+
+    let rect = sheet.response.rect;
+    let pages = sheet.inner;
+    let painter = ui.painter();
+    for page in 1..=pages {
+        let page_bottom = rect.top() + PRINT_PAGE_HEIGHT * page as f32;
+        painter.text(
+            egui::pos2(rect.right() - 8.0, page_bottom - 8.0),
+            egui::Align2::RIGHT_BOTTOM,
+            format!("Page {page} of {pages}"),
+            egui::FontId::proportional(11.0),
+            egui::Color32::from_gray(120),
+        );
+        if page < pages {
+            painter.hline(
+                rect.x_range(),
+                page_bottom,
+                egui::Stroke::new(2.0, egui::Color32::from_gray(96)),
+            );
+        }
+    }
+}
+
+fn render_document_with_styles(
+    ui: &mut egui::Ui,
+    doc: &HtmlDocument,
+    media: MediaType,
+    base_url: &str,
+    resources: &RenderResources<'_>,
+    action: &mut RenderAction,
+    form_state: &mut HashMap<String, String>,
+) {
+    let (styles, prefers_dark) = match media {
+        MediaType::Screen => (&doc.styles, doc.prefers_dark),
+        MediaType::Print => (&doc.print_styles, false),
+    };
+    let (page_bg, page_text) = default_page_colors(prefers_dark);
+    // Page defaults follow the document's color scheme, not the host app theme.
+    ui.painter().rect_filled(ui.max_rect(), 0.0, page_bg);
 
+    let viewport = ui.clip_rect();
+    let initial_containing_block = egui::Rect::from_min_size(ui.max_rect().min, viewport.size());
     let mut ctx = Ctx {
         base_url,
-        styles: &doc.styles,
+        styles,
         resources,
         action,
         form_state,
         form_stack: Vec::new(),
         form_fields: HashMap::new(),
+        form_rules: HashMap::new(),
         ancestor_stack: Vec::new(),
+        positioned: Vec::new(),
+        viewport,
+        placing_positioned: false,
+        js_enabled: doc.js_enabled,
+        min_font_size: doc.min_font_size,
+        max_font_size: doc.max_font_size,
     };
+    let root_direction = find_first_element(&doc.root.children, "html").and_then(element_direction);
     let inherited = StyleProps {
-        color: Some(egui::Color32::BLACK),
+        color: Some(page_text),
+        text_align: root_direction.map(TextDirection::start_align),
+        direction: root_direction,
         ..StyleProps::default()
     };
     if let Some(body) = find_first_element(&doc.root.children, "body") {
         let mut body_style = style_for(body, ctx.styles, &inherited, &ctx.ancestor_stack);
+        clamp_style_font_size(&mut body_style, &ctx);
         if body_style.bg.is_none() {
-            body_style.bg = Some(egui::Color32::WHITE);
+            body_style.bg = Some(page_bg);
         }
         if !matches!(body_style.display, Some(Display::None)) {
             render_box(ui, &body_style, |ui| {
@@ -1070,8 +1700,8 @@ pub fn render_document(
         }
     } else {
         let root_style = StyleProps {
-            bg: Some(egui::Color32::WHITE),
-            color: Some(egui::Color32::BLACK),
+            bg: Some(page_bg),
+            color: Some(page_text),
             ..StyleProps::default()
         };
         render_box(ui, &root_style, |ui| {
@@ -1080,117 +1710,256 @@ pub fn render_document(
             }
         });
     }
+    resolve_positioned_rects(&mut ctx.positioned, initial_containing_block);
+    paint_positioned_elements(ui, &mut ctx);
+}
+
+/// Paints deferred out-of-flow elements above normal flow, lowest z-index first.
+fn paint_positioned_elements(ui: &mut egui::Ui, ctx: &mut Ctx<'_>) {
+    while !ctx.positioned.is_empty() {
+        let pending = positioned_paint_order(std::mem::take(&mut ctx.positioned));
+        for entry in pending {
+            let rect = entry.rect.unwrap_or(ctx.viewport);
+            let mut builder = egui::UiBuilder::new().max_rect(rect);
+            if let Some(order) = z_layer_order_for(entry.z_index) {
+                builder = builder.layer_id(egui::LayerId::new(
+                    order,
+                    ui.id()
+                        .with(("css-positioned", entry.z_index, ui.next_auto_id())),
+                ));
+            }
+            let mut child = ui.new_child(builder);
+            let saved_ancestors = std::mem::replace(&mut ctx.ancestor_stack, entry.ancestors);
+            ctx.placing_positioned = true;
+            render_element(&mut child, entry.element, ctx, &entry.inherited);
+            ctx.placing_positioned = false;
+            ctx.ancestor_stack = saved_ancestors;
+        }
+    }
+}
+
+/// Places deferred boxes still waiting on a containing block against `container`.
+fn resolve_positioned_rects(entries: &mut [PositionedPaint<'_>], container: egui::Rect) {
+    for entry in entries.iter_mut().filter(|entry| entry.rect.is_none()) {
+        entry.rect = Some(positioned_rect(container, &entry.style));
+    }
+}
+
+/// Orders deferred elements by z-index, keeping document order for ties.
+fn positioned_paint_order(mut entries: Vec<PositionedPaint<'_>>) -> Vec<PositionedPaint<'_>> {
+    entries.sort_by_key(|entry| entry.z_index);
+    entries
+}
+
+/// Resolves an out-of-flow box against its containing block using the parsed insets.
+fn positioned_rect(container: egui::Rect, style: &StyleProps) -> egui::Rect {
+    let left = style.inset_left;
+    let right = style.inset_right;
+    let top = style.inset_top;
+    let bottom = style.inset_bottom;
+
+    let width = style
+        .width
+        .or_else(|| {
+            style
+                .width_percent
+                .map(|percent| container.width() * (percent / 100.0))
+        })
+        .or_else(|| match (left, right) {
+            (Some(left), Some(right)) => Some(container.width() - left - right),
+            _ => None,
+        })
+        .unwrap_or_else(|| container.width() - left.or(right).unwrap_or(0.0))
+        .max(1.0);
+    let height = style.height.or_else(|| match (top, bottom) {
+        (Some(top), Some(bottom)) => Some(container.height() - top - bottom),
+        _ => None,
+    });
+
+    let x = match (left, right) {
+        (Some(left), _) => container.min.x + left,
+        (None, Some(right)) => container.max.x - right - width,
+        (None, None) => container.min.x,
+    };
+    let y = match (top, bottom) {
+        (Some(top), _) => container.min.y + top,
+        (None, Some(bottom)) => container.max.y - bottom - height.unwrap_or(0.0).max(0.0),
+        (None, None) => container.min.y,
+    };
+    let height = height.unwrap_or(container.max.y - y).max(1.0);
+    egui::Rect::from_min_size(egui::pos2(x, y), egui::vec2(width, height))
 }
 
-fn render_node(ui: &mut egui::Ui, node: &HtmlNode, ctx: &mut Ctx<'_>, inherited: &StyleProps) {
+fn render_node<'a>(
+    ui: &mut egui::Ui,
+    node: &'a HtmlNode,
+    ctx: &mut Ctx<'a>,
+    inherited: &StyleProps,
+) {
     match node {
         HtmlNode::Text(t) => render_text(ui, t, inherited, TextEffects::default()),
         HtmlNode::Element(el) => render_element(ui, el, ctx, inherited),
     }
 }
 
-fn render_element(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, inherited: &StyleProps) {
+fn render_element<'a>(
+    ui: &mut egui::Ui,
+    el: &'a HtmlElement,
+    ctx: &mut Ctx<'a>,
+    inherited: &StyleProps,
+) {
     let tag = canonical_element_tag(el.tag.as_str());
-    if is_non_rendered_element_tag(tag) {
+    if is_skipped_render_tag(tag, ctx.js_enabled) {
         return;
     }
 
-    if element_has_hidden_semantics(el) {
+    let mut style = style_for(el, ctx.styles, inherited, &ctx.ancestor_stack);
+    apply_semantic_text_style(tag, &mut style);
+    clamp_style_font_size(&mut style, ctx);
+    if is_effectively_hidden(el, &style) {
         return;
     }
 
-    let mut style = style_for(el, ctx.styles, inherited, &ctx.ancestor_stack);
-    apply_semantic_text_style(tag, &mut style);
-    if style_suppresses_rendering(&style) || is_likely_screen_reader_only(&style) {
+    let position = style.position.unwrap_or(PositionMode::Static);
+    let placing = std::mem::take(&mut ctx.placing_positioned);
+    if matches!(position, PositionMode::Absolute | PositionMode::Fixed) && !placing {
+        let rect =
+            matches!(position, PositionMode::Fixed).then(|| positioned_rect(ctx.viewport, &style));
+        ctx.positioned.push(PositionedPaint {
+            element: el,
+            inherited: inherited.clone(),
+            ancestors: ctx.ancestor_stack.clone(),
+            z_index: style.z_index.unwrap_or(0),
+            style,
+            rect,
+        });
         return;
     }
 
     ctx.ancestor_stack.push(selector_subject(el));
+    if matches!(position, PositionMode::Static) {
+        render_element_content(ui, el, tag, ctx, &style);
+    } else {
+        // Out-of-flow descendants deferred while painting this box are placed against it.
+        let first_pending = ctx.positioned.len();
+        let block = ui
+            .scope(|ui| render_element_content(ui, el, tag, ctx, &style))
+            .response
+            .rect;
+        resolve_positioned_rects(&mut ctx.positioned[first_pending..], block);
+    }
+    ctx.ancestor_stack.pop();
+}
+
+fn render_element_content<'a>(
+    ui: &mut egui::Ui,
+    el: &'a HtmlElement,
+    tag: &str,
+    ctx: &mut Ctx<'a>,
+    style: &StyleProps,
+) {
     match tag {
-        "h1" => render_heading(ui, el, &style, 32.0),
-        "h2" => render_heading(ui, el, &style, 28.0),
-        "h3" => render_heading(ui, el, &style, 24.0),
-        "h4" => render_heading(ui, el, &style, 20.0),
-        "h5" => render_heading(ui, el, &style, 18.0),
-        "h6" => render_heading(ui, el, &style, 16.0),
-        "hr" => render_horizontal_rule(ui, &style),
+        "h1" => render_heading(ui, el, style, 32.0),
+        "h2" => render_heading(ui, el, style, 28.0),
+        "h3" => render_heading(ui, el, style, 24.0),
+        "h4" => render_heading(ui, el, style, 20.0),
+        "h5" => render_heading(ui, el, style, 18.0),
+        "h6" => render_heading(ui, el, style, 16.0),
+        "hr" => render_horizontal_rule(ui, style),
         "p" => {
-            render_box(ui, &style, |ui| {
+            render_box(ui, style, |ui| {
                 if element_has_only_text_children(&el.children) {
                     let text = collect_text(&el.children);
                     if !text.trim().is_empty() {
-                        render_text_block(ui, &text, &style, TextEffects::default());
+                        render_text_block(ui, &text, style, TextEffects::default());
                     }
                 } else {
-                    render_inline_wrapped(ui, &el.children, ctx, &style);
+                    render_inline_wrapped(ui, &el.children, ctx, style);
                 }
             });
-            add_default_bottom_spacing(ui, &style, 4.0);
+            add_default_bottom_spacing(ui, style, 4.0);
         }
         "br" => ui.add_space(2.0),
-        "pre" => render_pre(ui, el, &style),
-        "blockquote" => render_blockquote(ui, el, ctx, &style),
-        "details" => render_details(ui, el, ctx, &style),
-        "summary" => render_summary(ui, el, ctx, &style),
-        "dl" => render_definition_list(ui, el, ctx, &style),
-        "dt" => render_definition_term(ui, el, ctx, &style),
-        "dd" => render_definition_description(ui, el, ctx, &style),
-        "form" => render_form(ui, el, ctx, &style),
-        "center" => render_center(ui, el, ctx, &style),
+        "pre" => render_pre(ui, el, style),
+        "blockquote" => render_blockquote(ui, el, ctx, style),
+        "details" => render_details(ui, el, ctx, style),
+        "summary" => render_summary(ui, el, ctx, style),
+        "dl" => render_definition_list(ui, el, ctx, style),
+        "dt" => render_definition_term(ui, el, ctx, style),
+        "dd" => render_definition_description(ui, el, ctx, style),
+        "form" => render_form(ui, el, ctx, style),
+        "center" => render_center(ui, el, ctx, style),
         "dialog" => {
-            render_box(ui, &style, |ui| {
+            render_box(ui, style, |ui| {
                 for child in &el.children {
-                    render_node(ui, child, ctx, &style);
+                    render_node(ui, child, ctx, style);
                 }
             });
-            add_default_bottom_spacing(ui, &style, 2.0);
-        }
-        "table" => render_table(ui, el, ctx, &style),
-        "tr" => render_table_row(ui, el, ctx, &style, 0.0, 0.0),
-        "td" | "th" => render_table_cell(ui, el, ctx, &style, None, ui.available_width(), 0.0),
-        "ul" => render_list(ui, el, false, ctx, &style),
-        "ol" => render_list(ui, el, true, ctx, &style),
-        "menu" | "dir" => render_list(ui, el, false, ctx, &style),
+            add_default_bottom_spacing(ui, style, 2.0);
+        }
+        "table" => render_table(ui, el, ctx, style),
+        "tr" => {
+            let cells = table_row_cells(el);
+            let grid = build_table_grid(&[table_cell_spans(&cells)]);
+            let layout = table_row_layout(grid.rows.first().map(Vec::as_slice).unwrap_or_default());
+            render_table_row(
+                ui,
+                el,
+                &cells,
+                &layout,
+                grid.column_count,
+                ctx,
+                style,
+                TableSpacing {
+                    cell_spacing: 0.0,
+                    cell_padding: 0.0,
+                },
+            );
+        }
+        "td" | "th" => render_table_cell(ui, el, ctx, style, None, ui.available_width(), 0.0),
+        "ul" => render_list(ui, el, false, ctx, style),
+        "ol" => render_list(ui, el, true, ctx, style),
+        "menu" | "dir" => render_list(ui, el, false, ctx, style),
         "li" => {
-            render_box(ui, &style, |ui| {
-                if is_rtl_layout(&style) {
+            render_box(ui, style, |ui| {
+                if is_rtl_layout(style) {
                     ui.horizontal_wrapped(|ui| {
-                        render_inline(ui, &el.children, ctx, &style);
+                        render_inline(ui, &el.children, ctx, style);
                         ui.label("*");
                     });
                 } else {
                     ui.horizontal_wrapped(|ui| {
                         ui.label("*");
-                        render_inline(ui, &el.children, ctx, &style);
+                        render_inline(ui, &el.children, ctx, style);
                     });
                 }
             });
         }
-        "img" => render_img(ui, el, ctx, &style),
-        "input" => render_input(ui, el, ctx, &style, false),
-        "keygen" => render_input(ui, el, ctx, &style, false),
-        "button" => render_button(ui, el, ctx, &style, false),
-        "menuitem" => render_button(ui, el, ctx, &style, false),
-        "textarea" => render_textarea(ui, el, ctx, &style, false),
-        "select" => render_select(ui, el, ctx, &style, false),
+        "img" => render_img(ui, el, ctx, style),
+        "input" => render_input(ui, el, ctx, style, false),
+        "keygen" => render_input(ui, el, ctx, style, false),
+        "button" => render_button(ui, el, ctx, style, false),
+        "menuitem" => render_button(ui, el, ctx, style, false),
+        "textarea" => render_textarea(ui, el, ctx, style, false),
+        "select" => render_select(ui, el, ctx, style, false),
         "video" | "audio" | "canvas" | "svg" | "math" | "iframe" | "fencedframe" | "portal"
-        | "frame" | "embed" | "object" | "applet" => render_embedded_content(ui, el, ctx, &style),
-        "plaintext" | "xmp" => render_pre(ui, el, &style),
+        | "frame" | "embed" | "object" | "applet" => render_embedded_content(ui, el, ctx, style),
+        "plaintext" | "xmp" => render_pre(ui, el, style),
         "a" => {
             if anchor_has_element_children(el) {
-                render_anchor_container(ui, el, ctx, &style);
+                render_anchor_container(ui, el, ctx, style);
             } else {
-                render_box(ui, &style, |ui| {
-                    if is_rtl_layout(&style) {
+                render_box(ui, style, |ui| {
+                    if is_rtl_layout(style) {
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
-                            render_link(ui, el, ctx, &style);
+                            render_link(ui, el, ctx, style);
                         });
                     } else {
-                        ui.horizontal_wrapped(|ui| render_link(ui, el, ctx, &style));
+                        ui.horizontal_wrapped(|ui| render_link(ui, el, ctx, style));
                     }
                 });
             }
-            add_default_bottom_spacing(ui, &style, 2.0);
+            add_default_bottom_spacing(ui, style, 2.0);
         }
         _ => {
             let display = style.display.unwrap_or_else(|| {
@@ -1202,74 +1971,66 @@ fn render_element(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, inheri
             });
             match display {
                 Display::Block => {
-                    render_box(ui, &style, |ui| {
+                    render_box(ui, style, |ui| {
                         for child in &el.children {
-                            render_node(ui, child, ctx, &style);
+                            render_node(ui, child, ctx, style);
                         }
                     });
-                    add_default_bottom_spacing(ui, &style, 2.0);
+                    add_default_bottom_spacing(ui, style, 2.0);
                 }
                 Display::Flex => {
-                    render_flex(ui, el, ctx, &style);
-                    add_default_bottom_spacing(ui, &style, 2.0);
+                    render_flex(ui, el, ctx, style);
+                    add_default_bottom_spacing(ui, style, 2.0);
                 }
                 Display::Grid => {
-                    render_grid(ui, el, ctx, &style);
-                    add_default_bottom_spacing(ui, &style, 2.0);
+                    render_grid(ui, el, ctx, style);
+                    add_default_bottom_spacing(ui, style, 2.0);
                 }
                 Display::Inline => {
-                    ui.horizontal_wrapped(|ui| render_inline(ui, &el.children, ctx, &style));
+                    ui.horizontal_wrapped(|ui| render_inline(ui, &el.children, ctx, style));
                 }
                 Display::None => {}
             }
         }
     }
-    ctx.ancestor_stack.pop();
 }
 
 fn apply_semantic_text_style(tag: &str, style: &mut StyleProps) {
     match tag {
-        "strong" | "b" => {
-            if style.bold.is_none() {
-                style.bold = Some(true);
-            }
+        "strong" | "b" if style.bold.is_none() => {
+            style.bold = Some(true);
         }
-        "em" | "i" | "cite" => {
-            if style.italic.is_none() {
-                style.italic = Some(true);
-            }
+        "em" | "i" | "cite" if style.italic.is_none() => {
+            style.italic = Some(true);
         }
-        "u" | "ins" => {
-            if style.underline.is_none() {
-                style.underline = Some(true);
-            }
+        "u" | "ins" if style.underline.is_none() => {
+            style.underline = Some(true);
         }
-        "s" | "strike" | "del" => {
-            if style.strike.is_none() {
-                style.strike = Some(true);
-            }
+        "s" | "strike" | "del" if style.strike.is_none() => {
+            style.strike = Some(true);
         }
         _ => {}
     }
 }
 
-fn render_inline(ui: &mut egui::Ui, nodes: &[HtmlNode], ctx: &mut Ctx<'_>, inherited: &StyleProps) {
+fn render_inline<'a>(
+    ui: &mut egui::Ui,
+    nodes: &'a [HtmlNode],
+    ctx: &mut Ctx<'a>,
+    inherited: &StyleProps,
+) {
     for node in nodes {
         match node {
             HtmlNode::Text(t) => render_text(ui, t, inherited, TextEffects::default()),
             HtmlNode::Element(el) => {
                 let tag = canonical_element_tag(el.tag.as_str());
                 let style = style_for(el, ctx.styles, inherited, &ctx.ancestor_stack);
-                if is_non_rendered_element_tag(tag)
-                    || element_has_hidden_semantics(el)
-                    || style_suppresses_rendering(&style)
-                    || is_likely_screen_reader_only(&style)
-                {
+                if is_skipped_render_tag(tag, ctx.js_enabled) || is_effectively_hidden(el, &style) {
                     continue;
                 }
                 match tag {
                     "strong" | "b" => {
-                        let t = collapse_whitespace(&collect_text(&el.children));
+                        let t = white_space_text(&collect_rendered_text(&el.children), &style);
                         if !t.is_empty() {
                             render_text(
                                 ui,
@@ -1283,7 +2044,7 @@ fn render_inline(ui: &mut egui::Ui, nodes: &[HtmlNode], ctx: &mut Ctx<'_>, inher
                         }
                     }
                     "em" | "i" => {
-                        let t = collapse_whitespace(&collect_text(&el.children));
+                        let t = white_space_text(&collect_rendered_text(&el.children), &style);
                         if !t.is_empty() {
                             render_text(
                                 ui,
@@ -1297,7 +2058,7 @@ fn render_inline(ui: &mut egui::Ui, nodes: &[HtmlNode], ctx: &mut Ctx<'_>, inher
                         }
                     }
                     "u" | "ins" => {
-                        let t = collapse_whitespace(&collect_text(&el.children));
+                        let t = white_space_text(&collect_rendered_text(&el.children), &style);
                         if !t.is_empty() {
                             render_text(
                                 ui,
@@ -1311,7 +2072,7 @@ fn render_inline(ui: &mut egui::Ui, nodes: &[HtmlNode], ctx: &mut Ctx<'_>, inher
                         }
                     }
                     "del" => {
-                        let t = collapse_whitespace(&collect_text(&el.children));
+                        let t = white_space_text(&collect_rendered_text(&el.children), &style);
                         if !t.is_empty() {
                             render_text(
                                 ui,
@@ -1325,7 +2086,7 @@ fn render_inline(ui: &mut egui::Ui, nodes: &[HtmlNode], ctx: &mut Ctx<'_>, inher
                         }
                     }
                     "s" => {
-                        let t = collapse_whitespace(&collect_text(&el.children));
+                        let t = white_space_text(&collect_rendered_text(&el.children), &style);
                         if !t.is_empty() {
                             render_text(
                                 ui,
@@ -1339,7 +2100,7 @@ fn render_inline(ui: &mut egui::Ui, nodes: &[HtmlNode], ctx: &mut Ctx<'_>, inher
                         }
                     }
                     "mark" => {
-                        let t = collapse_whitespace(&collect_text(&el.children));
+                        let t = white_space_text(&collect_rendered_text(&el.children), &style);
                         if !t.is_empty() {
                             render_text(
                                 ui,
@@ -1353,7 +2114,7 @@ fn render_inline(ui: &mut egui::Ui, nodes: &[HtmlNode], ctx: &mut Ctx<'_>, inher
                         }
                     }
                     "small" => {
-                        let t = collapse_whitespace(&collect_text(&el.children));
+                        let t = white_space_text(&collect_rendered_text(&el.children), &style);
                         if !t.is_empty() {
                             render_text(
                                 ui,
@@ -1367,7 +2128,7 @@ fn render_inline(ui: &mut egui::Ui, nodes: &[HtmlNode], ctx: &mut Ctx<'_>, inher
                         }
                     }
                     "sub" => {
-                        let t = collapse_whitespace(&collect_text(&el.children));
+                        let t = white_space_text(&collect_rendered_text(&el.children), &style);
                         if !t.is_empty() {
                             render_text(
                                 ui,
@@ -1382,7 +2143,7 @@ fn render_inline(ui: &mut egui::Ui, nodes: &[HtmlNode], ctx: &mut Ctx<'_>, inher
                         }
                     }
                     "sup" => {
-                        let t = collapse_whitespace(&collect_text(&el.children));
+                        let t = white_space_text(&collect_rendered_text(&el.children), &style);
                         if !t.is_empty() {
                             render_text(
                                 ui,
@@ -1397,7 +2158,7 @@ fn render_inline(ui: &mut egui::Ui, nodes: &[HtmlNode], ctx: &mut Ctx<'_>, inher
                         }
                     }
                     "code" | "tt" | "kbd" | "samp" | "var" => {
-                        let t = collect_text(&el.children);
+                        let t = collect_rendered_text(&el.children);
                         if !t.is_empty() {
                             render_text(
                                 ui,
@@ -1411,7 +2172,7 @@ fn render_inline(ui: &mut egui::Ui, nodes: &[HtmlNode], ctx: &mut Ctx<'_>, inher
                         }
                     }
                     "q" => {
-                        let t = collapse_whitespace(&collect_text(&el.children));
+                        let t = white_space_text(&collect_rendered_text(&el.children), &style);
                         if !t.is_empty() {
                             let quoted = format!("\"{t}\"");
                             render_text(ui, &quoted, &style, TextEffects::default());
@@ -1455,11 +2216,11 @@ fn render_inline(ui: &mut egui::Ui, nodes: &[HtmlNode], ctx: &mut Ctx<'_>, inher
         }
     }
 }
-fn render_link(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style: &StyleProps) {
+fn render_link<'a>(ui: &mut egui::Ui, el: &'a HtmlElement, ctx: &mut Ctx<'a>, style: &StyleProps) {
     let href = attr(el, "href").map(ToOwned::to_owned);
     let disabled = has_attr(el, "disabled") || has_attr(el, "inert");
     let text = {
-        let raw = collapse_whitespace(&collect_text(&el.children));
+        let raw = white_space_text(&collect_rendered_text(&el.children), style);
         if raw.is_empty() {
             attr(el, "aria-label")
                 .map(collapse_whitespace)
@@ -1493,8 +2254,10 @@ fn render_link(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style: &S
                     ..TextEffects::default()
                 },
             );
-            if ui.link(rich).clicked() {
-                emit_inline_event(ctx, DomEventKind::Click, el, "onclick");
+            let response = ui.link(rich);
+            emit_interaction_events(ui, ctx, el, &response);
+            if response.clicked() {
+                emit_inline_event(ctx, DomEventKind::Click, el);
                 ctx.action.navigate_to = Some(url);
             }
             return;
@@ -1513,10 +2276,10 @@ fn anchor_has_element_children(el: &HtmlElement) -> bool {
         .any(|node| matches!(node, HtmlNode::Element(_)))
 }
 
-fn render_anchor_container(
+fn render_anchor_container<'a>(
     ui: &mut egui::Ui,
-    el: &HtmlElement,
-    ctx: &mut Ctx<'_>,
+    el: &'a HtmlElement,
+    ctx: &mut Ctx<'a>,
     style: &StyleProps,
 ) {
     let href = attr(el, "href").and_then(|value| resolve_link(ctx.base_url, value));
@@ -1540,8 +2303,9 @@ fn render_anchor_container(
             if response.hovered() {
                 ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
             }
+            emit_interaction_events(ui, ctx, el, &response);
             if response.clicked() {
-                emit_inline_event(ctx, DomEventKind::Click, el, "onclick");
+                emit_inline_event(ctx, DomEventKind::Click, el);
                 ctx.action.navigate_to = Some(url.clone());
             }
         }
@@ -1549,7 +2313,7 @@ fn render_anchor_container(
 }
 
 fn render_heading(ui: &mut egui::Ui, el: &HtmlElement, style: &StyleProps, default_size: f32) {
-    let text = collapse_whitespace(&collect_text(&el.children));
+    let text = white_space_text(&collect_rendered_text(&el.children), style);
     if text.is_empty() {
         return;
     }
@@ -1562,13 +2326,21 @@ fn render_heading(ui: &mut egui::Ui, el: &HtmlElement, style: &StyleProps, defau
     }
 
     render_box(ui, style, |ui| {
-        add_aligned_label(ui, egui::Label::new(rich), style);
+        let wrap_mode = white_space_wrap_mode(style, &TextEffects::default());
+        add_aligned_label(ui, egui::Label::new(rich).wrap_mode(wrap_mode), style);
     });
     add_default_bottom_spacing(ui, style, 4.0);
 }
 
 fn render_pre(ui: &mut egui::Ui, el: &HtmlElement, style: &StyleProps) {
-    let text = collect_text(&el.children);
+    let effects = TextEffects {
+        mono: true,
+        ..TextEffects::default()
+    };
+    let text = apply_white_space(
+        &collect_rendered_text(&el.children),
+        effective_white_space_mode(style, &effects),
+    );
     if text.is_empty() {
         return;
     }
@@ -1587,7 +2359,7 @@ fn render_pre(ui: &mut egui::Ui, el: &HtmlElement, style: &StyleProps) {
         if let Some(v) = style.font_size {
             rich = rich.size(v);
         }
-        ui.label(rich);
+        ui.add(egui::Label::new(rich).wrap_mode(white_space_wrap_mode(style, &effects)));
     });
 
     if let Some(margin_bottom) = style.margin.bottom {
@@ -1620,7 +2392,12 @@ fn render_horizontal_rule(ui: &mut egui::Ui, style: &StyleProps) {
     add_default_bottom_spacing(ui, style, 6.0);
 }
 
-fn render_blockquote(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style: &StyleProps) {
+fn render_blockquote<'a>(
+    ui: &mut egui::Ui,
+    el: &'a HtmlElement,
+    ctx: &mut Ctx<'a>,
+    style: &StyleProps,
+) {
     let border = style
         .border_color
         .unwrap_or(egui::Color32::from_rgb(110, 130, 154));
@@ -1641,7 +2418,12 @@ fn render_blockquote(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, sty
     add_default_bottom_spacing(ui, style, 4.0);
 }
 
-fn render_details(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style: &StyleProps) {
+fn render_details<'a>(
+    ui: &mut egui::Ui,
+    el: &'a HtmlElement,
+    ctx: &mut Ctx<'a>,
+    style: &StyleProps,
+) {
     let summary = el
         .children
         .iter()
@@ -1652,7 +2434,7 @@ fn render_details(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style:
             if summary.tag != "summary" {
                 return None;
             }
-            let text = collapse_whitespace(&collect_text(&summary.children));
+            let text = collapse_whitespace(&collect_rendered_text(&summary.children));
             if text.is_empty() {
                 Some("Details".to_owned())
             } else {
@@ -1681,8 +2463,13 @@ fn render_details(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style:
     add_default_bottom_spacing(ui, style, 2.0);
 }
 
-fn render_summary(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style: &StyleProps) {
-    let text = collapse_whitespace(&collect_text(&el.children));
+fn render_summary<'a>(
+    ui: &mut egui::Ui,
+    el: &'a HtmlElement,
+    ctx: &mut Ctx<'a>,
+    style: &StyleProps,
+) {
+    let text = white_space_text(&collect_rendered_text(&el.children), style);
     if !text.is_empty() {
         render_box(ui, style, |ui| {
             ui.label(build_rich_text(
@@ -1704,10 +2491,10 @@ fn render_summary(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style:
     add_default_bottom_spacing(ui, style, 2.0);
 }
 
-fn render_definition_list(
+fn render_definition_list<'a>(
     ui: &mut egui::Ui,
-    el: &HtmlElement,
-    ctx: &mut Ctx<'_>,
+    el: &'a HtmlElement,
+    ctx: &mut Ctx<'a>,
     style: &StyleProps,
 ) {
     render_box(ui, style, |ui| {
@@ -1726,10 +2513,10 @@ fn render_definition_list(
     add_default_bottom_spacing(ui, style, 2.0);
 }
 
-fn render_definition_term(
+fn render_definition_term<'a>(
     ui: &mut egui::Ui,
-    el: &HtmlElement,
-    ctx: &mut Ctx<'_>,
+    el: &'a HtmlElement,
+    ctx: &mut Ctx<'a>,
     style: &StyleProps,
 ) {
     let mut term_style = style.clone();
@@ -1742,10 +2529,10 @@ fn render_definition_term(
     add_default_bottom_spacing(ui, &term_style, 1.0);
 }
 
-fn render_definition_description(
+fn render_definition_description<'a>(
     ui: &mut egui::Ui,
-    el: &HtmlElement,
-    ctx: &mut Ctx<'_>,
+    el: &'a HtmlElement,
+    ctx: &mut Ctx<'a>,
     style: &StyleProps,
 ) {
     let indent = style.padding.left.unwrap_or(18.0).max(12.0);
@@ -1763,10 +2550,10 @@ fn render_definition_description(
     add_default_bottom_spacing(ui, style, 2.0);
 }
 
-fn render_embedded_content(
+fn render_embedded_content<'a>(
     ui: &mut egui::Ui,
-    el: &HtmlElement,
-    ctx: &mut Ctx<'_>,
+    el: &'a HtmlElement,
+    ctx: &mut Ctx<'a>,
     style: &StyleProps,
 ) {
     let source = attr(el, "src")
@@ -1818,7 +2605,7 @@ fn render_embedded_content(
     add_default_bottom_spacing(ui, style, 2.0);
 }
 
-fn render_form(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style: &StyleProps) {
+fn render_form<'a>(ui: &mut egui::Ui, el: &'a HtmlElement, ctx: &mut Ctx<'a>, style: &StyleProps) {
     let key = form_runtime_key(el);
     let action_url = attr(el, "action")
         .and_then(|value| resolve_link(ctx.base_url, value))
@@ -1835,6 +2622,8 @@ fn render_form(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style: &S
         method,
         form_id: attr(el, "id").map(ToOwned::to_owned),
         onsubmit,
+        novalidate: has_attr(el, "novalidate"),
+        focus_targets: Vec::new(),
     });
     ctx.form_fields.entry(key).or_default();
 
@@ -1843,11 +2632,103 @@ fn render_form(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style: &S
             render_node(ui, child, ctx, style);
         }
     });
-    ctx.form_stack.pop();
+    if let Some(form) = ctx.form_stack.pop() {
+        move_form_focus_on_tab(ui, &form.focus_targets);
+    }
     add_default_bottom_spacing(ui, style, 2.0);
 }
 
-fn render_center(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style: &StyleProps) {
+/// Records a rendered control for the enclosing form's Tab order.
+fn register_form_focus_target<'a>(
+    ui: &egui::Ui,
+    ctx: &mut Ctx<'a>,
+    el: &'a HtmlElement,
+    response: &egui::Response,
+) {
+    let Some(form) = ctx.form_stack.last_mut() else {
+        return;
+    };
+    form.focus_targets.push((response.id, parse_tabindex(el)));
+    if response.has_focus() {
+        // Keep egui from moving focus on Tab so the form's own order applies.
+        let filter = egui::EventFilter {
+            tab: true,
+            horizontal_arrows: true,
+            vertical_arrows: true,
+            escape: false,
+        };
+        ui.memory_mut(|memory| memory.set_focus_lock_filter(response.id, filter));
+    }
+}
+
+fn move_form_focus_on_tab(ui: &egui::Ui, targets: &[(egui::Id, Option<i32>)]) {
+    let Some(focused) = targets
+        .iter()
+        .position(|(id, _)| ui.memory(|memory| memory.has_focus(*id)))
+    else {
+        return;
+    };
+    let (tab, backwards) =
+        ui.input(|input| (input.key_pressed(egui::Key::Tab), input.modifiers.shift));
+    if !tab {
+        return;
+    }
+
+    let tabindexes = targets
+        .iter()
+        .map(|(_, tabindex)| *tabindex)
+        .collect::<Vec<_>>();
+    let order = form_focus_order(&tabindexes);
+    if order.is_empty() {
+        return;
+    }
+    let next = match order.iter().position(|index| *index == focused) {
+        Some(position) if backwards => (position + order.len() - 1) % order.len(),
+        Some(position) => (position + 1) % order.len(),
+        None if backwards => order.len() - 1,
+        None => 0,
+    };
+    if let Some((id, _)) = order.get(next).and_then(|index| targets.get(*index)) {
+        ui.memory_mut(|memory| memory.request_focus(*id));
+    }
+}
+
+/// Orders controls like browsers do: positive `tabindex` ascending, then `0` or unset in
+/// document order; negative values are skipped. Ties keep document order.
+fn form_focus_order(tabindexes: &[Option<i32>]) -> Vec<usize> {
+    let mut positive = tabindexes
+        .iter()
+        .enumerate()
+        .filter_map(|(index, tabindex)| {
+            tabindex
+                .filter(|value| *value > 0)
+                .map(|value| (value, index))
+        })
+        .collect::<Vec<_>>();
+    positive.sort();
+    positive
+        .into_iter()
+        .map(|(_, index)| index)
+        .chain(
+            tabindexes
+                .iter()
+                .enumerate()
+                .filter(|(_, tabindex)| tabindex.is_none_or(|value| value == 0))
+                .map(|(index, _)| index),
+        )
+        .collect()
+}
+
+fn parse_tabindex(el: &HtmlElement) -> Option<i32> {
+    attr(el, "tabindex").and_then(|value| value.trim().parse::<i32>().ok())
+}
+
+fn render_center<'a>(
+    ui: &mut egui::Ui,
+    el: &'a HtmlElement,
+    ctx: &mut Ctx<'a>,
+    style: &StyleProps,
+) {
     let mut centered = style.clone();
     if centered.text_align.is_none() {
         centered.text_align = Some(TextAlign::Center);
@@ -1863,7 +2744,7 @@ fn render_center(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style:
     add_default_bottom_spacing(ui, &centered, 2.0);
 }
 
-fn render_table(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style: &StyleProps) {
+fn render_table<'a>(ui: &mut egui::Ui, el: &'a HtmlElement, ctx: &mut Ctx<'a>, style: &StyleProps) {
     let mut rows = Vec::new();
     collect_table_rows(&el.children, &mut rows);
     let cell_spacing = attr(el, "cellspacing")
@@ -1889,16 +2770,156 @@ fn render_table(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style: &
             }
         }
 
+        let row_cells = rows
+            .iter()
+            .map(|row| table_row_cells(row))
+            .collect::<Vec<_>>();
+        let grid = build_table_grid(
+            &row_cells
+                .iter()
+                .map(|cells| table_cell_spans(cells))
+                .collect::<Vec<_>>(),
+        );
+
         for (index, row) in rows.iter().enumerate() {
             if index > 0 && cell_spacing > 0.0 {
                 ui.add_space(cell_spacing);
             }
-            render_table_row(ui, row, ctx, &table_style, cell_spacing, cell_padding);
+            let layout =
+                table_row_layout(grid.rows.get(index).map(Vec::as_slice).unwrap_or_default());
+            render_table_row(
+                ui,
+                row,
+                row_cells.get(index).map(Vec::as_slice).unwrap_or_default(),
+                &layout,
+                grid.column_count,
+                ctx,
+                &table_style,
+                TableSpacing {
+                    cell_spacing,
+                    cell_padding,
+                },
+            );
         }
     });
     add_default_bottom_spacing(ui, &table_style, 2.0);
 }
 
+fn table_row_cells(row: &HtmlElement) -> Vec<&HtmlElement> {
+    row.children
+        .iter()
+        .filter_map(|child| match child {
+            HtmlNode::Element(cell) if matches!(cell.tag.as_str(), "td" | "th") => Some(cell),
+            _ => None,
+        })
+        .collect()
+}
+
+fn table_cell_spans(cells: &[&HtmlElement]) -> Vec<(usize, usize)> {
+    cells
+        .iter()
+        .map(|cell| {
+            (
+                parse_usize_attr(cell, "colspan").unwrap_or(1),
+                parse_usize_attr(cell, "rowspan").unwrap_or(1),
+            )
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TableSpacing {
+    cell_spacing: f32,
+    cell_padding: f32,
+}
+
+/// A cell placed on the table grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TableGridCell {
+    column: usize,
+    colspan: usize,
+    rowspan: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct TableGrid {
+    column_count: usize,
+    /// Placed cells per row, in source order.
+    rows: Vec<Vec<TableGridCell>>,
+}
+
+const MAX_TABLE_SPAN: usize = 1000;
+
+/// Places cells given as `(colspan, rowspan)` per row, skipping slots that
+/// earlier rowspans still occupy. A rowspan of 0 extends to the last row.
+fn build_table_grid(rows: &[Vec<(usize, usize)>]) -> TableGrid {
+    let mut occupied: Vec<Vec<bool>> = vec![Vec::new(); rows.len()];
+    let mut placed_rows = Vec::with_capacity(rows.len());
+    let mut column_count = 0_usize;
+
+    for (row_index, cells) in rows.iter().enumerate() {
+        let mut placed = Vec::with_capacity(cells.len());
+        let mut column = 0_usize;
+        for &(colspan, rowspan) in cells {
+            while occupied[row_index].get(column).copied().unwrap_or(false) {
+                column = column.saturating_add(1);
+            }
+
+            let colspan = colspan.clamp(1, MAX_TABLE_SPAN);
+            let remaining_rows = rows.len().saturating_sub(row_index);
+            let rowspan = if rowspan == 0 {
+                remaining_rows
+            } else {
+                rowspan.min(MAX_TABLE_SPAN).min(remaining_rows)
+            };
+
+            for covered in occupied.iter_mut().skip(row_index).take(rowspan) {
+                if covered.len() < column.saturating_add(colspan) {
+                    covered.resize(column.saturating_add(colspan), false);
+                }
+                for slot in covered.iter_mut().skip(column).take(colspan) {
+                    *slot = true;
+                }
+            }
+
+            placed.push(TableGridCell {
+                column,
+                colspan,
+                rowspan,
+            });
+            column = column.saturating_add(colspan);
+            column_count = column_count.max(column);
+        }
+        placed_rows.push(placed);
+    }
+
+    TableGrid {
+        column_count,
+        rows: placed_rows,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TableRowItem {
+    /// Columns left blank because a cell from an earlier row spans into them.
+    Gap(usize),
+    /// Index into the row's cells plus the number of columns it covers.
+    Cell(usize, usize),
+}
+
+fn table_row_layout(placed: &[TableGridCell]) -> Vec<TableRowItem> {
+    let mut items = Vec::with_capacity(placed.len());
+    let mut column = 0_usize;
+    for (index, cell) in placed.iter().enumerate() {
+        if cell.column > column {
+            items.push(TableRowItem::Gap(cell.column - column));
+        }
+        items.push(TableRowItem::Cell(index, cell.colspan));
+        column = cell.column.saturating_add(cell.colspan);
+    }
+    items
+}
+
 fn collect_table_rows<'a>(nodes: &'a [HtmlNode], out: &mut Vec<&'a HtmlElement>) {
     for node in nodes {
         let HtmlNode::Element(el) = node else {
@@ -1913,46 +2934,58 @@ fn collect_table_rows<'a>(nodes: &'a [HtmlNode], out: &mut Vec<&'a HtmlElement>)
     }
 }
 
-fn render_table_row(
+#[allow(clippy::too_many_arguments)]
+fn render_table_row<'a>(
     ui: &mut egui::Ui,
-    row: &HtmlElement,
-    ctx: &mut Ctx<'_>,
+    row: &'a HtmlElement,
+    cells: &[&'a HtmlElement],
+    layout: &[TableRowItem],
+    column_count: usize,
+    ctx: &mut Ctx<'a>,
     inherited: &StyleProps,
-    cell_spacing: f32,
-    cell_padding: f32,
+    spacing: TableSpacing,
 ) {
+    let TableSpacing {
+        cell_spacing,
+        cell_padding,
+    } = spacing;
     let mut row_style = style_for(row, ctx.styles, inherited, &ctx.ancestor_stack);
     row_style = apply_html_alignment_attr(row, &row_style);
 
-    let mut cells = Vec::new();
-    for child in &row.children {
-        let HtmlNode::Element(cell) = child else {
-            continue;
-        };
-        if matches!(cell.tag.as_str(), "td" | "th") {
-            cells.push(cell);
-        }
-    }
-
     ctx.ancestor_stack.push(selector_subject(row));
     render_box(ui, &row_style, |ui| {
         ui.horizontal(|ui| {
             let row_width = ui.available_width().max(1.0);
-            let spacing_total = if cells.len() > 1 {
-                cell_spacing * (cells.len().saturating_sub(1) as f32)
+            let spacing_total = if layout.len() > 1 {
+                cell_spacing * (layout.len().saturating_sub(1) as f32)
             } else {
                 0.0
             };
+            let per_column = (row_width - spacing_total).max(1.0) / column_count.max(1) as f32;
 
             let mut resolved_widths = Vec::with_capacity(cells.len());
             let mut cell_spans = Vec::with_capacity(cells.len());
             let mut auto_width_indices = Vec::new();
-            let mut fixed_total = 0.0_f32;
+            let mut fixed_total = layout
+                .iter()
+                .map(|item| match item {
+                    TableRowItem::Gap(columns) => per_column * *columns as f32,
+                    TableRowItem::Cell(..) => 0.0,
+                })
+                .sum::<f32>();
+            let mut colspans = vec![1_usize; cells.len()];
+            for item in layout {
+                if let TableRowItem::Cell(index, colspan) = *item
+                    && let Some(slot) = colspans.get_mut(index)
+                {
+                    *slot = colspan;
+                }
+            }
 
             for (cell_index, cell) in cells.iter().enumerate() {
                 let mut cell_style = style_for(cell, ctx.styles, &row_style, &ctx.ancestor_stack);
                 cell_style = apply_html_alignment_attr(cell, &cell_style);
-                let colspan = parse_usize_attr(cell, "colspan").unwrap_or(1).max(1);
+                let colspan = colspans.get(cell_index).copied().unwrap_or(1).max(1);
 
                 let width_from_css = cell_style.width.or_else(|| {
                     cell_style
@@ -1996,36 +3029,31 @@ fn render_table_row(
                 }
             }
 
-            if is_rtl_layout(&row_style) {
-                for (index, cell) in cells.iter().rev().enumerate() {
-                    if index > 0 && cell_spacing > 0.0 {
-                        ui.add_space(cell_spacing);
-                    }
-                    let width_index = cells.len().saturating_sub(index + 1);
-                    render_table_cell(
-                        ui,
-                        cell,
-                        ctx,
-                        &row_style,
-                        resolved_widths.get(width_index).copied().flatten(),
-                        row_width,
-                        cell_padding,
-                    );
-                }
+            let ordered = if is_rtl_layout(&row_style) {
+                layout.iter().rev().copied().collect::<Vec<_>>()
             } else {
-                for (index, cell) in cells.iter().enumerate() {
-                    if index > 0 && cell_spacing > 0.0 {
-                        ui.add_space(cell_spacing);
+                layout.to_vec()
+            };
+            for (position, item) in ordered.into_iter().enumerate() {
+                if position > 0 && cell_spacing > 0.0 {
+                    ui.add_space(cell_spacing);
+                }
+                match item {
+                    TableRowItem::Gap(columns) => ui.add_space(per_column * columns as f32),
+                    TableRowItem::Cell(index, _) => {
+                        let Some(cell) = cells.get(index) else {
+                            continue;
+                        };
+                        render_table_cell(
+                            ui,
+                            cell,
+                            ctx,
+                            &row_style,
+                            resolved_widths.get(index).copied().flatten(),
+                            row_width,
+                            cell_padding,
+                        );
                     }
-                    render_table_cell(
-                        ui,
-                        cell,
-                        ctx,
-                        &row_style,
-                        resolved_widths.get(index).copied().flatten(),
-                        row_width,
-                        cell_padding,
-                    );
                 }
             }
         });
@@ -2033,10 +3061,10 @@ fn render_table_row(
     ctx.ancestor_stack.pop();
 }
 
-fn render_table_cell(
+fn render_table_cell<'a>(
     ui: &mut egui::Ui,
-    cell: &HtmlElement,
-    ctx: &mut Ctx<'_>,
+    cell: &'a HtmlElement,
+    ctx: &mut Ctx<'a>,
     inherited: &StyleProps,
     resolved_width: Option<f32>,
     row_available_width: f32,
@@ -2073,11 +3101,11 @@ fn render_table_cell(
     ctx.ancestor_stack.pop();
 }
 
-fn render_list(
+fn render_list<'a>(
     ui: &mut egui::Ui,
-    el: &HtmlElement,
+    el: &'a HtmlElement,
     numbered: bool,
-    ctx: &mut Ctx<'_>,
+    ctx: &mut Ctx<'a>,
     style: &StyleProps,
 ) {
     let reversed = numbered && has_attr(el, "reversed");
@@ -2149,8 +3177,8 @@ fn render_list(
                     unordered_list_marker(marker_kind.as_deref())
                 };
                 ctx.ancestor_stack.push(selector_subject(item));
-                if is_rtl_layout(&item_style) {
-                    render_inline(ui, &item.children, ctx, &item_style);
+                if is_rtl_layout(item_style) {
+                    render_inline(ui, &item.children, ctx, item_style);
                     if !mark.is_empty() {
                         ui.label(mark);
                     }
@@ -2158,7 +3186,7 @@ fn render_list(
                     if !mark.is_empty() {
                         ui.label(mark);
                     }
-                    render_inline(ui, &item.children, ctx, &item_style);
+                    render_inline(ui, &item.children, ctx, item_style);
                 }
                 ctx.ancestor_stack.pop();
             });
@@ -2255,7 +3283,10 @@ fn roman_list_marker(index: usize, uppercase: bool) -> String {
     }
 }
 
-fn render_img(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style: &StyleProps) {
+/// Distance in points outside the visible area at which lazy images start loading.
+const LAZY_IMAGE_VIEWPORT_MARGIN: f32 = 600.0;
+
+fn render_img<'a>(ui: &mut egui::Ui, el: &'a HtmlElement, ctx: &mut Ctx<'a>, style: &StyleProps) {
     let src = image_source_attr(el).map(ToOwned::to_owned);
     let alt = attr(el, "alt").unwrap_or("image").to_owned();
     let resolved = src
@@ -2312,8 +3343,8 @@ fn render_img(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style: &St
         }
     }
 
-    if let Some(url) = resolved.as_deref() {
-        if let Some(render_image) = ctx.resources.images.get(url) {
+    match image_render_decision(el, resolved.as_deref(), ctx.resources) {
+        ImageRenderDecision::Texture(render_image) => {
             let mut image =
                 egui::Image::new((render_image.texture_id, egui::vec2(width, height)));
             if let Some(radius) = style.border_radius {
@@ -2323,14 +3354,42 @@ fn render_img(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style: &St
                 image = image.tint(color_with_effective_opacity(egui::Color32::WHITE, style));
             }
             ui.add(image);
-            let margin_bottom = style.margin.bottom_or(0.0).max(0.0);
-            if margin_bottom > 0.0 {
-                ui.add_space(margin_bottom);
-            } else {
-                ui.add_space(2.0);
+            add_image_margin(ui, style);
+            return;
+        }
+        ImageRenderDecision::AltText(alt) => {
+            if !alt.is_empty() {
+                let color = style
+                    .color
+                    .unwrap_or(egui::Color32::from_rgb(140, 150, 165))
+                    .gamma_multiply(0.8);
+                ui.label(
+                    egui::RichText::new(alt)
+                        .italics()
+                        .color(color_with_effective_opacity(color, style)),
+                );
+                add_image_margin(ui, style);
             }
             return;
         }
+        ImageRenderDecision::BrokenPlaceholder => {
+            egui::Frame::NONE
+                .stroke(egui::Stroke::new(
+                    1.0,
+                    color_with_effective_opacity(egui::Color32::from_rgb(66, 78, 95), style),
+                ))
+                .inner_margin(4.0)
+                .show(ui, |ui| {
+                    ui.label(
+                        egui::RichText::new(BROKEN_IMAGE_MARKER)
+                            .small()
+                            .color(egui::Color32::from_rgb(140, 150, 165)),
+                    );
+                });
+            add_image_margin(ui, style);
+            return;
+        }
+        ImageRenderDecision::Pending => {}
     }
 
     let fill = color_with_effective_opacity(
@@ -2338,7 +3397,7 @@ fn render_img(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style: &St
         style,
     );
 
-    egui::Frame::NONE
+    let placeholder = egui::Frame::NONE
         .fill(fill)
         .stroke(egui::Stroke::new(
             1.0,
@@ -2354,6 +3413,17 @@ fn render_img(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style: &St
             });
         });
 
+    if let Some(url) = resolved.as_deref()
+        && ui.is_rect_visible(placeholder.response.rect.expand(LAZY_IMAGE_VIEWPORT_MARGIN))
+        && !ctx
+            .action
+            .image_requests
+            .iter()
+            .any(|requested| requested == url)
+    {
+        ctx.action.image_requests.push(url.to_owned());
+    }
+
     if let Some(url) = resolved {
         ui.horizontal_wrapped(|ui| {
             ui.label("src:");
@@ -2372,28 +3442,163 @@ fn render_img(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style: &St
     }
 }
 
+/// Marker drawn in place of an image that failed to load and has no `alt` text.
+const BROKEN_IMAGE_MARKER: &str = "[broken image]";
+
+/// What `render_img` paints for an `<img>` given the decoded and pending image sets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ImageRenderDecision<'a> {
+    Texture(RenderImage),
+    /// A lazy image that is not requested or fetched yet; keeps its reserved box.
+    Pending,
+    /// Failed or blocked image with `alt` text; empty alt marks it decorative.
+    AltText(&'a str),
+    BrokenPlaceholder,
+}
+
+fn image_render_decision<'a>(
+    el: &'a HtmlElement,
+    resolved: Option<&str>,
+    resources: &RenderResources<'_>,
+) -> ImageRenderDecision<'a> {
+    if let Some(render_image) = resolved.and_then(|url| resources.images.get(url)) {
+        return ImageRenderDecision::Texture(*render_image);
+    }
+    if resolved.is_some_and(|url| {
+        resources
+            .pending_images
+            .iter()
+            .any(|pending| pending == url)
+    }) {
+        return ImageRenderDecision::Pending;
+    }
+    match attr(el, "alt") {
+        Some(alt) => ImageRenderDecision::AltText(alt.trim()),
+        None => ImageRenderDecision::BrokenPlaceholder,
+    }
+}
+
+fn add_image_margin(ui: &mut egui::Ui, style: &StyleProps) {
+    let margin_bottom = style.margin.bottom_or(0.0).max(0.0);
+    if margin_bottom > 0.0 {
+        ui.add_space(margin_bottom);
+    } else {
+        ui.add_space(2.0);
+    }
+}
+
+fn clamp_style_font_size(style: &mut StyleProps, ctx: &Ctx<'_>) {
+    style.font_size = style
+        .font_size
+        .map(|size| clamp_font_size(size, ctx.min_font_size, ctx.max_font_size));
+}
+
+/// Clamps `size` into `min..=max`; a ceiling below the floor is raised to it.
+fn clamp_font_size(size: f32, min: f32, max: f32) -> f32 {
+    size.max(min).min(max.max(min))
+}
+
 fn render_text(ui: &mut egui::Ui, text: &str, style: &StyleProps, effects: TextEffects) {
-    let wrap_mode = match effective_white_space_mode(style, &effects) {
-        WhiteSpaceMode::NoWrap | WhiteSpaceMode::Pre => egui::TextWrapMode::Extend,
-        WhiteSpaceMode::Normal | WhiteSpaceMode::PreWrap | WhiteSpaceMode::PreLine => {
-            egui::TextWrapMode::Wrap
-        }
-    };
+    let wrap_mode = white_space_wrap_mode(style, &effects);
     let label = egui::Label::new(build_rich_text(text.to_owned(), style, effects)).wrap_mode(wrap_mode);
     ui.add(label);
 }
 
 fn render_text_block(ui: &mut egui::Ui, text: &str, style: &StyleProps, effects: TextEffects) {
-    let wrap_mode = match effective_white_space_mode(style, &effects) {
-        WhiteSpaceMode::NoWrap | WhiteSpaceMode::Pre => egui::TextWrapMode::Extend,
-        WhiteSpaceMode::Normal | WhiteSpaceMode::PreWrap | WhiteSpaceMode::PreLine => {
-            egui::TextWrapMode::Wrap
+    let wrap_mode = white_space_wrap_mode(style, &effects);
+    let wrap = wrap_mode == egui::TextWrapMode::Wrap;
+    let max_lines = if wrap {
+        line_clamp(style)
+    } else {
+        has_text_overflow_ellipsis(style).then_some(1)
+    };
+    let text = match max_lines {
+        Some(max_lines) => {
+            let measure = |candidate: &str| {
+                egui::WidgetText::from(build_rich_text(candidate.to_owned(), style, effects))
+                    .into_galley(
+                        ui,
+                        Some(egui::TextWrapMode::Extend),
+                        f32::INFINITY,
+                        egui::TextStyle::Body,
+                    )
+                    .size()
+                    .x
+            };
+            truncate_text_to_fit(text, ui.available_width(), max_lines, wrap, measure)
         }
+        None => text.to_owned(),
     };
-    let label = egui::Label::new(build_rich_text(text.to_owned(), style, effects)).wrap_mode(wrap_mode);
+    let label = egui::Label::new(build_rich_text(text, style, effects)).wrap_mode(wrap_mode);
     add_aligned_label(ui, label, style);
 }
 
+fn has_text_overflow_ellipsis(style: &StyleProps) -> bool {
+    raw_css_value(&style.raw_css, "text-overflow")
+        .and_then(|value| value.split_whitespace().last())
+        .is_some_and(|value| value.eq_ignore_ascii_case("ellipsis"))
+}
+
+fn line_clamp(style: &StyleProps) -> Option<usize> {
+    raw_css_value(&style.raw_css, "line-clamp")
+        .or_else(|| raw_css_value(&style.raw_css, "-webkit-line-clamp"))
+        .and_then(|value| value.parse::<usize>().ok())
+        .filter(|lines| *lines > 0)
+}
+
+/// Cuts `text` to `max_lines` lines of `max_width`, ending the last kept line with `…`.
+/// Unwrapped text is a single line; wrapped text breaks greedily between words.
+fn truncate_text_to_fit(
+    text: &str,
+    max_width: f32,
+    max_lines: usize,
+    wrap: bool,
+    measure: impl Fn(&str) -> f32,
+) -> String {
+    let mut lines = Vec::new();
+    if wrap {
+        let mut current = String::new();
+        for word in text.split_whitespace() {
+            let candidate = if current.is_empty() {
+                word.to_owned()
+            } else {
+                format!("{current} {word}")
+            };
+            if current.is_empty() || measure(&candidate) <= max_width {
+                current = candidate;
+            } else {
+                lines.push(std::mem::replace(&mut current, word.to_owned()));
+            }
+        }
+        if !current.is_empty() {
+            lines.push(current);
+        }
+    } else {
+        lines.push(text.to_owned());
+    }
+
+    let max_lines = max_lines.max(1);
+    let overflows = lines.len() > max_lines
+        || lines
+            .get(max_lines.min(lines.len()).saturating_sub(1))
+            .is_some_and(|line| measure(line) > max_width);
+    if !overflows {
+        return text.to_owned();
+    }
+
+    lines.truncate(max_lines);
+    let mut last = lines.pop().unwrap_or_default();
+    loop {
+        let candidate = format!("{}…", last.trim_end());
+        if last.is_empty() || measure(&candidate) <= max_width {
+            lines.push(candidate);
+            break;
+        }
+        last.pop();
+    }
+    lines.join("\n")
+}
+
 fn add_aligned_label(ui: &mut egui::Ui, label: egui::Label, style: &StyleProps) {
     let horizontal_align = match style.text_align.unwrap_or(TextAlign::Left) {
         TextAlign::Left | TextAlign::Justify => egui::Align::Min,
@@ -2416,10 +3621,10 @@ fn element_has_only_text_children(children: &[HtmlNode]) -> bool {
     })
 }
 
-fn render_inline_wrapped(
+fn render_inline_wrapped<'a>(
     ui: &mut egui::Ui,
-    nodes: &[HtmlNode],
-    ctx: &mut Ctx<'_>,
+    nodes: &'a [HtmlNode],
+    ctx: &mut Ctx<'a>,
     style: &StyleProps,
 ) {
     if is_rtl_layout(style) {
@@ -2432,13 +3637,16 @@ fn render_inline_wrapped(
 }
 
 fn is_rtl_layout(style: &StyleProps) -> bool {
-    matches!(style.text_align, Some(TextAlign::Right))
+    match style.direction {
+        Some(direction) => direction == TextDirection::Rtl,
+        None => matches!(style.text_align, Some(TextAlign::Right)),
+    }
 }
 
-fn render_input(
+fn render_input<'a>(
     ui: &mut egui::Ui,
-    el: &HtmlElement,
-    ctx: &mut Ctx<'_>,
+    el: &'a HtmlElement,
+    ctx: &mut Ctx<'a>,
     style: &StyleProps,
     inline_mode: bool,
 ) {
@@ -2489,7 +3697,14 @@ fn render_input(
         height = height.min(max_height.max(24.0));
     }
 
-    let text_control_style = tune_text_control_style(style);
+    if !disabled && input_type != "submit" && input_type != "button" {
+        register_form_field_rules(ctx, el);
+    }
+    let validation = form_control_validation(ctx, el);
+    let mut text_control_style = tune_text_control_style(style);
+    if validation.is_some() {
+        text_control_style.border_color = Some(INVALID_FORM_CONTROL_COLOR);
+    }
     let state_key = form_control_state_key(ctx.base_url, el, &input_type);
     let mut render_control = |ui: &mut egui::Ui| match input_type.as_str() {
         "submit" | "button" => {
@@ -2509,8 +3724,10 @@ fn render_input(
             if disabled {
                 return;
             }
+            emit_interaction_events(ui, ctx, el, &response);
+            register_form_focus_target(ui, ctx, el, &response);
             if response.clicked() {
-                emit_inline_event(ctx, DomEventKind::Click, el, "onclick");
+                emit_inline_event(ctx, DomEventKind::Click, el);
                 if input_type == "submit" {
                     submit_active_form(
                         ctx,
@@ -2563,11 +3780,14 @@ fn render_input(
                     set_active_form_field(ctx, name, None);
                 }
             }
+            emit_interaction_events(ui, ctx, el, &response);
+            register_form_focus_target(ui, ctx, el, &response);
             if response.changed() || (input_type == "radio" && response.clicked()) {
-                emit_inline_event(ctx, DomEventKind::Input, el, "oninput");
+                emit_inline_event(ctx, DomEventKind::Input, el);
+                emit_inline_event(ctx, DomEventKind::Change, el);
             }
             if response.clicked() {
-                emit_inline_event(ctx, DomEventKind::Click, el, "onclick");
+                emit_inline_event(ctx, DomEventKind::Click, el);
             }
         }
         _ => {
@@ -2604,11 +3824,23 @@ fn render_input(
                 if let Some(name) = attr(el, "name") {
                     set_active_form_field(ctx, name, None);
                 }
-            } else if response.changed() {
-                emit_inline_event(ctx, DomEventKind::Input, el, "oninput");
+            } else {
+                if response.changed() {
+                    emit_inline_event(ctx, DomEventKind::Input, el);
+                }
+                emit_text_change_on_commit(ui, ctx, el, &response);
+                emit_interaction_events(ui, ctx, el, &response);
+                register_form_focus_target(ui, ctx, el, &response);
             }
             if !disabled && response.clicked() {
-                emit_inline_event(ctx, DomEventKind::Click, el, "onclick");
+                emit_inline_event(ctx, DomEventKind::Click, el);
+            }
+            if let Some((validation_key, message)) = &validation {
+                if response.changed() {
+                    ctx.form_state.remove(validation_key);
+                } else {
+                    render_validation_message(ui, message);
+                }
             }
             let pressed_enter = !disabled
                 && response.lost_focus()
@@ -2628,14 +3860,14 @@ fn render_input(
     }
 }
 
-fn render_button(
+fn render_button<'a>(
     ui: &mut egui::Ui,
-    el: &HtmlElement,
-    ctx: &mut Ctx<'_>,
+    el: &'a HtmlElement,
+    ctx: &mut Ctx<'a>,
     style: &StyleProps,
     inline_mode: bool,
 ) {
-    let text = collapse_whitespace(&collect_text(&el.children));
+    let text = collapse_whitespace(&collect_rendered_text(&el.children));
     let label = if text.is_empty() {
         "Button".to_owned()
     } else {
@@ -2682,8 +3914,10 @@ fn render_button(
         if disabled {
             return;
         }
+        emit_interaction_events(ui, ctx, el, &response);
+        register_form_focus_target(ui, ctx, el, &response);
         if response.clicked() {
-            emit_inline_event(ctx, DomEventKind::Click, el, "onclick");
+            emit_inline_event(ctx, DomEventKind::Click, el);
             if button_type != "button" {
                 submit_active_form(
                     ctx,
@@ -2703,10 +3937,10 @@ fn render_button(
     }
 }
 
-fn render_textarea(
+fn render_textarea<'a>(
     ui: &mut egui::Ui,
-    el: &HtmlElement,
-    ctx: &mut Ctx<'_>,
+    el: &'a HtmlElement,
+    ctx: &mut Ctx<'a>,
     style: &StyleProps,
     inline_mode: bool,
 ) {
@@ -2749,7 +3983,14 @@ fn render_textarea(
         height = height.min(max_height.max(40.0));
     }
 
-    let text_control_style = tune_text_control_style(style);
+    if !disabled {
+        register_form_field_rules(ctx, el);
+    }
+    let validation = form_control_validation(ctx, el);
+    let mut text_control_style = tune_text_control_style(style);
+    if validation.is_some() {
+        text_control_style.border_color = Some(INVALID_FORM_CONTROL_COLOR);
+    }
     let state_key = form_control_state_key(ctx.base_url, el, "textarea");
     let mut render_control = |ui: &mut egui::Ui| {
         let mut text = ctx
@@ -2782,11 +4023,23 @@ fn render_textarea(
             if let Some(name) = attr(el, "name") {
                 set_active_form_field(ctx, name, None);
             }
-        } else if response.changed() {
-            emit_inline_event(ctx, DomEventKind::Input, el, "oninput");
+        } else {
+            if response.changed() {
+                emit_inline_event(ctx, DomEventKind::Input, el);
+            }
+            emit_text_change_on_commit(ui, ctx, el, &response);
+            emit_interaction_events(ui, ctx, el, &response);
+            register_form_focus_target(ui, ctx, el, &response);
         }
         if !disabled && response.clicked() {
-            emit_inline_event(ctx, DomEventKind::Click, el, "onclick");
+            emit_inline_event(ctx, DomEventKind::Click, el);
+        }
+        if let Some((validation_key, message)) = &validation {
+            if response.changed() {
+                ctx.form_state.remove(validation_key);
+            } else {
+                render_validation_message(ui, message);
+            }
         }
         ctx.form_state.insert(state_key.clone(), text);
     };
@@ -2799,10 +4052,10 @@ fn render_textarea(
     }
 }
 
-fn render_select(
+fn render_select<'a>(
     ui: &mut egui::Ui,
-    el: &HtmlElement,
-    ctx: &mut Ctx<'_>,
+    el: &'a HtmlElement,
+    ctx: &mut Ctx<'a>,
     style: &StyleProps,
     inline_mode: bool,
 ) {
@@ -2924,9 +4177,14 @@ fn render_select(
             if let Some(name) = attr(el, "name") {
                 set_active_form_field(ctx, name, None);
             }
-        } else if response.clicked() {
-            emit_inline_event(ctx, DomEventKind::Input, el, "oninput");
-            emit_inline_event(ctx, DomEventKind::Click, el, "onclick");
+        } else {
+            emit_interaction_events(ui, ctx, el, &response);
+            register_form_focus_target(ui, ctx, el, &response);
+            if response.clicked() {
+                emit_inline_event(ctx, DomEventKind::Input, el);
+                emit_inline_event(ctx, DomEventKind::Change, el);
+                emit_inline_event(ctx, DomEventKind::Click, el);
+            }
         }
     };
 
@@ -3114,11 +4372,7 @@ fn select_text_font_family(
 }
 
 fn normalize_text_for_render(input: &str, style: &StyleProps, effects: &TextEffects) -> String {
-    let mut out = match effective_white_space_mode(style, effects) {
-        WhiteSpaceMode::Normal | WhiteSpaceMode::NoWrap => collapse_whitespace(input),
-        WhiteSpaceMode::Pre | WhiteSpaceMode::PreWrap => input.to_owned(),
-        WhiteSpaceMode::PreLine => collapse_whitespace_preserve_newlines(input),
-    };
+    let mut out = apply_white_space(input, effective_white_space_mode(style, effects));
 
     match style.text_transform.unwrap_or(TextTransform::None) {
         TextTransform::None => {}
@@ -3130,6 +4384,32 @@ fn normalize_text_for_render(input: &str, style: &StyleProps, effects: &TextEffe
     out
 }
 
+/// `pre`/`pre-wrap` keep text verbatim, `pre-line` keeps only newlines, the rest collapse.
+fn apply_white_space(input: &str, mode: WhiteSpaceMode) -> String {
+    match mode {
+        WhiteSpaceMode::Normal | WhiteSpaceMode::NoWrap => collapse_whitespace(input),
+        WhiteSpaceMode::Pre | WhiteSpaceMode::PreWrap => input.to_owned(),
+        WhiteSpaceMode::PreLine => collapse_whitespace_preserve_newlines(input),
+    }
+}
+
+/// Element text with the element's own `white-space` mode applied.
+fn white_space_text(input: &str, style: &StyleProps) -> String {
+    apply_white_space(
+        input,
+        effective_white_space_mode(style, &TextEffects::default()),
+    )
+}
+
+fn white_space_wrap_mode(style: &StyleProps, effects: &TextEffects) -> egui::TextWrapMode {
+    match effective_white_space_mode(style, effects) {
+        WhiteSpaceMode::NoWrap | WhiteSpaceMode::Pre => egui::TextWrapMode::Extend,
+        WhiteSpaceMode::Normal | WhiteSpaceMode::PreWrap | WhiteSpaceMode::PreLine => {
+            egui::TextWrapMode::Wrap
+        }
+    }
+}
+
 fn effective_white_space_mode(style: &StyleProps, effects: &TextEffects) -> WhiteSpaceMode {
     style.white_space.unwrap_or(if effects.mono {
         WhiteSpaceMode::Pre
@@ -3198,6 +4478,13 @@ fn is_likely_screen_reader_only(style: &StyleProps) -> bool {
     tiny_width && tiny_height && no_padding && no_border
 }
 
+/// Shared visibility gate for block, inline, and text-collection paths.
+fn is_effectively_hidden(el: &HtmlElement, style: &StyleProps) -> bool {
+    element_has_hidden_semantics(el)
+        || style_suppresses_rendering(style)
+        || is_likely_screen_reader_only(style)
+}
+
 fn style_suppresses_rendering(style: &StyleProps) -> bool {
     matches!(style.display, Some(Display::None))
         || matches!(style.visibility_hidden, Some(true))
@@ -3237,42 +4524,89 @@ fn overflow_mode_y(style: &StyleProps) -> OverflowMode {
     style.overflow_y.unwrap_or(OverflowMode::Visible)
 }
 
-fn with_overflow_behavior(
-    ui: &mut egui::Ui,
-    style: &StyleProps,
-    body: impl FnOnce(&mut egui::Ui),
-) {
+/// How an element's content is laid out relative to its box edges.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OverflowBehavior {
+    Visible,
+    Clip,
+    Scroll {
+        horizontal: bool,
+        vertical: bool,
+        always_show_bars: bool,
+        max_height: Option<f32>,
+    },
+}
+
+/// Vertical scrolling only applies once the box has a height to overflow.
+fn overflow_behavior(style: &StyleProps) -> OverflowBehavior {
     let overflow_x = overflow_mode_x(style);
     let overflow_y = overflow_mode_y(style);
     let scroll_x = matches!(overflow_x, OverflowMode::Auto | OverflowMode::Scroll);
     let scroll_y = matches!(overflow_y, OverflowMode::Auto | OverflowMode::Scroll);
-    let hidden_clip = matches!(overflow_x, OverflowMode::Hidden)
-        || matches!(overflow_y, OverflowMode::Hidden);
+    let constrained_height = match (style.height, style.max_height) {
+        (Some(height), Some(max_height)) => Some(height.min(max_height)),
+        (height, max_height) => height.or(max_height),
+    }
+    .map(|height| height.max(0.0));
+    let vertical = scroll_y && constrained_height.is_some();
+
+    if scroll_x || vertical {
+        return OverflowBehavior::Scroll {
+            horizontal: scroll_x,
+            vertical,
+            always_show_bars: matches!(overflow_x, OverflowMode::Scroll)
+                || matches!(overflow_y, OverflowMode::Scroll),
+            max_height: constrained_height.filter(|_| vertical),
+        };
+    }
 
-    if scroll_x || scroll_y {
-        let mut scroll_area = egui::ScrollArea::new([scroll_x, scroll_y]).auto_shrink([false, false]);
-        if matches!(overflow_x, OverflowMode::Scroll) || matches!(overflow_y, OverflowMode::Scroll)
-        {
-            scroll_area = scroll_area.scroll_bar_visibility(egui::scroll_area::ScrollBarVisibility::AlwaysVisible);
-        }
-        scroll_area.show(ui, body);
-        return;
+    if matches!(overflow_x, OverflowMode::Hidden) || matches!(overflow_y, OverflowMode::Hidden) {
+        return OverflowBehavior::Clip;
     }
 
-    if hidden_clip {
-        let clip_rect = ui.max_rect();
-        ui.scope(|ui| {
-            ui.shrink_clip_rect(clip_rect);
-            body(ui);
-        });
-        return;
-    }
+    OverflowBehavior::Visible
+}
 
-    body(ui);
+fn with_overflow_behavior(
+    ui: &mut egui::Ui,
+    style: &StyleProps,
+    body: impl FnOnce(&mut egui::Ui),
+) {
+    match overflow_behavior(style) {
+        OverflowBehavior::Scroll {
+            horizontal,
+            vertical,
+            always_show_bars,
+            max_height,
+        } => {
+            let mut scroll_area =
+                egui::ScrollArea::new([horizontal, vertical]).auto_shrink([false, false]);
+            if let Some(max_height) = max_height {
+                scroll_area = scroll_area.max_height(max_height);
+            }
+            if always_show_bars {
+                scroll_area = scroll_area
+                    .scroll_bar_visibility(egui::scroll_area::ScrollBarVisibility::AlwaysVisible);
+            }
+            scroll_area.show(ui, body);
+        }
+        OverflowBehavior::Clip => {
+            let clip_rect = ui.max_rect();
+            ui.scope(|ui| {
+                ui.shrink_clip_rect(clip_rect);
+                body(ui);
+            });
+        }
+        OverflowBehavior::Visible => body(ui),
+    }
 }
 
 fn z_layer_order(style: &StyleProps) -> Option<egui::Order> {
-    match style.z_index.unwrap_or(0).cmp(&0) {
+    z_layer_order_for(style.z_index.unwrap_or(0))
+}
+
+fn z_layer_order_for(z_index: i32) -> Option<egui::Order> {
+    match z_index.cmp(&0) {
         std::cmp::Ordering::Less => Some(egui::Order::Background),
         std::cmp::Ordering::Greater => Some(egui::Order::Foreground),
         std::cmp::Ordering::Equal => None,
@@ -3327,7 +4661,10 @@ fn render_box(ui: &mut egui::Ui, style: &StyleProps, body: impl FnOnce(&mut egui
     );
     let border_radius = style.border_radius.unwrap_or(0.0).clamp(0.0, 255.0);
 
-    if !matches!(style.position.unwrap_or(PositionMode::Static), PositionMode::Static) {
+    if matches!(
+        style.position.unwrap_or(PositionMode::Static),
+        PositionMode::Relative | PositionMode::Sticky
+    ) {
         let (offset_x, offset_y) = position_offset(style);
         margin_left = (margin_left + offset_x).max(0.0);
         margin_top = (margin_top + offset_y).max(0.0);
@@ -3417,11 +4754,9 @@ fn render_box(ui: &mut egui::Ui, style: &StyleProps, body: impl FnOnce(&mut egui
                 bottom: margin_component(padding_bottom + border_bottom),
             })
             .show(ui, |ui| {
-                with_overflow_behavior(
-                    ui,
-                    style,
-                    body.take().expect("render_box body should run exactly once"),
-                );
+                if let Some(body) = body.take() {
+                    with_overflow_behavior(ui, style, body);
+                }
             })
             .response;
 
@@ -3476,11 +4811,9 @@ fn render_box(ui: &mut egui::Ui, style: &StyleProps, body: impl FnOnce(&mut egui
                         }
 
                         ui.vertical(|ui| {
-                            with_overflow_behavior(
-                                ui,
-                                style,
-                                body.take().expect("render_box body should run exactly once"),
-                            );
+                            if let Some(body) = body.take() {
+                                with_overflow_behavior(ui, style, body);
+                            }
                         });
 
                         if padding_right > 0.0 {
@@ -3550,6 +4883,7 @@ fn margin_component(value: f32) -> i8 {
     value.round().clamp(0.0, 127.0) as i8
 }
 
+#[allow(clippy::too_many_arguments)]
 fn paint_box_border(
     painter: &egui::Painter,
     rect: egui::Rect,
@@ -3604,7 +4938,7 @@ fn paint_box_border(
     }
 }
 
-fn render_flex(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style: &StyleProps) {
+fn render_flex<'a>(ui: &mut egui::Ui, el: &'a HtmlElement, ctx: &mut Ctx<'a>, style: &StyleProps) {
     let direction = style.flex_direction.unwrap_or(FlexDirection::Row);
     let flex_wrap = style.flex_wrap.unwrap_or(FlexWrap::NoWrap);
     let align_items = style.align_items.unwrap_or(AlignItems::Start);
@@ -3677,7 +5011,7 @@ fn render_flex(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style: &S
     });
 }
 
-fn render_grid(ui: &mut egui::Ui, el: &HtmlElement, ctx: &mut Ctx<'_>, style: &StyleProps) {
+fn render_grid<'a>(ui: &mut egui::Ui, el: &'a HtmlElement, ctx: &mut Ctx<'a>, style: &StyleProps) {
     let gap = style.gap.unwrap_or(0.0).max(0.0);
     let min_track_width = style
         .raw_css
@@ -3851,6 +5185,7 @@ fn style_for(
                     declaration,
                     CascadePriority {
                         important: declaration.important,
+                        inline: false,
                         specificity: rule.specificity,
                         source_order: declaration.source_order,
                     },
@@ -3868,6 +5203,7 @@ fn style_for(
                 &declaration,
                 CascadePriority {
                     important: declaration.important,
+                    inline: true,
                     specificity: 1000,
                     source_order: declaration.source_order,
                 },
@@ -3889,8 +5225,12 @@ fn style_for(
     if style.visibility_hidden.is_none() {
         style.visibility_hidden = inherited.visibility_hidden;
     }
+    let own_direction = element_direction(el);
+    style.direction = own_direction.or(inherited.direction);
     if style.text_align.is_none() {
-        style.text_align = inherited.text_align;
+        style.text_align = own_direction
+            .map(TextDirection::start_align)
+            .or(inherited.text_align);
     }
     if style.font_family.is_none() {
         style.font_family = inherited.font_family;
@@ -3933,17 +5273,55 @@ fn style_for(
     } else if let Some(own_opacity) = style.opacity {
         style.opacity = Some(own_opacity.clamp(0.0, 1.0));
     }
-    if style.text_align.is_none() {
-        if let Some(dir) = attr(el, "dir") {
-            if dir.eq_ignore_ascii_case("rtl") {
-                style.text_align = Some(TextAlign::Right);
-            } else if dir.eq_ignore_ascii_case("ltr") {
-                style.text_align = Some(TextAlign::Left);
+
+    style
+}
+
+/// Direction set by `el`'s own `dir` attribute; `auto` follows the first strong character.
+fn element_direction(el: &HtmlElement) -> Option<TextDirection> {
+    let dir = attr(el, "dir")?.trim();
+    if dir.eq_ignore_ascii_case("rtl") {
+        Some(TextDirection::Rtl)
+    } else if dir.eq_ignore_ascii_case("ltr") {
+        Some(TextDirection::Ltr)
+    } else if dir.eq_ignore_ascii_case("auto") {
+        Some(first_strong_direction(&el.children).unwrap_or(TextDirection::Ltr))
+    } else {
+        None
+    }
+}
+
+fn first_strong_direction(nodes: &[HtmlNode]) -> Option<TextDirection> {
+    nodes.iter().find_map(|node| match node {
+        HtmlNode::Text(text) => text.chars().find_map(strong_direction),
+        HtmlNode::Element(el) => {
+            // Nested `dir` elements and raw-text content are skipped, as in the HTML spec.
+            if attr(el, "dir").is_some()
+                || matches!(
+                    canonical_element_tag(el.tag.as_str()),
+                    "script" | "style" | "textarea"
+                )
+            {
+                None
+            } else {
+                first_strong_direction(&el.children)
             }
         }
-    }
+    })
+}
 
-    style
+fn strong_direction(ch: char) -> Option<TextDirection> {
+    let rtl = matches!(
+        u32::from(ch),
+        0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF | 0x10800..=0x10FFF | 0x1E800..=0x1EFFF
+    );
+    if rtl {
+        Some(TextDirection::Rtl)
+    } else if ch.is_alphabetic() {
+        Some(TextDirection::Ltr)
+    } else {
+        None
+    }
 }
 
 fn apply_declaration_with_cascade(
@@ -4501,12 +5879,10 @@ fn should_apply_priority(incoming: CascadePriority, existing: Option<CascadePrio
     }
 }
 
-fn extract_styles(root: &HtmlElement) -> StyleSheet {
+fn extract_style_source(root: &HtmlElement) -> String {
     let mut css = String::new();
     collect_style_source(&root.children, false, &mut css);
-    StyleSheet {
-        rules: parse_css_rules(&css),
-    }
+    css
 }
 
 fn collect_style_source(nodes: &[HtmlNode], inside_noscript: bool, out: &mut String) {
@@ -4588,6 +5964,159 @@ fn collect_script_descriptors(nodes: &[HtmlNode], base_url: &str, out: &mut Vec<
     }
 }
 
+fn last_icon_link_href(nodes: &[HtmlNode]) -> Option<&str> {
+    nodes.iter().rev().find_map(|node| {
+        let HtmlNode::Element(el) = node else {
+            return None;
+        };
+        if let Some(href) = last_icon_link_href(&el.children) {
+            return Some(href);
+        }
+        let is_icon = el.tag.eq_ignore_ascii_case("link")
+            && attr(el, "rel").is_some_and(|rel| {
+                rel.split_ascii_whitespace()
+                    .any(|token| token.eq_ignore_ascii_case("icon"))
+            });
+        is_icon
+            .then(|| attr(el, "href").map(str::trim))
+            .flatten()
+            .filter(|href| !href.is_empty())
+    })
+}
+
+fn find_element_by_id_mut<'a>(nodes: &'a mut [HtmlNode], id: &str) -> Option<&'a mut HtmlElement> {
+    for node in nodes {
+        let HtmlNode::Element(el) = node else {
+            continue;
+        };
+        if attr(el, "id").is_some_and(|value| value.trim() == id) {
+            return Some(el);
+        }
+        if let Some(found) = find_element_by_id_mut(&mut el.children, id) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+fn serialize_nodes(nodes: &[HtmlNode], raw_text: bool, out: &mut String) {
+    for node in nodes {
+        match node {
+            HtmlNode::Text(text) if raw_text => out.push_str(text),
+            HtmlNode::Text(text) => push_escaped_html(out, text, false),
+            HtmlNode::Element(el) => {
+                out.push('<');
+                out.push_str(&el.tag);
+                for (name, value) in &el.attrs {
+                    out.push(' ');
+                    out.push_str(name);
+                    out.push_str("=\"");
+                    push_escaped_html(out, value, true);
+                    out.push('"');
+                }
+                out.push('>');
+                if is_void(&el.tag) {
+                    continue;
+                }
+                // Script and style bodies are not entity-decoded by browsers, so they stay raw.
+                let raw_children = matches!(
+                    canonical_element_tag(&el.tag),
+                    "script" | "style" | "xmp" | "plaintext"
+                );
+                serialize_nodes(&el.children, raw_children, out);
+                out.push_str("</");
+                out.push_str(&el.tag);
+                out.push('>');
+            }
+        }
+    }
+}
+
+fn push_escaped_html(out: &mut String, text: &str, attribute: bool) {
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' if attribute => out.push_str("&quot;"),
+            _ => out.push(ch),
+        }
+    }
+}
+
+/// URL-valued attributes made absolute so links in a saved page still reach the original site.
+const OFFLINE_URL_ATTRIBUTES: &[&str] = &[
+    "href",
+    "src",
+    "action",
+    "formaction",
+    "poster",
+    "background",
+    "cite",
+];
+
+/// Drops scripts, `<base>` and stylesheet links, makes URLs absolute, and points images at their
+/// inlined data URLs. Inline event handlers go too, since the saved copy never runs script.
+fn inline_offline_resources(
+    nodes: &mut Vec<HtmlNode>,
+    base_url: &str,
+    image_data_urls: &HashMap<String, String>,
+) {
+    nodes.retain(|node| {
+        let HtmlNode::Element(el) = node else {
+            return true;
+        };
+        match canonical_element_tag(el.tag.as_str()) {
+            "script" | "base" => false,
+            "link" => !is_stylesheet_link(el),
+            _ => true,
+        }
+    });
+    for node in nodes {
+        let HtmlNode::Element(el) = node else {
+            continue;
+        };
+        let inlined = (canonical_element_tag(el.tag.as_str()) == "img")
+            .then(|| image_source_attr(el).and_then(|src| resolve_link(base_url, src)))
+            .flatten()
+            .and_then(|url| image_data_urls.get(&url))
+            .cloned();
+
+        el.attrs
+            .retain(|(name, _)| !name.to_ascii_lowercase().starts_with("on"));
+        for (name, value) in &mut el.attrs {
+            if OFFLINE_URL_ATTRIBUTES.contains(&name.as_str()) && !value.starts_with('#') {
+                if let Some(absolute) = resolve_link(base_url, value) {
+                    *value = absolute;
+                }
+            } else if name == "srcset" || name == "data-srcset" {
+                *value = srcset_candidates(value)
+                    .into_iter()
+                    .map(|(url, descriptor)| {
+                        let url = resolve_link(base_url, url).unwrap_or_else(|| url.to_owned());
+                        if descriptor.is_empty() {
+                            url
+                        } else {
+                            format!("{url} {descriptor}")
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+            }
+        }
+
+        if let Some(data_url) = inlined {
+            el.attrs
+                .retain(|(name, _)| name != "srcset" && name != "data-srcset");
+            match el.attrs.iter_mut().find(|(name, _)| name == "src") {
+                Some((_, src)) => *src = data_url,
+                None => el.attrs.push(("src".to_owned(), data_url)),
+            }
+        }
+        inline_offline_resources(&mut el.children, base_url, image_data_urls);
+    }
+}
+
 fn collect_id_elements(nodes: &[HtmlNode], max_elements: usize, out: &mut Vec<IdElementSnapshot>) {
     if out.len() >= max_elements {
         return;
@@ -4618,76 +6147,186 @@ fn collect_id_elements(nodes: &[HtmlNode], max_elements: usize, out: &mut Vec<Id
     }
 }
 
-fn collect_subresources_from_nodes(
-    nodes: &[HtmlNode],
+/// A subresource reference found in the tree; both the fetch plan and the manifest export
+/// are built from one walk so they cannot disagree about what a page references.
+#[derive(Debug)]
+enum DiscoveredResource<'a> {
+    /// `primary` marks the one URL an `<img>`/`<source>` actually fetches; `srcset` candidates and
+    /// fallback attributes are listed but not fetched.
+    Image {
+        kind: &'a str,
+        url: String,
+        lazy: bool,
+        descriptor: Option<&'a str>,
+        primary: bool,
+    },
+    Stylesheet(String),
+    ExternalScript(String),
+    InlineScript {
+        bytes: usize,
+    },
+    Icon {
+        rel: String,
+        url: String,
+    },
+    Preconnect {
+        rel: String,
+        url: String,
+    },
+    Preload {
+        rel: String,
+        url: String,
+        kind: &'a str,
+    },
+}
+
+fn discover_resources<'a>(
+    nodes: &'a [HtmlNode],
     base_url: &str,
-    stylesheets: &mut HashSet<String>,
-    images: &mut HashSet<String>,
-    scripts: &mut HashSet<String>,
+    out: &mut Vec<DiscoveredResource<'a>>,
 ) {
     for node in nodes {
         let HtmlNode::Element(el) = node else {
             continue;
         };
 
-        if canonical_element_tag(el.tag.as_str()) == "template" {
+        let tag = canonical_element_tag(el.tag.as_str());
+        if tag == "template" {
             continue;
         }
 
-        let tag = canonical_element_tag(el.tag.as_str());
+        let resolved = |name: &str| attr(el, name).and_then(|value| resolve_link(base_url, value));
         match tag {
-            "img" => {
-                if let Some(src) =
-                    image_source_attr(el).and_then(|value| resolve_link(base_url, value))
-                {
-                    images.insert(src);
+            "img" | "source" => {
+                let primary = image_source_attr(el).and_then(|value| resolve_link(base_url, value));
+                let lazy = tag == "img" && image_is_lazy(el);
+                let mut push_image = |kind, url: String, descriptor| {
+                    out.push(DiscoveredResource::Image {
+                        kind,
+                        primary: primary.as_ref() == Some(&url),
+                        url,
+                        lazy,
+                        descriptor,
+                    });
+                };
+                for name in ["src", "data-src"] {
+                    if let Some(url) = resolved(name) {
+                        push_image(tag, url, None);
+                    }
                 }
-            }
-            "source" => {
-                if let Some(src) =
-                    image_source_attr(el).and_then(|value| resolve_link(base_url, value))
-                {
-                    images.insert(src);
+                for name in ["srcset", "data-srcset"] {
+                    let Some(srcset) = attr(el, name) else {
+                        continue;
+                    };
+                    for (candidate, descriptor) in srcset_candidates(srcset) {
+                        if let Some(url) = resolve_link(base_url, candidate) {
+                            push_image("srcset", url, Some(descriptor));
+                        }
+                    }
                 }
             }
-            "link" => {
-                if is_stylesheet_link(el) {
-                    if let Some(href) =
-                        attr(el, "href").and_then(|value| resolve_link(base_url, value))
-                    {
-                        stylesheets.insert(href);
-                    }
+            "video" => {
+                if let Some(url) = resolved("poster") {
+                    out.push(DiscoveredResource::Image {
+                        kind: "poster",
+                        url,
+                        lazy: false,
+                        descriptor: None,
+                        primary: true,
+                    });
                 }
             }
-            "script" => {
-                if script_tag_is_executable(el)
-                    && let Some(src) =
-                        attr(el, "src").and_then(|value| resolve_link(base_url, value))
-                {
-                    scripts.insert(src);
+            "script" if script_tag_is_executable(el) => {
+                if let Some(url) = resolved("src") {
+                    out.push(DiscoveredResource::ExternalScript(url));
+                } else {
+                    let source = collect_text(&el.children);
+                    if !source.trim().is_empty() {
+                        out.push(DiscoveredResource::InlineScript {
+                            bytes: source.len(),
+                        });
+                    }
                 }
             }
-            "video" => {
-                if let Some(poster) =
-                    attr(el, "poster").and_then(|value| resolve_link(base_url, value))
-                {
-                    images.insert(poster);
+            "link" => {
+                if let Some(url) = resolved("href") {
+                    let rel = attr(el, "rel")
+                        .unwrap_or_default()
+                        .trim()
+                        .to_ascii_lowercase();
+                    let has_rel =
+                        |wanted: fn(&str) -> bool| rel.split_ascii_whitespace().any(wanted);
+                    if is_stylesheet_link(el) {
+                        out.push(DiscoveredResource::Stylesheet(url));
+                    } else if is_fetchable_resource_hint(el) {
+                        let kind = attr(el, "as").unwrap_or_default();
+                        out.push(DiscoveredResource::Preload { rel, url, kind });
+                    } else if has_rel(|token| token == "icon" || token.ends_with("-icon")) {
+                        out.push(DiscoveredResource::Icon { rel, url });
+                    } else if has_rel(|token| token == "preconnect" || token == "dns-prefetch") {
+                        out.push(DiscoveredResource::Preconnect { rel, url });
+                    }
                 }
             }
             _ => {}
         }
 
-        if let Some(background) =
+        if let Some(url) =
             parse_background_resource_attr(el).and_then(|value| resolve_link(base_url, value))
         {
-            images.insert(background);
+            out.push(DiscoveredResource::Image {
+                kind: "background",
+                url,
+                lazy: false,
+                descriptor: None,
+                primary: true,
+            });
         }
 
-        collect_subresources_from_nodes(&el.children, base_url, stylesheets, images, scripts);
+        discover_resources(&el.children, base_url, out);
     }
 }
 
-fn image_source_attr<'a>(el: &'a HtmlElement) -> Option<&'a str> {
+/// JSON object fragments for each category of `HtmlDocument::resource_manifest_json`.
+#[derive(Debug, Default)]
+struct ResourceManifestEntries {
+    stylesheets: Vec<String>,
+    scripts: Vec<String>,
+    images: Vec<String>,
+    icons: Vec<String>,
+    preconnects: Vec<String>,
+    preloads: Vec<String>,
+}
+
+fn resource_entry_json(fields: &[(&str, &str)]) -> String {
+    let mut out = String::from("{");
+    for (index, (name, value)) in fields.iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        push_json_string(&mut out, name);
+        out.push(':');
+        push_json_string(&mut out, value);
+    }
+    out.push('}');
+    out
+}
+
+/// Splits a `srcset` into `(url, descriptor)` pairs; the descriptor may be empty.
+fn srcset_candidates(srcset: &str) -> Vec<(&str, &str)> {
+    srcset
+        .split(',')
+        .filter_map(|candidate| {
+            let candidate = candidate.trim();
+            let (url, descriptor) = candidate
+                .split_once(|ch: char| ch.is_ascii_whitespace())
+                .unwrap_or((candidate, ""));
+            (!url.is_empty()).then_some((url, descriptor.trim()))
+        })
+        .collect()
+}
+
+fn image_source_attr(el: &HtmlElement) -> Option<&str> {
     attr(el, "src")
         .filter(|value| !value.trim().is_empty())
         .or_else(|| attr(el, "data-src").filter(|value| !value.trim().is_empty()))
@@ -4709,6 +6348,10 @@ fn parse_srcset_first_url(srcset: &str) -> Option<&str> {
     Some(url)
 }
 
+fn image_is_lazy(el: &HtmlElement) -> bool {
+    attr(el, "loading").is_some_and(|value| value.trim().eq_ignore_ascii_case("lazy"))
+}
+
 fn is_stylesheet_link(el: &HtmlElement) -> bool {
     attr(el, "rel")
         .map(|value| {
@@ -4719,6 +6362,26 @@ fn is_stylesheet_link(el: &HtmlElement) -> bool {
         .unwrap_or(false)
 }
 
+// `preconnect` and `dns-prefetch` never produce a fetch, so only hints that
+// name a resource are collected.
+fn is_fetchable_resource_hint(el: &HtmlElement) -> bool {
+    let Some(rel) = attr(el, "rel") else {
+        return false;
+    };
+    let mut tokens = rel.split_ascii_whitespace();
+    if tokens
+        .clone()
+        .any(|token| token.eq_ignore_ascii_case("nofollow"))
+    {
+        return false;
+    }
+    tokens.any(|token| {
+        token.eq_ignore_ascii_case("preload")
+            || token.eq_ignore_ascii_case("prefetch")
+            || token.eq_ignore_ascii_case("modulepreload")
+    })
+}
+
 fn script_tag_is_executable(el: &HtmlElement) -> bool {
     let script_type = attr(el, "type")
         .or_else(|| attr(el, "language"))
@@ -4740,12 +6403,26 @@ fn script_tag_is_executable(el: &HtmlElement) -> bool {
         || script_type == "application/x-javascript"
 }
 
+#[cfg(test)]
 fn parse_css_rules(css: &str) -> Vec<CssRule> {
+    parse_css_rules_for_media(css, MediaType::Screen)
+}
+
+#[cfg(test)]
+fn parse_css_rules_for_media(css: &str, media: MediaType) -> Vec<CssRule> {
+    parse_css_rules_from_order(css, media.into(), 0)
+}
+
+fn parse_css_rules_from_order(
+    css: &str,
+    media: MediaEnvironment,
+    first_order: usize,
+) -> Vec<CssRule> {
     let mut rules = Vec::new();
     let source = strip_css_comments(css);
-    let mut declaration_order = 0_usize;
+    let mut declaration_order = first_order;
     let mut blocks = Vec::new();
-    collect_css_rule_blocks(&source, &mut blocks);
+    collect_css_rule_blocks(&source, media, &mut blocks);
 
     for (selector_text, dec_text) in blocks {
         if selector_text.is_empty() || dec_text.is_empty() {
@@ -4771,7 +6448,7 @@ fn parse_css_rules(css: &str) -> Vec<CssRule> {
     rules
 }
 
-fn collect_css_rule_blocks(input: &str, out: &mut Vec<(String, String)>) {
+fn collect_css_rule_blocks(input: &str, media: MediaEnvironment, out: &mut Vec<(String, String)>) {
     let mut cursor = 0_usize;
     while let Some((selector, body, next_cursor)) = next_css_rule_block(input, cursor) {
         cursor = next_cursor;
@@ -4781,7 +6458,21 @@ fn collect_css_rule_blocks(input: &str, out: &mut Vec<(String, String)>) {
         }
 
         if is_css_grouping_at_rule(selector) {
-            collect_css_rule_blocks(body, out);
+            let media_query = selector
+                .get(..6)
+                .filter(|prefix| prefix.eq_ignore_ascii_case("@media"))
+                .and_then(|_| selector.get(6..));
+            if media_query.is_some_and(|query| !media_query_list_matches(query, media)) {
+                continue;
+            }
+            let supports_condition = selector
+                .get(..9)
+                .filter(|prefix| prefix.eq_ignore_ascii_case("@supports"))
+                .and_then(|_| selector.get(9..));
+            if supports_condition.is_some_and(|condition| !supports_condition_matches(condition)) {
+                continue;
+            }
+            collect_css_rule_blocks(body, media, out);
             continue;
         }
 
@@ -4943,6 +6634,55 @@ fn find_css_matching_brace(input: &str, open_brace: usize) -> Option<usize> {
     None
 }
 
+// The media type and `prefers-reduced-motion` are evaluated; other feature tests
+// such as `(min-width: ...)` are treated as matching.
+fn media_query_list_matches(query_list: &str, media: MediaEnvironment) -> bool {
+    let query_list = query_list.trim();
+    if query_list.is_empty() {
+        return true;
+    }
+
+    query_list.split(',').any(|query| {
+        let lower = query.trim().to_ascii_lowercase();
+        let mut words = lower.split_whitespace().peekable();
+        let negated = words.next_if_eq(&"not").is_some();
+        if !negated {
+            let _ = words.next_if_eq(&"only");
+        }
+        let type_matches = match words.peek().copied() {
+            None => return false,
+            Some(word) if word.starts_with('(') => true,
+            Some("all") => true,
+            Some("screen") => media.media == MediaType::Screen,
+            Some("print") => media.media == MediaType::Print,
+            Some(_) => false,
+        };
+        let features_match = lower
+            .split('(')
+            .skip(1)
+            .filter_map(|feature| feature.split(')').next())
+            .all(|feature| media_feature_matches(feature, media));
+        (type_matches && features_match) != negated
+    })
+}
+
+fn media_feature_matches(feature: &str, media: MediaEnvironment) -> bool {
+    let (name, value) = match feature.split_once(':') {
+        Some((name, value)) => (name.trim(), Some(value.trim())),
+        None => (feature.trim(), None),
+    };
+    match (name, value) {
+        ("prefers-reduced-motion", None | Some("reduce")) => media.reduce_motion,
+        ("prefers-reduced-motion", Some("no-preference")) => !media.reduce_motion,
+        ("prefers-reduced-motion", Some(_)) => false,
+        ("prefers-color-scheme", None) => true,
+        ("prefers-color-scheme", Some("dark")) => media.prefers_dark,
+        ("prefers-color-scheme", Some("light")) => !media.prefers_dark,
+        ("prefers-color-scheme", Some(_)) => false,
+        _ => true,
+    }
+}
+
 fn is_css_grouping_at_rule(selector: &str) -> bool {
     let lower = selector.trim().to_ascii_lowercase();
     lower.starts_with("@media")
@@ -4951,6 +6691,124 @@ fn is_css_grouping_at_rule(selector: &str) -> bool {
         || lower.starts_with("@document")
 }
 
+const MAX_SUPPORTS_CONDITION_DEPTH: usize = 32;
+
+/// Evaluates an `@supports` condition with `not`/`and`/`or` combinators.
+fn supports_condition_matches(condition: &str) -> bool {
+    let mut rest = condition.trim();
+    let matched = parse_supports_condition(&mut rest, MAX_SUPPORTS_CONDITION_DEPTH);
+    matched.is_some_and(|matched| matched && rest.trim().is_empty())
+}
+
+fn parse_supports_condition(rest: &mut &str, depth_left: usize) -> Option<bool> {
+    let depth_left = depth_left.checked_sub(1)?;
+    if let Some(after) = strip_supports_keyword(rest, "not") {
+        *rest = after;
+        return parse_supports_in_parens(rest, depth_left).map(|matched| !matched);
+    }
+
+    let mut matched = parse_supports_in_parens(rest, depth_left)?;
+    let mut combinator = None;
+    loop {
+        let keyword = if strip_supports_keyword(rest, "and").is_some() {
+            "and"
+        } else if strip_supports_keyword(rest, "or").is_some() {
+            "or"
+        } else {
+            return Some(matched);
+        };
+        if combinator.is_some_and(|previous| previous != keyword) {
+            return None;
+        }
+        combinator = Some(keyword);
+        *rest = strip_supports_keyword(rest, keyword)?;
+        let next = parse_supports_in_parens(rest, depth_left)?;
+        matched = if keyword == "and" {
+            matched && next
+        } else {
+            matched || next
+        };
+    }
+}
+
+fn parse_supports_in_parens(rest: &mut &str, depth_left: usize) -> Option<bool> {
+    let trimmed = rest.trim_start();
+    let (function, after_name) = match trimmed.find('(') {
+        Some(0) => (None, trimmed),
+        Some(open) => (Some(&trimmed[..open]), &trimmed[open..]),
+        None => return None,
+    };
+    let close = find_matching_paren(after_name)?;
+    let inner = after_name[1..close].trim();
+    *rest = &after_name[(close + 1)..];
+
+    if let Some(function) = function {
+        let function = function.to_ascii_lowercase();
+        return Some(function == "selector" && parse_selector(inner).is_some());
+    }
+
+    if inner.starts_with('(') || strip_supports_keyword(inner, "not").is_some() {
+        let mut nested = inner;
+        let matched = parse_supports_condition(&mut nested, depth_left)?;
+        return Some(matched && nested.trim().is_empty());
+    }
+
+    Some(
+        inner
+            .split_once(':')
+            .is_some_and(|(name, value)| supports_declaration(name, value)),
+    )
+}
+
+fn find_matching_paren(input: &str) -> Option<usize> {
+    let mut depth = 0_usize;
+    for (idx, ch) in input.char_indices() {
+        match ch {
+            '(' => depth = depth.saturating_add(1),
+            ')' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(idx);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn strip_supports_keyword<'a>(input: &'a str, keyword: &str) -> Option<&'a str> {
+    let trimmed = input.trim_start();
+    let head = trimmed.get(..keyword.len())?;
+    let tail = &trimmed[keyword.len()..];
+    (head.eq_ignore_ascii_case(keyword)
+        && tail.starts_with(|ch: char| ch.is_whitespace() || ch == '('))
+    .then_some(tail)
+}
+
+/// A declaration is supported when the property is known and, for properties the
+/// renderer interprets, the value parses.
+fn supports_declaration(name: &str, value: &str) -> bool {
+    let name = name.trim().to_ascii_lowercase();
+    let value = value.trim();
+    if name.is_empty() || value.is_empty() {
+        return false;
+    }
+    if name.starts_with("--") {
+        return true;
+    }
+
+    let css_wide_keyword = matches!(
+        value.to_ascii_lowercase().as_str(),
+        "inherit" | "initial" | "unset" | "revert" | "revert-layer"
+    );
+    let mut probe = StyleProps::default();
+    if apply_named_declaration(&name, value, &mut probe) {
+        return css_wide_keyword || !probe.is_empty();
+    }
+    is_mdn_reference_css_property(&name)
+}
+
 fn supports_inherit_keyword(property_name: &str) -> bool {
     matches!(
         property_name,
@@ -5629,7 +7487,8 @@ fn parse_declarations(input: &str) -> StyleProps {
     out
 }
 
-fn apply_named_declaration(name: &str, value: &str, out: &mut StyleProps) {
+/// Applies one declaration the renderer understands; returns `false` for other properties.
+fn apply_named_declaration(name: &str, value: &str, out: &mut StyleProps) -> bool {
     match name {
         "display" => {
             if let Some(v) = parse_display(value) {
@@ -5849,8 +7708,9 @@ fn apply_named_declaration(name: &str, value: &str, out: &mut StyleProps) {
         "border-right-width" => set_edge_value(&mut out.border_width.right, value),
         "border-bottom-width" => set_edge_value(&mut out.border_width.bottom, value),
         "border-left-width" => set_edge_value(&mut out.border_width.left, value),
-        _ => {}
+        _ => return false,
     }
+    true
 }
 
 fn apply_raw_css_aliases(style: &mut StyleProps) {
@@ -6965,51 +8825,125 @@ fn set_active_form_field(ctx: &mut Ctx<'_>, name: &str, value: Option<String>) {
     }
 }
 
-fn emit_inline_event(ctx: &mut Ctx<'_>, kind: DomEventKind, el: &HtmlElement, attr_name: &str) {
-    let Some(handler) = attr(el, attr_name).map(str::trim).map(ToOwned::to_owned) else {
-        return;
-    };
-    if handler.is_empty() {
-        return;
+fn emit_inline_event<'a>(ctx: &mut Ctx<'a>, kind: DomEventKind, el: &'a HtmlElement) {
+    if let Some(request) = inline_event_request(kind, el) {
+        ctx.action.dom_events.push(request);
     }
-    ctx.action.dom_events.push(DomEventRequest {
+}
+
+fn inline_event_request(kind: DomEventKind, el: &HtmlElement) -> Option<DomEventRequest> {
+    let handler = attr(el, kind.handler_attribute())
+        .map(str::trim)
+        .filter(|handler| !handler.is_empty())?;
+    Some(DomEventRequest {
         kind,
         target_id: attr(el, "id").map(ToOwned::to_owned),
-        inline_handler: handler,
-    });
+        inline_handler: handler.to_owned(),
+    })
 }
 
-fn submit_active_form(
-    ctx: &mut Ctx<'_>,
-    submit_name: Option<String>,
-    submit_value: Option<String>,
-    trigger: Option<&HtmlElement>,
+/// Emits hover, focus, blur and keydown handlers for an interactive widget.
+fn emit_interaction_events<'a>(
+    ui: &egui::Ui,
+    ctx: &mut Ctx<'a>,
+    el: &'a HtmlElement,
+    response: &egui::Response,
 ) {
-    let Some(form) = ctx.form_stack.last().cloned() else {
-        return;
-    };
-
-    if let Some(handler) = form.onsubmit.clone() {
-        let trimmed = handler.trim();
-        if !trimmed.is_empty() {
-            ctx.action.dom_events.push(DomEventRequest {
-                kind: DomEventKind::Submit,
-                target_id: form.form_id.clone(),
-                inline_handler: trimmed.to_owned(),
-            });
+    if attr(el, "onmouseover").is_some() {
+        let hover_id = response.id.with("pd_hovered");
+        let hovered = response.hovered();
+        let was_hovered = ui.ctx().data_mut(|data| {
+            let previous = data.get_temp::<bool>(hover_id).unwrap_or(false);
+            data.insert_temp(hover_id, hovered);
+            previous
+        });
+        if hovered && !was_hovered {
+            emit_inline_event(ctx, DomEventKind::MouseOver, el);
         }
     }
+    if response.gained_focus() {
+        emit_inline_event(ctx, DomEventKind::Focus, el);
+    }
+    if response.lost_focus() {
+        emit_inline_event(ctx, DomEventKind::Blur, el);
+    }
+    if response.has_focus()
+        && ui.input(|input| {
+            input
+                .events
+                .iter()
+                .any(|event| matches!(event, egui::Event::Key { pressed: true, .. }))
+        })
+    {
+        emit_inline_event(ctx, DomEventKind::KeyDown, el);
+    }
+}
 
-    if !form.method.eq_ignore_ascii_case("get") {
-        return;
+/// Fires `change` for text controls once an edited value is committed by leaving the field.
+fn emit_text_change_on_commit<'a>(
+    ui: &egui::Ui,
+    ctx: &mut Ctx<'a>,
+    el: &'a HtmlElement,
+    response: &egui::Response,
+) {
+    let pending_id = response.id.with("pd_change_pending");
+    if response.changed() {
+        ui.ctx().data_mut(|data| data.insert_temp(pending_id, true));
+    }
+    if response.lost_focus()
+        && ui
+            .ctx()
+            .data_mut(|data| data.remove_temp::<bool>(pending_id))
+            .unwrap_or(false)
+    {
+        emit_inline_event(ctx, DomEventKind::Change, el);
     }
+}
 
+fn submit_active_form<'a>(
+    ctx: &mut Ctx<'a>,
+    submit_name: Option<String>,
+    submit_value: Option<String>,
+    trigger: Option<&'a HtmlElement>,
+) {
+    let Some(form) = ctx.form_stack.last().cloned() else {
+        return;
+    };
     let mut fields = ctx
         .form_fields
         .get(&form.key)
         .cloned()
         .unwrap_or_else(HashMap::new);
 
+    let skip_validation =
+        form.novalidate || trigger.is_some_and(|trigger| has_attr(trigger, "formnovalidate"));
+    if !skip_validation {
+        let errors = ctx
+            .form_rules
+            .get(&form.key)
+            .map(|rules| validate_form_fields(&fields, rules))
+            .unwrap_or_default();
+        record_form_validation_errors(ctx, &form.key, &errors);
+        if !errors.is_empty() {
+            return;
+        }
+    }
+
+    if let Some(handler) = form.onsubmit.clone() {
+        let trimmed = handler.trim();
+        if !trimmed.is_empty() {
+            ctx.action.dom_events.push(DomEventRequest {
+                kind: DomEventKind::Submit,
+                target_id: form.form_id.clone(),
+                inline_handler: trimmed.to_owned(),
+            });
+        }
+    }
+
+    if !form.method.eq_ignore_ascii_case("get") {
+        return;
+    }
+
     if let Some(name) = submit_name
         .as_deref()
         .map(str::trim)
@@ -7028,25 +8962,203 @@ fn submit_active_form(
     }
 }
 
+/// Constraint attributes collected from a named form control.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct FormFieldRules {
+    /// `readonly` and `disabled` controls are barred from constraint validation.
+    barred: bool,
+    required: bool,
+    input_type: String,
+    pattern: Option<String>,
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+impl FormFieldRules {
+    fn from_element(el: &HtmlElement) -> Self {
+        let input_type = if el.tag == "input" {
+            attr(el, "type")
+                .map(|value| value.trim().to_ascii_lowercase())
+                .filter(|value| !value.is_empty())
+                .unwrap_or_else(|| "text".to_owned())
+        } else {
+            el.tag.clone()
+        };
+        let number = |name: &str| {
+            attr(el, name)
+                .and_then(|raw| raw.trim().parse::<f64>().ok())
+                .filter(|value| value.is_finite())
+        };
+        Self {
+            barred: has_attr(el, "readonly") || has_attr(el, "disabled"),
+            required: has_attr(el, "required"),
+            pattern: attr(el, "pattern")
+                .filter(|pattern| !pattern.is_empty())
+                .map(ToOwned::to_owned),
+            min: number("min"),
+            max: number("max"),
+            input_type,
+        }
+    }
+}
+
+/// A constraint a form field failed, reported before submission.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FormFieldError {
+    name: String,
+    message: String,
+}
+
+/// Checks `required`, email/url syntax, `pattern` and `min`/`max` for every ruled field.
+fn validate_form_fields(
+    fields: &HashMap<String, String>,
+    rules: &HashMap<String, FormFieldRules>,
+) -> Vec<FormFieldError> {
+    let mut errors = rules
+        .iter()
+        .filter_map(|(name, rules)| {
+            let value = fields.get(name).map(String::as_str).unwrap_or("");
+            validate_form_field(value, rules).map(|message| FormFieldError {
+                name: name.clone(),
+                message: message.to_owned(),
+            })
+        })
+        .collect::<Vec<_>>();
+    errors.sort_by(|left, right| left.name.cmp(&right.name));
+    errors
+}
+
+fn validate_form_field(value: &str, rules: &FormFieldRules) -> Option<String> {
+    if rules.barred {
+        return None;
+    }
+    if value.trim().is_empty() {
+        return rules
+            .required
+            .then(|| "Please fill out this field.".to_owned());
+    }
+    match rules.input_type.as_str() {
+        "email" if !value.split(',').all(|item| is_valid_email(item.trim())) => {
+            return Some("Please enter an email address.".to_owned());
+        }
+        "url" if Url::parse(value.trim()).is_err() => {
+            return Some("Please enter a URL.".to_owned());
+        }
+        "number" | "range" => {
+            let Some(number) = value.trim().parse::<f64>().ok().filter(|n| n.is_finite()) else {
+                return Some("Please enter a number.".to_owned());
+            };
+            if let Some(min) = rules.min.filter(|min| number < *min) {
+                return Some(format!("Value must be greater than or equal to {min}."));
+            }
+            if let Some(max) = rules.max.filter(|max| number > *max) {
+                return Some(format!("Value must be less than or equal to {max}."));
+            }
+        }
+        _ => {}
+    }
+    let pattern_applies = matches!(
+        rules.input_type.as_str(),
+        "text" | "search" | "url" | "tel" | "email" | "password"
+    );
+    if pattern_applies
+        && let Some(pattern) = rules.pattern.as_deref()
+        && let Ok(regex) = regress::Regex::with_flags(&format!("^(?:{pattern})$"), "u")
+        && regex.find(value).is_none()
+    {
+        return Some("Please match the requested format.".to_owned());
+    }
+    None
+}
+
+fn is_valid_email(value: &str) -> bool {
+    let Some((local, domain)) = value.split_once('@') else {
+        return false;
+    };
+    let local_ok = !local.is_empty()
+        && local
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ".!#$%&'*+/=?^_`{|}~-".contains(ch));
+    let domain_ok = !domain.is_empty()
+        && domain.split('.').all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || ch == '-')
+        });
+    local_ok && domain_ok
+}
+
+/// Remembers the constraints of a named control in the enclosing form.
+fn register_form_field_rules<'a>(ctx: &mut Ctx<'a>, el: &'a HtmlElement) {
+    let Some(form) = ctx.form_stack.last() else {
+        return;
+    };
+    let Some(name) = attr(el, "name")
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    else {
+        return;
+    };
+    let rules = FormFieldRules::from_element(el);
+    let entry = ctx
+        .form_rules
+        .entry(form.key.clone())
+        .or_default()
+        .entry(name.to_owned())
+        .or_insert_with(|| rules.clone());
+    // Radio groups are required when any member is.
+    entry.required |= rules.required;
+}
+
+fn form_validation_state_key(base_url: &str, form_key: &str, name: &str) -> String {
+    format!("{base_url}|invalid|{form_key}|{}", name.trim())
+}
+
+/// Replaces the form's stored validation messages so invalid controls render highlighted.
+fn record_form_validation_errors(ctx: &mut Ctx<'_>, form_key: &str, errors: &[FormFieldError]) {
+    let prefix = form_validation_state_key(ctx.base_url, form_key, "");
+    ctx.form_state.retain(|key, _| !key.starts_with(&prefix));
+    for error in errors {
+        ctx.form_state.insert(
+            form_validation_state_key(ctx.base_url, form_key, &error.name),
+            error.message.clone(),
+        );
+    }
+}
+
+/// Border and message color for controls that failed validation.
+const INVALID_FORM_CONTROL_COLOR: egui::Color32 = egui::Color32::from_rgb(200, 40, 40);
+
+/// Validation message stored for a control by the last blocked submission, if any.
+fn form_control_validation(ctx: &Ctx<'_>, el: &HtmlElement) -> Option<(String, String)> {
+    let form = ctx.form_stack.last()?;
+    let name = attr(el, "name")?;
+    let key = form_validation_state_key(ctx.base_url, &form.key, name);
+    let message = ctx.form_state.get(&key)?.clone();
+    Some((key, message))
+}
+
+fn render_validation_message(ui: &mut egui::Ui, message: &str) {
+    ui.label(
+        egui::RichText::new(message)
+            .small()
+            .color(INVALID_FORM_CONTROL_COLOR),
+    );
+}
+
 fn build_form_submit_url(action_url: &str, fields: &HashMap<String, String>) -> Option<String> {
-    let mut parsed = Url::parse(action_url).ok()?;
+    let mut url = Url::parse(action_url).ok()?;
     let mut pairs = fields
         .iter()
         .map(|(name, value)| (name.as_str(), value.as_str()))
         .collect::<Vec<_>>();
     pairs.sort_by(|left, right| left.0.cmp(right.0));
 
-    let mut serializer = url::form_urlencoded::Serializer::new(String::new());
-    for (name, value) in pairs {
-        serializer.append_pair(name, value);
-    }
-    let query = serializer.finish();
-    if query.is_empty() {
-        parsed.set_query(None);
-    } else {
-        parsed.set_query(Some(&query));
-    }
-    Some(parsed.to_string())
+    pd_net::url::set_query_pairs(&mut url, &pairs);
+    Some(url.to_string())
 }
 
 fn attr<'a>(el: &'a HtmlElement, name: &str) -> Option<&'a str> {
@@ -7066,7 +9178,7 @@ fn parse_usize_attr(el: &HtmlElement, name: &str) -> Option<usize> {
         .and_then(|value| usize::try_from(value).ok())
 }
 
-fn parse_background_resource_attr<'a>(el: &'a HtmlElement) -> Option<&'a str> {
+fn parse_background_resource_attr(el: &HtmlElement) -> Option<&str> {
     attr(el, "background").filter(|value| !value.trim().is_empty())
 }
 
@@ -7100,10 +9212,12 @@ fn resolve_link(base_url: &str, href: &str) -> Option<String> {
         _ => None,
     }
 }
-fn tokenize(source: &str) -> Vec<Token> {
+/// Returns the tokens plus whether start tags were cut off at `max_nodes`; text is not counted.
+fn tokenize(source: &str, max_nodes: usize) -> (Vec<Token>, bool) {
     let mut out = Vec::new();
     let bytes = source.as_bytes();
     let mut i = 0;
+    let mut nodes = 0_usize;
 
     while i < bytes.len() {
         if starts_with(bytes, i, b"<!--") {
@@ -7122,6 +9236,10 @@ fn tokenize(source: &str) -> Vec<Token> {
                 i = skip_decl(bytes, i);
                 continue;
             } else if let Some((tok, next)) = parse_start_tag(bytes, i) {
+                if nodes >= max_nodes {
+                    return (out, true);
+                }
+
                 let mut raw_text_tag: Option<String> = None;
                 if let Token::Start {
                     name, self_closing, ..
@@ -7133,6 +9251,7 @@ fn tokenize(source: &str) -> Vec<Token> {
                 }
 
                 out.push(tok);
+                nodes += 1;
                 i = next;
 
                 if let Some(tag_name) = raw_text_tag {
@@ -7160,15 +9279,20 @@ fn tokenize(source: &str) -> Vec<Token> {
         i = next;
     }
 
-    out
+    (out, false)
 }
 
-fn build_tree(tokens: Vec<Token>) -> HtmlElement {
+/// Builds the element tree; elements opened deeper than `max_depth` are kept as empty
+/// leaves and their content is reparented to the deepest allowed ancestor.
+fn build_tree(tokens: Vec<Token>, max_depth: usize) -> (HtmlElement, bool) {
     let mut stack = vec![HtmlElement {
         tag: "document".to_owned(),
         attrs: Vec::new(),
         children: Vec::new(),
     }];
+    // Tags opened past the depth cap, so their end tags don't close real ancestors.
+    let mut flattened: Vec<String> = Vec::new();
+    let mut depth_truncated = false;
 
     for token in tokens {
         match token {
@@ -7192,11 +9316,22 @@ fn build_tree(tokens: Vec<Token>) -> HtmlElement {
                     if let Some(cur) = stack.last_mut() {
                         cur.children.push(HtmlNode::Element(el));
                     }
+                } else if stack.len() >= max_depth.max(1) {
+                    depth_truncated = true;
+                    flattened.push(name);
+                    if let Some(cur) = stack.last_mut() {
+                        cur.children.push(HtmlNode::Element(el));
+                    }
                 } else {
                     stack.push(el);
                 }
             }
             Token::End { name } => {
+                if let Some(index) = flattened.iter().rposition(|open| *open == name) {
+                    flattened.truncate(index);
+                    continue;
+                }
+                flattened.clear();
                 while stack.len() > 1 {
                     let node = match stack.pop() {
                         Some(v) => v,
@@ -7224,11 +9359,55 @@ fn build_tree(tokens: Vec<Token>) -> HtmlElement {
         }
     }
 
-    stack.pop().unwrap_or(HtmlElement {
+    let root = stack.pop().unwrap_or(HtmlElement {
         tag: "document".to_owned(),
         attrs: Vec::new(),
         children: Vec::new(),
-    })
+    });
+    (root, depth_truncated)
+}
+
+/// Fills unset [`MetaInfo`] fields from `<meta>` tags, keeping the first value of each.
+fn collect_meta_tags(nodes: &[HtmlNode], meta: &mut MetaInfo) {
+    for node in nodes {
+        let HtmlNode::Element(el) = node else {
+            continue;
+        };
+        if el.tag == "body" {
+            continue;
+        }
+        if el.tag != "meta" {
+            collect_meta_tags(&el.children, meta);
+            continue;
+        }
+
+        let Some(content) = attr(el, "content")
+            .map(collapse_whitespace)
+            .map(|content| content.trim().to_owned())
+            .filter(|content| !content.is_empty())
+        else {
+            continue;
+        };
+        let key = attr(el, "property")
+            .or_else(|| attr(el, "name"))
+            .map(|key| key.trim().to_ascii_lowercase())
+            .unwrap_or_default();
+        let slot = match key.as_str() {
+            "description" => &mut meta.description,
+            "og:title" => &mut meta.og_title,
+            "og:image" | "og:image:url" => &mut meta.og_image,
+            "theme-color" => {
+                if meta.theme_color.is_none() {
+                    meta.theme_color = parse_color(&content);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        if slot.is_none() {
+            *slot = Some(content);
+        }
+    }
 }
 
 fn find_title(root: &HtmlElement) -> Option<String> {
@@ -7271,6 +9450,24 @@ fn collect_text(nodes: &[HtmlNode]) -> String {
     out
 }
 
+/// Like [`collect_text`], but skips subtrees marked `hidden`, `inert`, or `aria-hidden`.
+fn collect_rendered_text(nodes: &[HtmlNode]) -> String {
+    let mut out = String::new();
+    for node in nodes {
+        match node {
+            HtmlNode::Text(t) => out.push_str(t),
+            HtmlNode::Element(el) => {
+                if is_non_rendered_element_tag(el.tag.as_str()) || element_has_hidden_semantics(el)
+                {
+                    continue;
+                }
+                out.push_str(&collect_rendered_text(&el.children));
+            }
+        }
+    }
+    out
+}
+
 fn collect_renderable_text(
     nodes: &[HtmlNode],
     sheet: &StyleSheet,
@@ -7292,7 +9489,7 @@ fn collect_renderable_text(
                 }
 
                 let style = style_for(el, sheet, inherited, ancestors);
-                if matches!(style.display, Some(Display::None)) {
+                if is_effectively_hidden(el, &style) {
                     continue;
                 }
 
@@ -7323,6 +9520,134 @@ fn collect_static_fallback_text(nodes: &[HtmlNode], out: &mut String) {
     }
 }
 
+fn collect_accessibility_nodes(
+    nodes: &[HtmlNode],
+    sheet: &StyleSheet,
+    inherited: &StyleProps,
+    ancestors: &mut Vec<SelectorSubject>,
+    out: &mut Vec<String>,
+) {
+    for node in nodes {
+        match node {
+            HtmlNode::Text(text) => {
+                let collapsed = collapse_whitespace(text);
+                if !collapsed.is_empty() {
+                    out.push(accessibility_node_json("text", &collapsed, &[]));
+                }
+            }
+            HtmlNode::Element(el) => {
+                if is_non_rendered_element_tag(el.tag.as_str()) {
+                    continue;
+                }
+
+                let style = style_for(el, sheet, inherited, ancestors);
+                if is_effectively_hidden(el, &style) {
+                    continue;
+                }
+
+                let role = accessibility_role(el);
+                let name = accessibility_name(el, role);
+                let mut children = Vec::new();
+                ancestors.push(selector_subject(el));
+                collect_accessibility_nodes(&el.children, sheet, &style, ancestors, &mut children);
+                ancestors.pop();
+                out.push(accessibility_node_json(role, &name, &children));
+            }
+        }
+    }
+}
+
+fn accessibility_role(el: &HtmlElement) -> &str {
+    if let Some(role) = attr(el, "role")
+        .and_then(|value| value.split_whitespace().next())
+        .filter(|value| !value.is_empty())
+    {
+        return role;
+    }
+
+    match canonical_element_tag(el.tag.as_str()) {
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => "heading",
+        "img" => "img",
+        "a" if has_attr(el, "href") => "link",
+        "button" => "button",
+        "ul" | "ol" | "menu" | "dir" => "list",
+        "li" => "listitem",
+        "nav" => "navigation",
+        "main" => "main",
+        "header" => "banner",
+        "footer" => "contentinfo",
+        "aside" => "complementary",
+        "section" => "region",
+        "article" => "article",
+        "form" => "form",
+        "p" => "paragraph",
+        "table" => "table",
+        "tr" => "row",
+        "th" => "columnheader",
+        "td" => "cell",
+        "dialog" => "dialog",
+        "textarea" => "textbox",
+        "select" => "combobox",
+        "hr" => "separator",
+        "input" => match attr(el, "type").map(str::to_ascii_lowercase).as_deref() {
+            Some("checkbox") => "checkbox",
+            Some("radio") => "radio",
+            Some("button" | "submit" | "reset") => "button",
+            Some("range") => "slider",
+            _ => "textbox",
+        },
+        _ => "generic",
+    }
+}
+
+fn accessibility_name(el: &HtmlElement, role: &str) -> String {
+    if let Some(label) = attr(el, "aria-label")
+        .map(collapse_whitespace)
+        .filter(|value| !value.is_empty())
+    {
+        return label;
+    }
+
+    match role {
+        "img" => attr(el, "alt").map(collapse_whitespace).unwrap_or_default(),
+        "heading" | "link" | "button" | "listitem" | "cell" | "columnheader" | "paragraph" => {
+            collapse_whitespace(&collect_text(&el.children))
+        }
+        "textbox" | "combobox" => attr(el, "placeholder")
+            .or_else(|| attr(el, "title"))
+            .map(collapse_whitespace)
+            .unwrap_or_default(),
+        _ => attr(el, "title").map(collapse_whitespace).unwrap_or_default(),
+    }
+}
+
+fn accessibility_node_json(role: &str, name: &str, children: &[String]) -> String {
+    let mut out = String::from("{\"role\":");
+    push_json_string(&mut out, role);
+    out.push_str(",\"name\":");
+    push_json_string(&mut out, name);
+    out.push_str(",\"children\":[");
+    out.push_str(&children.join(","));
+    out.push_str("]}");
+    out
+}
+
+fn push_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+}
+
 fn find_first_element<'a>(nodes: &'a [HtmlNode], tag: &str) -> Option<&'a HtmlElement> {
     for node in nodes {
         let HtmlNode::Element(el) = node else {
@@ -7341,6 +9666,21 @@ fn find_first_element<'a>(nodes: &'a [HtmlNode], tag: &str) -> Option<&'a HtmlEl
     None
 }
 
+fn find_first_element_mut<'a>(nodes: &'a mut [HtmlNode], tag: &str) -> Option<&'a mut HtmlElement> {
+    for node in nodes {
+        let HtmlNode::Element(el) = node else {
+            continue;
+        };
+        if el.tag.eq_ignore_ascii_case(tag) {
+            return Some(el);
+        }
+        if let Some(found) = find_first_element_mut(&mut el.children, tag) {
+            return Some(found);
+        }
+    }
+    None
+}
+
 fn collapse_whitespace(input: &str) -> String {
     let mut out = String::new();
     let mut ws = false;
@@ -7736,17 +10076,27 @@ fn is_block(tag: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::{
-        AlignContent, AlignItems, Display, FlexDirection, FlexWrap, FontFamilyChoice, HtmlDocument,
-        HtmlElement, HtmlNode, JustifyContent, MDN_REFERENCE_ATTRIBUTES, MDN_REFERENCE_ELEMENTS,
-        OverflowMode, PositionMode, ScriptDescriptor, ScriptPosition, StyleProps, StyleSheet,
-        TextAlign, TextEffects, TextTransform, WhiteSpaceMode, collapse_whitespace, decode_entities, find_first_element,
+        AlignContent, AlignItems, CssRule, DEFAULT_MAX_FONT_SIZE, DEFAULT_MIN_FONT_SIZE, Display,
+        DomEventKind, FlexDirection, FlexWrap, FontFamilyChoice, FormFieldError, FormFieldRules,
+        HashMap, HtmlDocument, HtmlElement, HtmlNode, ImageRenderDecision, JustifyContent,
+        MDN_REFERENCE_ATTRIBUTES, MDN_REFERENCE_ELEMENTS, MediaEnvironment, MediaType, MetaInfo,
+        OverflowBehavior, OverflowMode, PositionMode, PositionedPaint, RenderImage,
+        RenderResources, ScriptDescriptor, ScriptPosition, StyleProps, StyleSheet, TableGridCell,
+        TableRowItem, TextAlign, TextDirection, TextEffects, TextTransform, WhiteSpaceMode,
+        apply_white_space, attr, build_form_submit_url, build_table_grid, clamp_font_size,
+        collapse_whitespace, collect_rendered_text, collect_text, decode_entities,
+        default_page_colors, find_element_by_id_mut, find_first_element, form_focus_order,
+        image_render_decision, inline_event_request, is_effectively_hidden,
         is_likely_screen_reader_only, is_mdn_reference_attribute, is_mdn_reference_css_property,
-        is_mdn_reference_element, is_void, mdn_reference_css_properties,
-        normalize_text_for_render, ordered_list_marker, parse_color, parse_css_rules,
-        parse_declarations, parse_legacy_font_size, resolve_link, selector_subject, style_for,
-        unordered_list_marker,
+        is_mdn_reference_element, is_rtl_layout, is_skipped_render_tag, is_void,
+        mdn_reference_css_properties, media_query_list_matches, normalize_text_for_render,
+        ordered_list_marker, overflow_behavior, parse_color, parse_css_rules,
+        parse_css_rules_for_media, parse_declarations, parse_legacy_font_size,
+        positioned_paint_order, positioned_rect, resolve_link, resolve_positioned_rects,
+        selector_subject, style_for, table_row_layout, truncate_text_to_fit, unordered_list_marker,
+        validate_form_fields,
     };
-    use eframe::egui::Color32;
+    use eframe::egui::{self, Color32};
 
     #[test]
     fn parses_title() {
@@ -7858,6 +10208,41 @@ mod tests {
         assert_eq!(doc.renderable_text_len(), "Shown".len());
     }
 
+    #[test]
+    fn aria_hidden_span_is_skipped_in_inline_context() {
+        let src = "<html><body><p>Shown <b>Bold<span aria-hidden=\"true\">Icon</span></b></p></body></html>";
+        let doc = HtmlDocument::parse(src);
+        assert_eq!(doc.renderable_text_len(), "Shown Bold".len());
+        let Some(bold) = find_first_element(&doc.root.children, "b") else {
+            panic!("expected a <b> element");
+        };
+        assert_eq!(collect_rendered_text(&bold.children), "Bold");
+        assert_eq!(collect_text(&bold.children), "BoldIcon");
+    }
+
+    #[test]
+    fn hidden_div_is_skipped_in_block_context() {
+        let src =
+            "<html><body><div hidden>Hidden</div><div inert>Inert</div><p>Shown</p></body></html>";
+        let doc = HtmlDocument::parse(src);
+        assert_eq!(doc.renderable_text_len(), "Shown".len());
+        let Some(div) = find_first_element(&doc.root.children, "div") else {
+            panic!("expected a <div> element");
+        };
+        assert!(is_effectively_hidden(div, &StyleProps::default()));
+    }
+
+    #[test]
+    fn aria_hidden_false_is_rendered() {
+        let src = "<html><body><span aria-hidden=\"false\">Shown</span></body></html>";
+        let doc = HtmlDocument::parse(src);
+        assert_eq!(doc.renderable_text_len(), "Shown".len());
+        let Some(span) = find_first_element(&doc.root.children, "span") else {
+            panic!("expected a <span> element");
+        };
+        assert!(!is_effectively_hidden(span, &StyleProps::default()));
+    }
+
     #[test]
     fn renderable_text_ignores_noscript_content() {
         let src = "<html><body><noscript>Enable JS</noscript></body></html>";
@@ -7865,6 +10250,20 @@ mod tests {
         assert_eq!(doc.renderable_text_len(), 0);
     }
 
+    #[test]
+    fn noscript_renders_only_when_js_is_disabled() {
+        assert!(is_skipped_render_tag("noscript", true));
+        assert!(!is_skipped_render_tag("noscript", false));
+        assert!(!is_skipped_render_tag("NOSCRIPT", false));
+        assert!(is_skipped_render_tag("script", false));
+        assert!(!is_skipped_render_tag("p", true));
+
+        let mut doc = HtmlDocument::parse("<body><noscript>Enable JS</noscript></body>");
+        assert!(doc.js_enabled);
+        doc.set_js_enabled(false);
+        assert!(!doc.js_enabled);
+    }
+
     #[test]
     fn renderable_text_ignores_template_content() {
         let src = "<html><body><template><p>Hidden</p></template><p>Shown</p></body></html>";
@@ -7916,34 +10315,193 @@ mod tests {
     }
 
     #[test]
-    fn parses_nested_media_css_rules() {
-        let css = "@media screen and (min-width: 100px){ .hero{display:block} #q{width:100%} }";
-        let rules = parse_css_rules(css);
-        assert_eq!(rules.len(), 2);
-        assert!(rules.iter().any(|rule| {
-            rule.sel.segments.iter().any(|segment| {
-                segment
-                    .simple
-                    .classes
+    fn print_only_media_rules_apply_in_print_mode() {
+        let css = "p { color: #000000; } \
+                   @media print { .no-print { display: none; } } \
+                   @media screen { .screen-only { color: #ff0000; } }";
+        let screen = parse_css_rules_for_media(css, MediaType::Screen);
+        let print = parse_css_rules_for_media(css, MediaType::Print);
+        let has_class = |rules: &[CssRule], class: &str| {
+            rules.iter().any(|rule| {
+                rule.sel
+                    .segments
                     .iter()
-                    .any(|class_name| class_name == "hero")
+                    .any(|segment| segment.simple.classes.iter().any(|name| name == class))
             })
-        }));
-        assert!(rules.iter().any(|rule| {
-            rule.sel
-                .segments
-                .iter()
-                .any(|segment| segment.simple.id.as_deref() == Some("q"))
-        }));
+        };
+
+        assert_eq!(screen.len(), 2);
+        assert!(!has_class(&screen, "no-print"));
+        assert!(has_class(&screen, "screen-only"));
+        assert_eq!(print.len(), 2);
+        assert!(has_class(&print, "no-print"));
+        assert!(!has_class(&print, "screen-only"));
     }
 
     #[test]
-    fn keeps_semicolons_inside_css_function_values() {
-        let css = ".icon{background-image:url(\"data:image/svg+xml;utf8,<svg></svg>\");color:#111}";
-        let rules = parse_css_rules(css);
-        assert_eq!(rules.len(), 1);
-        assert!(
-            rules[0]
+    fn matches_media_query_lists_by_type() {
+        assert!(media_query_list_matches(
+            " screen, print",
+            MediaType::Print.into()
+        ));
+        assert!(media_query_list_matches(
+            "only screen and (max-width: 600px)",
+            MediaType::Screen.into()
+        ));
+        assert!(!media_query_list_matches(
+            "not print",
+            MediaType::Print.into()
+        ));
+        assert!(media_query_list_matches(
+            "not print",
+            MediaType::Screen.into()
+        ));
+        assert!(media_query_list_matches(
+            "(min-width: 40em)",
+            MediaType::Print.into()
+        ));
+        assert!(!media_query_list_matches(
+            "speech",
+            MediaType::Screen.into()
+        ));
+    }
+
+    #[test]
+    fn evaluates_prefers_reduced_motion_against_setting() {
+        let reduced = MediaEnvironment {
+            media: MediaType::Screen,
+            reduce_motion: true,
+            prefers_dark: false,
+        };
+        let full: MediaEnvironment = MediaType::Screen.into();
+        let query = "screen and (prefers-reduced-motion: reduce)";
+        assert!(media_query_list_matches(query, reduced));
+        assert!(!media_query_list_matches(query, full));
+        assert!(media_query_list_matches(
+            "(prefers-reduced-motion: no-preference)",
+            full
+        ));
+        assert!(media_query_list_matches(
+            "not (prefers-reduced-motion)",
+            full
+        ));
+
+        let mut doc = HtmlDocument::parse(
+            "<html><head><style>.spin { color: #ff0000; } \
+             @media (prefers-reduced-motion: reduce) { .spin { color: #0000ff; } }\
+             </style></head></html>",
+        );
+        doc.append_stylesheet_source(".other { color: #00ff00; }");
+        let el = HtmlElement {
+            tag: "div".to_owned(),
+            attrs: vec![("class".to_owned(), "spin".to_owned())],
+            children: Vec::new(),
+        };
+
+        assert!(!doc.reduce_motion);
+        let style = style_for(&el, &doc.styles, &StyleProps::default(), &[]);
+        assert_eq!(style.color, Some(Color32::from_rgb(255, 0, 0)));
+
+        doc.set_reduce_motion(true);
+        assert_eq!(doc.css_rule_count(), 3);
+        let style = style_for(&el, &doc.styles, &StyleProps::default(), &[]);
+        assert_eq!(style.color, Some(Color32::from_rgb(0, 0, 255)));
+
+        doc.set_reduce_motion(false);
+        assert_eq!(doc.css_rule_count(), 2);
+    }
+
+    #[test]
+    fn dark_scheme_sets_dark_defaults_and_matches_dark_media_rules() {
+        let mut doc = HtmlDocument::parse(
+            "<html><head><style>.card { color: #000000; } \
+             @media (prefers-color-scheme: dark) { .card { color: #ffffff; } }\
+             </style></head></html>",
+        );
+        let el = HtmlElement {
+            tag: "div".to_owned(),
+            attrs: vec![("class".to_owned(), "card".to_owned())],
+            children: Vec::new(),
+        };
+
+        doc.set_prefers_dark(true);
+        assert!(doc.prefers_dark);
+        let style = style_for(&el, &doc.styles, &StyleProps::default(), &[]);
+        assert_eq!(style.color, Some(Color32::from_rgb(255, 255, 255)));
+        let (bg, text) = default_page_colors(doc.prefers_dark);
+        assert!(bg.r() < 64 && text.r() > 192);
+
+        let print_style = style_for(&el, &doc.print_styles, &StyleProps::default(), &[]);
+        assert_eq!(print_style.color, Some(Color32::from_rgb(0, 0, 0)));
+    }
+
+    #[test]
+    fn light_scheme_keeps_light_defaults_and_skips_dark_media_rules() {
+        let doc = HtmlDocument::parse(
+            "<html><head><style>.card { color: #000000; } \
+             @media (prefers-color-scheme: dark) { .card { color: #ffffff; } }\
+             @media (prefers-color-scheme: light) { .card { background: #eeeeee; } }\
+             </style></head></html>",
+        );
+        let el = HtmlElement {
+            tag: "div".to_owned(),
+            attrs: vec![("class".to_owned(), "card".to_owned())],
+            children: Vec::new(),
+        };
+
+        assert!(!doc.prefers_dark);
+        let style = style_for(&el, &doc.styles, &StyleProps::default(), &[]);
+        assert_eq!(style.color, Some(Color32::from_rgb(0, 0, 0)));
+        assert_eq!(style.bg, Some(Color32::from_rgb(238, 238, 238)));
+        assert_eq!(default_page_colors(false), (Color32::WHITE, Color32::BLACK));
+    }
+
+    #[test]
+    fn supports_blocks_keep_only_supported_feature_queries() {
+        let kept = |condition: &str| {
+            let css = format!("@supports {condition} {{ .a {{ color: red; }} }}");
+            parse_css_rules(&css).len() == 1
+        };
+
+        assert!(kept("(display: grid)"));
+        assert!(!kept("(display: nonsense)"));
+        assert!(!kept("(made-up-property: 1)"));
+        assert!(kept("not (display: nonsense)"));
+        assert!(!kept("not (display: grid)"));
+        assert!(!kept("(display: grid) and (display: nonsense)"));
+        assert!(kept("(display: nonsense) or (display: flex)"));
+        assert!(kept("(display: grid) and (not (display: nonsense))"));
+    }
+
+    #[test]
+    fn parses_nested_media_css_rules() {
+        let css = "@media screen and (min-width: 100px){ .hero{display:block} #q{width:100%} }";
+        let rules = parse_css_rules(css);
+        assert_eq!(rules.len(), 2);
+        assert!(rules.iter().any(|rule| {
+            rule.sel.segments.iter().any(|segment| {
+                segment
+                    .simple
+                    .classes
+                    .iter()
+                    .any(|class_name| class_name == "hero")
+            })
+        }));
+        assert!(rules.iter().any(|rule| {
+            rule.sel
+                .segments
+                .iter()
+                .any(|segment| segment.simple.id.as_deref() == Some("q"))
+        }));
+    }
+
+    #[test]
+    fn keeps_semicolons_inside_css_function_values() {
+        let css = ".icon{background-image:url(\"data:image/svg+xml;utf8,<svg></svg>\");color:#111}";
+        let rules = parse_css_rules(css);
+        assert_eq!(rules.len(), 1);
+        assert!(
+            rules[0]
                 .declarations
                 .iter()
                 .any(|declaration| declaration.name == "color")
@@ -8042,6 +10600,117 @@ mod tests {
         assert_eq!(style.overflow_y, Some(OverflowMode::Auto));
     }
 
+    #[test]
+    fn decides_scrolling_and_clipping_from_overflow() {
+        let scrollable = parse_declarations("height:120px;overflow:auto;");
+        assert_eq!(
+            overflow_behavior(&scrollable),
+            OverflowBehavior::Scroll {
+                horizontal: true,
+                vertical: true,
+                always_show_bars: false,
+                max_height: Some(120.0),
+            }
+        );
+
+        let capped = parse_declarations("max-height:80px;overflow-y:scroll;");
+        assert_eq!(
+            overflow_behavior(&capped),
+            OverflowBehavior::Scroll {
+                horizontal: false,
+                vertical: true,
+                always_show_bars: true,
+                max_height: Some(80.0),
+            }
+        );
+
+        let unconstrained = parse_declarations("overflow-y:auto;");
+        assert_eq!(overflow_behavior(&unconstrained), OverflowBehavior::Visible);
+
+        let hidden = parse_declarations("height:40px;overflow:hidden;");
+        assert_eq!(overflow_behavior(&hidden), OverflowBehavior::Clip);
+
+        let visible = parse_declarations("height:40px;overflow:visible;");
+        assert_eq!(overflow_behavior(&visible), OverflowBehavior::Visible);
+    }
+
+    #[test]
+    fn resolves_absolute_rect_from_insets_against_container() {
+        let container =
+            egui::Rect::from_min_size(egui::pos2(100.0, 50.0), egui::vec2(400.0, 300.0));
+        let pinned =
+            parse_declarations("position:absolute;top:10px;right:16px;width:120px;height:40px;");
+        let rect = positioned_rect(container, &pinned);
+        assert_eq!(rect.min, egui::pos2(364.0, 60.0));
+        assert_eq!(rect.size(), egui::vec2(120.0, 40.0));
+
+        let stretched = parse_declarations("position:absolute;top:0;right:0;bottom:0;left:0;");
+        assert_eq!(positioned_rect(container, &stretched), container);
+
+        let footer = parse_declarations("position:absolute;left:20px;bottom:10px;height:30px;");
+        let rect = positioned_rect(container, &footer);
+        assert_eq!(rect.min, egui::pos2(120.0, 310.0));
+        assert_eq!(rect.width(), 380.0);
+    }
+
+    #[test]
+    fn deferred_absolute_boxes_resolve_against_their_positioned_ancestor() {
+        let element = HtmlElement {
+            tag: "div".to_owned(),
+            attrs: Vec::new(),
+            children: Vec::new(),
+        };
+        let fixed = egui::Rect::from_min_size(egui::pos2(0.0, 0.0), egui::vec2(5.0, 5.0));
+        let entry = |rect| PositionedPaint {
+            element: &element,
+            inherited: StyleProps::default(),
+            ancestors: Vec::new(),
+            style: parse_declarations("position:absolute;top:10px;right:0;width:50px;"),
+            rect,
+            z_index: 0,
+        };
+        let mut entries = vec![entry(None), entry(Some(fixed))];
+
+        let block = egui::Rect::from_min_size(egui::pos2(100.0, 200.0), egui::vec2(300.0, 150.0));
+        resolve_positioned_rects(&mut entries, block);
+        assert_eq!(
+            entries[0].rect,
+            Some(egui::Rect::from_min_size(
+                egui::pos2(350.0, 210.0),
+                egui::vec2(50.0, 140.0)
+            ))
+        );
+        assert_eq!(entries[1].rect, Some(fixed));
+    }
+
+    #[test]
+    fn paints_higher_z_index_positioned_elements_later() {
+        let elements = ["modal", "first", "backdrop", "second"].map(|id| HtmlElement {
+            tag: "div".to_owned(),
+            attrs: vec![("id".to_owned(), id.to_owned())],
+            children: Vec::new(),
+        });
+        let entry = |element, z_index: i32| PositionedPaint {
+            element,
+            inherited: StyleProps::default(),
+            ancestors: Vec::new(),
+            style: StyleProps::default(),
+            rect: None,
+            z_index,
+        };
+        let ordered = positioned_paint_order(vec![
+            entry(&elements[0], 10),
+            entry(&elements[1], 0),
+            entry(&elements[2], -1),
+            entry(&elements[3], 0),
+        ]);
+        let ids = ordered
+            .iter()
+            .filter_map(|entry| attr(entry.element, "id"))
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["backdrop", "first", "second", "modal"]);
+    }
+
     #[test]
     fn parses_visibility_and_opacity_declarations() {
         let hidden = parse_declarations("visibility:hidden;opacity:0;");
@@ -8111,6 +10780,25 @@ mod tests {
         assert_eq!(style.border_radius, Some(12.0));
     }
 
+    #[test]
+    fn white_space_modes_normalize_multi_line_text() {
+        let input = "  one   two\n\tthree  \n four";
+        assert_eq!(
+            apply_white_space(input, WhiteSpaceMode::Normal),
+            "one two three four"
+        );
+        assert_eq!(
+            apply_white_space(input, WhiteSpaceMode::NoWrap),
+            "one two three four"
+        );
+        assert_eq!(apply_white_space(input, WhiteSpaceMode::Pre), input);
+        assert_eq!(apply_white_space(input, WhiteSpaceMode::PreWrap), input);
+        assert_eq!(
+            apply_white_space(input, WhiteSpaceMode::PreLine),
+            "one two\nthree\nfour"
+        );
+    }
+
     #[test]
     fn normalizes_text_with_white_space_and_transform() {
         let style = StyleProps {
@@ -8163,6 +10851,63 @@ mod tests {
         );
     }
 
+    #[test]
+    fn exports_categorized_resource_manifest_json() {
+        let src = "<html><head><link rel=\"stylesheet\" href=\"css/site.css\">\
+                   <link rel=\"icon\" href=\"/favicon.ico\">\
+                   <link rel=\"preconnect\" href=\"https://cdn.example.com\">\
+                   <script>window.ready = true;</script></head>\
+                   <body><img src=\"/hero.png\" srcset=\"hero-2x.png 2x, /hero-3x.png 3x\">\
+                   <script src=\"https://static.example.com/app.js\"></script></body></html>";
+        let doc = HtmlDocument::parse(src);
+        let json = doc.resource_manifest_json("https://example.com/base/index.html");
+        assert_eq!(
+            json,
+            concat!(
+                "{\"stylesheets\":[{\"url\":\"https://example.com/base/css/site.css\"}],",
+                "\"scripts\":[{\"type\":\"inline\",\"bytes\":20},",
+                "{\"type\":\"external\",\"url\":\"https://static.example.com/app.js\"}],",
+                "\"images\":[{\"type\":\"img\",\"url\":\"https://example.com/hero.png\",",
+                "\"loading\":\"eager\"},",
+                "{\"type\":\"srcset\",\"url\":\"https://example.com/base/hero-2x.png\",",
+                "\"descriptor\":\"2x\"},",
+                "{\"type\":\"srcset\",\"url\":\"https://example.com/hero-3x.png\",",
+                "\"descriptor\":\"3x\"}],",
+                "\"icons\":[{\"rel\":\"icon\",\"url\":\"https://example.com/favicon.ico\"}],",
+                "\"preconnects\":[{\"rel\":\"preconnect\",\"url\":\"https://cdn.example.com\"}],",
+                "\"preloads\":[]}"
+            )
+        );
+    }
+
+    #[test]
+    fn resource_manifest_lists_every_fetched_subresource() {
+        let src = "<html><head><link rel=\"preload\" href=\"/font.woff2\" as=\"font\"></head>\
+                   <body background=\"/bg.png\"><img loading=\"lazy\" srcset=\"/a.png 1x, /b.png 2x\">\
+                   <video poster=\"/poster.jpg\"></video></body></html>";
+        let doc = HtmlDocument::parse(src);
+        let base = "https://example.com/index.html";
+        let manifest = doc.collect_subresources(base);
+        assert_eq!(
+            manifest.lazy_images,
+            vec!["https://example.com/a.png".to_owned()]
+        );
+
+        let json = doc.resource_manifest_json(base);
+        for url in manifest
+            .images
+            .iter()
+            .chain(&manifest.lazy_images)
+            .chain(&manifest.preloads)
+        {
+            assert!(
+                json.contains(&format!("\"url\":\"{url}\"")),
+                "{url} missing"
+            );
+        }
+        assert!(json.contains("\"url\":\"https://example.com/b.png\""));
+    }
+
     #[test]
     fn collects_legacy_image_alias_sources() {
         let src = "<html><body><image src=\"/legacy.png\"></body></html>";
@@ -8174,6 +10919,106 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rowspan_reserves_columns_in_following_rows() {
+        // <tr><td rowspan=2>A</td><td>B</td><td>C</td></tr><tr><td>D</td><td>E</td></tr>
+        let grid = build_table_grid(&[vec![(1, 2), (1, 1), (1, 1)], vec![(1, 1), (1, 1)]]);
+        assert_eq!(grid.column_count, 3);
+        assert_eq!(
+            grid.rows[1],
+            vec![
+                TableGridCell {
+                    column: 1,
+                    colspan: 1,
+                    rowspan: 1,
+                },
+                TableGridCell {
+                    column: 2,
+                    colspan: 1,
+                    rowspan: 1,
+                },
+            ]
+        );
+        assert_eq!(grid.rows[0].len(), 3);
+        assert_eq!(
+            table_row_layout(&grid.rows[1]),
+            vec![
+                TableRowItem::Gap(1),
+                TableRowItem::Cell(0, 1),
+                TableRowItem::Cell(1, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn rowspan_is_clamped_and_combines_with_colspan() {
+        let grid = build_table_grid(&[vec![(2, 0), (1, 1)], vec![(1, 1)], vec![(1, 5)]]);
+        assert_eq!(grid.rows[0][0].rowspan, 3);
+        assert_eq!(grid.rows[1][0].column, 2);
+        assert_eq!(grid.rows[2][0].column, 2);
+        assert_eq!(grid.rows[2][0].rowspan, 1);
+        assert_eq!(
+            table_row_layout(&grid.rows[2]),
+            vec![TableRowItem::Gap(2), TableRowItem::Cell(0, 1)]
+        );
+    }
+
+    #[test]
+    fn drops_resource_hints_unless_honored() {
+        let src = "<html><head>\
+                   <link rel=\"stylesheet\" href=\"/site.css\">\
+                   <link rel=\"preload\" as=\"style\" href=\"/late.css\">\
+                   <link rel=\"prefetch\" href=\"/next.js\">\
+                   <link rel=\"prefetch nofollow\" href=\"/private.js\">\
+                   <link rel=\"preconnect\" href=\"https://cdn.example.com\">\
+                   <link rel=\"dns-prefetch\" href=\"https://fonts.example.com\">\
+                   </head><body></body></html>";
+        let doc = HtmlDocument::parse(src);
+        let base = "https://example.com/index.html";
+
+        let mut honored = doc.collect_subresources(base);
+        honored.apply_resource_hint_policy(true);
+        assert_eq!(
+            honored.preloads,
+            vec![
+                "https://example.com/late.css".to_owned(),
+                "https://example.com/next.js".to_owned(),
+            ]
+        );
+
+        let mut default_plan = doc.collect_subresources(base);
+        default_plan.apply_resource_hint_policy(false);
+        assert!(default_plan.preloads.is_empty());
+        assert_eq!(
+            default_plan.stylesheets,
+            vec!["https://example.com/site.css".to_owned()]
+        );
+        assert!(default_plan.scripts.is_empty());
+    }
+
+    #[test]
+    fn separates_lazy_images_from_eager_images() {
+        let src = "<html><body>\
+                   <img src=\"/hero.png\">\
+                   <img src=\"/below.png\" loading=\"lazy\">\
+                   <img src=\"/shared.png\" loading=\"lazy\">\
+                   <img src=\"/shared.png\">\
+                   </body></html>";
+        let doc = HtmlDocument::parse(src);
+        let manifest = doc.collect_subresources("https://example.com/base/index.html");
+        assert_eq!(
+            manifest.images,
+            vec![
+                "https://example.com/hero.png".to_owned(),
+                "https://example.com/shared.png".to_owned(),
+            ]
+        );
+        assert_eq!(
+            manifest.lazy_images,
+            vec!["https://example.com/below.png".to_owned()]
+        );
+    }
+
     #[test]
     fn collects_image_sources_from_srcset_and_source_tags() {
         let src = "<html><body>\
@@ -8260,6 +11105,218 @@ mod tests {
         assert_eq!(nodes[1].tag_name, "INPUT");
     }
 
+    #[test]
+    fn truncation_adds_ellipsis_to_text_wider_than_the_limit() {
+        let measure = |text: &str| text.chars().count() as f32;
+        assert_eq!(
+            truncate_text_to_fit("hello wonderful world", 10.0, 1, false, measure),
+            "hello won…"
+        );
+    }
+
+    #[test]
+    fn line_clamp_keeps_the_first_lines() {
+        let measure = |text: &str| text.chars().count() as f32;
+        assert_eq!(
+            truncate_text_to_fit("one two three four five six", 9.0, 2, true, measure),
+            "one two\nthree…"
+        );
+    }
+
+    #[test]
+    fn truncation_leaves_short_text_unchanged() {
+        let measure = |text: &str| text.chars().count() as f32;
+        assert_eq!(
+            truncate_text_to_fit("short", 10.0, 1, false, measure),
+            "short"
+        );
+        assert_eq!(
+            truncate_text_to_fit("two short lines", 9.0, 2, true, measure),
+            "two short lines"
+        );
+    }
+
+    #[test]
+    fn form_focus_order_sorts_positive_tabindex_before_document_order() {
+        assert_eq!(
+            form_focus_order(&[None, Some(3), Some(1), None, Some(1)]),
+            vec![2, 4, 1, 0, 3]
+        );
+    }
+
+    #[test]
+    fn form_focus_order_skips_negative_tabindex() {
+        assert_eq!(
+            form_focus_order(&[None, Some(-1), Some(2), Some(-5)]),
+            vec![2, 0]
+        );
+    }
+
+    #[test]
+    fn form_focus_order_places_zero_tabindex_after_positive_values() {
+        assert_eq!(
+            form_focus_order(&[Some(0), Some(2), None, Some(0)]),
+            vec![1, 0, 2, 3]
+        );
+    }
+
+    #[test]
+    fn inline_style_declarations_parse_in_order() {
+        let mut doc = HtmlDocument::parse(
+            "<html><body><p id=\"a\" style=\"Color: red; margin:0 auto ;display:block\">A</p></body></html>",
+        );
+        let Some(el) = find_element_by_id_mut(&mut doc.root.children, "a") else {
+            panic!("missing element");
+        };
+        assert_eq!(
+            el.inline_style_declarations(),
+            vec![
+                ("color".to_owned(), "red".to_owned()),
+                ("margin".to_owned(), "0 auto".to_owned()),
+                ("display".to_owned(), "block".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn inline_style_declarations_keep_semicolons_inside_urls() {
+        let mut doc = HtmlDocument::parse(
+            "<html><body><div id=\"a\" style=\"background: url(data:image/png;base64,AAAA); color: blue\"></div></body></html>",
+        );
+        let Some(el) = find_element_by_id_mut(&mut doc.root.children, "a") else {
+            panic!("missing element");
+        };
+        assert_eq!(
+            el.inline_style_declarations(),
+            vec![
+                (
+                    "background".to_owned(),
+                    "url(data:image/png;base64,AAAA)".to_owned()
+                ),
+                ("color".to_owned(), "blue".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn inline_style_declarations_skip_empty_and_malformed_styles() {
+        let mut doc = HtmlDocument::parse(
+            "<html><body><p id=\"a\" style=\"\">A</p><p id=\"b\" style=\"; nonsense ; :red; color:\">B</p><p id=\"c\">C</p></body></html>",
+        );
+        for id in ["a", "b", "c"] {
+            let Some(el) = find_element_by_id_mut(&mut doc.root.children, id) else {
+                panic!("missing element {id}");
+            };
+            assert!(el.inline_style_declarations().is_empty());
+        }
+    }
+
+    #[test]
+    fn script_inline_style_writes_replace_matching_declarations() {
+        let src = "<html><body><p id=\"note\" style=\"color: red; DISPLAY:block\">Hidden</p>\
+                   <p>Shown</p></body></html>";
+        let mut doc = HtmlDocument::parse(src);
+        let full_len = doc.renderable_text_len();
+        assert!(full_len > "HiddenShown".len());
+
+        assert!(doc.set_inline_style_property("note", "display", "none"));
+        let nodes = doc.collect_id_elements(1);
+        assert!(
+            nodes[0]
+                .attributes
+                .iter()
+                .any(|(name, value)| name == "style" && value == "color: red; display: none")
+        );
+        assert_eq!(doc.renderable_text_len(), "Shown".len());
+
+        assert!(doc.set_inline_style_property("note", "display", ""));
+        assert_eq!(doc.renderable_text_len(), full_len);
+        assert!(!doc.set_inline_style_property("missing", "display", "none"));
+    }
+
+    #[test]
+    fn serialized_html_round_trips_structure_and_text() {
+        let src = "<html><head><title>T &amp; C</title><style>p > a{color:red}</style></head>\
+                   <body><p id=\"lead\" class=\"a b\">Hello <b>world</b><br>again</p>\
+                   <script>if (1 < 2 && ok) {}</script></body></html>";
+        let doc = HtmlDocument::parse(src);
+        let html = doc.to_html();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<br>again"));
+        assert!(!html.contains("</br>"));
+        assert!(html.contains("<style>p > a{color:red}</style>"));
+        assert!(html.contains("<script>if (1 < 2 && ok) {}</script>"));
+
+        let reparsed = HtmlDocument::parse(&html);
+        assert_eq!(reparsed.title.as_deref(), Some("T & C"));
+        assert_eq!(reparsed.to_html(), html);
+        let lead = reparsed.collect_id_elements(1);
+        assert_eq!(lead[0].text_content, "Hello worldagain");
+        assert_eq!(lead[0].attributes, doc.collect_id_elements(1)[0].attributes);
+    }
+
+    #[test]
+    fn serialized_html_escapes_text_and_attributes() {
+        let mut doc = HtmlDocument::parse("<p id=\"x\" title=\"a\">text</p>");
+        let HtmlNode::Element(p) = &mut doc.root.children[0] else {
+            panic!("expected element");
+        };
+        p.attrs[1].1 = "say \"hi\" <now> & later".to_owned();
+        p.children = vec![HtmlNode::Text("1 < 2 > 0 & \"quoted\"".to_owned())];
+
+        let html = doc.to_html();
+        assert!(html.contains("title=\"say &quot;hi&quot; &lt;now&gt; &amp; later\""));
+        assert!(html.contains(">1 &lt; 2 &gt; 0 &amp; \"quoted\"</p>"));
+
+        let reparsed = HtmlDocument::parse(&html);
+        let node = &reparsed.collect_id_elements(1)[0];
+        assert_eq!(node.text_content, "1 < 2 > 0 & \"quoted\"");
+        assert!(
+            node.attributes
+                .iter()
+                .any(|(name, value)| name == "title" && value == "say \"hi\" <now> & later")
+        );
+    }
+
+    #[test]
+    fn offline_html_inlines_fetched_stylesheets_and_images() {
+        let mut doc = HtmlDocument::parse(
+            "<html><head><link rel=\"stylesheet\" href=\"/site.css\"></head>\
+             <body><img src=\"/logo.png\" srcset=\"/logo@2x.png 2x\"><img src=\"/other.png\"></body></html>",
+        );
+        doc.append_stylesheet_source("body{margin:0}");
+        let images = HashMap::from([(
+            "https://example.com/logo.png".to_owned(),
+            "data:image/png;base64,AAAA".to_owned(),
+        )]);
+
+        let html = doc.to_offline_html("https://example.com/page", &images);
+        assert!(!html.contains("<link"));
+        assert!(html.contains("<head><style>body{margin:0}</style></head>"));
+        assert!(html.contains("<img src=\"data:image/png;base64,AAAA\">"));
+        assert!(html.contains("<img src=\"https://example.com/other.png\">"));
+    }
+
+    #[test]
+    fn offline_html_drops_scripts_and_absolutizes_urls() {
+        let doc = HtmlDocument::parse(
+            "<html><head><base href=\"/docs/\"><script src=\"/app.js\"></script></head>\
+             <body onload=\"boot()\"><a href=\"next.html\" onclick=\"track()\">Next</a>\
+             <a href=\"#top\">Top</a><image src=\"hero.png\" srcset=\"hero.png 1x, big/hero.png 2x\">\
+             <script>alert(1)</script></body></html>",
+        );
+
+        let html = doc.to_offline_html("https://example.com/blog/post", &HashMap::new());
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("<base"));
+        assert!(!html.contains("onclick") && !html.contains("onload"));
+        assert!(html.contains("<a href=\"https://example.com/blog/next.html\">Next</a>"));
+        assert!(html.contains("<a href=\"#top\">Top</a>"));
+        assert!(html.contains(
+            "srcset=\"https://example.com/blog/hero.png 1x, https://example.com/blog/big/hero.png 2x\""
+        ));
+    }
+
     #[test]
     fn counts_inline_style_tags_and_rules() {
         let src = "<html><head><style>p{color:red}a{color:blue}</style></head><body></body></html>";
@@ -8308,6 +11365,79 @@ mod tests {
         assert_eq!(style.color, Some(Color32::from_rgb(255, 0, 0)));
     }
 
+    #[test]
+    fn cascade_prefers_later_rule_at_equal_specificity() {
+        let sheet = StyleSheet {
+            rules: parse_css_rules(".card { color: #101010; } .card { color: #202020; }"),
+        };
+
+        let el = HtmlElement {
+            tag: "div".to_owned(),
+            attrs: vec![("class".to_owned(), "card".to_owned())],
+            children: Vec::new(),
+        };
+
+        let style = style_for(&el, &sheet, &StyleProps::default(), &[]);
+        assert_eq!(style.color, Some(Color32::from_rgb(32, 32, 32)));
+    }
+
+    #[test]
+    fn cascade_prefers_low_specificity_important_over_id_rule() {
+        let sheet = StyleSheet {
+            rules: parse_css_rules(
+                "div { color: #ff0000 !important; } #hero.card { color: #0000ff; }",
+            ),
+        };
+
+        let el = HtmlElement {
+            tag: "div".to_owned(),
+            attrs: vec![
+                ("id".to_owned(), "hero".to_owned()),
+                ("class".to_owned(), "card".to_owned()),
+            ],
+            children: Vec::new(),
+        };
+
+        let style = style_for(&el, &sheet, &StyleProps::default(), &[]);
+        assert_eq!(style.color, Some(Color32::from_rgb(255, 0, 0)));
+    }
+
+    #[test]
+    fn cascade_prefers_inline_style_over_class_rules() {
+        let sheet = StyleSheet {
+            rules: parse_css_rules("div.card { color: #ff0000; } .card { color: #00ff00; }"),
+        };
+
+        let el = HtmlElement {
+            tag: "div".to_owned(),
+            attrs: vec![
+                ("class".to_owned(), "card".to_owned()),
+                ("style".to_owned(), "color: #0000ff;".to_owned()),
+            ],
+            children: Vec::new(),
+        };
+
+        let style = style_for(&el, &sheet, &StyleProps::default(), &[]);
+        assert_eq!(style.color, Some(Color32::from_rgb(0, 0, 255)));
+    }
+
+    #[test]
+    fn appended_stylesheet_wins_equal_specificity_ties() {
+        let mut doc = HtmlDocument::parse(
+            "<html><head><style>.b { color: #00ff00; } .a { color: #ff0000; }</style></head></html>",
+        );
+        doc.append_stylesheet_source(".a { color: #0000ff; }");
+
+        let el = HtmlElement {
+            tag: "p".to_owned(),
+            attrs: vec![("class".to_owned(), "a".to_owned())],
+            children: Vec::new(),
+        };
+
+        let style = style_for(&el, &doc.styles, &StyleProps::default(), &[]);
+        assert_eq!(style.color, Some(Color32::from_rgb(0, 0, 255)));
+    }
+
     #[test]
     fn inherit_keyword_resets_to_parent_value() {
         let sheet = StyleSheet {
@@ -8425,6 +11555,58 @@ mod tests {
         assert_eq!(style.text_align, Some(TextAlign::Right));
     }
 
+    fn dir_element(tag: &str, dir: &str, children: Vec<HtmlNode>) -> HtmlElement {
+        HtmlElement {
+            tag: tag.to_owned(),
+            attrs: vec![("dir".to_owned(), dir.to_owned())],
+            children,
+        }
+    }
+
+    #[test]
+    fn dir_rtl_is_inherited_by_descendants() {
+        let sheet = StyleSheet::default();
+        let parent = dir_element("div", "rtl", Vec::new());
+        let parent_style = style_for(&parent, &sheet, &StyleProps::default(), &[]);
+        let child = HtmlElement {
+            tag: "li".to_owned(),
+            attrs: Vec::new(),
+            children: Vec::new(),
+        };
+        let child_style = style_for(&child, &sheet, &parent_style, &[]);
+        assert_eq!(child_style.direction, Some(TextDirection::Rtl));
+        assert!(is_rtl_layout(&child_style));
+    }
+
+    #[test]
+    fn child_dir_ltr_overrides_rtl_ancestor() {
+        let sheet = StyleSheet::default();
+        let parent = dir_element("div", "rtl", Vec::new());
+        let parent_style = style_for(&parent, &sheet, &StyleProps::default(), &[]);
+        let child = dir_element("p", "ltr", Vec::new());
+        let child_style = style_for(&child, &sheet, &parent_style, &[]);
+        assert_eq!(child_style.direction, Some(TextDirection::Ltr));
+        assert_eq!(child_style.text_align, Some(TextAlign::Left));
+        assert!(!is_rtl_layout(&child_style));
+    }
+
+    #[test]
+    fn dir_auto_follows_first_strong_character() {
+        let sheet = StyleSheet::default();
+        let arabic = dir_element(
+            "p",
+            "auto",
+            vec![HtmlNode::Text("123 مرحبا hello".to_owned())],
+        );
+        let style = style_for(&arabic, &sheet, &StyleProps::default(), &[]);
+        assert_eq!(style.direction, Some(TextDirection::Rtl));
+        assert_eq!(style.text_align, Some(TextAlign::Right));
+
+        let latin = dir_element("p", "auto", vec![HtmlNode::Text("hello مرحبا".to_owned())]);
+        let style = style_for(&latin, &sheet, &StyleProps::default(), &[]);
+        assert_eq!(style.direction, Some(TextDirection::Ltr));
+    }
+
     #[test]
     fn html_presentational_attributes_map_into_style() {
         let sheet = StyleSheet::default();
@@ -8453,6 +11635,15 @@ mod tests {
         assert_eq!(style.font_size, Some(24.0));
     }
 
+    #[test]
+    fn clamps_font_sizes_to_configured_limits() {
+        let clamp = |size| clamp_font_size(size, DEFAULT_MIN_FONT_SIZE, DEFAULT_MAX_FONT_SIZE);
+        assert_eq!(clamp(6.0), DEFAULT_MIN_FONT_SIZE);
+        assert_eq!(clamp(200.0), DEFAULT_MAX_FONT_SIZE);
+        assert_eq!(clamp(18.0), 18.0);
+        assert_eq!(clamp_font_size(40.0, 16.0, 12.0), 16.0);
+    }
+
     #[test]
     fn parses_legacy_font_size_values() {
         assert_eq!(parse_legacy_font_size("1"), Some(10.0));
@@ -8594,6 +11785,372 @@ mod tests {
         assert_eq!(style.display, None);
     }
 
+    #[test]
+    fn accessibility_json_reports_heading_role_and_text() {
+        let doc = HtmlDocument::parse("<html><body><h2>  Release   notes </h2></body></html>");
+        let json = doc.to_accessibility_json();
+        assert!(json.starts_with("{\"role\":\"document\""));
+        assert!(json.contains("{\"role\":\"heading\",\"name\":\"Release notes\""));
+    }
+
+    #[test]
+    fn accessibility_json_names_images_from_alt_text() {
+        let doc = HtmlDocument::parse(
+            "<html><body><img src=\"logo.png\" alt=\"Pixel &quot;Dust&quot; logo\"></body></html>",
+        );
+        let json = doc.to_accessibility_json();
+        assert!(json.contains("{\"role\":\"img\",\"name\":\"Pixel \\\"Dust\\\" logo\""));
+    }
+
+    #[test]
+    fn accessibility_json_omits_display_none_elements() {
+        let doc = HtmlDocument::parse(
+            "<html><head><style>.gone { display:none; }</style></head><body>\
+             <p>Shown</p><div class=\"gone\"><button>Hidden</button></div>\
+             <nav hidden>Skip</nav></body></html>",
+        );
+        let json = doc.to_accessibility_json();
+        assert!(json.contains("{\"role\":\"paragraph\",\"name\":\"Shown\""));
+        assert!(!json.contains("Hidden"));
+        assert!(!json.contains("navigation"));
+    }
+
+    #[test]
+    fn builds_change_and_focus_event_requests_from_inline_handlers() {
+        let doc = HtmlDocument::parse(
+            "<html><body><input id=\"qty\" onchange=\" updateTotal(this.value) \" \
+             onfocus=\"highlight()\"></body></html>",
+        );
+        let input = find_first_element(&doc.root.children, "input")
+            .unwrap_or_else(|| panic!("missing input"));
+
+        let change = inline_event_request(DomEventKind::Change, input)
+            .unwrap_or_else(|| panic!("missing change request"));
+        assert_eq!(change.kind.event_type(), "change");
+        assert_eq!(change.target_id.as_deref(), Some("qty"));
+        assert_eq!(change.inline_handler, "updateTotal(this.value)");
+
+        let focus = inline_event_request(DomEventKind::Focus, input)
+            .unwrap_or_else(|| panic!("missing focus request"));
+        assert_eq!(focus.inline_handler, "highlight()");
+        assert!(inline_event_request(DomEventKind::KeyDown, input).is_none());
+    }
+
+    #[test]
+    fn image_render_decision_falls_back_to_alt_or_broken_marker() {
+        let docs = [
+            "<img src=\"a.png\" alt=\"Logo\">",
+            "<img src=\"b.png\" alt=\" Team photo \">",
+            "<img src=\"c.png\">",
+            "<img src=\"d.png\" loading=\"lazy\" alt=\"Later\">",
+        ]
+        .map(HtmlDocument::parse);
+        let img = |index: usize| {
+            find_first_element(&docs[index].root.children, "img")
+                .unwrap_or_else(|| panic!("missing img {index}"))
+        };
+        let decoded = RenderImage {
+            texture_id: egui::TextureId::Managed(7),
+            size: egui::vec2(32.0, 16.0),
+        };
+        let mut images = HashMap::new();
+        images.insert("https://example.test/a.png".to_owned(), decoded);
+        let pending_images = ["https://example.test/d.png".to_owned()];
+        let resources = RenderResources {
+            images: &images,
+            pending_images: &pending_images,
+        };
+
+        assert_eq!(
+            image_render_decision(img(0), Some("https://example.test/a.png"), &resources),
+            ImageRenderDecision::Texture(decoded)
+        );
+        assert_eq!(
+            image_render_decision(img(1), Some("https://example.test/b.png"), &resources),
+            ImageRenderDecision::AltText("Team photo")
+        );
+        assert_eq!(
+            image_render_decision(img(2), Some("https://example.test/c.png"), &resources),
+            ImageRenderDecision::BrokenPlaceholder
+        );
+        assert_eq!(
+            image_render_decision(img(2), None, &resources),
+            ImageRenderDecision::BrokenPlaceholder
+        );
+        assert_eq!(
+            image_render_decision(img(3), Some("https://example.test/d.png"), &resources),
+            ImageRenderDecision::Pending
+        );
+    }
+
+    #[test]
+    fn lazy_image_that_is_no_longer_pending_falls_back_to_alt() {
+        let doc = HtmlDocument::parse("<img src=\"d.png\" loading=\"lazy\" alt=\"Later\">");
+        let img =
+            find_first_element(&doc.root.children, "img").unwrap_or_else(|| panic!("missing img"));
+        let images = HashMap::new();
+        let resources = RenderResources {
+            images: &images,
+            pending_images: &[],
+        };
+
+        assert_eq!(
+            image_render_decision(img, Some("https://example.test/d.png"), &resources),
+            ImageRenderDecision::AltText("Later")
+        );
+    }
+
+    fn form_rules_for(html: &str) -> HashMap<String, FormFieldRules> {
+        let doc = HtmlDocument::parse(html);
+        let mut rules = HashMap::new();
+        for tag in ["input", "textarea"] {
+            let mut pending = doc.root.children.iter().collect::<Vec<_>>();
+            while let Some(node) = pending.pop() {
+                if let HtmlNode::Element(el) = node {
+                    if el.tag == tag
+                        && let Some(name) = attr(el, "name")
+                    {
+                        rules.insert(name.to_owned(), FormFieldRules::from_element(el));
+                    }
+                    pending.extend(el.children.iter());
+                }
+            }
+        }
+        rules
+    }
+
+    fn form_values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| ((*name).to_owned(), (*value).to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn form_submit_url_replaces_the_query_and_keeps_fragment_and_userinfo() {
+        let fields = HashMap::from([
+            ("q".to_owned(), "rust & c++".to_owned()),
+            ("lang".to_owned(), "en".to_owned()),
+        ]);
+        assert_eq!(
+            build_form_submit_url("https://example.com/search?old=1#results", &fields).as_deref(),
+            Some("https://example.com/search?lang=en&q=rust+%26+c%2B%2B#results")
+        );
+        assert_eq!(
+            build_form_submit_url("https://user@example.com/find", &fields).as_deref(),
+            Some("https://user@example.com/find?lang=en&q=rust+%26+c%2B%2B")
+        );
+        assert_eq!(
+            build_form_submit_url("https://example.com/find?old=1", &HashMap::new()).as_deref(),
+            Some("https://example.com/find")
+        );
+    }
+
+    #[test]
+    fn form_validation_skips_readonly_and_disabled_fields() {
+        let rules = form_rules_for(
+            "<form><input name=\"code\" required readonly>\
+             <input name=\"mail\" type=\"email\" disabled></form>",
+        );
+        assert!(
+            validate_form_fields(&form_values(&[("code", ""), ("mail", "nope")]), &rules)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn form_validation_blocks_empty_required_field() {
+        let rules = form_rules_for(
+            "<form><input name=\"user\" required><textarea name=\"bio\"></textarea></form>",
+        );
+        let errors = validate_form_fields(&form_values(&[("user", "  "), ("bio", "")]), &rules);
+        assert_eq!(
+            errors,
+            vec![FormFieldError {
+                name: "user".to_owned(),
+                message: "Please fill out this field.".to_owned(),
+            }]
+        );
+        assert_eq!(
+            validate_form_fields(&HashMap::new(), &rules)
+                .iter()
+                .map(|error| error.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["user"]
+        );
+    }
+
+    #[test]
+    fn form_validation_flags_malformed_email_and_pattern() {
+        let rules = form_rules_for(
+            "<form><input type=\"email\" name=\"mail\">\
+             <input name=\"zip\" pattern=\"[0-9]{5}\"></form>",
+        );
+        let errors = validate_form_fields(
+            &form_values(&[("mail", "someone@@example"), ("zip", "1234a")]),
+            &rules,
+        );
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| (error.name.as_str(), error.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("mail", "Please enter an email address."),
+                ("zip", "Please match the requested format."),
+            ]
+        );
+        // The pattern must match the whole value, not a substring.
+        assert_eq!(
+            validate_form_fields(&form_values(&[("zip", "123456")]), &rules).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn form_validation_rejects_numbers_outside_min_and_max() {
+        let rules = form_rules_for(
+            "<form><input type=\"number\" name=\"qty\" min=\"1\" max=\"10\"></form>",
+        );
+        let message = |value: &str| {
+            validate_form_fields(&form_values(&[("qty", value)]), &rules)
+                .first()
+                .map(|error| error.message.clone())
+        };
+        assert_eq!(
+            message("0").as_deref(),
+            Some("Value must be greater than or equal to 1.")
+        );
+        assert_eq!(
+            message("11").as_deref(),
+            Some("Value must be less than or equal to 10.")
+        );
+        assert_eq!(message("ten").as_deref(), Some("Please enter a number."));
+        assert_eq!(message("10"), None);
+    }
+
+    #[test]
+    fn form_validation_passes_valid_form() {
+        let rules = form_rules_for(
+            "<form><input name=\"user\" required>\
+             <input type=\"email\" name=\"mail\" required>\
+             <input type=\"url\" name=\"site\">\
+             <input name=\"zip\" pattern=\"[0-9]{5}\">\
+             <input type=\"number\" name=\"qty\" min=\"1\" max=\"10\"></form>",
+        );
+        let fields = form_values(&[
+            ("user", "ada"),
+            ("mail", "ada@example.com"),
+            ("site", "https://example.com/"),
+            ("zip", "12345"),
+            ("qty", "3"),
+        ]);
+        assert!(validate_form_fields(&fields, &rules).is_empty());
+    }
+
+    fn element_depth(nodes: &[HtmlNode]) -> usize {
+        nodes
+            .iter()
+            .filter_map(|node| match node {
+                HtmlNode::Element(el) => Some(1 + element_depth(&el.children)),
+                HtmlNode::Text(_) => None,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn flattens_elements_nested_past_the_depth_limit() {
+        let source = format!(
+            "<section>{}deep{}</section><p>after</p>",
+            "<div>".repeat(5_000),
+            "</div>".repeat(5_000)
+        );
+        let doc = HtmlDocument::parse_with_limits(&source, 100_000, 32);
+        assert!(doc.truncated);
+        assert_eq!(element_depth(&doc.root.children), 32);
+        assert_eq!(
+            collapse_whitespace(&collect_text(&doc.root.children)),
+            "deepafter"
+        );
+        let top_level = doc
+            .root
+            .children
+            .iter()
+            .filter_map(|node| match node {
+                HtmlNode::Element(el) => Some(el.tag.as_str()),
+                HtmlNode::Text(_) => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(top_level, vec!["section", "p"]);
+
+        // The default limit keeps pathological nesting from reaching the recursive renderer.
+        let doc = HtmlDocument::parse(&"<div>".repeat(20_000));
+        assert!(doc.truncated);
+        assert!(element_depth(&doc.root.children) <= 256);
+    }
+
+    #[test]
+    fn normally_nested_documents_are_unaffected_by_the_depth_limit() {
+        let source = "<html><body><ul><li><a href=\"/\"><b>Home</b></a></li></ul></body></html>";
+        let limited = HtmlDocument::parse_with_limits(source, 100_000, 32);
+        let unlimited = HtmlDocument::parse(source);
+        assert!(!limited.truncated);
+        assert_eq!(element_depth(&limited.root.children), 6);
+        assert_eq!(
+            format!("{:?}", limited.root),
+            format!("{:?}", unlimited.root)
+        );
+    }
+
+    #[test]
+    fn stops_building_nodes_at_the_limit() {
+        let doc = HtmlDocument::parse_with_max_nodes(&"<p>row</p>".repeat(50), 10);
+        assert!(doc.truncated);
+        let paragraphs = doc
+            .root
+            .children
+            .iter()
+            .filter(|node| matches!(node, HtmlNode::Element(el) if el.tag == "p"))
+            .count();
+        assert_eq!(paragraphs, 10);
+
+        let small = HtmlDocument::parse_with_max_nodes("<p>Hello</p><p>World</p>", 10);
+        assert!(!small.truncated);
+        assert_eq!(small.root.children.len(), 2);
+    }
+
+    #[test]
+    fn meta_tags_reads_description_opengraph_and_theme_color() {
+        let doc = HtmlDocument::parse(
+            "<html><head><title>Docs</title>\
+             <meta name=\"Description\" content=\"  Guides   and reference \">\
+             <meta property=\"og:title\" content=\"PixelDust Docs\">\
+             <meta property=\"og:image\" content=\"/social.png\">\
+             <meta property=\"og:image\" content=\"/second.png\">\
+             <meta name=\"theme-color\" content=\"#336699\"></head>\
+             <body><meta name=\"description\" content=\"body meta\"></body></html>",
+        );
+        assert_eq!(
+            doc.meta_tags(),
+            MetaInfo {
+                description: Some("Guides and reference".to_owned()),
+                og_title: Some("PixelDust Docs".to_owned()),
+                og_image: Some("/social.png".to_owned()),
+                theme_color: Some(Color32::from_rgb(0x33, 0x66, 0x99)),
+            }
+        );
+    }
+
+    #[test]
+    fn meta_tags_are_none_without_meta_elements() {
+        let doc = HtmlDocument::parse(
+            "<html><head><title>Plain</title><meta charset=\"utf-8\">\
+             <meta name=\"description\" content=\"  \"></head><body><p>Hi</p></body></html>",
+        );
+        assert_eq!(doc.meta_tags(), MetaInfo::default());
+    }
+
     fn collect_visible_text(nodes: &[HtmlNode]) -> String {
         let mut out = String::new();
         for node in nodes {
@@ -5,13 +5,26 @@ use pd_ipc::ProcessRole;
 use pd_net::NetStack;
 use pd_privacy::PrivacyPolicy;
 use pd_renderer::RendererProcess;
+use pd_security::SandboxFlags;
 use pd_security::SecurityPolicy;
 use pd_storage::StorageConfig;
 use pd_storage::StorageManager;
+use std::collections::VecDeque;
+use std::io::BufRead;
+use std::io::BufReader;
 use std::path::PathBuf;
 use std::process::Child;
+use std::process::ChildStderr;
 use std::process::Command;
 use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::Instant;
+
+/// Longest `restart_exited_workers` waits for an exited worker's stderr to drain.
+const STDERR_DRAIN_GRACE: Duration = Duration::from_millis(50);
 
 /// Browser process top-level orchestration object.
 #[derive(Debug)]
@@ -37,6 +50,9 @@ pub struct RuntimeLaunchConfig {
     pub executable: PathBuf,
     pub inherit_stdio: bool,
     pub extra_args: Vec<String>,
+    /// Keeps the last N stderr lines of each worker for crash reports; 0 discards stderr.
+    /// Ignored when `inherit_stdio` is set.
+    pub stderr_tail_lines: usize,
 }
 
 impl RuntimeLaunchConfig {
@@ -45,6 +61,7 @@ impl RuntimeLaunchConfig {
             executable,
             inherit_stdio: false,
             extra_args: Vec::new(),
+            stderr_tail_lines: 0,
         }
     }
 }
@@ -53,7 +70,59 @@ impl RuntimeLaunchConfig {
 #[derive(Debug)]
 pub struct WorkerProcess {
     pub role: ProcessRole,
+    pub sandbox: SandboxFlags,
     pub child: Child,
+    stderr: Option<StderrCapture>,
+}
+
+/// Ring buffer of a worker's most recent stderr lines, filled by a drain thread.
+#[derive(Debug)]
+struct StderrCapture {
+    lines: Arc<Mutex<VecDeque<String>>>,
+    reader: Option<JoinHandle<()>>,
+}
+
+impl StderrCapture {
+    fn start(stderr: ChildStderr, role: ProcessRole, max_lines: usize) -> Self {
+        let lines = Arc::new(Mutex::new(VecDeque::with_capacity(max_lines)));
+        let sink = Arc::clone(&lines);
+        let reader = std::thread::spawn(move || {
+            for line in BufReader::new(stderr).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                let Ok(mut lines) = sink.lock() else {
+                    break;
+                };
+                if lines.len() >= max_lines {
+                    lines.pop_front();
+                }
+                lines.push_back(format!("[{}] {line}", role.as_str()));
+            }
+        });
+        Self {
+            lines,
+            reader: Some(reader),
+        }
+    }
+
+    /// Returns the captured tail once stderr reaches EOF or [`STDERR_DRAIN_GRACE`] passes.
+    ///
+    /// The drain thread is never joined: a grandchild holding the pipe open would otherwise
+    /// stall the caller, so an unfinished reader is left to exit on its own.
+    fn finish(self) -> Option<String> {
+        if let Some(reader) = &self.reader {
+            let deadline = Instant::now() + STDERR_DRAIN_GRACE;
+            while !reader.is_finished() && Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        }
+        let lines = self.lines.lock().ok()?;
+        if lines.is_empty() {
+            return None;
+        }
+        Some(lines.iter().cloned().collect::<Vec<_>>().join("\n"))
+    }
 }
 
 /// Worker process liveness snapshot.
@@ -71,6 +140,9 @@ pub struct WorkerRestart {
     pub role: ProcessRole,
     pub old_pid: u32,
     pub new_pid: u32,
+    pub exit_code: Option<i32>,
+    /// Last stderr lines of the exited worker, prefixed with its role.
+    pub crash_tail: Option<String>,
 }
 
 /// Browser runtime handle for spawned workers and channel policy.
@@ -140,19 +212,24 @@ impl BrowserRuntime {
                 )
             })?;
 
-            if exited.is_none() {
+            let Some(status) = exited else {
                 continue;
-            }
+            };
 
             let role = worker.role;
-            let new_child = spawn_worker_process(&self.launch_config, role)?;
+            let crash_tail = worker.stderr.take().and_then(StderrCapture::finish);
+            let (new_child, stderr) =
+                spawn_worker_process(&self.launch_config, role, worker.sandbox)?;
             let new_pid = new_child.id();
             worker.child = new_child;
+            worker.stderr = stderr;
 
             restarts.push(WorkerRestart {
                 role,
                 old_pid,
                 new_pid,
+                exit_code: status.code(),
+                crash_tail,
             });
         }
 
@@ -215,8 +292,14 @@ impl Browser {
             ProcessRole::Network,
             ProcessRole::Storage,
         ] {
-            let child = spawn_worker_process(config, role)?;
-            workers.push(WorkerProcess { role, child });
+            let sandbox = worker_sandbox_flags(&self.security, role);
+            let (child, stderr) = spawn_worker_process(config, role, sandbox)?;
+            workers.push(WorkerProcess {
+                role,
+                sandbox,
+                child,
+                stderr,
+            });
         }
 
         Ok(BrowserRuntime {
@@ -227,7 +310,8 @@ impl Browser {
     }
 }
 
-fn default_storage_root() -> PathBuf {
+/// Persistent storage root: `PIXELDUST_STORAGE_DIR`, else `.pixeldust` under the working directory.
+pub fn default_storage_root() -> PathBuf {
     if let Some(override_root) = std::env::var_os("PIXELDUST_STORAGE_DIR") {
         return PathBuf::from(override_root);
     }
@@ -246,18 +330,34 @@ fn hardened_channels() -> pd_core::BrowserResult<Vec<ChannelConfig>> {
     ])
 }
 
-fn worker_command_args(extra_args: &[String], role: ProcessRole) -> Vec<String> {
-    let mut args = Vec::with_capacity(extra_args.len() + 2);
+fn worker_sandbox_flags(security: &SecurityPolicy, role: ProcessRole) -> SandboxFlags {
+    match role {
+        ProcessRole::Browser => SandboxFlags::empty(),
+        ProcessRole::Renderer => security.sandbox_renderer_flags,
+        ProcessRole::Network => security.sandbox_network_flags,
+        ProcessRole::Storage => security.sandbox_storage_flags,
+    }
+}
+
+fn worker_command_args(
+    extra_args: &[String],
+    role: ProcessRole,
+    sandbox: SandboxFlags,
+) -> Vec<String> {
+    let mut args = Vec::with_capacity(extra_args.len() + 4);
     args.extend(extra_args.iter().cloned());
     args.push("--pd-role".to_owned());
     args.push(role.as_str().to_owned());
+    args.push("--pd-sandbox".to_owned());
+    args.push(sandbox.to_arg_value());
     args
 }
 
 fn spawn_worker_process(
     config: &RuntimeLaunchConfig,
     role: ProcessRole,
-) -> pd_core::BrowserResult<Child> {
+    sandbox: SandboxFlags,
+) -> pd_core::BrowserResult<(Child, Option<StderrCapture>)> {
     if config.executable.as_os_str().is_empty() {
         return Err(pd_core::BrowserError::new(
             "browser.runtime.executable_missing",
@@ -266,9 +366,11 @@ fn spawn_worker_process(
     }
 
     let mut command = Command::new(&config.executable);
-    for arg in worker_command_args(&config.extra_args, role) {
+    for arg in worker_command_args(&config.extra_args, role, sandbox) {
         command.arg(arg);
     }
+    // Sandbox enforcement is not wired yet; workers receive the intended flags.
+    command.env("PIXELDUST_SANDBOX_FLAGS", sandbox.to_arg_value());
 
     if config.inherit_stdio {
         command.stdin(Stdio::inherit());
@@ -277,10 +379,14 @@ fn spawn_worker_process(
     } else {
         command.stdin(Stdio::null());
         command.stdout(Stdio::null());
-        command.stderr(Stdio::null());
+        if config.stderr_tail_lines > 0 {
+            command.stderr(Stdio::piped());
+        } else {
+            command.stderr(Stdio::null());
+        }
     }
 
-    command.spawn().map_err(|error| {
+    let mut child = command.spawn().map_err(|error| {
         pd_core::BrowserError::new(
             "browser.runtime.spawn_failed",
             format!(
@@ -289,14 +395,23 @@ fn spawn_worker_process(
                 config.executable.display()
             ),
         )
-    })
+    })?;
+    let stderr = child
+        .stderr
+        .take()
+        .map(|stderr| StderrCapture::start(stderr, role, config.stderr_tail_lines));
+    Ok((child, stderr))
 }
 
 #[cfg(test)]
 mod tests {
     use super::Browser;
+    use super::RuntimeLaunchConfig;
     use super::worker_command_args;
+    use super::worker_sandbox_flags;
     use pd_ipc::ProcessRole;
+    use pd_security::SandboxFlags;
+    use pd_security::SecurityPolicy;
 
     #[test]
     fn boot_reports_hardened_defaults() {
@@ -315,6 +430,7 @@ mod tests {
         let args = worker_command_args(
             &["--headless".to_owned(), "--log-level=warn".to_owned()],
             ProcessRole::Renderer,
+            SandboxFlags::NO_NETWORK.union(SandboxFlags::NO_SPAWN),
         );
         assert_eq!(
             args,
@@ -322,8 +438,110 @@ mod tests {
                 "--headless".to_owned(),
                 "--log-level=warn".to_owned(),
                 "--pd-role".to_owned(),
-                "renderer".to_owned()
+                "renderer".to_owned(),
+                "--pd-sandbox".to_owned(),
+                "no-network,no-spawn".to_owned()
             ]
         );
     }
+
+    #[test]
+    fn worker_sandbox_flags_follow_security_policy() {
+        let security = SecurityPolicy::default();
+        let renderer = worker_sandbox_flags(&security, ProcessRole::Renderer);
+        assert!(renderer.contains(SandboxFlags::NO_SPAWN));
+        assert!(renderer.contains(SandboxFlags::NO_NETWORK));
+        let network = worker_sandbox_flags(&security, ProcessRole::Network);
+        assert!(!network.contains(SandboxFlags::NO_NETWORK));
+        let storage = worker_sandbox_flags(&security, ProcessRole::Storage);
+        assert!(!storage.contains(SandboxFlags::NO_FILESYSTEM));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn restart_records_exit_code_and_stderr_tail() {
+        let mut config = RuntimeLaunchConfig::new("/bin/sh".into());
+        config.extra_args = vec![
+            "-c".to_owned(),
+            "echo starting >&2; echo first >&2; echo second >&2; echo fatal: boom >&2; exit 3"
+                .to_owned(),
+        ];
+        config.stderr_tail_lines = 2;
+
+        let browser = Browser::new().unwrap_or_else(|_| unreachable!());
+        let mut runtime = browser
+            .boot_with_runtime(&config)
+            .unwrap_or_else(|_| unreachable!());
+
+        let mut restarts = Vec::new();
+        for _ in 0..200 {
+            restarts.extend(
+                runtime
+                    .restart_exited_workers()
+                    .unwrap_or_else(|_| unreachable!()),
+            );
+            if restarts.len() >= runtime.worker_count() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        let _ = runtime.shutdown();
+
+        let Some(restart) = restarts
+            .iter()
+            .find(|restart| restart.role == ProcessRole::Renderer)
+        else {
+            panic!("renderer worker was not restarted");
+        };
+        assert_eq!(restart.exit_code, Some(3));
+        assert_eq!(
+            restart.crash_tail.as_deref(),
+            Some("[renderer] second\n[renderer] fatal: boom")
+        );
+        assert_ne!(restart.old_pid, restart.new_pid);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn restart_does_not_wait_for_stderr_held_open_by_a_grandchild() {
+        let mut config = RuntimeLaunchConfig::new("/bin/sh".into());
+        config.extra_args = vec![
+            "-c".to_owned(),
+            "echo fatal: held >&2; sleep 3 & exit 4".to_owned(),
+        ];
+        config.stderr_tail_lines = 4;
+
+        let browser = Browser::new().unwrap_or_else(|_| unreachable!());
+        let mut runtime = browser
+            .boot_with_runtime(&config)
+            .unwrap_or_else(|_| unreachable!());
+
+        let mut restarts = Vec::new();
+        for _ in 0..200 {
+            let started = std::time::Instant::now();
+            restarts.extend(
+                runtime
+                    .restart_exited_workers()
+                    .unwrap_or_else(|_| unreachable!()),
+            );
+            assert!(started.elapsed() < std::time::Duration::from_millis(1500));
+            if restarts.len() >= runtime.worker_count() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        let _ = runtime.shutdown();
+
+        let Some(restart) = restarts
+            .iter()
+            .find(|restart| restart.role == ProcessRole::Renderer)
+        else {
+            panic!("renderer worker was not restarted");
+        };
+        assert_eq!(restart.exit_code, Some(4));
+        assert_eq!(
+            restart.crash_tail.as_deref(),
+            Some("[renderer] fatal: held")
+        );
+    }
 }
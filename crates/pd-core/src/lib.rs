@@ -1,15 +1,18 @@
 ﻿//! Shared primitives used across PixelDust crates.
 
 use core::fmt;
+use std::error::Error;
+use std::sync::Arc;
 
 /// Result alias used across the workspace.
 pub type BrowserResult<T> = Result<T, BrowserError>;
 
 /// Top-level error type for early scaffolding.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct BrowserError {
     pub code: &'static str,
     pub message: String,
+    source: Option<Arc<dyn Error + Send + Sync>>,
 }
 
 impl BrowserError {
@@ -17,14 +20,102 @@ impl BrowserError {
         Self {
             code,
             message: message.into(),
+            source: None,
         }
     }
+
+    /// Chains `source` as the underlying cause reported by [`Error::source`].
+    pub fn with_source(mut self, source: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        self.source = Some(Arc::from(source.into()));
+        self
+    }
+}
+
+// Equality is by code and message; the chained source is diagnostic only.
+impl PartialEq for BrowserError {
+    fn eq(&self, other: &Self) -> bool {
+        self.code == other.code && self.message == other.message
+    }
 }
 
+impl Eq for BrowserError {}
+
 impl fmt::Display for BrowserError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}: {}", self.code, self.message)
     }
 }
 
-impl std::error::Error for BrowserError {}
+impl Error for BrowserError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn Error + 'static))
+    }
+}
+
+/// Wraps foreign errors into [`BrowserError`] without repeating `map_err` boilerplate.
+pub trait ResultExt<T> {
+    /// Maps `Err(e)` to a [`BrowserError`] with `code`, the message `"{message}: {e}"`, and `e`
+    /// chained as its source.
+    fn context(self, code: &'static str, message: impl Into<String>) -> BrowserResult<T>;
+}
+
+impl<T, E> ResultExt<T> for Result<T, E>
+where
+    E: Into<Box<dyn Error + Send + Sync>>,
+{
+    fn context(self, code: &'static str, message: impl Into<String>) -> BrowserResult<T> {
+        self.map_err(|error| {
+            let source = error.into();
+            BrowserError::new(code, format!("{}: {source}", message.into())).with_source(source)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BrowserError;
+    use super::BrowserResult;
+    use super::ResultExt;
+    use std::error::Error;
+    use std::io;
+
+    #[test]
+    fn context_passes_ok_values_through() {
+        let value: Result<u8, io::Error> = Ok(7);
+        assert_eq!(value.context("core.test", "unused"), Ok(7));
+    }
+
+    #[test]
+    fn context_wraps_errors_with_code_message_and_source() {
+        let failure: Result<(), io::Error> =
+            Err(io::Error::new(io::ErrorKind::NotFound, "missing file"));
+        let error = match failure.context("core.test_failed", "failed to open config") {
+            Ok(()) => panic!("expected an error"),
+            Err(error) => error,
+        };
+
+        assert_eq!(error.code, "core.test_failed");
+        assert_eq!(error.message, "failed to open config: missing file");
+        let source = error.source().map(ToString::to_string);
+        assert_eq!(source.as_deref(), Some("missing file"));
+        let io_source = error
+            .source()
+            .and_then(|source| source.downcast_ref::<io::Error>());
+        assert_eq!(
+            io_source.map(io::Error::kind),
+            Some(io::ErrorKind::NotFound)
+        );
+    }
+
+    #[test]
+    fn chained_errors_compare_by_code_and_message() {
+        let plain = BrowserError::new("core.test", "boom");
+        let chained = BrowserError::new("core.test", "boom").with_source("inner");
+        assert_eq!(plain, chained);
+        assert!(plain.source().is_none());
+        let result: BrowserResult<()> = Err(chained);
+        assert!(result.is_err_and(|error| error.source().is_some()));
+    }
+}
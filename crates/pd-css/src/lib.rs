@@ -1,5 +1,9 @@
 //! CSS tokenization and stylesheet model.
 
+/// How many grouping at-rules (`@media`, `@supports`, ...) may nest before deeper blocks are
+/// skipped.
+pub const DEFAULT_MAX_NESTING_DEPTH: usize = 32;
+
 /// Style rules compiled from source CSS.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StyleSheet {
@@ -22,14 +26,24 @@ pub struct CssParser;
 
 impl CssParser {
     pub fn parse(&self, input: &str) -> StyleSheet {
+        self.parse_with_max_nesting_depth(input, DEFAULT_MAX_NESTING_DEPTH)
+    }
+
+    /// Parses `input`, leaving the contents of grouping at-rules nested deeper than
+    /// `max_nesting_depth` unparsed.
+    pub fn parse_with_max_nesting_depth(
+        &self,
+        input: &str,
+        max_nesting_depth: usize,
+    ) -> StyleSheet {
         let sanitized = strip_comments_preserve_strings(input);
         let mut rules = Vec::new();
-        parse_rules_recursive(&sanitized, &mut rules);
+        parse_rules_recursive(&sanitized, &mut rules, max_nesting_depth);
         StyleSheet { rules }
     }
 }
 
-fn parse_rules_recursive(input: &str, out: &mut Vec<String>) {
+fn parse_rules_recursive(input: &str, out: &mut Vec<String>, nesting_left: usize) {
     let mut cursor = 0_usize;
 
     while let Some((selector_raw, body_raw, next_cursor)) = next_rule_block(input, cursor) {
@@ -41,7 +55,9 @@ fn parse_rules_recursive(input: &str, out: &mut Vec<String>) {
         }
 
         if is_grouping_at_rule(&selector) {
-            parse_rules_recursive(body_raw, out);
+            if let Some(nesting_left) = nesting_left.checked_sub(1) {
+                parse_rules_recursive(body_raw, out, nesting_left);
+            }
             continue;
         }
 
@@ -547,4 +563,37 @@ mod tests {
             r#".icon{background-image:url("data:image/svg+xml;utf8,<svg></svg>");color:red}"#
         );
     }
+
+    #[test]
+    fn parses_grouping_rules_nested_within_the_depth_limit() {
+        let parser = CssParser;
+        let sheet = parser.parse_with_max_nesting_depth(
+            "@media screen { @supports (display: grid) { .grid { display: grid; } } .a { color: red; } }",
+            2,
+        );
+        assert_eq!(sheet.rules, [".grid{display:grid}", ".a{color:red}"]);
+    }
+
+    #[test]
+    fn stops_at_the_nesting_limit_and_keeps_earlier_rules() {
+        let depth = 10_000;
+        let mut css = String::from("p { margin: 0; } ");
+        for _ in 0..depth {
+            css.push_str("@media screen { ");
+        }
+        css.push_str(".deep { color: red; }");
+        for _ in 0..depth {
+            css.push_str(" }");
+        }
+        css.push_str(" .after { color: blue; }");
+
+        let sheet = CssParser.parse(&css);
+        assert_eq!(sheet.rules, ["p{margin:0}", ".after{color:blue}"]);
+
+        let shallow = CssParser.parse_with_max_nesting_depth(
+            "@media a { .one { color: red; } @media b { .two { color: red; } } }",
+            1,
+        );
+        assert_eq!(shallow.rules, [".one{color:red}"]);
+    }
 }
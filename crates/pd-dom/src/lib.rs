@@ -10,6 +10,8 @@ pub struct Document {
     pub root: NodeId,
     pub node_count: u32,
     pub text_bytes: u32,
+    /// Set when the parser stopped adding nodes at its node limit.
+    pub truncated: bool,
 }
 
 impl Document {
@@ -19,6 +21,7 @@ impl Document {
             root: 0,
             node_count: 0,
             text_bytes: 0,
+            truncated: false,
         }
     }
 
@@ -26,3 +29,241 @@ impl Document {
         self.root != 0
     }
 }
+
+/// Payload of a single DOM node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeData {
+    Document,
+    Element {
+        name: String,
+        attributes: Vec<(String, String)>,
+    },
+    Text(String),
+}
+
+/// Node stored in a [`NodeArena`]; links are arena ids.
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub parent: Option<NodeId>,
+    pub children: Vec<NodeId>,
+    pub data: NodeData,
+}
+
+/// Arena-backed DOM tree. Ids start at 1 so that `0` keeps meaning "no node".
+///
+/// Equality is structural: two arenas are equal when their trees have the same shape and
+/// payloads, whatever ids the nodes were given.
+#[derive(Debug, Clone)]
+pub struct NodeArena {
+    nodes: Vec<Node>,
+}
+
+impl NodeArena {
+    /// Creates an arena holding only the document node.
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node {
+                parent: None,
+                children: Vec::new(),
+                data: NodeData::Document,
+            }],
+        }
+    }
+
+    pub fn root(&self) -> NodeId {
+        1
+    }
+
+    /// Number of nodes below the document node.
+    pub fn len(&self) -> usize {
+        self.nodes.len().saturating_sub(1)
+    }
+
+    /// True while the arena holds only the document node.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn node(&self, id: NodeId) -> Option<&Node> {
+        let index = usize::try_from(id.checked_sub(1)?).ok()?;
+        self.nodes.get(index)
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> Option<&mut Node> {
+        let index = usize::try_from(id.checked_sub(1)?).ok()?;
+        self.nodes.get_mut(index)
+    }
+
+    /// Appends an element under `parent`; returns `None` when `parent` is missing or a text node.
+    pub fn append_element(&mut self, parent: NodeId, name: &str) -> Option<NodeId> {
+        self.append(
+            parent,
+            NodeData::Element {
+                name: name.to_ascii_lowercase(),
+                attributes: Vec::new(),
+            },
+        )
+    }
+
+    /// Appends a text node under `parent`; returns `None` when `parent` is missing or a text node.
+    pub fn append_text(&mut self, parent: NodeId, text: &str) -> Option<NodeId> {
+        self.append(parent, NodeData::Text(text.to_owned()))
+    }
+
+    /// Sets or replaces an attribute; returns `false` when `id` is not an element.
+    pub fn set_attribute(&mut self, id: NodeId, name: &str, value: &str) -> bool {
+        let Some(Node {
+            data: NodeData::Element { attributes, .. },
+            ..
+        }) = self.node_mut(id)
+        else {
+            return false;
+        };
+
+        let name = name.to_ascii_lowercase();
+        match attributes
+            .iter_mut()
+            .find(|(existing, _)| *existing == name)
+        {
+            Some((_, existing)) => value.clone_into(existing),
+            None => attributes.push((name, value.to_owned())),
+        }
+        true
+    }
+
+    fn append(&mut self, parent: NodeId, data: NodeData) -> Option<NodeId> {
+        if matches!(self.node(parent)?.data, NodeData::Text(_)) {
+            return None;
+        }
+
+        let id = NodeId::try_from(self.nodes.len()).ok()?.checked_add(1)?;
+        self.nodes.push(Node {
+            parent: Some(parent),
+            children: Vec::new(),
+            data,
+        });
+        self.node_mut(parent)?.children.push(id);
+        Some(id)
+    }
+
+    /// Indented dump of the tree, one node per line, for asserting parser output in tests.
+    pub fn to_debug_string(&self) -> String {
+        let mut out = String::new();
+        self.write_debug(self.root(), 0, &mut out);
+        out
+    }
+
+    fn write_debug(&self, id: NodeId, depth: usize, out: &mut String) {
+        let Some(node) = self.node(id) else {
+            return;
+        };
+
+        out.push_str(&"  ".repeat(depth));
+        match &node.data {
+            NodeData::Document => out.push_str("#document"),
+            NodeData::Element { name, attributes } => {
+                out.push('<');
+                out.push_str(name);
+                for (attr, value) in attributes {
+                    out.push_str(&format!(" {attr}={value:?}"));
+                }
+                out.push('>');
+            }
+            NodeData::Text(text) => out.push_str(&format!("{text:?}")),
+        }
+        out.push('\n');
+
+        for &child in &node.children {
+            self.write_debug(child, depth + 1, out);
+        }
+    }
+
+    fn same_subtree(&self, id: NodeId, other: &Self, other_id: NodeId) -> bool {
+        let (Some(left), Some(right)) = (self.node(id), other.node(other_id)) else {
+            return false;
+        };
+
+        left.data == right.data
+            && left.children.len() == right.children.len()
+            && left
+                .children
+                .iter()
+                .zip(&right.children)
+                .all(|(&child, &other_child)| self.same_subtree(child, other, other_child))
+    }
+}
+
+impl Default for NodeArena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartialEq for NodeArena {
+    fn eq(&self, other: &Self) -> bool {
+        self.same_subtree(self.root(), other, other.root())
+    }
+}
+
+impl Eq for NodeArena {}
+
+#[cfg(test)]
+mod tests {
+    use super::NodeArena;
+
+    #[test]
+    fn equality_ignores_node_ids() {
+        let mut depth_first = NodeArena::new();
+        let mut breadth_first = NodeArena::new();
+        let built = (|| -> Option<()> {
+            let nav = depth_first.append_element(depth_first.root(), "nav")?;
+            depth_first.append_text(nav, "menu")?;
+            let main = depth_first.append_element(depth_first.root(), "main")?;
+            depth_first.append_text(main, "body")?;
+
+            let nav = breadth_first.append_element(breadth_first.root(), "nav")?;
+            let main = breadth_first.append_element(breadth_first.root(), "main")?;
+            breadth_first.append_text(nav, "menu")?;
+            breadth_first.append_text(main, "body")?;
+            Some(())
+        })();
+        assert!(built.is_some());
+        assert_eq!(depth_first, breadth_first);
+
+        breadth_first.append_element(breadth_first.root(), "footer");
+        assert_ne!(depth_first, breadth_first);
+    }
+
+    #[test]
+    fn debug_string_shows_nesting_and_text() {
+        let mut arena = NodeArena::new();
+        assert!(arena.is_empty());
+
+        let built = (|| -> Option<()> {
+            let html = arena.append_element(arena.root(), "HTML")?;
+            let para = arena.append_element(html, "p")?;
+            arena.set_attribute(para, "class", "lead");
+            arena.append_text(para, "Hello ")?;
+            let bold = arena.append_element(para, "b")?;
+            arena.append_text(bold, "world")?;
+            Some(())
+        })();
+        assert!(built.is_some());
+
+        assert_eq!(
+            arena.to_debug_string(),
+            concat!(
+                "#document\n",
+                "  <html>\n",
+                "    <p class=\"lead\">\n",
+                "      \"Hello \"\n",
+                "      <b>\n",
+                "        \"world\"\n",
+            )
+        );
+        assert_eq!(arena.len(), 5);
+        assert!(!arena.is_empty());
+        assert_eq!(arena.node(4).and_then(|text| text.parent), Some(3));
+        assert!(arena.append_element(4, "span").is_none());
+    }
+}
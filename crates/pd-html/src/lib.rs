@@ -1,6 +1,11 @@
 //! HTML tokenization and parsing boundaries.
 
 use pd_dom::Document;
+use pd_dom::NodeArena;
+use pd_dom::NodeId;
+
+/// Upper bound on element nodes kept from a single document.
+pub const DEFAULT_MAX_NODES: u32 = 500_000;
 
 /// Parses raw HTML into a DOM document.
 #[derive(Debug, Default)]
@@ -8,90 +13,344 @@ pub struct HtmlParser;
 
 impl HtmlParser {
     pub fn parse(&self, input: &str) -> Document {
-        let summary = summarize_document(input);
+        self.parse_with_max_nodes(input, DEFAULT_MAX_NODES)
+    }
 
-        Document {
-            title: summary.title,
-            root: if summary.node_count > 0 { 1 } else { 0 },
-            node_count: summary.node_count,
-            text_bytes: summary.text_bytes,
-        }
+    /// Stops adding element nodes after `max_nodes` and marks the document truncated.
+    pub fn parse_with_max_nodes(&self, input: &str, max_nodes: u32) -> Document {
+        stream_document([input], max_nodes, |_| {})
+    }
+
+    /// Parses `chunks` in arrival order, calling `on_element` as each top-level element closes.
+    ///
+    /// `<html>`, `<head>`, and `<body>` are transparent, so their children count as top-level.
+    /// Tags, comments, and raw text split across chunks are held back until they complete, so
+    /// the result matches [`HtmlParser::parse`] on the concatenated input.
+    pub fn parse_incremental<'a>(
+        &self,
+        chunks: impl IntoIterator<Item = &'a str>,
+        on_element: impl FnMut(StreamedElement<'_>),
+    ) -> Document {
+        stream_document(chunks, DEFAULT_MAX_NODES, on_element)
+    }
+
+    /// Builds the element and text tree for `input`.
+    ///
+    /// Comments, doctypes, and whitespace-only text are dropped. Text and attribute values are
+    /// kept verbatim; character references are not decoded yet.
+    pub fn parse_tree(&self, input: &str) -> NodeArena {
+        let mut state = StreamState::new(DEFAULT_MAX_NODES);
+        state.tree = Some(TreeBuilder::default());
+        state.feed(input, true, &mut |_| {});
+        state.tree.map(|tree| tree.arena).unwrap_or_default()
     }
 }
 
-#[derive(Debug, Default)]
-struct HtmlSummary {
-    title: String,
+fn stream_document<'a>(
+    chunks: impl IntoIterator<Item = &'a str>,
+    max_nodes: u32,
+    mut on_element: impl FnMut(StreamedElement<'_>),
+) -> Document {
+    let mut state = StreamState::new(max_nodes);
+    for chunk in chunks {
+        state.feed(chunk, false, &mut on_element);
+    }
+    state.feed("", true, &mut on_element);
+
+    Document {
+        root: if state.node_count > 0 { 1 } else { 0 },
+        title: state.title.unwrap_or_default(),
+        node_count: state.node_count,
+        text_bytes: state.text_bytes,
+        truncated: state.truncated,
+    }
+}
+
+/// A top-level element completed during [`HtmlParser::parse_incremental`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamedElement<'a> {
+    /// Lowercased tag name.
+    pub name: &'a str,
+    /// Raw markup from the start tag through the end tag, or to end of input if never closed.
+    pub markup: &'a str,
+}
+
+#[derive(Debug)]
+struct StreamState {
+    max_nodes: u32,
+    buffer: String,
+    cursor: usize,
+    element_start: Option<usize>,
+    open_elements: Vec<String>,
+    title: Option<String>,
     node_count: u32,
     text_bytes: u32,
+    truncated: bool,
+    /// Only set by [`HtmlParser::parse_tree`]; streaming callers skip the allocations.
+    tree: Option<TreeBuilder>,
 }
 
-fn summarize_document(input: &str) -> HtmlSummary {
-    let bytes = input.as_bytes();
-    let mut idx = 0_usize;
-    let mut node_count = 0_u32;
-    let mut text_bytes = 0_u32;
-    let mut title: Option<String> = None;
+impl StreamState {
+    fn new(max_nodes: u32) -> Self {
+        Self {
+            max_nodes,
+            buffer: String::new(),
+            cursor: 0,
+            element_start: None,
+            open_elements: Vec::new(),
+            title: None,
+            node_count: 0,
+            text_bytes: 0,
+            truncated: false,
+            tree: None,
+        }
+    }
 
-    while idx < bytes.len() {
-        if bytes[idx] != b'<' {
-            let next = find_byte(bytes, idx, b'<').unwrap_or(bytes.len());
-            text_bytes = text_bytes.saturating_add(count_visible_text_bytes(&input[idx..next]));
-            idx = next;
-            continue;
+    fn feed(
+        &mut self,
+        chunk: &str,
+        at_eof: bool,
+        on_element: &mut impl FnMut(StreamedElement<'_>),
+    ) {
+        if self.truncated {
+            return;
         }
 
-        if starts_with(bytes, idx, b"<!--") {
-            idx = skip_comment(bytes, idx);
-            continue;
+        self.buffer.push_str(chunk);
+        self.advance(at_eof, on_element);
+        if self.truncated {
+            self.buffer.clear();
+            self.cursor = 0;
+            return;
         }
 
-        if starts_with(bytes, idx, b"<!") {
-            idx = skip_to_gt(bytes, idx.saturating_add(2));
-            continue;
+        if at_eof {
+            if let (Some(start), Some(name)) =
+                (self.element_start.take(), self.open_elements.first())
+            {
+                on_element(StreamedElement {
+                    name,
+                    markup: &self.buffer[start..],
+                });
+            }
+            self.open_elements.clear();
         }
 
-        if starts_with(bytes, idx, b"<?") {
-            idx = skip_processing_instruction(bytes, idx);
-            continue;
+        // Only the unfinished top-level element and unparsed input need to stay buffered.
+        let keep_from = self.element_start.unwrap_or(self.cursor);
+        self.buffer.drain(..keep_from);
+        self.cursor -= keep_from;
+        if let Some(start) = &mut self.element_start {
+            *start -= keep_from;
         }
+    }
 
-        let Some((tag, next_idx)) = parse_tag(bytes, idx) else {
-            idx = idx.saturating_add(1);
-            continue;
-        };
+    fn advance(&mut self, at_eof: bool, on_element: &mut impl FnMut(StreamedElement<'_>)) {
+        let input = self.buffer.as_str();
+        let bytes = input.as_bytes();
+        let mut idx = self.cursor;
+
+        while idx < bytes.len() {
+            if bytes[idx] != b'<' {
+                let next = find_byte(bytes, idx, b'<').unwrap_or(bytes.len());
+                self.text_bytes = self
+                    .text_bytes
+                    .saturating_add(count_visible_text_bytes(&input[idx..next]));
+                if let Some(tree) = &mut self.tree {
+                    tree.push_text(&input[idx..next]);
+                }
+                idx = next;
+                continue;
+            }
 
-        if !tag.is_end {
-            node_count = node_count.saturating_add(1);
+            // Every markup construct is decidable from its first four bytes.
+            if !at_eof && bytes.len() - idx < 4 {
+                break;
+            }
 
-            if tag.name == "title" {
-                let (raw_title, after_title) =
-                    read_raw_text_until_end_tag(input, next_idx, "title");
-                if title.is_none() {
-                    let collapsed = collapse_whitespace(raw_title);
-                    if !collapsed.is_empty() {
-                        title = Some(collapsed);
-                    }
+            if starts_with(bytes, idx, b"<!--") {
+                if !at_eof && find_subslice(bytes, idx.saturating_add(4), b"-->").is_none() {
+                    break;
+                }
+                idx = skip_comment(bytes, idx);
+                continue;
+            }
+
+            if starts_with(bytes, idx, b"<!") {
+                if !at_eof && find_byte(bytes, idx.saturating_add(2), b'>').is_none() {
+                    break;
+                }
+                idx = skip_to_gt(bytes, idx.saturating_add(2));
+                continue;
+            }
+
+            if starts_with(bytes, idx, b"<?") {
+                if !at_eof && find_subslice(bytes, idx.saturating_add(2), b"?>").is_none() {
+                    break;
                 }
-                idx = after_title;
+                idx = skip_processing_instruction(bytes, idx);
                 continue;
             }
 
-            if !tag.self_closing && (tag.name == "script" || tag.name == "style") {
-                let (_, after_raw) = read_raw_text_until_end_tag(input, next_idx, &tag.name);
-                idx = after_raw;
+            let Some((tag, next_idx)) = parse_tag(bytes, idx) else {
+                if !at_eof && tag_may_complete(bytes, idx) {
+                    break;
+                }
+                idx = idx.saturating_add(1);
+                continue;
+            };
+
+            if tag.is_end {
+                if let Some(tree) = &mut self.tree {
+                    tree.close(&tag.name);
+                }
+                if let Some(position) = self
+                    .open_elements
+                    .iter()
+                    .rposition(|open| *open == tag.name)
+                {
+                    self.open_elements.truncate(position);
+                    if let (true, Some(start)) = (self.open_elements.is_empty(), self.element_start)
+                    {
+                        self.element_start = None;
+                        on_element(StreamedElement {
+                            name: &tag.name,
+                            markup: &input[start..next_idx],
+                        });
+                    }
+                }
+                idx = next_idx;
                 continue;
             }
+
+            if self.node_count >= self.max_nodes {
+                self.truncated = true;
+                break;
+            }
+
+            let raw_text = tag.name == "title"
+                || (!tag.self_closing && (tag.name == "script" || tag.name == "style"));
+            let mut end_idx = next_idx;
+            let mut raw_content = None;
+            if raw_text {
+                let (text, after) = match read_raw_text_until_end_tag(input, next_idx, &tag.name) {
+                    Some(found) => found,
+                    None if at_eof => (&input[next_idx..], bytes.len()),
+                    None => break,
+                };
+                if tag.name == "title" && self.title.is_none() {
+                    let collapsed = collapse_whitespace(text);
+                    if !collapsed.is_empty() {
+                        self.title = Some(collapsed);
+                    }
+                }
+                end_idx = after;
+                raw_content = Some(text);
+            }
+            self.node_count = self.node_count.saturating_add(1);
+
+            let closes_now = raw_text || tag.self_closing || is_void_element(&tag.name);
+            if let Some(tree) = &mut self.tree {
+                tree.open(&tag.name, &input[idx..next_idx], raw_content, closes_now);
+            }
+            if !is_transparent_wrapper(&tag.name) {
+                if self.open_elements.is_empty() && closes_now {
+                    on_element(StreamedElement {
+                        name: &tag.name,
+                        markup: &input[idx..end_idx],
+                    });
+                } else if !closes_now {
+                    if self.open_elements.is_empty() {
+                        self.element_start = Some(idx);
+                    }
+                    self.open_elements.push(tag.name);
+                }
+            }
+            idx = end_idx;
+        }
+
+        self.cursor = idx;
+    }
+}
+
+/// Arena under construction plus the open-element stack used to place new nodes.
+#[derive(Debug, Default)]
+struct TreeBuilder {
+    arena: NodeArena,
+    open_elements: Vec<(String, NodeId)>,
+}
+
+impl TreeBuilder {
+    fn current(&self) -> NodeId {
+        self.open_elements
+            .last()
+            .map_or(self.arena.root(), |(_, id)| *id)
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if !text.trim().is_empty() {
+            self.arena.append_text(self.current(), text);
+        }
+    }
+
+    fn open(&mut self, name: &str, tag_markup: &str, raw_text: Option<&str>, closes_now: bool) {
+        let Some(id) = self.arena.append_element(self.current(), name) else {
+            return;
+        };
+        for (attr, value) in parse_attributes(tag_markup) {
+            self.arena.set_attribute(id, &attr, &value);
+        }
+        if let Some(text) = raw_text.filter(|text| !text.is_empty()) {
+            self.arena.append_text(id, text);
+        }
+        if !closes_now {
+            self.open_elements.push((name.to_owned(), id));
         }
+    }
 
-        idx = next_idx;
+    /// Pops back to the nearest open element named `name`; stray end tags are ignored.
+    fn close(&mut self, name: &str) {
+        if let Some(position) = self
+            .open_elements
+            .iter()
+            .rposition(|(open, _)| open == name)
+        {
+            self.open_elements.truncate(position);
+        }
     }
+}
+
+fn is_transparent_wrapper(name: &str) -> bool {
+    matches!(name, "html" | "head" | "body")
+}
 
-    HtmlSummary {
-        title: title.unwrap_or_default(),
-        node_count,
-        text_bytes,
+fn is_void_element(name: &str) -> bool {
+    matches!(
+        name,
+        "area"
+            | "base"
+            | "br"
+            | "col"
+            | "embed"
+            | "hr"
+            | "img"
+            | "input"
+            | "link"
+            | "meta"
+            | "source"
+            | "track"
+            | "wbr"
+    )
+}
+
+/// Whether an unparsed `<` at `start` could still become a tag once more input arrives.
+fn tag_may_complete(bytes: &[u8], start: usize) -> bool {
+    let mut idx = start.saturating_add(1);
+    if bytes.get(idx).copied() == Some(b'/') {
+        idx = idx.saturating_add(1);
     }
+    idx = skip_spaces(bytes, idx);
+    bytes.get(idx).is_none_or(|byte| is_tag_name_char(*byte))
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -172,11 +431,68 @@ fn parse_tag(bytes: &[u8], start: usize) -> Option<(ParsedTag, usize)> {
     None
 }
 
+/// Attributes of a complete start tag such as `<a href='/x' hidden>`; the first of any
+/// duplicated names wins, as in browsers.
+fn parse_attributes(tag_markup: &str) -> Vec<(String, String)> {
+    let bytes = tag_markup.as_bytes();
+    let mut idx = skip_spaces(bytes, 1);
+    while idx < bytes.len() && is_tag_name_char(bytes[idx]) {
+        idx = idx.saturating_add(1);
+    }
+
+    let mut attributes: Vec<(String, String)> = Vec::new();
+    loop {
+        while idx < bytes.len() && (bytes[idx].is_ascii_whitespace() || bytes[idx] == b'/') {
+            idx = idx.saturating_add(1);
+        }
+        if idx >= bytes.len() || bytes[idx] == b'>' {
+            return attributes;
+        }
+
+        let name_start = idx;
+        while idx < bytes.len()
+            && !bytes[idx].is_ascii_whitespace()
+            && !matches!(bytes[idx], b'=' | b'>' | b'/')
+        {
+            idx = idx.saturating_add(1);
+        }
+        let name = tag_markup[name_start..idx].to_ascii_lowercase();
+
+        let mut value = "";
+        let after_name = skip_spaces(bytes, idx);
+        if bytes.get(after_name).copied() == Some(b'=') {
+            idx = skip_spaces(bytes, after_name.saturating_add(1));
+            match bytes.get(idx).copied() {
+                Some(quote @ (b'"' | b'\'')) => {
+                    let value_start = idx.saturating_add(1);
+                    let value_end = find_byte(bytes, value_start, quote).unwrap_or(bytes.len());
+                    value = &tag_markup[value_start..value_end];
+                    idx = value_end.saturating_add(1);
+                }
+                _ => {
+                    let value_start = idx;
+                    while idx < bytes.len()
+                        && !bytes[idx].is_ascii_whitespace()
+                        && bytes[idx] != b'>'
+                    {
+                        idx = idx.saturating_add(1);
+                    }
+                    value = &tag_markup[value_start..idx];
+                }
+            }
+        }
+
+        if !name.is_empty() && attributes.iter().all(|(existing, _)| *existing != name) {
+            attributes.push((name, value.to_owned()));
+        }
+    }
+}
+
 fn read_raw_text_until_end_tag<'a>(
     input: &'a str,
     start: usize,
     tag_name: &str,
-) -> (&'a str, usize) {
+) -> Option<(&'a str, usize)> {
     let bytes = input.as_bytes();
     let tag_bytes = tag_name.as_bytes();
     let mut idx = start;
@@ -188,14 +504,14 @@ fn read_raw_text_until_end_tag<'a>(
             && tag_name_boundary(bytes, idx.saturating_add(2 + tag_bytes.len()))
         {
             if let Some((_, end_idx)) = parse_tag(bytes, idx) {
-                return (&input[start..idx], end_idx);
+                return Some((&input[start..idx], end_idx));
             }
         }
 
         idx = idx.saturating_add(1);
     }
 
-    (&input[start..], bytes.len())
+    None
 }
 
 fn count_visible_text_bytes(segment: &str) -> u32 {
@@ -294,6 +610,24 @@ fn find_byte(bytes: &[u8], from: usize, byte: u8) -> Option<usize> {
 #[cfg(test)]
 mod tests {
     use super::HtmlParser;
+    use super::StreamedElement;
+
+    const STREAM_SAMPLE: &str = "<!DOCTYPE html><html><head><title>Stream</title>\
+        <style>p > a { color: red }</style></head><body><!-- lead --><h1 class=\"x\">Head</h1>\
+        <div><p>One <a href='/a>b'>link</a></p><img src=\"/i.png\"></div>\
+        <script>if (a < b) {}</script><p>Tail</p></body></html>";
+
+    fn collect_elements(
+        parser: &HtmlParser,
+        chunks: &[&str],
+    ) -> (Vec<(String, String)>, pd_dom::Document) {
+        let mut elements = Vec::new();
+        let document =
+            parser.parse_incremental(chunks.iter().copied(), |element: StreamedElement<'_>| {
+                elements.push((element.name.to_owned(), element.markup.to_owned()));
+            });
+        (elements, document)
+    }
 
     #[test]
     fn parses_title_and_root() {
@@ -331,4 +665,118 @@ mod tests {
         let doc = parser.parse("<TiTlE data-a='1'>   Hello    PixelDust </tItLe>");
         assert_eq!(doc.title, "Hello PixelDust");
     }
+
+    #[test]
+    fn stops_adding_nodes_at_the_limit() {
+        let doc = HtmlParser.parse_with_max_nodes(&"<p>row</p>".repeat(50), 10);
+        assert_eq!(doc.node_count, 10);
+        assert!(doc.truncated);
+        assert_eq!(doc.text_bytes, 30);
+    }
+
+    #[test]
+    fn small_documents_are_not_truncated() {
+        let doc = HtmlParser.parse_with_max_nodes("<html><body><p>Hello</p></body></html>", 10);
+        assert_eq!(doc.node_count, 3);
+        assert!(!doc.truncated);
+    }
+
+    #[test]
+    fn tags_split_across_chunks_parse_like_the_whole_document() {
+        let parser = HtmlParser;
+        let (whole_elements, whole_document) = collect_elements(&parser, &[STREAM_SAMPLE]);
+        assert_eq!(whole_document, parser.parse(STREAM_SAMPLE));
+
+        let mid_tag = STREAM_SAMPLE.find("class=").unwrap_or_default();
+        let (elements, document) = collect_elements(
+            &parser,
+            &[&STREAM_SAMPLE[..mid_tag], &STREAM_SAMPLE[mid_tag..]],
+        );
+        assert_eq!(elements, whole_elements);
+        assert_eq!(document, whole_document);
+
+        for split in 1..STREAM_SAMPLE.len() {
+            let (elements, document) =
+                collect_elements(&parser, &[&STREAM_SAMPLE[..split], &STREAM_SAMPLE[split..]]);
+            assert_eq!(elements, whole_elements, "split at byte {split}");
+            assert_eq!(document, whole_document, "split at byte {split}");
+        }
+    }
+
+    #[test]
+    fn callback_fires_once_per_completed_top_level_element() {
+        let parser = HtmlParser;
+        let mut chunks = Vec::new();
+        let mut rest = STREAM_SAMPLE;
+        while !rest.is_empty() {
+            let (chunk, tail) = rest.split_at(rest.len().min(7));
+            chunks.push(chunk);
+            rest = tail;
+        }
+
+        let (elements, document) = collect_elements(&parser, &chunks);
+        let names = elements
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["title", "style", "h1", "div", "script", "p"]);
+        assert_eq!(elements[2].1, "<h1 class=\"x\">Head</h1>");
+        assert!(elements[3].1.ends_with("<img src=\"/i.png\"></div>"));
+        assert_eq!(document.title, "Stream");
+    }
+
+    #[test]
+    fn trees_parsed_from_the_same_html_are_equal() {
+        let parser = HtmlParser;
+        assert_eq!(
+            parser.parse_tree(STREAM_SAMPLE),
+            parser.parse_tree(STREAM_SAMPLE)
+        );
+
+        let with_extra_child = STREAM_SAMPLE.replace("<p>Tail</p>", "<p>Tail</p><hr>");
+        assert_ne!(
+            parser.parse_tree(STREAM_SAMPLE),
+            parser.parse_tree(&with_extra_child)
+        );
+    }
+
+    #[test]
+    fn parse_tree_builds_elements_attributes_and_text() {
+        let tree = HtmlParser.parse_tree(STREAM_SAMPLE);
+        assert_eq!(
+            tree.to_debug_string(),
+            concat!(
+                "#document\n",
+                "  <html>\n",
+                "    <head>\n",
+                "      <title>\n",
+                "        \"Stream\"\n",
+                "      <style>\n",
+                "        \"p > a { color: red }\"\n",
+                "    <body>\n",
+                "      <h1 class=\"x\">\n",
+                "        \"Head\"\n",
+                "      <div>\n",
+                "        <p>\n",
+                "          \"One \"\n",
+                "          <a href=\"/a>b\">\n",
+                "            \"link\"\n",
+                "        <img src=\"/i.png\">\n",
+                "      <script>\n",
+                "        \"if (a < b) {}\"\n",
+                "      <p>\n",
+                "        \"Tail\"\n",
+            )
+        );
+    }
+
+    #[test]
+    fn parse_tree_keeps_the_first_duplicate_attribute() {
+        let tree =
+            HtmlParser.parse_tree("<input type=text TYPE=\"hidden\" disabled value = 'a b'/>x");
+        assert_eq!(
+            tree.to_debug_string(),
+            "#document\n  <input type=\"text\" disabled=\"\" value=\"a b\">\n  \"x\"\n"
+        );
+    }
 }
@@ -1,20 +1,42 @@
 //! Process messaging and isolation channel definitions.
 
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
 use pd_core::BrowserError;
 use pd_core::BrowserResult;
+use pd_core::ResultExt;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Write;
 use std::sync::mpsc;
 use std::time::Duration;
 
 const DEFAULT_MAX_MESSAGE_BYTES: usize = 64 * 1024;
 const FRAME_PREFIX_BYTES: usize = 4;
+/// Suggested `threshold_bytes` for [`encode_compressed_frame`].
+pub const DEFAULT_COMPRESSION_THRESHOLD_BYTES: usize = 4 * 1024;
+const COMPRESSION_FLAG_NONE: u8 = 0;
+const COMPRESSION_FLAG_GZIP: u8 = 1;
 const MESSAGE_TAG_PING: u8 = 1;
 const MESSAGE_TAG_PONG: u8 = 2;
 const MESSAGE_TAG_HEALTH_CHECK: u8 = 3;
 const MESSAGE_TAG_HEALTH_REPORT: u8 = 4;
 const MESSAGE_TAG_SHUTDOWN: u8 = 5;
+const MESSAGE_TAG_HELLO: u8 = 6;
+/// Typed-message protocol version written by this build.
+pub const IPC_PROTOCOL_VERSION: u8 = 1;
+/// Oldest typed-message protocol version this build still decodes.
+pub const MIN_IPC_PROTOCOL_VERSION: u8 = 1;
+/// Per-field cap for health-report detail text, independent of the channel frame cap.
+const MAX_HEALTH_DETAIL_BYTES: usize = 1024;
+/// Leading byte of serde-codec payloads; never a valid manual-codec tag.
+#[cfg(feature = "serde-codec")]
+const SERDE_CODEC_MARKER: u8 = 0xB1;
 
 /// Browser runtime process roles.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-codec", derive(serde::Serialize, serde::Deserialize))]
 pub enum ProcessRole {
     Browser,
     Renderer,
@@ -45,6 +67,7 @@ impl ProcessRole {
 
 /// Typed IPC message envelope used across process roles.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-codec", derive(serde::Serialize, serde::Deserialize))]
 pub enum IpcMessage {
     Ping {
         request_id: u64,
@@ -62,6 +85,36 @@ pub enum IpcMessage {
         detail: String,
     },
     Shutdown,
+    /// Channel-setup handshake advertising the sender's supported protocol versions.
+    Hello {
+        min_version: u8,
+        max_version: u8,
+    },
+}
+
+impl IpcMessage {
+    /// Handshake message advertising the versions this build supports.
+    pub fn hello() -> Self {
+        Self::Hello {
+            min_version: MIN_IPC_PROTOCOL_VERSION,
+            max_version: IPC_PROTOCOL_VERSION,
+        }
+    }
+}
+
+/// Selects the highest protocol version supported by both handshake peers.
+pub fn negotiate_protocol_version(remote_min: u8, remote_max: u8) -> BrowserResult<u8> {
+    let highest = remote_max.min(IPC_PROTOCOL_VERSION);
+    if remote_min > remote_max || highest < remote_min.max(MIN_IPC_PROTOCOL_VERSION) {
+        return Err(BrowserError::new(
+            "ipc.version_unsupported",
+            format!(
+                "no common IPC protocol version (local {MIN_IPC_PROTOCOL_VERSION}..={IPC_PROTOCOL_VERSION}, remote {remote_min}..={remote_max})"
+            ),
+        ));
+    }
+
+    Ok(highest)
 }
 
 /// Defines how processes communicate.
@@ -114,29 +167,161 @@ impl LocalIpcEndpoint {
 
     pub fn send(&self, payload: &[u8]) -> BrowserResult<()> {
         let frame = encode_frame(payload, self.config.max_message_bytes)?;
-        self.tx.send(frame).map_err(|error| {
-            BrowserError::new(
-                "ipc.send_failed",
-                format!(
-                    "failed to send message from {} endpoint: {error}",
-                    self.config.role.as_str()
-                ),
-            )
-        })
+        self.tx.send(frame).context(
+            "ipc.send_failed",
+            format!(
+                "failed to send message from {} endpoint",
+                self.config.role.as_str()
+            ),
+        )
     }
 
     pub fn recv_timeout(&self, timeout: Duration) -> BrowserResult<Vec<u8>> {
-        let frame = self.rx.recv_timeout(timeout).map_err(|error| {
-            BrowserError::new(
-                "ipc.recv_failed",
+        let frame = self.rx.recv_timeout(timeout).context(
+            "ipc.recv_failed",
+            format!(
+                "failed to receive message for {} endpoint",
+                self.config.role.as_str()
+            ),
+        )?;
+        decode_frame(&frame, self.config.max_message_bytes)
+    }
+}
+
+/// Fans a typed message out to several endpoints, encoding it once.
+pub struct IpcBroadcaster {
+    endpoints: Vec<LocalIpcEndpoint>,
+}
+
+impl IpcBroadcaster {
+    pub fn new(endpoints: Vec<LocalIpcEndpoint>) -> Self {
+        Self { endpoints }
+    }
+
+    pub fn endpoints(&self) -> &[LocalIpcEndpoint] {
+        &self.endpoints
+    }
+
+    /// Sends to every endpoint; failures are aggregated after all deliveries are attempted.
+    pub fn broadcast(&self, message: &IpcMessage) -> BrowserResult<()> {
+        let payload = encode_message_payload(message)?;
+        let failures = self
+            .endpoints
+            .iter()
+            .filter_map(|endpoint| {
+                endpoint
+                    .send(&payload)
+                    .err()
+                    .map(|error| format!("{} ({})", endpoint.role().as_str(), error.code))
+            })
+            .collect::<Vec<_>>();
+
+        if failures.is_empty() {
+            return Ok(());
+        }
+
+        Err(BrowserError::new(
+            "ipc.broadcast_failed",
+            format!(
+                "broadcast failed for {} of {} endpoints: {}",
+                failures.len(),
+                self.endpoints.len(),
+                failures.join(", ")
+            ),
+        ))
+    }
+}
+
+/// Typed message transport over byte streams such as worker stdio pipes.
+pub struct FramedStream<R: Read, W: Write> {
+    reader: R,
+    writer: W,
+    max_message_bytes: usize,
+}
+
+impl<R: Read, W: Write> FramedStream<R, W> {
+    pub fn new(reader: R, writer: W, config: &ChannelConfig) -> BrowserResult<Self> {
+        config.validate()?;
+        Ok(Self {
+            reader,
+            writer,
+            max_message_bytes: config.max_message_bytes,
+        })
+    }
+
+    pub fn into_parts(self) -> (R, W) {
+        (self.reader, self.writer)
+    }
+
+    pub fn send(&mut self, message: &IpcMessage) -> BrowserResult<()> {
+        let frame = encode_message(message, self.max_message_bytes)?;
+        self.writer
+            .write_all(&frame)
+            .and_then(|()| self.writer.flush())
+            .context("ipc.stream_write_failed", "failed to write IPC frame")
+    }
+
+    pub fn recv(&mut self) -> BrowserResult<IpcMessage> {
+        let mut len_bytes = [0_u8; FRAME_PREFIX_BYTES];
+        match read_full(&mut self.reader, &mut len_bytes)? {
+            0 => {
+                return Err(BrowserError::new(
+                    "ipc.stream_closed",
+                    "IPC stream closed before the next frame",
+                ));
+            }
+            FRAME_PREFIX_BYTES => {}
+            read => {
+                return Err(BrowserError::new(
+                    "ipc.stream_truncated",
+                    format!(
+                        "IPC stream ended inside frame prefix ({read} of {FRAME_PREFIX_BYTES} bytes)"
+                    ),
+                ));
+            }
+        }
+
+        let payload_len = u32::from_be_bytes(len_bytes) as usize;
+        if payload_len > self.max_message_bytes {
+            return Err(BrowserError::new(
+                "ipc.message_too_large",
                 format!(
-                    "failed to receive message for {} endpoint: {error}",
-                    self.config.role.as_str()
+                    "decoded payload exceeds max_message_bytes ({} > {})",
+                    payload_len, self.max_message_bytes
                 ),
-            )
-        })?;
-        decode_frame(&frame, self.config.max_message_bytes)
+            ));
+        }
+
+        let mut payload = vec![0_u8; payload_len];
+        let read = read_full(&mut self.reader, &mut payload)?;
+        if read != payload_len {
+            return Err(BrowserError::new(
+                "ipc.stream_truncated",
+                format!("IPC stream ended inside frame payload ({read} of {payload_len} bytes)"),
+            ));
+        }
+
+        decode_message_payload(&payload)
+    }
+}
+
+/// Reads until `buf` is full or EOF, returning the number of bytes read.
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> BrowserResult<usize> {
+    let mut filled = 0_usize;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+            Err(error) => {
+                return Err(BrowserError::new(
+                    "ipc.stream_read_failed",
+                    format!("failed to read IPC frame: {error}"),
+                ));
+            }
+        }
     }
+    Ok(filled)
 }
 
 /// Creates paired in-memory IPC endpoints.
@@ -226,6 +411,103 @@ pub fn decode_frame(frame: &[u8], max_message_bytes: usize) -> BrowserResult<Vec
     Ok(frame[FRAME_PREFIX_BYTES..].to_vec())
 }
 
+/// Encodes a payload as a length-prefixed frame with a compression flag byte.
+///
+/// Payloads of at least `threshold_bytes` are gzip-compressed when that makes them smaller;
+/// `max_message_bytes` always applies to the uncompressed payload.
+pub fn encode_compressed_frame(
+    payload: &[u8],
+    max_message_bytes: usize,
+    threshold_bytes: usize,
+) -> BrowserResult<Vec<u8>> {
+    if payload.len() > max_message_bytes {
+        return Err(BrowserError::new(
+            "ipc.message_too_large",
+            format!(
+                "payload exceeds max_message_bytes ({} > {})",
+                payload.len(),
+                max_message_bytes
+            ),
+        ));
+    }
+
+    let compressed = if payload.len() >= threshold_bytes {
+        Some(gzip_payload(payload)?).filter(|compressed| compressed.len() < payload.len())
+    } else {
+        None
+    };
+
+    let (flag, body) = match &compressed {
+        Some(compressed) => (COMPRESSION_FLAG_GZIP, compressed.as_slice()),
+        None => (COMPRESSION_FLAG_NONE, payload),
+    };
+
+    let mut flagged = Vec::with_capacity(1 + body.len());
+    flagged.push(flag);
+    flagged.extend_from_slice(body);
+    encode_frame(&flagged, max_message_bytes.saturating_add(1))
+}
+
+/// Decodes a frame produced by [`encode_compressed_frame`], decompressing if flagged.
+pub fn decode_compressed_frame(frame: &[u8], max_message_bytes: usize) -> BrowserResult<Vec<u8>> {
+    let flagged = decode_frame(frame, max_message_bytes.saturating_add(1))?;
+    let Some((&flag, body)) = flagged.split_first() else {
+        return Err(BrowserError::new(
+            "ipc.message_empty",
+            "compressed frame is missing its compression flag",
+        ));
+    };
+
+    match flag {
+        COMPRESSION_FLAG_NONE => {
+            if body.len() > max_message_bytes {
+                return Err(BrowserError::new(
+                    "ipc.message_too_large",
+                    format!(
+                        "decoded payload exceeds max_message_bytes ({} > {})",
+                        body.len(),
+                        max_message_bytes
+                    ),
+                ));
+            }
+            Ok(body.to_vec())
+        }
+        COMPRESSION_FLAG_GZIP => gunzip_payload(body, max_message_bytes),
+        other => Err(BrowserError::new(
+            "ipc.compression_flag_unknown",
+            format!("unknown IPC compression flag `{other}`"),
+        )),
+    }
+}
+
+fn gzip_payload(payload: &[u8]) -> BrowserResult<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+    encoder
+        .write_all(payload)
+        .and_then(|()| encoder.finish())
+        .context("ipc.compression_failed", "failed to gzip IPC payload")
+}
+
+fn gunzip_payload(body: &[u8], max_message_bytes: usize) -> BrowserResult<Vec<u8>> {
+    let limit = u64::try_from(max_message_bytes)
+        .unwrap_or(u64::MAX)
+        .saturating_add(1);
+    let mut decoded = Vec::new();
+    GzDecoder::new(body)
+        .take(limit)
+        .read_to_end(&mut decoded)
+        .context("ipc.decompression_failed", "failed to gunzip IPC payload")?;
+
+    if decoded.len() > max_message_bytes {
+        return Err(BrowserError::new(
+            "ipc.message_too_large",
+            format!("decompressed payload exceeds max_message_bytes ({max_message_bytes})"),
+        ));
+    }
+
+    Ok(decoded)
+}
+
 /// Encodes a typed IPC message as a framed payload.
 pub fn encode_message(message: &IpcMessage, max_message_bytes: usize) -> BrowserResult<Vec<u8>> {
     let payload = encode_message_payload(message)?;
@@ -238,7 +520,83 @@ pub fn decode_message(frame: &[u8], max_message_bytes: usize) -> BrowserResult<I
     decode_message_payload(&payload)
 }
 
+/// Encodes a typed IPC message with the serde/bincode codec as a framed payload.
+#[cfg(feature = "serde-codec")]
+pub fn encode_message_serde(
+    message: &IpcMessage,
+    max_message_bytes: usize,
+) -> BrowserResult<Vec<u8>> {
+    use bincode::Options;
+
+    validate_message_fields(message)?;
+    let body = bincode::DefaultOptions::new().serialize(message).context(
+        "ipc.serde_encode_failed",
+        "failed to serialize typed IPC message",
+    )?;
+
+    let mut payload = Vec::with_capacity(1 + body.len());
+    payload.push(SERDE_CODEC_MARKER);
+    payload.extend_from_slice(&body);
+    encode_frame(&payload, max_message_bytes)
+}
+
+/// Decodes a framed typed IPC message produced by [`encode_message_serde`].
+#[cfg(feature = "serde-codec")]
+pub fn decode_message_serde(frame: &[u8], max_message_bytes: usize) -> BrowserResult<IpcMessage> {
+    use bincode::Options;
+
+    let payload = decode_frame(frame, max_message_bytes)?;
+    let Some((&marker, body)) = payload.split_first() else {
+        return Err(BrowserError::new(
+            "ipc.message_empty",
+            "typed IPC payload is empty",
+        ));
+    };
+    if marker != SERDE_CODEC_MARKER {
+        return Err(BrowserError::new(
+            "ipc.serde_codec_mismatch",
+            format!("payload marker `{marker}` does not identify a serde-codec message"),
+        ));
+    }
+
+    let message: IpcMessage = bincode::DefaultOptions::new()
+        .with_limit(u64::try_from(max_message_bytes).unwrap_or(u64::MAX))
+        .reject_trailing_bytes()
+        .deserialize(body)
+        .context(
+            "ipc.serde_decode_failed",
+            "failed to deserialize typed IPC message",
+        )?;
+    validate_message_fields(&message)?;
+    Ok(message)
+}
+
+fn validate_message_fields(message: &IpcMessage) -> BrowserResult<()> {
+    if let IpcMessage::HealthReport { detail, .. } = message
+        && detail.len() > MAX_HEALTH_DETAIL_BYTES
+    {
+        return Err(BrowserError::new(
+            "ipc.health_detail_too_large",
+            format!(
+                "health-report detail exceeds field limit ({} > {MAX_HEALTH_DETAIL_BYTES} bytes)",
+                detail.len()
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
 fn encode_message_payload(message: &IpcMessage) -> BrowserResult<Vec<u8>> {
+    validate_message_fields(message)?;
+    let body = encode_message_body(message)?;
+    let mut out = Vec::with_capacity(1 + body.len());
+    out.push(IPC_PROTOCOL_VERSION);
+    out.extend_from_slice(&body);
+    Ok(out)
+}
+
+fn encode_message_body(message: &IpcMessage) -> BrowserResult<Vec<u8>> {
     match message {
         IpcMessage::Ping { request_id } => {
             let mut out = Vec::with_capacity(1 + 8);
@@ -285,6 +643,10 @@ fn encode_message_payload(message: &IpcMessage) -> BrowserResult<Vec<u8>> {
             Ok(out)
         }
         IpcMessage::Shutdown => Ok(vec![MESSAGE_TAG_SHUTDOWN]),
+        IpcMessage::Hello {
+            min_version,
+            max_version,
+        } => Ok(vec![MESSAGE_TAG_HELLO, *min_version, *max_version]),
     }
 }
 
@@ -297,6 +659,16 @@ fn decode_message_payload(payload: &[u8]) -> BrowserResult<IpcMessage> {
     }
 
     let mut offset = 0_usize;
+    let version = read_u8(payload, &mut offset, "version")?;
+    if !(MIN_IPC_PROTOCOL_VERSION..=IPC_PROTOCOL_VERSION).contains(&version) {
+        return Err(BrowserError::new(
+            "ipc.version_unsupported",
+            format!(
+                "typed IPC protocol version `{version}` is outside supported range {MIN_IPC_PROTOCOL_VERSION}..={IPC_PROTOCOL_VERSION}"
+            ),
+        ));
+    }
+
     let tag = read_u8(payload, &mut offset, "tag")?;
     let message = match tag {
         MESSAGE_TAG_PING => IpcMessage::Ping {
@@ -322,14 +694,20 @@ fn decode_message_payload(payload: &[u8]) -> BrowserResult<IpcMessage> {
                 }
             };
             let detail = read_string_u16(payload, &mut offset, "detail")?;
-            IpcMessage::HealthReport {
+            let message = IpcMessage::HealthReport {
                 request_id,
                 role,
                 healthy,
                 detail,
-            }
+            };
+            validate_message_fields(&message)?;
+            message
         }
         MESSAGE_TAG_SHUTDOWN => IpcMessage::Shutdown,
+        MESSAGE_TAG_HELLO => IpcMessage::Hello {
+            min_version: read_u8(payload, &mut offset, "min_version")?,
+            max_version: read_u8(payload, &mut offset, "max_version")?,
+        },
         other => {
             return Err(BrowserError::new(
                 "ipc.message_tag_unknown",
@@ -401,12 +779,10 @@ fn read_u64(payload: &[u8], offset: &mut usize, field: &str) -> BrowserResult<u6
 fn read_string_u16(payload: &[u8], offset: &mut usize, field: &str) -> BrowserResult<String> {
     let len = usize::from(read_u16(payload, offset, field)?);
     let bytes = read_exact(payload, offset, len, field)?;
-    String::from_utf8(bytes.to_vec()).map_err(|error| {
-        BrowserError::new(
-            "ipc.message_utf8_invalid",
-            format!("typed IPC field `{field}` is not valid UTF-8: {error}"),
-        )
-    })
+    String::from_utf8(bytes.to_vec()).context(
+        "ipc.message_utf8_invalid",
+        format!("typed IPC field `{field}` is not valid UTF-8"),
+    )
 }
 
 fn read_exact<'a>(
@@ -430,16 +806,38 @@ fn read_exact<'a>(
 
 #[cfg(test)]
 mod tests {
+    use super::COMPRESSION_FLAG_GZIP;
+    use super::COMPRESSION_FLAG_NONE;
     use super::ChannelConfig;
+    use super::FramedStream;
+    use super::IPC_PROTOCOL_VERSION;
+    use super::IpcBroadcaster;
     use super::IpcMessage;
+    use super::MAX_HEALTH_DETAIL_BYTES;
+    use super::MESSAGE_TAG_SHUTDOWN;
     use super::ProcessRole;
+    use super::decode_compressed_frame;
     use super::decode_frame;
     use super::decode_message;
+    use super::encode_compressed_frame;
     use super::encode_frame;
     use super::encode_message;
     use super::local_channel_pair;
+    use super::negotiate_protocol_version;
+    use std::io::Cursor;
+    use std::io::Read;
     use std::time::Duration;
 
+    /// Reader that yields at most one byte per call to exercise partial reads.
+    struct TrickleReader(Cursor<Vec<u8>>);
+
+    impl Read for TrickleReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = buf.len().min(1);
+            self.0.read(&mut buf[..len])
+        }
+    }
+
     #[test]
     fn role_roundtrip_from_str() {
         assert_eq!(
@@ -506,7 +904,7 @@ mod tests {
 
     #[test]
     fn typed_message_rejects_unknown_tag() {
-        let frame = encode_frame(&[99], 64);
+        let frame = encode_frame(&[IPC_PROTOCOL_VERSION, 99], 64);
         assert!(frame.is_ok());
         let decoded = decode_message(&frame.unwrap_or_else(|_| unreachable!()), 64);
         assert!(decoded.is_err());
@@ -514,4 +912,270 @@ mod tests {
             assert_eq!(error.code, "ipc.message_tag_unknown");
         }
     }
+
+    #[test]
+    fn health_report_detail_respects_field_cap() {
+        let report = |detail: String| IpcMessage::HealthReport {
+            request_id: 7,
+            role: ProcessRole::Network,
+            healthy: false,
+            detail,
+        };
+
+        let normal = encode_message(&report("dns resolver degraded".to_owned()), 64 * 1024);
+        assert!(normal.is_ok());
+
+        let at_cap = encode_message(&report("x".repeat(MAX_HEALTH_DETAIL_BYTES)), 64 * 1024);
+        assert!(at_cap.is_ok());
+
+        let oversized = encode_message(&report("x".repeat(MAX_HEALTH_DETAIL_BYTES + 1)), 64 * 1024);
+        assert_eq!(
+            oversized.err().map(|error| error.code),
+            Some("ipc.health_detail_too_large")
+        );
+    }
+
+    #[test]
+    fn broadcast_reaches_live_endpoints_and_reports_failures() {
+        let mut senders = Vec::new();
+        let mut receivers = Vec::new();
+        for role in [
+            ProcessRole::Renderer,
+            ProcessRole::Network,
+            ProcessRole::Storage,
+        ] {
+            let pair = local_channel_pair(
+                ChannelConfig::hardened(role).unwrap_or_else(|_| unreachable!()),
+                ChannelConfig::hardened(ProcessRole::Browser).unwrap_or_else(|_| unreachable!()),
+            );
+            let (sender, receiver) = pair.unwrap_or_else(|_| unreachable!());
+            senders.push(sender);
+            receivers.push(receiver);
+        }
+
+        let network_peer = receivers.remove(1);
+        drop(network_peer);
+
+        let broadcaster = IpcBroadcaster::new(senders);
+        let result = broadcaster.broadcast(&IpcMessage::Shutdown);
+        let error = match result {
+            Ok(()) => panic!("broadcast to a closed endpoint should fail"),
+            Err(error) => error,
+        };
+        assert_eq!(error.code, "ipc.broadcast_failed");
+        assert!(error.message.contains("network"));
+        assert!(!error.message.contains("renderer"));
+
+        for receiver in receivers {
+            let frame = receiver
+                .recv_timeout(Duration::from_secs(1))
+                .unwrap_or_else(|_| unreachable!());
+            assert_eq!(frame, vec![IPC_PROTOCOL_VERSION, MESSAGE_TAG_SHUTDOWN]);
+        }
+    }
+
+    #[test]
+    fn framed_stream_roundtrips_messages_with_partial_reads() {
+        let config =
+            ChannelConfig::hardened(ProcessRole::Renderer).unwrap_or_else(|_| unreachable!());
+        let messages = vec![
+            IpcMessage::Ping { request_id: 3 },
+            IpcMessage::HealthReport {
+                request_id: 4,
+                role: ProcessRole::Renderer,
+                healthy: true,
+                detail: "painting".to_owned(),
+            },
+            IpcMessage::Shutdown,
+        ];
+
+        let mut writer = FramedStream::new(std::io::empty(), Vec::new(), &config)
+            .unwrap_or_else(|_| unreachable!());
+        for message in &messages {
+            assert!(writer.send(message).is_ok());
+        }
+        let (_, bytes) = writer.into_parts();
+
+        let mut reader =
+            FramedStream::new(TrickleReader(Cursor::new(bytes)), std::io::sink(), &config)
+                .unwrap_or_else(|_| unreachable!());
+        for message in messages {
+            assert_eq!(reader.recv(), Ok(message));
+        }
+        assert_eq!(
+            reader.recv().err().map(|error| error.code),
+            Some("ipc.stream_closed")
+        );
+    }
+
+    #[test]
+    fn framed_stream_detects_truncated_frames() {
+        let config =
+            ChannelConfig::hardened(ProcessRole::Network).unwrap_or_else(|_| unreachable!());
+        let frame = encode_message(&IpcMessage::Pong { request_id: 11 }, 64)
+            .unwrap_or_else(|_| unreachable!());
+
+        let truncated_payload = frame[..frame.len() - 2].to_vec();
+        let mut stream =
+            FramedStream::new(Cursor::new(truncated_payload), std::io::sink(), &config)
+                .unwrap_or_else(|_| unreachable!());
+        assert_eq!(
+            stream.recv().err().map(|error| error.code),
+            Some("ipc.stream_truncated")
+        );
+
+        let truncated_prefix = frame[..2].to_vec();
+        let mut stream = FramedStream::new(Cursor::new(truncated_prefix), std::io::sink(), &config)
+            .unwrap_or_else(|_| unreachable!());
+        assert_eq!(
+            stream.recv().err().map(|error| error.code),
+            Some("ipc.stream_truncated")
+        );
+    }
+
+    #[test]
+    fn compressed_frame_shrinks_large_payloads_and_roundtrips() {
+        let payload = "<div class=\"row\">PixelDust</div>"
+            .repeat(400)
+            .into_bytes();
+        let frame =
+            encode_compressed_frame(&payload, 64 * 1024, 1024).unwrap_or_else(|_| unreachable!());
+        assert_eq!(frame[4], COMPRESSION_FLAG_GZIP);
+        assert!(frame.len() < payload.len());
+        assert_eq!(
+            decode_compressed_frame(&frame, 64 * 1024),
+            Ok(payload.clone())
+        );
+
+        assert_eq!(
+            decode_compressed_frame(&frame, payload.len() - 1)
+                .err()
+                .map(|error| error.code),
+            Some("ipc.message_too_large")
+        );
+    }
+
+    #[test]
+    fn compressed_frame_keeps_small_payloads_plain() {
+        let frame = encode_compressed_frame(b"ping", 64, 1024).unwrap_or_else(|_| unreachable!());
+        assert_eq!(frame[4], COMPRESSION_FLAG_NONE);
+        assert_eq!(&frame[5..], b"ping");
+        assert_eq!(decode_compressed_frame(&frame, 64), Ok(b"ping".to_vec()));
+    }
+
+    #[test]
+    fn corrupted_compressed_frame_is_rejected() {
+        let payload = "a".repeat(8 * 1024).into_bytes();
+        let mut frame =
+            encode_compressed_frame(&payload, 64 * 1024, 1024).unwrap_or_else(|_| unreachable!());
+        let last = frame.len() - 1;
+        for byte in &mut frame[12..last] {
+            *byte ^= 0x5a;
+        }
+        assert_eq!(
+            decode_compressed_frame(&frame, 64 * 1024)
+                .err()
+                .map(|error| error.code),
+            Some("ipc.decompression_failed")
+        );
+    }
+
+    #[test]
+    fn typed_messages_carry_protocol_version() {
+        for message in [IpcMessage::Ping { request_id: 5 }, IpcMessage::hello()] {
+            let frame = encode_message(&message, 64).unwrap_or_else(|_| unreachable!());
+            assert_eq!(frame[4], IPC_PROTOCOL_VERSION);
+            assert_eq!(decode_message(&frame, 64), Ok(message));
+        }
+
+        let future =
+            encode_frame(&[IPC_PROTOCOL_VERSION + 1, 5], 64).unwrap_or_else(|_| unreachable!());
+        assert_eq!(
+            decode_message(&future, 64).err().map(|error| error.code),
+            Some("ipc.version_unsupported")
+        );
+    }
+
+    #[test]
+    fn handshake_selects_highest_common_version() {
+        assert_eq!(
+            negotiate_protocol_version(1, IPC_PROTOCOL_VERSION + 3),
+            Ok(IPC_PROTOCOL_VERSION)
+        );
+        assert_eq!(negotiate_protocol_version(0, 1), Ok(1));
+        assert_eq!(
+            negotiate_protocol_version(IPC_PROTOCOL_VERSION + 1, IPC_PROTOCOL_VERSION + 2)
+                .err()
+                .map(|error| error.code),
+            Some("ipc.version_unsupported")
+        );
+    }
+
+    #[cfg(feature = "serde-codec")]
+    fn sample_messages() -> Vec<IpcMessage> {
+        vec![
+            IpcMessage::Ping { request_id: 1 },
+            IpcMessage::Pong {
+                request_id: u64::MAX,
+            },
+            IpcMessage::HealthCheck { request_id: 9 },
+            IpcMessage::HealthReport {
+                request_id: 42,
+                role: ProcessRole::Storage,
+                healthy: true,
+                detail: "quota ok".to_owned(),
+            },
+            IpcMessage::Shutdown,
+            IpcMessage::hello(),
+        ]
+    }
+
+    #[cfg(feature = "serde-codec")]
+    #[test]
+    fn serde_codec_roundtrips_every_variant() {
+        use super::decode_message_serde;
+        use super::encode_message_serde;
+
+        for message in sample_messages() {
+            let frame = encode_message_serde(&message, 4096).unwrap_or_else(|_| unreachable!());
+            assert_eq!(decode_message_serde(&frame, 4096), Ok(message));
+        }
+
+        let oversized = encode_message_serde(
+            &IpcMessage::HealthReport {
+                request_id: 1,
+                role: ProcessRole::Renderer,
+                healthy: true,
+                detail: "x".repeat(512),
+            },
+            64,
+        );
+        assert_eq!(
+            oversized.err().map(|error| error.code),
+            Some("ipc.message_too_large")
+        );
+    }
+
+    #[cfg(feature = "serde-codec")]
+    #[test]
+    fn cross_codec_decode_fails_cleanly() {
+        use super::decode_message_serde;
+        use super::encode_message_serde;
+
+        for message in sample_messages() {
+            let manual = encode_message(&message, 4096).unwrap_or_else(|_| unreachable!());
+            assert_eq!(
+                decode_message_serde(&manual, 4096)
+                    .err()
+                    .map(|error| error.code),
+                Some("ipc.serde_codec_mismatch")
+            );
+
+            let serde = encode_message_serde(&message, 4096).unwrap_or_else(|_| unreachable!());
+            assert_eq!(
+                decode_message(&serde, 4096).err().map(|error| error.code),
+                Some("ipc.version_unsupported")
+            );
+        }
+    }
 }
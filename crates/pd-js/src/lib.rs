@@ -1,19 +1,137 @@
 //! JavaScript runtime integration surface.
 
 use boa_engine::Context;
+use boa_engine::JsError;
+use boa_engine::JsObject;
+use boa_engine::JsValue;
+use boa_engine::Script;
 use boa_engine::Source;
+use boa_engine::builtins::promise::{OperationType, PromiseState};
+use boa_engine::context::{ContextBuilder, HostHooks};
+use boa_engine::js_string;
+use boa_engine::object::builtins::JsPromise;
 use pd_dom::Document;
 
 const BOOTSTRAP_ENV: &str = r#"
 globalThis.window = globalThis;
 globalThis.self = globalThis;
 globalThis.global = globalThis;
+globalThis.__pd_beacons = [];
 globalThis.navigator = {
   userAgent: "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/122.0.0.0 Safari/537.36",
   language: "en-US",
   languages: ["en-US", "en"],
   platform: "Win32",
-  sendBeacon: function () { return true; }
+  sendBeacon: function (url, data) {
+    if (globalThis.__pd_beacons.length < 64) {
+      globalThis.__pd_beacons.push({ url: String(url), data: data == null ? "" : String(data) });
+    }
+    return true;
+  }
+};
+globalThis.__pd_permission_requests = [];
+globalThis.__pd_record_permission_request = function (kind) {
+  if (globalThis.__pd_permission_requests.length < 64) {
+    globalThis.__pd_permission_requests.push({ kind: kind });
+  }
+};
+globalThis.__pd_geolocation_denied = function (error) {
+  globalThis.__pd_record_permission_request("geolocation");
+  if (typeof error === "function") {
+    globalThis.setTimeout(function () {
+      error({ code: 1, message: "User denied Geolocation", PERMISSION_DENIED: 1, POSITION_UNAVAILABLE: 2, TIMEOUT: 3 });
+    }, 0);
+  }
+};
+globalThis.navigator.geolocation = {
+  getCurrentPosition: function (_success, error) {
+    globalThis.__pd_geolocation_denied(error);
+  },
+  watchPosition: function (_success, error) {
+    globalThis.__pd_geolocation_denied(error);
+    return 0;
+  },
+  clearWatch: function () {}
+};
+globalThis.Notification = function (title) {
+  this.title = String(title || "");
+  this.onclick = null;
+  this.onclose = null;
+  this.onerror = null;
+  this.onshow = null;
+};
+globalThis.Notification.prototype.close = function () {};
+globalThis.Notification.permission = "default";
+globalThis.Notification.requestPermission = function (callback) {
+  globalThis.__pd_record_permission_request("notifications");
+  globalThis.Notification.permission = "denied";
+  if (typeof callback === "function") {
+    globalThis.setTimeout(function () { callback("denied"); }, 0);
+  }
+  return Promise.resolve("denied");
+};
+globalThis.__pd_event_source_urls = [];
+globalThis.__pd_sse_seed = [];
+globalThis.EventSource = function (url) {
+  var source = this;
+  source.url = String(url || "");
+  source.readyState = 0;
+  source.withCredentials = false;
+  source.onopen = null;
+  source.onmessage = null;
+  source.onerror = null;
+  source.__pd_listeners = Object.create(null);
+  if (globalThis.__pd_event_source_urls.length < 64) {
+    globalThis.__pd_event_source_urls.push(source.url);
+  }
+  globalThis.setTimeout(function () {
+    if (source.readyState === 2) {
+      return;
+    }
+    source.readyState = 1;
+    source.dispatchEvent({ type: "open" });
+    for (var i = 0; i < globalThis.__pd_sse_seed.length; i++) {
+      var seeded = globalThis.__pd_sse_seed[i];
+      if (source.readyState !== 2 && seeded[0] === source.url) {
+        source.dispatchEvent({ type: "message", data: seeded[1], origin: "", lastEventId: "" });
+      }
+    }
+  }, 0);
+};
+globalThis.EventSource.CONNECTING = 0;
+globalThis.EventSource.OPEN = 1;
+globalThis.EventSource.CLOSED = 2;
+globalThis.EventSource.prototype.addEventListener = function (type, handler) {
+  var key = String(type || "");
+  if (!key || typeof handler !== "function") {
+    return;
+  }
+  if (!this.__pd_listeners[key]) {
+    this.__pd_listeners[key] = [];
+  }
+  this.__pd_listeners[key].push(handler);
+};
+globalThis.EventSource.prototype.removeEventListener = function (type, handler) {
+  var handlers = this.__pd_listeners[String(type || "")];
+  var index = handlers ? handlers.indexOf(handler) : -1;
+  if (index >= 0) {
+    handlers.splice(index, 1);
+  }
+};
+globalThis.EventSource.prototype.dispatchEvent = function (event) {
+  event.target = this;
+  var property = this["on" + event.type];
+  if (typeof property === "function") {
+    property.call(this, event);
+  }
+  var handlers = (this.__pd_listeners[event.type] || []).slice();
+  for (var i = 0; i < handlers.length; i++) {
+    handlers[i].call(this, event);
+  }
+  return true;
+};
+globalThis.EventSource.prototype.close = function () {
+  this.readyState = 2;
 };
 globalThis.console = {
   log: function () {},
@@ -27,6 +145,73 @@ globalThis.performance = {
   measure: function () {},
   getEntriesByType: function () { return []; }
 };
+globalThis.__pd_default_prevented = false;
+globalThis.__pd_prevent_default = function (event) {
+  if (event && event.cancelable !== false) {
+    event.defaultPrevented = true;
+    globalThis.__pd_default_prevented = true;
+  }
+};
+globalThis.__pd_mutations = [];
+globalThis.__pd_record_mutation = function (mutation) {
+  if (globalThis.__pd_mutations.length < 1024) {
+    globalThis.__pd_mutations.push(mutation);
+  }
+};
+globalThis.__pd_uncaught_errors = [];
+globalThis.__pd_record_uncaught = function (message) {
+  if (globalThis.__pd_uncaught_errors.length < 64) {
+    globalThis.__pd_uncaught_errors.push(String(message));
+  }
+};
+globalThis.__pd_describe_error = function (error) {
+  try {
+    return String(error);
+  } catch (_) {
+    return "uncaught exception";
+  }
+};
+globalThis.__pd_report_error = function (error) {
+  var message = globalThis.__pd_describe_error(error);
+  if (typeof globalThis.onerror === "function") {
+    try { globalThis.onerror(message, "", 0, 0, error); } catch (_) {}
+  }
+  if (typeof globalThis.dispatchEvent === "function") {
+    try { globalThis.dispatchEvent({ type: "error", message: message, error: error }); } catch (_) {}
+  }
+  globalThis.__pd_record_uncaught(message);
+};
+globalThis.__pd_rejections = [];
+globalThis.__pd_track_rejection = function (promise, reason) {
+  if (globalThis.__pd_rejections.length < 256) {
+    globalThis.__pd_rejections.push({ promise: promise, reason: reason });
+  }
+};
+globalThis.__pd_untrack_rejection = function (promise) {
+  globalThis.__pd_rejections = globalThis.__pd_rejections.filter(function (entry) {
+    return entry.promise !== promise;
+  });
+};
+globalThis.__pd_flush_rejections = function () {
+  var pending = globalThis.__pd_rejections;
+  globalThis.__pd_rejections = [];
+  for (var i = 0; i < pending.length; i++) {
+    var event = {
+      type: "unhandledrejection",
+      promise: pending[i].promise,
+      reason: pending[i].reason,
+      preventDefault: function () {}
+    };
+    if (typeof globalThis.onunhandledrejection === "function") {
+      try { globalThis.onunhandledrejection(event); } catch (_) {}
+    }
+    if (typeof globalThis.dispatchEvent === "function") {
+      try { globalThis.dispatchEvent(event); } catch (_) {}
+    }
+    globalThis.__pd_record_uncaught("Unhandled rejection: " + globalThis.__pd_describe_error(event.reason));
+  }
+  return pending.length;
+};
 globalThis.__pd_timer_queue = [];
 globalThis.__pd_timer_cancelled = {};
 globalThis.__pd_next_timer_id = 1;
@@ -68,13 +253,34 @@ globalThis.matchMedia = function (query) {
   };
 };
 globalThis.queueMicrotask = function (callback) {
-  return globalThis.setTimeout(callback, 0);
+  if (typeof callback !== "function") {
+    throw new TypeError("queueMicrotask requires a function");
+  }
+  Promise.resolve().then(function () {
+    try {
+      callback();
+    } catch (error) {
+      globalThis.__pd_report_error(error);
+    }
+  });
+};
+globalThis.__pd_timers_fired = 0;
+globalThis.__pd_pending_timer_count = function () {
+  var pending = 0;
+  for (var i = 0; i < globalThis.__pd_timer_queue.length; i++) {
+    var task = globalThis.__pd_timer_queue[i];
+    if (task && !globalThis.__pd_timer_cancelled[String(task.id)]) {
+      pending++;
+    }
+  }
+  return pending;
 };
 globalThis.__pd_flush_timers = function (limit) {
   var maxRuns = Number(limit) || 0;
   if (maxRuns < 1) {
     maxRuns = 1;
   }
+  globalThis.__pd_flush_rejections();
   var runs = 0;
   while (globalThis.__pd_timer_queue.length > 0 && runs < maxRuns) {
     var task = globalThis.__pd_timer_queue.shift();
@@ -83,15 +289,36 @@ globalThis.__pd_flush_timers = function (limit) {
     }
     var cancelled = !!globalThis.__pd_timer_cancelled[String(task.id)];
     delete globalThis.__pd_timer_cancelled[String(task.id)];
+    runs++;
     if (!cancelled) {
-      task.cb();
+      globalThis.__pd_timers_fired++;
+      try {
+        task.cb();
+      } catch (error) {
+        globalThis.__pd_report_error(error);
+      }
     }
-    runs++;
   }
   return runs;
 };
 "#;
 
+/// Clears the per-execution outputs `BOOTSTRAP_ENV` sets up, so a warm context reports only
+/// what the current execution did while keeping page globals alive.
+const RESET_EXECUTION_STATE: &str = r#"
+globalThis.__pd_beacons = [];
+globalThis.__pd_permission_requests = [];
+globalThis.__pd_event_source_urls = [];
+globalThis.__pd_sse_seed = [];
+globalThis.__pd_default_prevented = false;
+globalThis.__pd_mutations = [];
+globalThis.__pd_uncaught_errors = [];
+globalThis.__pd_rejections = [];
+globalThis.__pd_timer_queue = [];
+globalThis.__pd_timer_cancelled = {};
+globalThis.__pd_timers_fired = 0;
+"#;
+
 /// Script payload to execute.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ScriptSource {
@@ -106,6 +333,29 @@ pub struct JsHostEnvironment {
     pub document_title: String,
     pub cookie_header: String,
     pub elements_by_id: Vec<JsHostElement>,
+    pub determinism: ScriptingDeterminism,
+    /// Server-sent `(url, data)` messages delivered to `EventSource`s opened on `url`.
+    pub sse_events: Vec<(String, String)>,
+    /// Layout box per element id, returned by `getBoundingClientRect()`.
+    pub element_rects: Vec<(String, JsRect)>,
+}
+
+/// Element border-box geometry in CSS pixels relative to the viewport.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JsRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// Pins script-visible time and randomness so runs are reproducible.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScriptingDeterminism {
+    /// Milliseconds returned by `Date.now()`, `new Date()` and `performance.now()`.
+    pub fixed_now: Option<u64>,
+    /// Seed for the `Math.random` replacement.
+    pub random_seed: Option<u64>,
 }
 
 /// ID-indexed element metadata exposed to JS.
@@ -122,32 +372,50 @@ pub struct JsHostElement {
 pub struct JsRuntimeConfig {
     /// Soft limit: when exceeded, execution continues but a runtime warning is recorded.
     pub max_scripts: usize,
+    /// Hard limit: scripts past this count are skipped rather than run.
+    pub max_scripts_hard: Option<usize>,
     /// Preferred script-size budget in bytes.
     pub max_script_bytes: usize,
     pub max_error_messages: usize,
     pub recursion_limit: usize,
     pub stack_size_limit: usize,
     pub loop_iteration_limit: u64,
+    /// Cap on timer callbacks run by the end-of-execution drain.
+    pub max_timer_runs: usize,
 }
 
 impl Default for JsRuntimeConfig {
     fn default() -> Self {
         Self {
             max_scripts: 128,
+            max_scripts_hard: None,
             max_script_bytes: 2 * 1024 * 1024,
             max_error_messages: 24,
             recursion_limit: 64,
             stack_size_limit: 1024,
             loop_iteration_limit: 100_000,
+            max_timer_runs: 1024,
         }
     }
 }
 
+/// Category of a script failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScriptErrorKind {
+    /// The source failed to parse, so nothing ran.
+    Syntax,
+    /// The script parsed but threw while running.
+    Runtime,
+    /// The engine stopped the script at a loop, recursion or stack limit.
+    ResourceLimit,
+}
+
 /// Per-script execution error.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ScriptError {
     pub origin: String,
     pub message: String,
+    pub kind: ScriptErrorKind,
 }
 
 /// Runtime outcome summary.
@@ -156,10 +424,51 @@ pub struct JsExecutionReport {
     pub scripts_seen: usize,
     pub scripts_executed: usize,
     pub scripts_failed: usize,
+    pub syntax_errors: usize,
+    pub runtime_errors: usize,
+    pub resource_limit_errors: usize,
     pub scripts_skipped: usize,
+    pub timers_fired: usize,
+    pub timers_dropped: usize,
     pub errors: Vec<ScriptError>,
 }
 
+impl JsExecutionReport {
+    fn record_failure(&mut self, kind: ScriptErrorKind) {
+        self.scripts_failed = self.scripts_failed.saturating_add(1);
+        let counter = match kind {
+            ScriptErrorKind::Syntax => &mut self.syntax_errors,
+            ScriptErrorKind::Runtime => &mut self.runtime_errors,
+            ScriptErrorKind::ResourceLimit => &mut self.resource_limit_errors,
+        };
+        *counter = counter.saturating_add(1);
+    }
+}
+
+/// Permission-gated capability requested by page script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PermissionKind {
+    Geolocation,
+    Notifications,
+}
+
+impl PermissionKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Geolocation => "geolocation",
+            Self::Notifications => "notifications",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "geolocation" => Some(Self::Geolocation),
+            "notifications" => Some(Self::Notifications),
+            _ => None,
+        }
+    }
+}
+
 /// Runtime execution output.
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct JsExecutionOutput {
@@ -167,6 +476,45 @@ pub struct JsExecutionOutput {
     pub document_title: Option<String>,
     pub location_href: Option<String>,
     pub document_cookie: Option<String>,
+    /// Permission prompts requested by scripts; the host decides policy and denies by default.
+    pub permission_requests: Vec<PermissionKind>,
+    /// Uncaught errors and unhandled promise rejections, as reported to `window.onerror`.
+    pub uncaught_errors: Vec<String>,
+    /// DOM changes made by scripts, in the order they happened.
+    pub mutations: Vec<DomMutation>,
+    /// Origins of the scripts in which a handler called `event.preventDefault()` on a
+    /// cancelable event, in run order; hosts dispatch each event as its own script.
+    pub default_prevented: Vec<String>,
+    /// URLs passed to `new EventSource(url)`; no connection is made.
+    pub event_source_urls: Vec<String>,
+    /// `navigator.sendBeacon` calls; nothing is sent until the host decides to.
+    pub beacons: Vec<Beacon>,
+}
+
+/// One `navigator.sendBeacon(url, data)` call, with `url` as the script passed it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Beacon {
+    pub url: String,
+    pub data: String,
+}
+
+/// Script-made DOM change for the host to apply before re-rendering.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DomMutation {
+    /// `element.style.<property> = value`; `property` is already kebab-case.
+    InlineStyle {
+        element_id: String,
+        property: String,
+        value: String,
+    },
+    /// `element.setAttribute(name, value)` on an element with an `id`.
+    Attribute {
+        element_id: String,
+        name: String,
+        value: String,
+    },
+    /// `document.head.appendChild(link)` for a script-created `<link>`.
+    HeadLink { rel: String, href: String },
 }
 
 /// Script engine facade.
@@ -191,20 +539,17 @@ impl JsRuntime {
         scripts: &[ScriptSource],
     ) -> JsExecutionOutput {
         if scripts.is_empty() {
-            return JsExecutionOutput {
-                report: JsExecutionReport::default(),
-                document_title: Some(host.document_title.clone()),
-                location_href: Some(host.page_url.clone()),
-                document_cookie: Some(host.cookie_header.clone()),
-            };
+            return empty_execution_output(host);
         }
+        let mut context = self.build_context();
+        self.execute_in_context(context.as_mut(), false, host, scripts)
+    }
 
-        let mut report = JsExecutionReport {
-            scripts_seen: scripts.len(),
-            ..JsExecutionReport::default()
-        };
-
-        let mut context = Context::default();
+    fn build_context(&self) -> Option<Context> {
+        let mut context = ContextBuilder::new()
+            .host_hooks(&REJECTION_TRACKING_HOOKS)
+            .build()
+            .ok()?;
         context
             .runtime_limits_mut()
             .set_recursion_limit(self.config.recursion_limit);
@@ -214,11 +559,41 @@ impl JsRuntime {
         context
             .runtime_limits_mut()
             .set_loop_iteration_limit(self.config.loop_iteration_limit);
-        if let Err(error) = context.eval(Source::from_bytes(BOOTSTRAP_ENV.as_bytes())) {
-            report.scripts_failed = 1;
+        Some(context)
+    }
+
+    /// Runs `scripts` in `context`; a `warm` context already holds the environment shims and
+    /// only has its per-execution state reset.
+    fn execute_in_context(
+        &self,
+        context: Option<&mut Context>,
+        warm: bool,
+        host: &JsHostEnvironment,
+        scripts: &[ScriptSource],
+    ) -> JsExecutionOutput {
+        let mut report = JsExecutionReport {
+            scripts_seen: scripts.len(),
+            ..JsExecutionReport::default()
+        };
+
+        let Some(context) = context else {
+            report.scripts_skipped = scripts.len();
+            return JsExecutionOutput {
+                report,
+                ..JsExecutionOutput::default()
+            };
+        };
+        let environment = if warm {
+            RESET_EXECUTION_STATE
+        } else {
+            BOOTSTRAP_ENV
+        };
+        if let Err((kind, error)) = evaluate_script(context, environment.as_bytes()) {
+            report.record_failure(kind);
             report.errors.push(ScriptError {
                 origin: "bootstrap".to_owned(),
                 message: error.to_string(),
+                kind,
             });
             report.scripts_skipped = scripts.len();
             return JsExecutionOutput {
@@ -226,15 +601,24 @@ impl JsRuntime {
                 document_title: None,
                 location_href: None,
                 document_cookie: None,
+                permission_requests: Vec::new(),
+                uncaught_errors: Vec::new(),
+                mutations: Vec::new(),
+                default_prevented: Vec::new(),
+                event_source_urls: Vec::new(),
+                beacons: Vec::new(),
             };
         }
 
-        let host_bootstrap = build_host_bootstrap(host);
-        if let Err(error) = context.eval(Source::from_bytes(host_bootstrap.as_bytes())) {
-            report.scripts_failed = report.scripts_failed.saturating_add(1);
+        let mut host_bootstrap = build_host_bootstrap(host);
+        host_bootstrap.push_str(&build_determinism_bootstrap(&host.determinism));
+        host_bootstrap.push_str(&build_sse_bootstrap(&host.sse_events));
+        if let Err((kind, error)) = evaluate_script(context, host_bootstrap.as_bytes()) {
+            report.record_failure(kind);
             report.errors.push(ScriptError {
                 origin: "host-bootstrap".to_owned(),
                 message: error.to_string(),
+                kind,
             });
             report.scripts_skipped = scripts.len();
             return JsExecutionOutput {
@@ -242,6 +626,12 @@ impl JsRuntime {
                 document_title: None,
                 location_href: None,
                 document_cookie: None,
+                permission_requests: Vec::new(),
+                uncaught_errors: Vec::new(),
+                mutations: Vec::new(),
+                default_prevented: Vec::new(),
+                event_source_urls: Vec::new(),
+                beacons: Vec::new(),
             };
         }
 
@@ -255,11 +645,18 @@ impl JsRuntime {
                     scripts.len(),
                     self.config.max_scripts
                 ),
+                kind: ScriptErrorKind::ResourceLimit,
             });
         }
 
         let hard_cap = hard_script_byte_cap(self.config.max_script_bytes);
-        for script in scripts {
+        let script_limit = self.config.max_scripts_hard.unwrap_or(usize::MAX);
+        let mut default_prevented = Vec::new();
+        for (index, script) in scripts.iter().enumerate() {
+            if index >= script_limit {
+                report.scripts_skipped = report.scripts_skipped.saturating_add(1);
+                continue;
+            }
             let source_bytes = script.source.as_bytes();
             let source_len = source_bytes.len();
             if source_len > hard_cap {
@@ -267,15 +664,23 @@ impl JsRuntime {
                 continue;
             }
 
-            match context.eval(Source::from_bytes(source_bytes)) {
+            let _ = context.eval(Source::from_bytes(
+                b"globalThis.__pd_default_prevented = false;",
+            ));
+            match evaluate_script(context, source_bytes) {
                 Ok(_) => {
                     report.scripts_executed = report.scripts_executed.saturating_add(1);
+                    context.run_jobs();
                     let _ = context.eval(Source::from_bytes(
                         b"(typeof __pd_flush_timers === 'function') ? __pd_flush_timers(128) : 0;",
                     ));
                 }
-                Err(error) => {
-                    report.scripts_failed = report.scripts_failed.saturating_add(1);
+                Err((kind, error)) => {
+                    report.record_failure(kind);
+                    if kind == ScriptErrorKind::Runtime {
+                        let thrown = error.to_opaque(context);
+                        call_global(context, "__pd_report_error", &[thrown]);
+                    }
                     if report.errors.len() < self.config.max_error_messages {
                         report.errors.push(ScriptError {
                             origin: script.origin.clone(),
@@ -287,17 +692,37 @@ impl JsRuntime {
                             } else {
                                 error.to_string()
                             },
+                            kind,
                         });
                     }
+                    // Reactions queued before the throw still run at the microtask checkpoint.
+                    context.run_jobs();
                 }
             }
+            if read_usize(
+                context,
+                b"globalThis.__pd_default_prevented === true ? 1 : 0",
+            ) > 0
+            {
+                default_prevented.push(script.origin.clone());
+            }
         }
 
+        drain_pending_timers(context, self.config.max_timer_runs);
+        report.timers_fired = read_usize(context, b"globalThis.__pd_timers_fired");
+        report.timers_dropped = read_usize(context, b"globalThis.__pd_pending_timer_count()");
+
         JsExecutionOutput {
             report,
-            document_title: read_document_title(&mut context),
-            location_href: read_location_href(&mut context),
-            document_cookie: read_document_cookie(&mut context),
+            document_title: read_document_title(context),
+            location_href: read_location_href(context),
+            document_cookie: read_document_cookie(context),
+            permission_requests: read_permission_requests(context),
+            uncaught_errors: read_uncaught_errors(context),
+            mutations: read_mutations(context),
+            default_prevented,
+            event_source_urls: read_event_source_urls(context),
+            beacons: read_beacons(context),
         }
     }
 
@@ -306,6 +731,217 @@ impl JsRuntime {
     }
 }
 
+fn empty_execution_output(host: &JsHostEnvironment) -> JsExecutionOutput {
+    JsExecutionOutput {
+        report: JsExecutionReport::default(),
+        document_title: Some(host.document_title.clone()),
+        location_href: Some(host.page_url.clone()),
+        document_cookie: Some(host.cookie_header.clone()),
+        permission_requests: Vec::new(),
+        uncaught_errors: Vec::new(),
+        mutations: Vec::new(),
+        default_prevented: Vec::new(),
+        event_source_urls: Vec::new(),
+        beacons: Vec::new(),
+    }
+}
+
+/// Thresholds after which a reused context is torn down and recreated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextRecyclePolicy {
+    /// Recycle once the context has served this many executions.
+    pub max_executions: Option<usize>,
+    /// Recycle once the context has executed this many script bytes.
+    pub max_executed_bytes: Option<usize>,
+}
+
+impl Default for ContextRecyclePolicy {
+    fn default() -> Self {
+        Self {
+            max_executions: Some(64),
+            max_executed_bytes: Some(32 * 1024 * 1024),
+        }
+    }
+}
+
+/// Lifetime counters for a [`RecyclingJsRuntime`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JsContextStats {
+    /// Executions served by the current context.
+    pub executions: usize,
+    /// Script bytes executed by the current context.
+    pub executed_bytes: usize,
+    /// Times a context was torn down and recreated.
+    pub recycles: usize,
+}
+
+/// Runtime that keeps one context alive across executions, so page globals persist
+/// until the [`ContextRecyclePolicy`] forces a fresh context or the page origin changes.
+#[derive(Debug)]
+pub struct RecyclingJsRuntime {
+    runtime: JsRuntime,
+    policy: ContextRecyclePolicy,
+    context: Option<Context>,
+    /// Origin of the page the live context last ran scripts for.
+    context_origin: Option<String>,
+    stats: JsContextStats,
+}
+
+impl RecyclingJsRuntime {
+    pub fn new(config: JsRuntimeConfig, policy: ContextRecyclePolicy) -> Self {
+        Self {
+            runtime: JsRuntime::new(config),
+            policy,
+            context: None,
+            context_origin: None,
+            stats: JsContextStats::default(),
+        }
+    }
+
+    pub fn stats(&self) -> JsContextStats {
+        self.stats
+    }
+
+    pub fn execute_scripts_with_host(
+        &mut self,
+        host: &JsHostEnvironment,
+        scripts: &[ScriptSource],
+    ) -> JsExecutionOutput {
+        if scripts.is_empty() {
+            return empty_execution_output(host);
+        }
+        let origin = page_origin(&host.page_url);
+        let origin_changed = self.context_origin.as_deref() != Some(origin);
+        if self.context.is_some() && (origin_changed || self.recycle_due()) {
+            self.context = None;
+            self.stats = JsContextStats {
+                recycles: self.stats.recycles.saturating_add(1),
+                ..JsContextStats::default()
+            };
+        }
+        let warm = self.context.is_some();
+        if !warm {
+            self.context = self.runtime.build_context();
+            self.context_origin = Some(origin.to_owned());
+        }
+
+        let output = self
+            .runtime
+            .execute_in_context(self.context.as_mut(), warm, host, scripts);
+        let executed_bytes = scripts.iter().map(|script| script.source.len()).sum();
+        self.stats.executions = self.stats.executions.saturating_add(1);
+        self.stats.executed_bytes = self.stats.executed_bytes.saturating_add(executed_bytes);
+        output
+    }
+
+    fn recycle_due(&self) -> bool {
+        self.policy
+            .max_executions
+            .is_some_and(|limit| self.stats.executions >= limit)
+            || self
+                .policy
+                .max_executed_bytes
+                .is_some_and(|limit| self.stats.executed_bytes >= limit)
+    }
+}
+
+/// `scheme://authority` of `url`; the whole string when it has no authority.
+fn page_origin(url: &str) -> &str {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url;
+    };
+    let authority_len = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    &url[..scheme.len() + 3 + authority_len]
+}
+
+/// Forwards promise rejection tracking to the bootstrap's unhandledrejection queue.
+struct RejectionTrackingHooks;
+
+static REJECTION_TRACKING_HOOKS: RejectionTrackingHooks = RejectionTrackingHooks;
+
+impl HostHooks for RejectionTrackingHooks {
+    fn promise_rejection_tracker(
+        &self,
+        promise: &JsObject,
+        operation: OperationType,
+        context: &mut Context,
+    ) {
+        let promise_value = JsValue::from(promise.clone());
+        match operation {
+            OperationType::Reject => {
+                let reason = match JsPromise::from_object(promise.clone()).map(|p| p.state()) {
+                    Ok(PromiseState::Rejected(reason)) => reason,
+                    _ => JsValue::undefined(),
+                };
+                call_global(context, "__pd_track_rejection", &[promise_value, reason]);
+            }
+            OperationType::Handle => {
+                call_global(context, "__pd_untrack_rejection", &[promise_value]);
+            }
+        }
+    }
+}
+
+/// Calls a bootstrap helper on the global object, ignoring missing helpers and throws.
+fn call_global(context: &mut Context, name: &str, args: &[JsValue]) {
+    let global = context.global_object();
+    let Ok(function) = global.get(js_string!(name), context) else {
+        return;
+    };
+    if let Some(function) = function.as_callable() {
+        let _ = function.call(&JsValue::undefined(), args, context);
+    }
+}
+
+/// Parses then runs `source`, keeping parse failures apart from runtime throws.
+fn evaluate_script(context: &mut Context, source: &[u8]) -> Result<(), (ScriptErrorKind, JsError)> {
+    let script = Script::parse(Source::from_bytes(source), None, context)
+        .map_err(|error| (ScriptErrorKind::Syntax, error))?;
+    script.evaluate(context).map(|_| ()).map_err(|error| {
+        let kind = if error
+            .as_native()
+            .is_some_and(|native| native.is_runtime_limit())
+        {
+            ScriptErrorKind::ResourceLimit
+        } else {
+            ScriptErrorKind::Runtime
+        };
+        (kind, error)
+    })
+}
+
+/// Runs queued timers (including ones scheduled by other timers) until idle or `max_runs` is hit.
+fn drain_pending_timers(context: &mut Context, max_runs: usize) {
+    const TIMER_FLUSH_BATCH: usize = 128;
+
+    let mut runs = 0_usize;
+    while runs < max_runs {
+        context.run_jobs();
+        let batch = (max_runs - runs).min(TIMER_FLUSH_BATCH);
+        let source = format!("__pd_flush_timers({batch})");
+        let ran = match context.eval(Source::from_bytes(source.as_bytes())) {
+            Ok(value) => value.to_number(context).map_or(0, |ran| ran as usize),
+            // A throwing callback is consumed before it runs, so the queue still advances.
+            Err(_) => 1,
+        };
+        if ran == 0 {
+            break;
+        }
+        runs = runs.saturating_add(ran);
+    }
+    context.run_jobs();
+    call_global(context, "__pd_flush_rejections", &[]);
+}
+
+fn read_usize(context: &mut Context, expression: &[u8]) -> usize {
+    context
+        .eval(Source::from_bytes(expression))
+        .ok()
+        .and_then(|value| value.to_number(context).ok())
+        .filter(|value| value.is_finite() && *value >= 0.0)
+        .map_or(0, |value| value as usize)
+}
+
 fn read_document_title(context: &mut Context) -> Option<String> {
     let value = context
         .eval(Source::from_bytes(
@@ -336,11 +972,114 @@ fn read_document_cookie(context: &mut Context) -> Option<String> {
     Some(js_string.to_std_string_escaped())
 }
 
+fn read_permission_requests(context: &mut Context) -> Vec<PermissionKind> {
+    let Ok(value) = context.eval(Source::from_bytes(
+        b"Array.isArray(globalThis.__pd_permission_requests) ? globalThis.__pd_permission_requests.map(function (r) { return String(r && r.kind); }).join(',') : ''",
+    )) else {
+        return Vec::new();
+    };
+    let Ok(js_string) = value.to_string(context) else {
+        return Vec::new();
+    };
+
+    js_string
+        .to_std_string_escaped()
+        .split(',')
+        .filter_map(PermissionKind::from_name)
+        .collect()
+}
+
+fn read_uncaught_errors(context: &mut Context) -> Vec<String> {
+    let count = read_usize(
+        context,
+        b"Array.isArray(globalThis.__pd_uncaught_errors) ? globalThis.__pd_uncaught_errors.length : 0",
+    );
+    (0..count)
+        .filter_map(|index| {
+            let source = format!("String(globalThis.__pd_uncaught_errors[{index}])");
+            let value = context.eval(Source::from_bytes(source.as_bytes())).ok()?;
+            let js_string = value.to_string(context).ok()?;
+            Some(js_string.to_std_string_escaped())
+        })
+        .collect()
+}
+
+fn read_event_source_urls(context: &mut Context) -> Vec<String> {
+    let count = read_usize(
+        context,
+        b"Array.isArray(globalThis.__pd_event_source_urls) ? globalThis.__pd_event_source_urls.length : 0",
+    );
+    (0..count)
+        .filter_map(|index| {
+            let source = format!("String(globalThis.__pd_event_source_urls[{index}])");
+            let value = context.eval(Source::from_bytes(source.as_bytes())).ok()?;
+            let js_string = value.to_string(context).ok()?;
+            Some(js_string.to_std_string_escaped())
+        })
+        .collect()
+}
+
+fn read_beacons(context: &mut Context) -> Vec<Beacon> {
+    let count = read_usize(
+        context,
+        b"Array.isArray(globalThis.__pd_beacons) ? globalThis.__pd_beacons.length : 0",
+    );
+    (0..count)
+        .filter_map(|index| {
+            let mut field = |name: &str| {
+                let source = format!("String(globalThis.__pd_beacons[{index}].{name})");
+                let value = context.eval(Source::from_bytes(source.as_bytes())).ok()?;
+                let js_string = value.to_string(context).ok()?;
+                Some(js_string.to_std_string_escaped())
+            };
+            Some(Beacon {
+                url: field("url")?,
+                data: field("data")?,
+            })
+        })
+        .collect()
+}
+
+fn read_mutations(context: &mut Context) -> Vec<DomMutation> {
+    let count = read_usize(
+        context,
+        b"Array.isArray(globalThis.__pd_mutations) ? globalThis.__pd_mutations.length : 0",
+    );
+    (0..count)
+        .filter_map(|index| {
+            let mut field = |name: &str| {
+                let source = format!("String(globalThis.__pd_mutations[{index}].{name})");
+                let value = context.eval(Source::from_bytes(source.as_bytes())).ok()?;
+                let js_string = value.to_string(context).ok()?;
+                Some(js_string.to_std_string_escaped())
+            };
+            match field("kind")?.as_str() {
+                "inline-style" => Some(DomMutation::InlineStyle {
+                    element_id: field("id")?,
+                    property: field("property")?,
+                    value: field("value")?,
+                }),
+                "attribute" => Some(DomMutation::Attribute {
+                    element_id: field("id")?,
+                    name: field("name")?,
+                    value: field("value")?,
+                }),
+                "head-link" => Some(DomMutation::HeadLink {
+                    rel: field("rel")?,
+                    href: field("href")?,
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
 fn build_host_bootstrap(host: &JsHostEnvironment) -> String {
     let location = js_string_literal(&host.page_url);
     let title = js_string_literal(&host.document_title);
     let cookie_header = js_string_literal(&host.cookie_header);
     let elements = build_elements_by_id_object(&host.elements_by_id);
+    let rects = build_element_rects_object(&host.element_rects);
 
     format!(
         r##"
@@ -439,6 +1178,50 @@ fn build_host_bootstrap(host: &JsHostEnvironment) -> String {
   }}
 
   const __pd_elements = {elements};
+  const __pd_rects = {rects};
+  function __pd_bounding_rect(id) {{
+    const rect = id != null && Object.prototype.hasOwnProperty.call(__pd_rects, id)
+      ? __pd_rects[id]
+      : [0, 0, 0, 0];
+    return {{
+      x: rect[0],
+      y: rect[1],
+      width: rect[2],
+      height: rect[3],
+      top: rect[1],
+      left: rect[0],
+      right: rect[0] + rect[2],
+      bottom: rect[1] + rect[3]
+    }};
+  }}
+  function __pd_css_property(name) {{
+    return String(name).replace(/[A-Z]/g, function(letter) {{
+      return "-" + letter.toLowerCase();
+    }});
+  }}
+  function __pd_style_for(node) {{
+    if (!node.style) {{
+      node.style = {{}};
+    }}
+    if (typeof Proxy !== "function") {{
+      return node.style;
+    }}
+    return new Proxy(node.style, {{
+      set: function(target, name, value) {{
+        const text = value == null ? "" : String(value);
+        target[name] = text;
+        if (typeof name === "string") {{
+          globalThis.__pd_record_mutation({{
+            kind: "inline-style",
+            id: node.id,
+            property: __pd_css_property(name),
+            value: text
+          }});
+        }}
+        return true;
+      }}
+    }});
+  }}
   function __pd_clone(node) {{
     if (!node) {{
       return null;
@@ -448,7 +1231,7 @@ fn build_host_bootstrap(host: &JsHostEnvironment) -> String {
       tagName: node.tagName,
       textContent: node.textContent,
       innerText: node.textContent,
-      style: {{}},
+      style: __pd_style_for(node),
       getAttribute: function(name) {{
         const key = String(name);
         return Object.prototype.hasOwnProperty.call(node.attributes, key)
@@ -456,10 +1239,23 @@ fn build_host_bootstrap(host: &JsHostEnvironment) -> String {
           : null;
       }},
       setAttribute: function(name, value) {{
-        node.attributes[String(name)] = String(value);
+        const key = String(name).toLowerCase();
+        const text = String(value);
+        node.attributes[key] = text;
+        if (node.id) {{
+          globalThis.__pd_record_mutation({{
+            kind: "attribute",
+            id: node.id,
+            name: key,
+            value: text
+          }});
+        }}
       }},
       appendChild: function() {{}},
-      removeChild: function() {{}}
+      removeChild: function() {{}},
+      getBoundingClientRect: function() {{
+        return __pd_bounding_rect(node.id);
+      }}
     }});
     return el;
   }}
@@ -482,6 +1278,18 @@ fn build_host_bootstrap(host: &JsHostEnvironment) -> String {
     documentURI: {location},
     readyState: "complete",
     body: __pd_makeEventTarget({{}}),
+    head: __pd_makeEventTarget({{
+      appendChild: function(child) {{
+        if (child && String(child.tagName).toUpperCase() === "LINK") {{
+          globalThis.__pd_record_mutation({{
+            kind: "head-link",
+            rel: child.rel == null ? "" : String(child.rel),
+            href: child.href == null ? "" : String(child.href)
+          }});
+        }}
+        return child;
+      }}
+    }}),
     documentElement: __pd_makeEventTarget({{}}),
     location: globalThis.location,
     getElementById: function(id) {{
@@ -518,6 +1326,9 @@ fn build_host_bootstrap(host: &JsHostEnvironment) -> String {
         getAttribute: function(name) {{
           const key = String(name);
           return Object.prototype.hasOwnProperty.call(this, key) ? this[key] : null;
+        }},
+        getBoundingClientRect: function() {{
+          return __pd_bounding_rect(null);
         }}
       }});
     }}
@@ -545,6 +1356,63 @@ fn build_host_bootstrap(host: &JsHostEnvironment) -> String {
     )
 }
 
+fn build_sse_bootstrap(events: &[(String, String)]) -> String {
+    let entries = events
+        .iter()
+        .map(|(url, data)| format!("[{},{}]", js_string_literal(url), js_string_literal(data)))
+        .collect::<Vec<_>>();
+    format!("\nglobalThis.__pd_sse_seed = [{}];\n", entries.join(","))
+}
+
+fn build_determinism_bootstrap(determinism: &ScriptingDeterminism) -> String {
+    let mut out = String::new();
+    if let Some(fixed_now) = determinism.fixed_now {
+        out.push_str(&format!(
+            r#"
+(function() {{
+  const __pd_fixed_now = {fixed_now};
+  const __pd_RealDate = globalThis.Date;
+  function __pd_FixedDate() {{
+    if (!(this instanceof __pd_FixedDate)) {{
+      return new __pd_RealDate(__pd_fixed_now).toString();
+    }}
+    if (arguments.length === 0) {{
+      return new __pd_RealDate(__pd_fixed_now);
+    }}
+    return new __pd_RealDate(...arguments);
+  }}
+  __pd_FixedDate.prototype = __pd_RealDate.prototype;
+  __pd_FixedDate.now = function() {{ return __pd_fixed_now; }};
+  __pd_FixedDate.parse = __pd_RealDate.parse;
+  __pd_FixedDate.UTC = __pd_RealDate.UTC;
+  globalThis.Date = __pd_FixedDate;
+  globalThis.performance.now = function() {{ return __pd_fixed_now; }};
+}})();
+"#
+        ));
+    }
+    if let Some(seed) = determinism.random_seed {
+        // mulberry32 keyed by both halves of the seed.
+        let high = seed >> 32;
+        let low = seed & 0xFFFF_FFFF;
+        out.push_str(&format!(
+            r#"
+(function() {{
+  let __pd_state = ({low} ^ Math.imul({high}, 0x9E3779B9)) >>> 0;
+  Math.random = function() {{
+    __pd_state = (__pd_state + 0x6D2B79F5) >>> 0;
+    let t = __pd_state;
+    t = Math.imul(t ^ (t >>> 15), t | 1);
+    t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+    return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+  }};
+}})();
+"#
+        ));
+    }
+    out
+}
+
 fn build_elements_by_id_object(elements: &[JsHostElement]) -> String {
     let mut out = String::from("{");
     for (index, element) in elements.iter().enumerate() {
@@ -563,6 +1431,23 @@ fn build_elements_by_id_object(elements: &[JsHostElement]) -> String {
     out
 }
 
+fn build_element_rects_object(rects: &[(String, JsRect)]) -> String {
+    let entries = rects
+        .iter()
+        .map(|(id, rect)| {
+            format!(
+                "{}:[{},{},{},{}]",
+                js_string_literal(id),
+                rect.x,
+                rect.y,
+                rect.width,
+                rect.height
+            )
+        })
+        .collect::<Vec<_>>();
+    format!("{{{}}}", entries.join(","))
+}
+
 fn build_attributes_object(attributes: &[(String, String)]) -> String {
     let mut out = String::from("{");
     for (index, (name, value)) in attributes.iter().enumerate() {
@@ -591,7 +1476,11 @@ fn hard_script_byte_cap(preferred: usize) -> usize {
 
 #[cfg(test)]
 mod tests {
-    use super::{JsHostElement, JsHostEnvironment, JsRuntime, JsRuntimeConfig, ScriptSource};
+    use super::{
+        Beacon, ContextRecyclePolicy, DomMutation, JsHostElement, JsHostEnvironment, JsRect,
+        JsRuntime, JsRuntimeConfig, PermissionKind, RecyclingJsRuntime, ScriptErrorKind,
+        ScriptSource, ScriptingDeterminism,
+    };
 
     #[test]
     fn executes_scripts_against_host_document() {
@@ -606,6 +1495,9 @@ mod tests {
                 text_content: "hello".to_owned(),
                 attributes: vec![("class".to_owned(), "banner".to_owned())],
             }],
+            determinism: ScriptingDeterminism::default(),
+            sse_events: Vec::new(),
+            element_rects: Vec::new(),
         };
         let scripts = vec![ScriptSource {
             origin: "inline:1".to_owned(),
@@ -641,6 +1533,27 @@ mod tests {
         assert_eq!(output.report.scripts_skipped, 0);
     }
 
+    #[test]
+    fn hard_script_limit_skips_scripts_past_the_cap() {
+        let runtime = JsRuntime::new(JsRuntimeConfig {
+            max_scripts: 1,
+            max_scripts_hard: Some(2),
+            ..JsRuntimeConfig::default()
+        });
+        let scripts = (1..=5)
+            .map(|index| ScriptSource {
+                origin: format!("inline:{index}"),
+                source: "document.title = String(Number(document.title || 0) + 1);".to_owned(),
+            })
+            .collect::<Vec<_>>();
+
+        let output = runtime.execute_scripts_with_host(&JsHostEnvironment::default(), &scripts);
+        assert_eq!(output.report.scripts_seen, 5);
+        assert_eq!(output.report.scripts_executed, 2);
+        assert_eq!(output.report.scripts_skipped, 3);
+        assert_eq!(output.document_title.as_deref(), Some("2"));
+    }
+
     #[test]
     fn attempts_moderately_oversized_script() {
         let runtime = JsRuntime::new(JsRuntimeConfig {
@@ -682,6 +1595,9 @@ mod tests {
             document_title: "Before".to_owned(),
             cookie_header: "sid=abc".to_owned(),
             elements_by_id: Vec::new(),
+            determinism: ScriptingDeterminism::default(),
+            sse_events: Vec::new(),
+            element_rects: Vec::new(),
         };
         let scripts = vec![ScriptSource {
             origin: "inline:cookie".to_owned(),
@@ -711,4 +1627,520 @@ mod tests {
         assert_eq!(output.report.scripts_failed, 0);
         assert_eq!(output.document_title.as_deref(), Some("raf-ok"));
     }
+
+    #[test]
+    fn notification_permission_request_is_recorded_and_denied() {
+        let runtime = JsRuntime::new(JsRuntimeConfig::default());
+        let scripts = vec![ScriptSource {
+            origin: "inline:notify".to_owned(),
+            source: "Notification.requestPermission().then(function (result) { document.title = result + ':' + Notification.permission; });"
+                .to_owned(),
+        }];
+
+        let output = runtime.execute_scripts_with_host(&JsHostEnvironment::default(), &scripts);
+        assert_eq!(output.report.scripts_executed, 1);
+        assert_eq!(
+            output.permission_requests,
+            vec![PermissionKind::Notifications]
+        );
+        assert_eq!(output.document_title.as_deref(), Some("denied:denied"));
+    }
+
+    #[test]
+    fn drains_chained_timers_before_returning() {
+        let runtime = JsRuntime::new(JsRuntimeConfig::default());
+        let scripts = vec![ScriptSource {
+            origin: "inline:chain".to_owned(),
+            source: "setTimeout(function () {
+                document.title = 'first';
+                setTimeout(function () {
+                    setTimeout(function () { document.title = 'third'; }, 0);
+                }, 0);
+            }, 0);"
+                .to_owned(),
+        }];
+
+        let output = runtime.execute_scripts_with_host(&JsHostEnvironment::default(), &scripts);
+        assert_eq!(output.document_title.as_deref(), Some("third"));
+        assert!(output.report.timers_fired >= 2);
+        assert_eq!(output.report.timers_dropped, 0);
+    }
+
+    #[test]
+    fn timer_drain_reports_dropped_timers_past_cap() {
+        let runtime = JsRuntime::new(JsRuntimeConfig {
+            max_timer_runs: 4,
+            ..JsRuntimeConfig::default()
+        });
+        let scripts = vec![ScriptSource {
+            origin: "inline:loop".to_owned(),
+            source: "function tick() { setTimeout(tick, 0); } setTimeout(tick, 0);".to_owned(),
+        }];
+
+        let output = runtime.execute_scripts_with_host(&JsHostEnvironment::default(), &scripts);
+        assert_eq!(output.report.scripts_executed, 1);
+        assert_eq!(output.report.timers_dropped, 1);
+    }
+
+    #[test]
+    fn classifies_syntax_runtime_and_resource_limit_errors() {
+        let runtime = JsRuntime::new(JsRuntimeConfig::default());
+        let scripts = vec![
+            ScriptSource {
+                origin: "inline:syntax".to_owned(),
+                source: "function broken( {".to_owned(),
+            },
+            ScriptSource {
+                origin: "inline:throw".to_owned(),
+                source: "throw new Error('page threw');".to_owned(),
+            },
+            ScriptSource {
+                origin: "inline:spin".to_owned(),
+                source: "while (true) {}".to_owned(),
+            },
+        ];
+
+        let report = runtime.execute_scripts(&scripts);
+        assert_eq!(report.scripts_failed, 3);
+        assert_eq!(report.syntax_errors, 1);
+        assert_eq!(report.runtime_errors, 1);
+        assert_eq!(report.resource_limit_errors, 1);
+        let kinds = report
+            .errors
+            .iter()
+            .map(|error| (error.origin.as_str(), error.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                ("inline:syntax", ScriptErrorKind::Syntax),
+                ("inline:throw", ScriptErrorKind::Runtime),
+                ("inline:spin", ScriptErrorKind::ResourceLimit),
+            ]
+        );
+    }
+
+    #[test]
+    fn fixed_now_pins_date_and_performance_clocks() {
+        let runtime = JsRuntime::new(JsRuntimeConfig::default());
+        let host = JsHostEnvironment {
+            determinism: ScriptingDeterminism {
+                fixed_now: Some(1_700_000_000_000),
+                random_seed: None,
+            },
+            ..JsHostEnvironment::default()
+        };
+        let scripts = vec![ScriptSource {
+            origin: "inline:clock".to_owned(),
+            source: "document.title = [Date.now(), new Date().getTime(), performance.now(), \
+                     new Date(0).getTime()].join(',');"
+                .to_owned(),
+        }];
+
+        let output = runtime.execute_scripts_with_host(&host, &scripts);
+        assert_eq!(output.report.scripts_failed, 0);
+        assert_eq!(
+            output.document_title.as_deref(),
+            Some("1700000000000,1700000000000,1700000000000,0")
+        );
+    }
+
+    #[test]
+    fn random_seed_makes_math_random_repeatable() {
+        let runtime = JsRuntime::new(JsRuntimeConfig::default());
+        let run = |seed: u64| {
+            let host = JsHostEnvironment {
+                determinism: ScriptingDeterminism {
+                    fixed_now: None,
+                    random_seed: Some(seed),
+                },
+                ..JsHostEnvironment::default()
+            };
+            let scripts = vec![ScriptSource {
+                origin: "inline:random".to_owned(),
+                source: "document.title = [Math.random(), Math.random(), Math.random()].join(',');"
+                    .to_owned(),
+            }];
+            runtime
+                .execute_scripts_with_host(&host, &scripts)
+                .document_title
+                .unwrap_or_default()
+        };
+
+        let first = run(42);
+        assert!(!first.is_empty());
+        assert_eq!(first, run(42));
+        assert_ne!(first, run(43));
+    }
+
+    #[test]
+    fn window_onerror_captures_errors_thrown_in_timers() {
+        let runtime = JsRuntime::new(JsRuntimeConfig::default());
+        let scripts = vec![ScriptSource {
+            origin: "inline:onerror".to_owned(),
+            source: "window.onerror = function (message) { document.title = 'caught:' + message; };
+            setTimeout(function () { throw new Error('late failure'); }, 0);
+            setTimeout(function () { document.cookie = 'after=1'; }, 0);"
+                .to_owned(),
+        }];
+
+        let output = runtime.execute_scripts_with_host(&JsHostEnvironment::default(), &scripts);
+        assert_eq!(output.report.scripts_failed, 0);
+        assert_eq!(
+            output.document_title.as_deref(),
+            Some("caught:Error: late failure")
+        );
+        assert_eq!(output.document_cookie.as_deref(), Some("after=1"));
+        assert_eq!(
+            output.uncaught_errors,
+            vec!["Error: late failure".to_owned()]
+        );
+    }
+
+    #[test]
+    fn reports_unhandled_rejections_to_listeners() {
+        let runtime = JsRuntime::new(JsRuntimeConfig::default());
+        let scripts = vec![ScriptSource {
+            origin: "inline:rejection".to_owned(),
+            source: "window.addEventListener('unhandledrejection', function (event) {
+                document.title = 'rejected:' + event.reason;
+            });
+            Promise.reject('handled').catch(function () {});
+            Promise.reject('dropped');"
+                .to_owned(),
+        }];
+
+        let output = runtime.execute_scripts_with_host(&JsHostEnvironment::default(), &scripts);
+        assert_eq!(output.document_title.as_deref(), Some("rejected:dropped"));
+        assert_eq!(
+            output.uncaught_errors,
+            vec!["Unhandled rejection: dropped".to_owned()]
+        );
+    }
+
+    #[test]
+    fn records_inline_style_writes_as_mutations() {
+        let runtime = JsRuntime::new(JsRuntimeConfig::default());
+        let host = JsHostEnvironment {
+            elements_by_id: vec![JsHostElement {
+                id: "hero".to_owned(),
+                tag_name: "DIV".to_owned(),
+                text_content: String::new(),
+                attributes: Vec::new(),
+            }],
+            ..JsHostEnvironment::default()
+        };
+        let scripts = vec![ScriptSource {
+            origin: "inline:style".to_owned(),
+            source: "var hero = document.getElementById('hero');
+            hero.style.display = 'none';
+            hero.style.backgroundColor = 'red';
+            document.title = document.getElementById('hero').style.display;"
+                .to_owned(),
+        }];
+
+        let output = runtime.execute_scripts_with_host(&host, &scripts);
+        assert_eq!(output.report.scripts_failed, 0);
+        assert_eq!(output.document_title.as_deref(), Some("none"));
+        assert_eq!(
+            output.mutations,
+            vec![
+                DomMutation::InlineStyle {
+                    element_id: "hero".to_owned(),
+                    property: "display".to_owned(),
+                    value: "none".to_owned(),
+                },
+                DomMutation::InlineStyle {
+                    element_id: "hero".to_owned(),
+                    property: "background-color".to_owned(),
+                    value: "red".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn records_attribute_writes_and_head_links() {
+        let runtime = JsRuntime::new(JsRuntimeConfig::default());
+        let host = JsHostEnvironment {
+            elements_by_id: vec![JsHostElement {
+                id: "icon".to_owned(),
+                tag_name: "LINK".to_owned(),
+                text_content: String::new(),
+                attributes: vec![("rel".to_owned(), "icon".to_owned())],
+            }],
+            ..JsHostEnvironment::default()
+        };
+        let scripts = vec![ScriptSource {
+            origin: "inline:favicon".to_owned(),
+            source: "document.getElementById('icon').setAttribute('HREF', '/unread.png');
+            var link = document.createElement('link');
+            link.rel = 'icon';
+            link.href = '/badge.ico';
+            document.head.appendChild(link);"
+                .to_owned(),
+        }];
+
+        let output = runtime.execute_scripts_with_host(&host, &scripts);
+        assert_eq!(output.report.scripts_failed, 0);
+        assert_eq!(
+            output.mutations,
+            vec![
+                DomMutation::Attribute {
+                    element_id: "icon".to_owned(),
+                    name: "href".to_owned(),
+                    value: "/unread.png".to_owned(),
+                },
+                DomMutation::HeadLink {
+                    rel: "icon".to_owned(),
+                    href: "/badge.ico".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn send_beacon_records_target_and_payload() {
+        let runtime = JsRuntime::new(JsRuntimeConfig::default());
+        let scripts = vec![ScriptSource {
+            origin: "inline:beacon".to_owned(),
+            source: "document.title = String(navigator.sendBeacon('/collect', 'x'));".to_owned(),
+        }];
+
+        let output = runtime.execute_scripts_with_host(&JsHostEnvironment::default(), &scripts);
+        assert_eq!(output.document_title.as_deref(), Some("true"));
+        assert_eq!(
+            output.beacons,
+            vec![Beacon {
+                url: "/collect".to_owned(),
+                data: "x".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn bounding_client_rect_reads_seeded_layout_boxes() {
+        let runtime = JsRuntime::new(JsRuntimeConfig::default());
+        let element = |id: &str| JsHostElement {
+            id: id.to_owned(),
+            tag_name: "DIV".to_owned(),
+            text_content: String::new(),
+            attributes: Vec::new(),
+        };
+        let host = JsHostEnvironment {
+            elements_by_id: vec![element("hero"), element("footer")],
+            element_rects: vec![(
+                "hero".to_owned(),
+                JsRect {
+                    x: 10,
+                    y: 20,
+                    width: 300,
+                    height: 40,
+                },
+            )],
+            ..JsHostEnvironment::default()
+        };
+        let scripts = vec![ScriptSource {
+            origin: "inline:rect".to_owned(),
+            source: "function describe(id) { \
+                       var r = document.getElementById(id).getBoundingClientRect(); \
+                       return [r.x, r.y, r.width, r.height, r.top, r.left, r.right, r.bottom].join(','); \
+                     } \
+                     document.title = describe('hero') + '|' + describe('footer');"
+                .to_owned(),
+        }];
+
+        let output = runtime.execute_scripts_with_host(&host, &scripts);
+        assert_eq!(output.report.scripts_failed, 0);
+        assert_eq!(
+            output.document_title.as_deref(),
+            Some("10,20,300,40,20,10,310,60|0,0,0,0,0,0,0,0")
+        );
+    }
+
+    #[test]
+    fn prevent_default_is_tracked_per_dispatched_event() {
+        let runtime = JsRuntime::new(JsRuntimeConfig::default());
+        let event_script = |origin: &str, handler: &str| ScriptSource {
+            origin: origin.to_owned(),
+            source: format!(
+                "var event = {{ type: 'submit', cancelable: true, defaultPrevented: false,
+                  preventDefault: function () {{ __pd_prevent_default(this); }} }};
+                (function (event) {{ {handler} }})(event);
+                document.title += String(event.defaultPrevented);"
+            ),
+        };
+        let scripts = vec![
+            event_script("dom-event:keydown:1", "event.preventDefault();"),
+            event_script("dom-event:submit:2", "document.cookie = 'seen=1';"),
+            event_script("dom-event:click:3", "event.preventDefault();"),
+        ];
+        let output = runtime.execute_scripts_with_host(&JsHostEnvironment::default(), &scripts);
+
+        assert_eq!(
+            output.default_prevented,
+            vec![
+                "dom-event:keydown:1".to_owned(),
+                "dom-event:click:3".to_owned()
+            ]
+        );
+        assert_eq!(output.document_title.as_deref(), Some("truefalsetrue"));
+    }
+
+    #[test]
+    fn event_source_records_url_and_delivers_seeded_messages() {
+        let runtime = JsRuntime::new(JsRuntimeConfig::default());
+        let host = JsHostEnvironment {
+            sse_events: vec![
+                ("/stream".to_owned(), "hello".to_owned()),
+                ("/other".to_owned(), "ignored".to_owned()),
+            ],
+            ..JsHostEnvironment::default()
+        };
+        let scripts = vec![ScriptSource {
+            origin: "inline:sse".to_owned(),
+            source: "var source = new EventSource('/stream'); \
+                     source.onmessage = function (event) { document.title = 'got ' + event.data; };"
+                .to_owned(),
+        }];
+
+        let output = runtime.execute_scripts_with_host(&host, &scripts);
+        assert_eq!(output.report.scripts_failed, 0);
+        assert_eq!(output.event_source_urls, vec!["/stream".to_owned()]);
+        assert_eq!(output.document_title.as_deref(), Some("got hello"));
+    }
+
+    #[test]
+    fn recycling_runtime_resets_globals_after_execution_threshold() {
+        let mut runtime = RecyclingJsRuntime::new(
+            JsRuntimeConfig::default(),
+            ContextRecyclePolicy {
+                max_executions: Some(2),
+                max_executed_bytes: None,
+            },
+        );
+        let scripts = vec![ScriptSource {
+            origin: "inline:counter".to_owned(),
+            source: "globalThis.visits = (globalThis.visits || 0) + 1; \
+                     document.title = String(globalThis.visits);"
+                .to_owned(),
+        }];
+        let host = JsHostEnvironment::default();
+
+        let titles = (0..3)
+            .map(|_| {
+                runtime
+                    .execute_scripts_with_host(&host, &scripts)
+                    .document_title
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            titles,
+            vec![
+                Some("1".to_owned()),
+                Some("2".to_owned()),
+                Some("1".to_owned())
+            ]
+        );
+        let stats = runtime.stats();
+        assert_eq!(stats.recycles, 1);
+        assert_eq!(stats.executions, 1);
+        assert_eq!(stats.executed_bytes, scripts[0].source.len());
+    }
+
+    #[test]
+    fn recycling_runtime_recycles_when_the_page_origin_changes() {
+        let mut runtime =
+            RecyclingJsRuntime::new(JsRuntimeConfig::default(), ContextRecyclePolicy::default());
+        let scripts = vec![ScriptSource {
+            origin: "inline:counter".to_owned(),
+            source: "globalThis.visits = (globalThis.visits || 0) + 1; \
+                     document.title = String(globalThis.visits);"
+                .to_owned(),
+        }];
+        let host_at = |page_url: &str| JsHostEnvironment {
+            page_url: page_url.to_owned(),
+            ..JsHostEnvironment::default()
+        };
+
+        let titles = [
+            "https://a.example/one",
+            "https://a.example/two?q=1",
+            "https://b.example/one",
+        ]
+        .into_iter()
+        .map(|url| {
+            runtime
+                .execute_scripts_with_host(&host_at(url), &scripts)
+                .document_title
+        })
+        .collect::<Vec<_>>();
+        assert_eq!(
+            titles,
+            vec![
+                Some("1".to_owned()),
+                Some("2".to_owned()),
+                Some("1".to_owned())
+            ]
+        );
+        assert_eq!(runtime.stats().recycles, 1);
+    }
+
+    #[test]
+    fn warm_context_keeps_shims_but_reports_only_the_current_execution() {
+        let mut runtime =
+            RecyclingJsRuntime::new(JsRuntimeConfig::default(), ContextRecyclePolicy::default());
+        let host = JsHostEnvironment::default();
+        let first = runtime.execute_scripts_with_host(
+            &host,
+            &[ScriptSource {
+                origin: "inline:patch".to_owned(),
+                source: "navigator.patched = 'yes'; navigator.sendBeacon('/first', 'x');"
+                    .to_owned(),
+            }],
+        );
+        assert_eq!(first.beacons.len(), 1);
+
+        let second = runtime.execute_scripts_with_host(
+            &host,
+            &[ScriptSource {
+                origin: "inline:read".to_owned(),
+                source: "document.title = String(navigator.patched);".to_owned(),
+            }],
+        );
+        assert_eq!(second.document_title.as_deref(), Some("yes"));
+        assert!(second.beacons.is_empty());
+    }
+
+    #[test]
+    fn promise_reactions_and_microtasks_run_before_timers_and_final_read() {
+        let runtime = JsRuntime::new(JsRuntimeConfig::default());
+        let scripts = vec![
+            ScriptSource {
+                origin: "inline:promise".to_owned(),
+                source: "var order = []; \
+                         setTimeout(function () { order.push('timer'); document.title = order.join(','); }, 0); \
+                         queueMicrotask(function () { order.push('microtask'); }); \
+                         Promise.resolve('then').then(function (value) { order.push(value); });"
+                    .to_owned(),
+            },
+            ScriptSource {
+                origin: "inline:throws".to_owned(),
+                source: "Promise.resolve().then(function () { order.push('after-throw'); }); \
+                         throw new Error('boom');"
+                    .to_owned(),
+            },
+            ScriptSource {
+                origin: "inline:reads".to_owned(),
+                source: "document.title = order.join(',');".to_owned(),
+            },
+        ];
+
+        let output = runtime.execute_scripts_with_host(&JsHostEnvironment::default(), &scripts);
+        assert_eq!(output.report.runtime_errors, 1);
+        assert_eq!(
+            output.document_title.as_deref(),
+            Some("microtask,then,timer,after-throw")
+        );
+    }
 }
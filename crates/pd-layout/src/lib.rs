@@ -65,6 +65,7 @@ mod tests {
             root: 1,
             node_count: 12,
             text_bytes: 320,
+            truncated: false,
         };
         let css = CssParser.parse("body{color:red} .card{padding:8px}");
         let tree = engine.compute(&doc, &css);
@@ -0,0 +1,228 @@
+//! Response body text decoding: byte-order marks, `<meta>` charsets and `Content-Type` labels.
+
+use encoding_rs::Encoding;
+
+/// Decodes a response body as text: a byte-order mark wins, then an HTML `<meta>` charset,
+/// then the `Content-Type` charset; anything else is read as (lossy) UTF-8.
+pub fn decode_text(body: &[u8], content_type: &str) -> String {
+    // A byte-order mark outranks both the header and any meta charset.
+    if let Some((bom, bom_len)) = sniff_byte_order_mark(body) {
+        return decode_with_byte_order_mark(bom, &body[bom_len..]);
+    }
+
+    let charset = detect_response_charset(body, content_type);
+    if let Some(label) = charset {
+        if let Some(encoding) = Encoding::for_label(label.as_bytes()) {
+            let (decoded, _, _) = encoding.decode(body);
+            return decoded.into_owned();
+        }
+    }
+
+    String::from_utf8_lossy(body).into_owned()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ByteOrderMark {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Utf32Le,
+    Utf32Be,
+}
+
+fn sniff_byte_order_mark(body: &[u8]) -> Option<(ByteOrderMark, usize)> {
+    // UTF-32LE must be checked before UTF-16LE since it shares the `FF FE` prefix.
+    if body.starts_with(&[0xFF, 0xFE, 0x00, 0x00]) {
+        Some((ByteOrderMark::Utf32Le, 4))
+    } else if body.starts_with(&[0x00, 0x00, 0xFE, 0xFF]) {
+        Some((ByteOrderMark::Utf32Be, 4))
+    } else if body.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        Some((ByteOrderMark::Utf8, 3))
+    } else if body.starts_with(&[0xFF, 0xFE]) {
+        Some((ByteOrderMark::Utf16Le, 2))
+    } else if body.starts_with(&[0xFE, 0xFF]) {
+        Some((ByteOrderMark::Utf16Be, 2))
+    } else {
+        None
+    }
+}
+
+fn decode_with_byte_order_mark(bom: ByteOrderMark, payload: &[u8]) -> String {
+    let encoding = match bom {
+        ByteOrderMark::Utf8 => encoding_rs::UTF_8,
+        ByteOrderMark::Utf16Le => encoding_rs::UTF_16LE,
+        ByteOrderMark::Utf16Be => encoding_rs::UTF_16BE,
+        ByteOrderMark::Utf32Le | ByteOrderMark::Utf32Be => {
+            return payload
+                .chunks(4)
+                .map(|unit| {
+                    let Ok(bytes) = <[u8; 4]>::try_from(unit) else {
+                        return char::REPLACEMENT_CHARACTER;
+                    };
+                    let scalar = if bom == ByteOrderMark::Utf32Le {
+                        u32::from_le_bytes(bytes)
+                    } else {
+                        u32::from_be_bytes(bytes)
+                    };
+                    char::from_u32(scalar).unwrap_or(char::REPLACEMENT_CHARACTER)
+                })
+                .collect();
+        }
+    };
+    let (decoded, _) = encoding.decode_without_bom_handling(payload);
+    decoded.into_owned()
+}
+
+fn detect_response_charset(body: &[u8], content_type: &str) -> Option<String> {
+    let is_html = content_type.to_ascii_lowercase().contains("text/html")
+        || content_type
+            .to_ascii_lowercase()
+            .contains("application/xhtml+xml");
+
+    if is_html {
+        if let Some(meta_charset) = parse_charset_from_html_prefix(body) {
+            return Some(meta_charset);
+        }
+    }
+
+    parse_charset_from_content_type(content_type)
+}
+
+pub fn parse_charset_from_content_type(content_type: &str) -> Option<String> {
+    for part in content_type.split(';').skip(1) {
+        let Some((name, value)) = part.split_once('=') else {
+            continue;
+        };
+        if !name.trim().eq_ignore_ascii_case("charset") {
+            continue;
+        }
+
+        let label = value.trim().trim_matches('"').trim_matches('\'');
+        if !label.is_empty() {
+            return Some(label.to_owned());
+        }
+    }
+
+    None
+}
+
+/// Finds a `charset=` label in the first 8 KiB of an HTML document.
+pub fn parse_charset_from_html_prefix(body: &[u8]) -> Option<String> {
+    let prefix_len = body.len().min(8192);
+    let prefix = String::from_utf8_lossy(&body[..prefix_len]);
+    let lower = prefix.to_ascii_lowercase();
+    let mut search_start = 0_usize;
+
+    while let Some(relative) = lower[search_start..].find("charset=") {
+        let charset_start = search_start + relative + "charset=".len();
+        let remainder = &prefix[charset_start..];
+        if let Some(label) = parse_charset_label(remainder) {
+            return Some(label);
+        }
+        search_start = charset_start;
+    }
+
+    None
+}
+
+fn parse_charset_label(input: &str) -> Option<String> {
+    let trimmed = input.trim_start();
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    let mut chars = trimmed.chars();
+    let first = chars.next()?;
+
+    if first == '"' || first == '\'' {
+        let rest = &trimmed[first.len_utf8()..];
+        let end = rest.find(first)?;
+        let label = rest[..end].trim();
+        return if label.is_empty() {
+            None
+        } else {
+            Some(label.to_owned())
+        };
+    }
+
+    let end = trimmed
+        .find(|ch: char| ch.is_whitespace() || matches!(ch, '"' | '\'' | ';' | '>' | '/'))
+        .unwrap_or(trimmed.len());
+    let label = trimmed[..end].trim();
+    if label.is_empty() {
+        None
+    } else {
+        Some(label.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode_text;
+    use super::parse_charset_from_content_type;
+    use super::parse_charset_from_html_prefix;
+
+    #[test]
+    fn parses_charset_from_content_type_header() {
+        let content_type = "text/html; charset=ISO-8859-1";
+        let parsed = parse_charset_from_content_type(content_type);
+        assert_eq!(parsed.as_deref(), Some("ISO-8859-1"));
+    }
+
+    #[test]
+    fn prefers_meta_charset_for_html() {
+        let html = "<html><head><meta charset=\"UTF-8\"></head><body>hello</body></html>";
+        let parsed = parse_charset_from_html_prefix(html.as_bytes());
+        assert_eq!(parsed.as_deref(), Some("UTF-8"));
+    }
+
+    #[test]
+    fn decodes_html_using_meta_charset_before_header_charset() {
+        let html = b"<html><head><meta charset=\"UTF-8\"></head><body>\xE2\x82\xAC</body></html>";
+        let decoded = decode_text(html, "text/html; charset=ISO-8859-1");
+        assert!(decoded.contains("\u{20AC}"));
+    }
+
+    #[test]
+    fn byte_order_mark_overrides_declared_charset() {
+        let mut utf16 = vec![0xFF, 0xFE];
+        for unit in "h\u{e9}llo \u{20AC}".encode_utf16() {
+            utf16.extend_from_slice(&unit.to_le_bytes());
+        }
+        assert_eq!(
+            decode_text(&utf16, "text/html; charset=ISO-8859-1"),
+            "h\u{e9}llo \u{20AC}"
+        );
+
+        let utf8 = b"\xEF\xBB\xBF<p>caf\xC3\xA9</p>";
+        assert_eq!(
+            decode_text(utf8, "text/html; charset=windows-1252"),
+            "<p>caf\u{e9}</p>"
+        );
+
+        let utf32 = [
+            0x00, 0x00, 0xFE, 0xFF, 0x00, 0x00, 0x00, 0x41, 0x00, 0x01, 0xF6, 0x00,
+        ];
+        assert_eq!(decode_text(&utf32, "text/plain"), "A\u{1F600}");
+    }
+
+    #[test]
+    fn decodes_without_byte_order_mark_using_declared_charset() {
+        let latin1 = b"caf\xE9";
+        assert_eq!(
+            decode_text(latin1, "text/plain; charset=ISO-8859-1"),
+            "caf\u{e9}"
+        );
+    }
+
+    #[test]
+    fn decodes_windows_1252_only_characters() {
+        assert_eq!(
+            decode_text(
+                b"\x80 and \x93quoted\x94",
+                "text/plain; charset=windows-1252"
+            ),
+            "\u{20AC} and \u{201C}quoted\u{201D}"
+        );
+    }
+}
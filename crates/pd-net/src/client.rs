@@ -3,7 +3,9 @@
 use crate::PreparedRequest;
 use crate::dns::DnsResolver;
 use crate::dns::SystemDnsResolver;
+use crate::http::ContentRange;
 use crate::http::Header;
+use crate::http::HttpMethod;
 use crate::http::HttpRequest;
 use crate::http::HttpResponse;
 use crate::http::HttpStatusCode;
@@ -11,19 +13,24 @@ use crate::http::HttpVersion;
 use crate::pool::ConnectionKey;
 use crate::pool::ConnectionPool;
 use crate::pool::InMemoryConnectionPool;
+use crate::pool::KeepAliveHint;
 use crate::pool::PoolStats;
+use crate::pool::PooledConnection;
+use crate::pool::SharedConnectionPool;
 use crate::tls::StrictTlsPolicy;
 use crate::tls_backend::RustlsTlsAdapter;
 use crate::tls_backend::TlsBackendAdapter;
-use crate::transport::BoxedIoStream;
+use crate::transport::Proxy;
 use crate::transport::TcpTransport;
 use crate::transport::Transport;
+use crate::transport::establish_proxy_tunnel;
 use brotli::Decompressor;
 use flate2::read::DeflateDecoder;
 use flate2::read::GzDecoder;
 use flate2::read::ZlibDecoder;
 use pd_core::BrowserError;
 use pd_core::BrowserResult;
+use pd_core::ResultExt;
 use std::io::Cursor;
 use std::io::Read;
 use std::io::Write;
@@ -33,8 +40,16 @@ use std::time::Duration;
 
 const MAX_RESPONSE_HEAD_BYTES: usize = 128 * 1024;
 const MAX_CHUNK_LINE_BYTES: usize = 8 * 1024;
+const DEFAULT_MAX_RETRIES: u32 = 2;
+const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_millis(100);
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(2);
+/// How long to wait for `100 Continue` before uploading the body anyway.
+const EXPECT_CONTINUE_TIMEOUT: Duration = Duration::from_secs(1);
+/// Upper bound on a content-decoded body, regardless of how small the compressed body was.
+pub const DEFAULT_MAX_DECOMPRESSED_BYTES: usize = 256 * 1024 * 1024;
 
 /// HTTP/1.1 client with pluggable resolver/transport/pool/tls backend.
+#[derive(Clone)]
 pub struct Http11Client<
     R = SystemDnsResolver,
     T = TcpTransport,
@@ -52,6 +67,12 @@ pub struct Http11Client<
     tls_adapter: A,
     tls_policy: StrictTlsPolicy,
     connect_timeout: Duration,
+    proxy: Option<Proxy>,
+    max_retries: u32,
+    retry_backoff: Duration,
+    retry_non_idempotent: bool,
+    expect_continue_threshold: Option<usize>,
+    max_decompressed_bytes: usize,
 }
 
 impl Http11Client {
@@ -66,6 +87,22 @@ impl Http11Client {
     }
 }
 
+/// Client whose clones share one connection pool, so parallel fetches reuse connections.
+pub type SharedHttp11Client =
+    Http11Client<SystemDnsResolver, TcpTransport, SharedConnectionPool, RustlsTlsAdapter>;
+
+impl SharedHttp11Client {
+    pub fn shared(tls_policy: StrictTlsPolicy) -> BrowserResult<Self> {
+        Self::with_parts(
+            SystemDnsResolver,
+            TcpTransport,
+            SharedConnectionPool::default(),
+            RustlsTlsAdapter,
+            tls_policy,
+        )
+    }
+}
+
 impl<R, T, P, A> Http11Client<R, T, P, A>
 where
     R: DnsResolver,
@@ -88,6 +125,12 @@ where
             tls_adapter,
             tls_policy,
             connect_timeout: Duration::from_secs(10),
+            proxy: None,
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_backoff: DEFAULT_RETRY_BACKOFF,
+            retry_non_idempotent: false,
+            expect_continue_threshold: None,
+            max_decompressed_bytes: DEFAULT_MAX_DECOMPRESSED_BYTES,
         })
     }
 
@@ -95,6 +138,37 @@ where
         self.connect_timeout = timeout;
     }
 
+    /// Routes new connections through `proxy`; pooled connections opened earlier are kept.
+    pub fn set_proxy(&mut self, proxy: Option<Proxy>) {
+        self.proxy = proxy;
+    }
+
+    /// Retries connection-level failures of GET/HEAD up to `max_retries` extra attempts.
+    pub fn set_max_retries(&mut self, max_retries: u32) {
+        self.max_retries = max_retries;
+    }
+
+    /// Base delay before the first retry; doubles per attempt up to a fixed cap.
+    pub fn set_retry_backoff(&mut self, backoff: Duration) {
+        self.retry_backoff = backoff;
+    }
+
+    /// Opts methods other than GET/HEAD into retries, e.g. for replay-safe POST endpoints.
+    pub fn set_retry_non_idempotent(&mut self, allow: bool) {
+        self.retry_non_idempotent = allow;
+    }
+
+    /// Sends `Expect: 100-continue` for bodies of at least `threshold` bytes and holds the
+    /// body until the server agrees; `None` always sends the body with the headers.
+    pub fn set_expect_continue_threshold(&mut self, threshold: Option<usize>) {
+        self.expect_continue_threshold = threshold;
+    }
+
+    /// Aborts content decoding with `net.decompress_bomb` once a body inflates past `max_bytes`.
+    pub fn set_max_decompressed_bytes(&mut self, max_bytes: usize) {
+        self.max_decompressed_bytes = max_bytes;
+    }
+
     pub fn pool_stats(&self) -> PoolStats {
         self.pool.stats()
     }
@@ -103,39 +177,211 @@ where
         self.tls_policy.validate()?;
         validate_prepared_request(&prepared)?;
 
+        let retries = if self.retry_non_idempotent
+            || matches!(prepared.request.method, HttpMethod::Get | HttpMethod::Head)
+        {
+            self.max_retries
+        } else {
+            0
+        };
+        let mut attempt = 0;
+        loop {
+            match self.execute_once(&prepared) {
+                Err(error) if attempt < retries && is_retryable_error(&error) => {
+                    std::thread::sleep(retry_delay(self.retry_backoff, attempt));
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn execute_once(&mut self, prepared: &PreparedRequest) -> BrowserResult<HttpResponse> {
+        let body_len = prepared.request.body.len();
+        if body_len > 0
+            && self
+                .expect_continue_threshold
+                .is_some_and(|threshold| body_len >= threshold)
+        {
+            return self.execute_expect_continue(prepared);
+        }
+
         let key = ConnectionKey::from_url(&prepared.request.url);
-        let mut stream = match self.pool.checkout(&key) {
+        let mut connection = match self.pool.checkout(&key) {
             Some(existing) => existing,
-            None => self.open_stream(&prepared)?,
+            None => self.open_stream(prepared)?,
         };
 
-        write_request(&mut *stream, &prepared.request)?;
-        let outcome = read_response(&mut *stream, &prepared.request)?;
+        let forward_proxy = self
+            .proxy
+            .as_ref()
+            .filter(|proxy| !proxy.tunnels(prepared.request.url.is_secure()));
+        write_request(&mut *connection.stream, &prepared.request, forward_proxy)?;
+        let outcome = read_response(
+            &mut *connection.stream,
+            &prepared.request,
+            self.max_decompressed_bytes,
+        )?;
+        let mut response = outcome.response;
+        response.tls = connection.tls.clone();
 
         if outcome.reusable {
-            self.pool.checkin(key, stream);
+            connection.requests_served += 1;
+            connection.keep_alive = KeepAliveHint::from_headers(&response.headers);
+            self.pool.checkin(key, connection);
         }
 
-        Ok(outcome.response)
+        Ok(response)
     }
 
-    fn open_stream(&self, prepared: &PreparedRequest) -> BrowserResult<BoxedIoStream> {
+    /// Sends the head, waits briefly for `100 Continue`, and only then uploads the body.
+    ///
+    /// Uses a fresh connection so the socket read timeout can be shortened while waiting.
+    fn execute_expect_continue(
+        &mut self,
+        prepared: &PreparedRequest,
+    ) -> BrowserResult<HttpResponse> {
+        let key = ConnectionKey::from_url(&prepared.request.url);
+        let socket = self.connect(prepared)?;
+        let control = socket.try_clone().context(
+            "net.http.socket_clone_failed",
+            "failed to clone socket handle for 100-continue",
+        )?;
+        let mut connection = self.wrap_tls(socket, prepared)?;
+        let stream = &mut connection.stream;
+
+        let forward_proxy = self
+            .proxy
+            .as_ref()
+            .filter(|proxy| !proxy.tunnels(prepared.request.url.is_secure()));
+        let head = encode_request_head(&prepared.request, forward_proxy, true);
+        write_bytes(&mut *stream, &head)?;
+
+        control
+            .set_read_timeout(Some(EXPECT_CONTINUE_TIMEOUT))
+            .context(
+                "net.http.read_timeout_failed",
+                "failed to shorten read timeout for 100-continue",
+            )?;
+        let interim = await_continue(&mut *stream);
+        control
+            .set_read_timeout(Some(self.connect_timeout))
+            .context(
+                "net.http.read_timeout_failed",
+                "failed to restore read timeout after 100-continue",
+            )?;
+
+        let prefetched = match interim? {
+            ContinueOutcome::Proceed(prefetched) => {
+                write_bytes(&mut *stream, &prepared.request.body)?;
+                prefetched
+            }
+            ContinueOutcome::FinalResponse(prefetched) => {
+                // The server answered without the body; the connection is dropped, not reused.
+                let mut reader = Cursor::new(prefetched).chain(&mut **stream);
+                let mut response =
+                    read_response(&mut reader, &prepared.request, self.max_decompressed_bytes)?
+                        .response;
+                response.tls = connection.tls;
+                return Ok(response);
+            }
+        };
+
+        let outcome = {
+            let mut reader = Cursor::new(prefetched).chain(&mut **stream);
+            read_response(&mut reader, &prepared.request, self.max_decompressed_bytes)?
+        };
+        let mut response = outcome.response;
+        response.tls = connection.tls.clone();
+        if outcome.reusable {
+            connection.requests_served = 1;
+            connection.keep_alive = KeepAliveHint::from_headers(&response.headers);
+            self.pool.checkin(key, connection);
+        }
+        Ok(response)
+    }
+
+    fn open_stream(&self, prepared: &PreparedRequest) -> BrowserResult<PooledConnection> {
+        let stream = self.connect(prepared)?;
+        self.wrap_tls(stream, prepared)
+    }
+
+    fn connect(&self, prepared: &PreparedRequest) -> BrowserResult<TcpStream> {
         let host = prepared.request.url.host();
         let port = prepared.request.url.port();
-        let addresses = self.dns.resolve(host, port)?;
+        match &self.proxy {
+            Some(proxy) => self.open_proxied_stream(proxy, prepared),
+            None => {
+                let addresses = self.dns.resolve(host, port)?;
+                connect_first_available(&self.transport, &addresses, self.connect_timeout)
+            }
+        }
+    }
 
-        connect_first_available(&self.transport, &addresses, self.connect_timeout).and_then(
-            |stream| match &prepared.tls {
-                Some(handshake) => {
-                    self.tls_adapter
-                        .connect_tls(stream, handshake, &self.tls_policy)
-                }
-                None => Ok(Box::new(stream)),
-            },
-        )
+    fn wrap_tls(
+        &self,
+        stream: TcpStream,
+        prepared: &PreparedRequest,
+    ) -> BrowserResult<PooledConnection> {
+        match &prepared.tls {
+            Some(handshake) => {
+                let tls = self
+                    .tls_adapter
+                    .connect_tls(stream, handshake, &self.tls_policy)?;
+                let mut connection = PooledConnection::new(tls.stream);
+                connection.tls = Some(tls.session);
+                Ok(connection)
+            }
+            None => Ok(PooledConnection::new(Box::new(stream))),
+        }
+    }
+
+    fn open_proxied_stream(
+        &self,
+        proxy: &Proxy,
+        prepared: &PreparedRequest,
+    ) -> BrowserResult<TcpStream> {
+        let url = &prepared.request.url;
+        let mut stream = self
+            .dns
+            .resolve(&proxy.host, proxy.port)
+            .and_then(|addresses| {
+                connect_first_available(&self.transport, &addresses, self.connect_timeout)
+            })
+            .map_err(|error| {
+                BrowserError::new(
+                    "net.proxy_failed",
+                    format!(
+                        "failed to reach proxy `{}:{}`: {}",
+                        proxy.host, proxy.port, error.message
+                    ),
+                )
+            })?;
+
+        if proxy.tunnels(url.is_secure()) {
+            establish_proxy_tunnel(&mut stream, proxy, url.host(), url.port())?;
+        }
+        Ok(stream)
     }
 }
 
+/// Connection-level failures worth another attempt; HTTP error statuses are never retried.
+fn is_retryable_error(error: &BrowserError) -> bool {
+    matches!(
+        error.code,
+        "net.transport.connect_failed"
+            | "net.http.write_failed"
+            | "net.http.flush_failed"
+            | "net.http.read_head_failed"
+            | "net.http.unexpected_eof"
+    )
+}
+
+fn retry_delay(base: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(2_u32.saturating_pow(attempt))
+        .min(MAX_RETRY_BACKOFF)
+}
+
 fn validate_prepared_request(prepared: &PreparedRequest) -> BrowserResult<()> {
     if prepared.request.url.is_secure() && prepared.tls.is_none() {
         return Err(BrowserError::new(
@@ -179,11 +425,30 @@ fn connect_first_available<T: Transport>(
     }
 }
 
-fn write_request(stream: &mut dyn Write, request: &HttpRequest) -> BrowserResult<()> {
+/// Writes the request; a forwarding HTTP proxy gets the absolute-form target and credentials.
+fn write_request(
+    stream: &mut dyn Write,
+    request: &HttpRequest,
+    forward_proxy: Option<&Proxy>,
+) -> BrowserResult<()> {
+    let mut encoded = encode_request_head(request, forward_proxy, false);
+    encoded.extend_from_slice(&request.body);
+    write_bytes(stream, &encoded)
+}
+
+fn encode_request_head(
+    request: &HttpRequest,
+    forward_proxy: Option<&Proxy>,
+    expect_continue: bool,
+) -> Vec<u8> {
+    let target = match forward_proxy {
+        Some(_) => request.url.as_str().to_owned(),
+        None => request.request_target(),
+    };
     let mut encoded = Vec::new();
     encoded.extend_from_slice(request.method.as_str().as_bytes());
     encoded.push(b' ');
-    encoded.extend_from_slice(request.request_target().as_bytes());
+    encoded.extend_from_slice(target.as_bytes());
     encoded.push(b' ');
     encoded.extend_from_slice(request.version.as_str().as_bytes());
     encoded.extend_from_slice(b"\r\n");
@@ -194,25 +459,95 @@ fn write_request(stream: &mut dyn Write, request: &HttpRequest) -> BrowserResult
         encoded.extend_from_slice(header.value.as_bytes());
         encoded.extend_from_slice(b"\r\n");
     }
+    if let Some(credentials) = forward_proxy.and_then(Proxy::basic_authorization) {
+        encoded.extend_from_slice(b"Proxy-Authorization: ");
+        encoded.extend_from_slice(credentials.as_bytes());
+        encoded.extend_from_slice(b"\r\n");
+    }
+    if expect_continue && request.header("expect").is_none() {
+        encoded.extend_from_slice(b"Expect: 100-continue\r\n");
+    }
     encoded.extend_from_slice(b"\r\n");
-    encoded.extend_from_slice(&request.body);
+    encoded
+}
 
-    stream.write_all(&encoded).map_err(|error| {
-        BrowserError::new(
-            "net.http.write_failed",
-            format!("failed to write HTTP request bytes: {error}"),
-        )
-    })?;
-    stream.flush().map_err(|error| {
-        BrowserError::new(
-            "net.http.flush_failed",
-            format!("failed to flush HTTP request bytes: {error}"),
-        )
-    })?;
+fn write_bytes(stream: &mut dyn Write, bytes: &[u8]) -> BrowserResult<()> {
+    stream.write_all(bytes).context(
+        "net.http.write_failed",
+        "failed to write HTTP request bytes",
+    )?;
+    stream.flush().context(
+        "net.http.flush_failed",
+        "failed to flush HTTP request bytes",
+    )?;
 
     Ok(())
 }
 
+/// What the server said before the body of an `Expect: 100-continue` request was sent.
+enum ContinueOutcome {
+    /// `100 Continue` arrived or the wait timed out; holds bytes read past the interim head.
+    Proceed(Vec<u8>),
+    /// A final status arrived first; holds the response bytes read so far.
+    FinalResponse(Vec<u8>),
+}
+
+fn await_continue(stream: &mut dyn Read) -> BrowserResult<ContinueOutcome> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0_u8; 4096];
+    loop {
+        if let Some(head_end) = find_header_end(&buffer) {
+            let head = std::str::from_utf8(&buffer[..head_end]).context(
+                "net.http.head_invalid_utf8",
+                "HTTP response head is not valid UTF-8 text",
+            )?;
+            let status_line = head.split("\r\n").next().unwrap_or_default();
+            let (_, status, _) = parse_status_line(status_line)?;
+            match status.as_u16() {
+                100 => return Ok(ContinueOutcome::Proceed(buffer.split_off(head_end))),
+                // Other interim responses such as `102 Processing` are skipped.
+                101..=199 => {
+                    buffer.drain(..head_end);
+                    continue;
+                }
+                _ => return Ok(ContinueOutcome::FinalResponse(buffer)),
+            }
+        }
+
+        match stream.read(&mut chunk) {
+            Ok(0) => {
+                return Err(BrowserError::new(
+                    "net.http.unexpected_eof",
+                    "unexpected EOF while waiting for 100 Continue",
+                ));
+            }
+            Ok(read) => buffer.extend_from_slice(&chunk[..read]),
+            Err(error)
+                if buffer.is_empty()
+                    && matches!(
+                        error.kind(),
+                        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                    ) =>
+            {
+                return Ok(ContinueOutcome::Proceed(Vec::new()));
+            }
+            Err(error) => {
+                return Err(error).context(
+                    "net.http.read_head_failed",
+                    "failed while waiting for 100 Continue",
+                );
+            }
+        }
+
+        if buffer.len() > MAX_RESPONSE_HEAD_BYTES {
+            return Err(BrowserError::new(
+                "net.http.head_too_large",
+                format!("HTTP response head exceeds {MAX_RESPONSE_HEAD_BYTES} bytes"),
+            ));
+        }
+    }
+}
+
 struct ResponseReadOutcome {
     response: HttpResponse,
     reusable: bool,
@@ -221,18 +556,17 @@ struct ResponseReadOutcome {
 fn read_response(
     stream: &mut dyn Read,
     request: &HttpRequest,
+    max_decompressed_bytes: usize,
 ) -> BrowserResult<ResponseReadOutcome> {
     let mut buffer = Vec::new();
     let mut chunk = [0_u8; 4096];
     let mut header_end: Option<usize> = None;
 
     while header_end.is_none() {
-        let read = stream.read(&mut chunk).map_err(|error| {
-            BrowserError::new(
-                "net.http.read_head_failed",
-                format!("failed while reading HTTP response head: {error}"),
-            )
-        })?;
+        let read = stream.read(&mut chunk).context(
+            "net.http.read_head_failed",
+            "failed while reading HTTP response head",
+        )?;
 
         if read == 0 {
             return Err(BrowserError::new(
@@ -264,18 +598,16 @@ fn read_response(
 
     let head_bytes = &buffer[..header_end];
     let mut body_bytes = buffer[header_end..].to_vec();
-    let head_text = std::str::from_utf8(head_bytes).map_err(|error| {
-        BrowserError::new(
-            "net.http.head_invalid_utf8",
-            format!("HTTP response head is not valid UTF-8 text: {error}"),
-        )
-    })?;
+    let head_text = std::str::from_utf8(head_bytes).context(
+        "net.http.head_invalid_utf8",
+        "HTTP response head is not valid UTF-8 text",
+    )?;
 
     let mut lines = head_text.split("\r\n");
     let status_line = lines.next().ok_or_else(|| {
         BrowserError::new("net.http.status_line_missing", "missing HTTP status line")
     })?;
-    let (version, status) = parse_status_line(status_line)?;
+    let (version, status, status_text) = parse_status_line(status_line)?;
 
     let mut headers = Vec::new();
     for line in lines {
@@ -304,10 +636,35 @@ fn read_response(
         ));
     }
 
+    let mut warnings = Vec::new();
+    let content_range = if status.as_u16() == 206 {
+        let value = headers
+            .iter()
+            .find(|header| header.name.eq_ignore_ascii_case("content-range"))
+            .map(|header| header.value.as_str())
+            .unwrap_or_default();
+        let range = ContentRange::parse(value);
+        if range.is_none() {
+            warnings.push(BrowserError::new(
+                "net.http.content_range_invalid",
+                format!(
+                    "invalid Content-Range `{value}`; reading the 206 body as the full resource"
+                ),
+            ));
+        }
+        range
+    } else {
+        None
+    };
     let content_length = if has_chunked_transfer {
         None
     } else {
-        parse_content_length(&headers)?
+        match parse_content_length(&headers)? {
+            Some(len) => Some(len),
+            None => {
+                content_range.map(|range| usize::try_from(range.byte_count()).unwrap_or(usize::MAX))
+            }
+        }
     };
     let has_no_body = request.method.as_str() == "HEAD" || status_disallows_body(status.as_u16());
 
@@ -317,29 +674,39 @@ fn read_response(
         body_bytes = read_chunked_body(stream, body_bytes)?;
         true
     } else if let Some(len) = content_length {
+        let mut complete = true;
         if body_bytes.len() < len {
             let remaining = len - body_bytes.len();
-            let mut rest = vec![0_u8; remaining];
-            stream.read_exact(&mut rest).map_err(|error| {
-                BrowserError::new(
+            let mut rest = Vec::with_capacity(remaining);
+            stream
+                .take(remaining as u64)
+                .read_to_end(&mut rest)
+                .context(
                     "net.http.read_body_failed",
-                    format!("failed to read HTTP body bytes: {error}"),
-                )
-            })?;
+                    "failed to read HTTP body bytes",
+                )?;
             body_bytes.extend_from_slice(&rest);
+            if body_bytes.len() < len {
+                complete = false;
+                warnings.push(BrowserError::new(
+                    "net.content_length_short",
+                    format!(
+                        "connection closed after {} of {len} declared body bytes",
+                        body_bytes.len()
+                    ),
+                ));
+            }
         } else if body_bytes.len() > len {
             body_bytes.truncate(len);
         }
 
-        true
+        complete
     } else if header_contains(&headers, "connection", "close") {
         let mut tail = Vec::new();
-        stream.read_to_end(&mut tail).map_err(|error| {
-            BrowserError::new(
-                "net.http.read_body_failed",
-                format!("failed while draining connection-close response body: {error}"),
-            )
-        })?;
+        stream.read_to_end(&mut tail).context(
+            "net.http.read_body_failed",
+            "failed while draining connection-close response body",
+        )?;
         body_bytes.extend_from_slice(&tail);
         false
     } else {
@@ -350,14 +717,18 @@ fn read_response(
     };
 
     if !has_no_body {
-        body_bytes = decode_content_encoding(&headers, &body_bytes)?;
+        body_bytes = decode_content_encoding(&headers, &body_bytes, max_decompressed_bytes)?;
     }
 
     let response = HttpResponse {
         version,
         status,
+        status_text,
         headers,
         body: if has_no_body { Vec::new() } else { body_bytes },
+        warnings,
+        content_range,
+        tls: None,
     };
 
     Ok(ResponseReadOutcome {
@@ -399,7 +770,7 @@ impl<'a> PrefixedStreamReader<'a> {
         if prefix_take < out.len() {
             self.stream
                 .read_exact(&mut out[prefix_take..])
-                .map_err(|error| BrowserError::new(code, format!("{detail}: {error}")))?;
+                .context(code, detail)?;
         }
 
         Ok(())
@@ -417,12 +788,10 @@ fn read_chunked_body(stream: &mut dyn Read, prefetched: Vec<u8>) -> BrowserResul
         }
 
         let size_token = size_line.split(';').next().unwrap_or_default().trim();
-        let chunk_size = usize::from_str_radix(size_token, 16).map_err(|error| {
-            BrowserError::new(
-                "net.http.chunk_size_invalid",
-                format!("invalid chunk size `{size_token}`: {error}"),
-            )
-        })?;
+        let chunk_size = usize::from_str_radix(size_token, 16).context(
+            "net.http.chunk_size_invalid",
+            format!("invalid chunk size `{size_token}`"),
+        )?;
 
         if chunk_size == 0 {
             drain_chunk_trailers(&mut reader)?;
@@ -493,12 +862,10 @@ fn read_crlf_line(reader: &mut PrefixedStreamReader<'_>) -> BrowserResult<String
 
         if line.len() >= 2 && line[line.len() - 2..] == *b"\r\n" {
             line.truncate(line.len() - 2);
-            return String::from_utf8(line).map_err(|error| {
-                BrowserError::new(
-                    "net.http.chunk_line_invalid_utf8",
-                    format!("chunk metadata line is not valid UTF-8: {error}"),
-                )
-            });
+            return String::from_utf8(line).context(
+                "net.http.chunk_line_invalid_utf8",
+                "chunk metadata line is not valid UTF-8",
+            );
         }
     }
 }
@@ -510,7 +877,7 @@ fn find_header_end(buffer: &[u8]) -> Option<usize> {
         .map(|idx| idx + 4)
 }
 
-fn parse_status_line(line: &str) -> BrowserResult<(HttpVersion, HttpStatusCode)> {
+fn parse_status_line(line: &str) -> BrowserResult<(HttpVersion, HttpStatusCode, String)> {
     let mut parts = line.splitn(3, ' ');
     let version = parts.next().ok_or_else(|| {
         BrowserError::new(
@@ -538,27 +905,24 @@ fn parse_status_line(line: &str) -> BrowserResult<(HttpVersion, HttpStatusCode)>
         }
     };
 
-    let code_value = code_text.parse::<u16>().map_err(|error| {
-        BrowserError::new(
-            "net.http.status_line_invalid",
-            format!("invalid status code `{code_text}`: {error}"),
-        )
-    })?;
+    let code_value = code_text.parse::<u16>().context(
+        "net.http.status_line_invalid",
+        format!("invalid status code `{code_text}`"),
+    )?;
 
     let code = HttpStatusCode::new(code_value)?;
-    Ok((version, code))
+    let reason = parts.next().unwrap_or_default().to_owned();
+    Ok((version, code, reason))
 }
 
 fn parse_content_length(headers: &[Header]) -> BrowserResult<Option<usize>> {
     let mut value: Option<usize> = None;
     for header in headers {
         if header.name.eq_ignore_ascii_case("content-length") {
-            let parsed = header.value.trim().parse::<usize>().map_err(|error| {
-                BrowserError::new(
-                    "net.http.content_length_invalid",
-                    format!("invalid Content-Length `{}`: {error}", header.value),
-                )
-            })?;
+            let parsed = header.value.trim().parse::<usize>().context(
+                "net.http.content_length_invalid",
+                format!("invalid Content-Length `{}`", header.value),
+            )?;
 
             if let Some(existing) = value {
                 if existing != parsed {
@@ -609,7 +973,11 @@ fn header_contains(headers: &[Header], name: &str, value: &str) -> bool {
     })
 }
 
-fn decode_content_encoding(headers: &[Header], body: &[u8]) -> BrowserResult<Vec<u8>> {
+fn decode_content_encoding(
+    headers: &[Header],
+    body: &[u8],
+    max_decompressed_bytes: usize,
+) -> BrowserResult<Vec<u8>> {
     let encodings = content_encodings(headers);
     if encodings.is_empty() {
         return Ok(body.to_vec());
@@ -619,9 +987,9 @@ fn decode_content_encoding(headers: &[Header], body: &[u8]) -> BrowserResult<Vec
     for encoding in encodings.iter().rev() {
         decoded = match encoding.as_str() {
             "identity" => decoded,
-            "gzip" | "x-gzip" => decode_gzip(&decoded)?,
-            "deflate" => decode_deflate(&decoded)?,
-            "br" => decode_brotli(&decoded)?,
+            "gzip" | "x-gzip" => decode_gzip(&decoded, max_decompressed_bytes)?,
+            "deflate" => decode_deflate(&decoded, max_decompressed_bytes)?,
+            "br" => decode_brotli(&decoded, max_decompressed_bytes)?,
             _ => {
                 return Err(BrowserError::new(
                     "net.http.content_encoding_unsupported",
@@ -652,66 +1020,89 @@ fn content_encodings(headers: &[Header]) -> Vec<String> {
     encodings
 }
 
-fn decode_gzip(body: &[u8]) -> BrowserResult<Vec<u8>> {
-    let mut decoder = GzDecoder::new(Cursor::new(body));
-    let mut decoded = Vec::new();
-    decoder.read_to_end(&mut decoded).map_err(|error| {
-        BrowserError::new(
-            "net.http.decode_failed",
-            format!("gzip decode failed: {error}"),
-        )
-    })?;
-    Ok(decoded)
+fn decode_gzip(body: &[u8], max_bytes: usize) -> BrowserResult<Vec<u8>> {
+    inflate_capped(GzDecoder::new(Cursor::new(body)), max_bytes, "gzip")
 }
 
-fn decode_deflate(body: &[u8]) -> BrowserResult<Vec<u8>> {
-    let mut zlib_decoder = ZlibDecoder::new(Cursor::new(body));
-    let mut zlib_decoded = Vec::new();
-    if zlib_decoder.read_to_end(&mut zlib_decoded).is_ok() {
-        return Ok(zlib_decoded);
+fn decode_deflate(body: &[u8], max_bytes: usize) -> BrowserResult<Vec<u8>> {
+    match inflate_capped(ZlibDecoder::new(Cursor::new(body)), max_bytes, "deflate") {
+        Err(error) if error.code != "net.decompress_bomb" => {
+            inflate_capped(DeflateDecoder::new(Cursor::new(body)), max_bytes, "deflate")
+        }
+        result => result,
     }
+}
 
-    let mut raw_decoder = DeflateDecoder::new(Cursor::new(body));
-    let mut raw_decoded = Vec::new();
-    raw_decoder.read_to_end(&mut raw_decoded).map_err(|error| {
-        BrowserError::new(
-            "net.http.decode_failed",
-            format!("deflate decode failed: {error}"),
-        )
-    })?;
-    Ok(raw_decoded)
+fn decode_brotli(body: &[u8], max_bytes: usize) -> BrowserResult<Vec<u8>> {
+    inflate_capped(
+        Decompressor::new(Cursor::new(body), 4096),
+        max_bytes,
+        "brotli",
+    )
 }
 
-fn decode_brotli(body: &[u8]) -> BrowserResult<Vec<u8>> {
-    let mut decoder = Decompressor::new(Cursor::new(body), 4096);
+/// Reads at most one byte past `max_bytes` from `decoder`, so a bomb never fully inflates.
+fn inflate_capped(decoder: impl Read, max_bytes: usize, encoding: &str) -> BrowserResult<Vec<u8>> {
+    let limit = u64::try_from(max_bytes)
+        .unwrap_or(u64::MAX)
+        .saturating_add(1);
     let mut decoded = Vec::new();
-    decoder.read_to_end(&mut decoded).map_err(|error| {
-        BrowserError::new(
-            "net.http.decode_failed",
-            format!("brotli decode failed: {error}"),
-        )
-    })?;
+    decoder.take(limit).read_to_end(&mut decoded).context(
+        "net.http.decode_failed",
+        format!("{encoding} decode failed"),
+    )?;
+
+    if decoded.len() > max_bytes {
+        return Err(BrowserError::new(
+            "net.decompress_bomb",
+            format!("{encoding} body expands beyond max_decompressed_bytes ({max_bytes})"),
+        ));
+    }
+
     Ok(decoded)
 }
 
 #[cfg(test)]
 mod tests {
+    use super::DEFAULT_MAX_DECOMPRESSED_BYTES;
+    use super::Http11Client;
     use super::decode_content_encoding;
     use super::find_header_end;
+    use super::inflate_capped;
     use super::parse_status_line;
     use super::read_chunked_body;
     use super::read_response;
     use super::status_disallows_body;
+    use super::write_request;
+    use crate::PreparedRequest;
+    use crate::dns::DnsResolver;
+    use crate::http::ContentRange;
     use crate::http::Header;
     use crate::http::HttpMethod;
     use crate::http::HttpRequest;
+    use crate::pool::InMemoryConnectionPool;
+    use crate::tls::StrictTlsPolicy;
+    use crate::tls_backend::RustlsTlsAdapter;
+    use crate::transport::Proxy;
+    use crate::transport::TcpTransport;
+    use crate::transport::Transport;
     use crate::url::BrowserUrl;
     use brotli::CompressorWriter;
     use flate2::Compression;
     use flate2::write::GzEncoder;
     use flate2::write::ZlibEncoder;
+    use pd_core::BrowserError;
+    use pd_core::BrowserResult;
+    use std::cell::Cell;
     use std::io::Cursor;
+    use std::io::Read;
     use std::io::Write;
+    use std::net::SocketAddr;
+    use std::net::TcpListener;
+    use std::net::TcpStream;
+    use std::rc::Rc;
+    use std::thread;
+    use std::time::Duration;
 
     #[test]
     fn header_terminator_is_detected() {
@@ -732,6 +1123,24 @@ mod tests {
         assert!(parsed.is_ok());
     }
 
+    #[test]
+    fn status_line_parser_preserves_reason_phrase() {
+        let parsed = parse_status_line("HTTP/1.1 404 Not Found");
+        assert!(matches!(parsed, Ok((_, _, ref reason)) if reason == "Not Found"));
+    }
+
+    #[test]
+    fn status_line_parser_allows_missing_reason_phrase() {
+        let parsed = parse_status_line("HTTP/1.1 204");
+        assert!(matches!(parsed, Ok((_, _, ref reason)) if reason.is_empty()));
+    }
+
+    #[test]
+    fn status_line_parser_keeps_custom_reason_phrase_verbatim() {
+        let parsed = parse_status_line("HTTP/1.1 418 I'm a  Teapot (really)");
+        assert!(matches!(parsed, Ok((_, _, ref reason)) if reason == "I'm a  Teapot (really)"));
+    }
+
     #[test]
     fn detects_bodyless_status_codes() {
         assert!(status_disallows_body(101));
@@ -778,7 +1187,7 @@ mod tests {
         let raw = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
                     4\r\nWiki\r\n5\r\npedia\r\n0\r\n\r\n";
         let mut stream = Cursor::new(raw.to_vec());
-        let outcome = read_response(&mut stream, &request);
+        let outcome = read_response(&mut stream, &request, DEFAULT_MAX_DECOMPRESSED_BYTES);
         assert!(outcome.is_ok());
         let outcome = match outcome {
             Ok(value) => value,
@@ -807,7 +1216,7 @@ mod tests {
 
         let raw = b"HTTP/1.0 200 OK\r\nContent-Length: 2\r\n\r\nok";
         let mut stream = Cursor::new(raw.to_vec());
-        let outcome = read_response(&mut stream, &request);
+        let outcome = read_response(&mut stream, &request, DEFAULT_MAX_DECOMPRESSED_BYTES);
         assert!(outcome.is_ok());
         let outcome = match outcome {
             Ok(value) => value,
@@ -819,6 +1228,97 @@ mod tests {
         assert!(!outcome.reusable);
     }
 
+    fn get_request(url: &str) -> HttpRequest {
+        let url = match BrowserUrl::parse(url) {
+            Ok(value) => value,
+            Err(error) => panic!("{error}"),
+        };
+        match HttpRequest::builder(HttpMethod::Get, url).build() {
+            Ok(value) => value,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    #[test]
+    fn full_content_length_body_has_no_warning() {
+        let request = get_request("https://example.com/full");
+        let raw = b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhel";
+        let mut stream = Cursor::new(raw.to_vec()).chain(Cursor::new(b"lo".to_vec()));
+        let outcome = match read_response(&mut stream, &request, DEFAULT_MAX_DECOMPRESSED_BYTES) {
+            Ok(value) => value,
+            Err(error) => panic!("{error}"),
+        };
+
+        assert_eq!(outcome.response.body, b"hello");
+        assert!(outcome.response.warnings.is_empty());
+        assert!(outcome.reusable);
+    }
+
+    #[test]
+    fn short_content_length_body_records_warning() {
+        let request = get_request("https://example.com/short");
+        let raw = b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nhel";
+        let mut stream = Cursor::new(raw.to_vec()).chain(Cursor::new(b"lo".to_vec()));
+        let outcome = match read_response(&mut stream, &request, DEFAULT_MAX_DECOMPRESSED_BYTES) {
+            Ok(value) => value,
+            Err(error) => panic!("{error}"),
+        };
+
+        assert_eq!(outcome.response.body, b"hello");
+        let codes = outcome
+            .response
+            .warnings
+            .iter()
+            .map(|warning| warning.code)
+            .collect::<Vec<_>>();
+        assert_eq!(codes, vec!["net.content_length_short"]);
+        assert!(!outcome.reusable);
+    }
+
+    #[test]
+    fn partial_content_response_reads_only_the_returned_range() {
+        let request = get_request("https://example.com/big.png");
+        let raw =
+            b"HTTP/1.1 206 Partial Content\r\nContent-Range: bytes 0-3/9000\r\n\r\n\x89PNGtrailing";
+        let mut stream = Cursor::new(raw.to_vec());
+        let outcome = match read_response(&mut stream, &request, DEFAULT_MAX_DECOMPRESSED_BYTES) {
+            Ok(value) => value,
+            Err(error) => panic!("{error}"),
+        };
+
+        assert_eq!(outcome.response.status.as_u16(), 206);
+        assert_eq!(outcome.response.body, b"\x89PNG");
+        assert_eq!(
+            outcome.response.content_range,
+            Some(ContentRange {
+                start: 0,
+                end: 3,
+                complete_length: Some(9000),
+            })
+        );
+    }
+
+    #[test]
+    fn partial_content_without_content_range_is_read_as_a_full_body() {
+        let request = get_request("https://example.com/big.png");
+        let raw = b"HTTP/1.1 206 Partial Content\r\nContent-Length: 4\r\n\r\nabcd";
+        let mut stream = Cursor::new(raw.to_vec());
+        let outcome = match read_response(&mut stream, &request, DEFAULT_MAX_DECOMPRESSED_BYTES) {
+            Ok(value) => value,
+            Err(error) => panic!("{error}"),
+        };
+
+        assert_eq!(outcome.response.body, b"abcd");
+        assert_eq!(outcome.response.content_range, None);
+        assert!(
+            outcome
+                .response
+                .warnings
+                .iter()
+                .any(|warning| warning.code == "net.http.content_range_invalid")
+        );
+    }
+
     #[test]
     fn rejects_unsupported_transfer_encoding() {
         let url = BrowserUrl::parse("https://example.com/unsupported-te");
@@ -837,7 +1337,7 @@ mod tests {
 
         let raw = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: gzip\r\nConnection: close\r\n\r\nbody";
         let mut stream = Cursor::new(raw.to_vec());
-        let outcome = read_response(&mut stream, &request);
+        let outcome = read_response(&mut stream, &request, DEFAULT_MAX_DECOMPRESSED_BYTES);
         assert!(outcome.is_err());
         if let Err(error) = outcome {
             assert_eq!(error.code, "net.http.transfer_encoding_unsupported");
@@ -862,7 +1362,7 @@ mod tests {
             Err(error) => panic!("{error}"),
         };
 
-        let decoded = decode_content_encoding(&[header], &encoded);
+        let decoded = decode_content_encoding(&[header], &encoded, DEFAULT_MAX_DECOMPRESSED_BYTES);
         assert_eq!(decoded, Ok(b"hello gzip".to_vec()));
     }
 
@@ -884,7 +1384,7 @@ mod tests {
             Err(error) => panic!("{error}"),
         };
 
-        let decoded = decode_content_encoding(&[header], &encoded);
+        let decoded = decode_content_encoding(&[header], &encoded, DEFAULT_MAX_DECOMPRESSED_BYTES);
         assert_eq!(decoded, Ok(b"hello deflate".to_vec()));
     }
 
@@ -906,7 +1406,370 @@ mod tests {
             Err(error) => panic!("{error}"),
         };
 
-        let decoded = decode_content_encoding(&[header], &encoded);
+        let decoded = decode_content_encoding(&[header], &encoded, DEFAULT_MAX_DECOMPRESSED_BYTES);
         assert_eq!(decoded, Ok(b"hello br".to_vec()));
     }
+
+    #[test]
+    fn decompressed_size_cap_allows_bodies_within_the_limit() {
+        let payload = vec![b'a'; 4096];
+        let mut encoded = Vec::new();
+        {
+            let mut encoder = GzEncoder::new(&mut encoded, Compression::default());
+            assert!(encoder.write_all(&payload).is_ok());
+            assert!(encoder.finish().is_ok());
+        }
+        let header = match Header::new("Content-Encoding", "gzip") {
+            Ok(value) => value,
+            Err(error) => panic!("{error}"),
+        };
+
+        let decoded = decode_content_encoding(&[header], &encoded, payload.len());
+        assert_eq!(decoded, Ok(payload));
+    }
+
+    #[test]
+    fn decompressed_size_cap_rejects_compression_bombs() {
+        let mut encoded = Vec::new();
+        {
+            let mut encoder = GzEncoder::new(&mut encoded, Compression::best());
+            assert!(encoder.write_all(&vec![0_u8; 4 * 1024 * 1024]).is_ok());
+            assert!(encoder.finish().is_ok());
+        }
+        assert!(encoded.len() < 16 * 1024);
+        let header = match Header::new("Content-Encoding", "gzip") {
+            Ok(value) => value,
+            Err(error) => panic!("{error}"),
+        };
+
+        let decoded = decode_content_encoding(&[header], &encoded, 64 * 1024);
+        assert_eq!(
+            decoded.map_err(|error| error.code),
+            Err("net.decompress_bomb")
+        );
+
+        // An endless decoder stream is cut off at the cap instead of being drained.
+        let endless = inflate_capped(std::io::repeat(0), 1024, "test");
+        assert_eq!(
+            endless.map_err(|error| error.code),
+            Err("net.decompress_bomb")
+        );
+    }
+
+    #[test]
+    fn forward_proxy_requests_use_absolute_form_and_credentials() {
+        let url = match BrowserUrl::parse("http://example.test:8080/page?q=1") {
+            Ok(value) => value,
+            Err(error) => panic!("{error}"),
+        };
+        let request = match HttpRequest::builder(HttpMethod::Get, url).build() {
+            Ok(value) => value,
+            Err(error) => panic!("{error}"),
+        };
+        let proxy = Proxy::http("127.0.0.1", 3128).with_auth("user", "pass");
+
+        let mut encoded = Vec::new();
+        assert!(write_request(&mut encoded, &request, Some(&proxy)).is_ok());
+        let encoded = String::from_utf8_lossy(&encoded);
+        assert!(encoded.starts_with("GET http://example.test:8080/page?q=1 HTTP/1.1\r\n"));
+        assert!(encoded.contains("Host: example.test:8080\r\n"));
+        assert!(encoded.contains("Proxy-Authorization: Basic dXNlcjpwYXNz\r\n"));
+
+        let mut direct = Vec::new();
+        assert!(write_request(&mut direct, &request, None).is_ok());
+        let direct = String::from_utf8_lossy(&direct);
+        assert!(direct.starts_with("GET /page?q=1 HTTP/1.1\r\n"));
+        assert!(!direct.contains("Proxy-Authorization"));
+    }
+
+    #[test]
+    fn client_routes_plain_http_through_stub_proxy() {
+        let listener = match TcpListener::bind("127.0.0.1:0") {
+            Ok(listener) => listener,
+            Err(error) => panic!("{error}"),
+        };
+        let port = match listener.local_addr() {
+            Ok(address) => address.port(),
+            Err(error) => panic!("{error}"),
+        };
+        let handle = thread::spawn(move || {
+            let Ok((mut stream, _)) = listener.accept() else {
+                return String::new();
+            };
+            let mut head = Vec::new();
+            let mut byte = [0_u8; 1];
+            while !head.ends_with(b"\r\n\r\n") && stream.read_exact(&mut byte).is_ok() {
+                head.push(byte[0]);
+            }
+            let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 7\r\n\r\nproxied");
+            String::from_utf8_lossy(&head).into_owned()
+        });
+
+        let url = match BrowserUrl::parse("http://origin.test/hello") {
+            Ok(value) => value,
+            Err(error) => panic!("{error}"),
+        };
+        let request = match HttpRequest::builder(HttpMethod::Get, url).build() {
+            Ok(value) => value,
+            Err(error) => panic!("{error}"),
+        };
+        let mut client = match Http11Client::new(StrictTlsPolicy::default()) {
+            Ok(client) => client,
+            Err(error) => panic!("{error}"),
+        };
+        client.set_proxy(Some(Proxy::http("127.0.0.1", port)));
+
+        let response = client.execute(PreparedRequest { request, tls: None });
+        let head = handle.join().unwrap_or_default();
+        assert!(head.starts_with("GET http://origin.test/hello HTTP/1.1\r\n"));
+        match response {
+            Ok(response) => assert_eq!(response.body, b"proxied"),
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    #[test]
+    fn unreachable_proxy_surfaces_proxy_error() {
+        let port = match TcpListener::bind("127.0.0.1:0").and_then(|listener| listener.local_addr())
+        {
+            Ok(address) => address.port(),
+            Err(error) => panic!("{error}"),
+        };
+        let url = match BrowserUrl::parse("http://origin.test/") {
+            Ok(value) => value,
+            Err(error) => panic!("{error}"),
+        };
+        let request = match HttpRequest::builder(HttpMethod::Get, url).build() {
+            Ok(value) => value,
+            Err(error) => panic!("{error}"),
+        };
+        let mut client = match Http11Client::new(StrictTlsPolicy::default()) {
+            Ok(client) => client,
+            Err(error) => panic!("{error}"),
+        };
+        client.set_proxy(Some(Proxy::socks5("127.0.0.1", port)));
+
+        match client.execute(PreparedRequest { request, tls: None }) {
+            Err(error) => assert_eq!(error.code, "net.proxy_failed"),
+            Ok(_) => panic!("closed proxy port must fail"),
+        }
+    }
+
+    struct FixedResolver(SocketAddr);
+
+    impl DnsResolver for FixedResolver {
+        fn resolve(&self, _host: &str, _port: u16) -> BrowserResult<Vec<SocketAddr>> {
+            Ok(vec![self.0])
+        }
+    }
+
+    /// Fails the first `failures` connects, then dials the real address.
+    struct FlakyTransport {
+        failures: Rc<Cell<usize>>,
+        attempts: Rc<Cell<usize>>,
+    }
+
+    impl Transport for FlakyTransport {
+        fn connect(&self, address: SocketAddr, timeout: Duration) -> BrowserResult<TcpStream> {
+            self.attempts.set(self.attempts.get() + 1);
+            if self.failures.get() > 0 {
+                self.failures.set(self.failures.get() - 1);
+                return Err(BrowserError::new(
+                    "net.transport.connect_failed",
+                    "connection reset by stub",
+                ));
+            }
+            TcpTransport.connect(address, timeout)
+        }
+    }
+
+    fn flaky_client(
+        address: SocketAddr,
+        failures: usize,
+    ) -> (Http11Client<FixedResolver, FlakyTransport>, Rc<Cell<usize>>) {
+        let attempts = Rc::new(Cell::new(0));
+        let transport = FlakyTransport {
+            failures: Rc::new(Cell::new(failures)),
+            attempts: Rc::clone(&attempts),
+        };
+        let mut client = match Http11Client::with_parts(
+            FixedResolver(address),
+            transport,
+            InMemoryConnectionPool::default(),
+            RustlsTlsAdapter,
+            StrictTlsPolicy::default(),
+        ) {
+            Ok(client) => client,
+            Err(error) => panic!("{error}"),
+        };
+        client.set_retry_backoff(Duration::from_millis(1));
+        (client, attempts)
+    }
+
+    fn plain_request(method: HttpMethod) -> PreparedRequest {
+        let url = match BrowserUrl::parse("http://origin.test/retry") {
+            Ok(value) => value,
+            Err(error) => panic!("{error}"),
+        };
+        match HttpRequest::builder(method, url).build() {
+            Ok(request) => PreparedRequest { request, tls: None },
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    #[test]
+    fn retries_get_after_transient_connect_failures() {
+        let listener = match TcpListener::bind("127.0.0.1:0") {
+            Ok(listener) => listener,
+            Err(error) => panic!("{error}"),
+        };
+        let address = match listener.local_addr() {
+            Ok(address) => address,
+            Err(error) => panic!("{error}"),
+        };
+        let handle = thread::spawn(move || {
+            if let Ok((mut stream, _)) = listener.accept() {
+                let mut head = Vec::new();
+                let mut byte = [0_u8; 1];
+                while !head.ends_with(b"\r\n\r\n") && stream.read_exact(&mut byte).is_ok() {
+                    head.push(byte[0]);
+                }
+                let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok");
+            }
+        });
+
+        let (mut client, attempts) = flaky_client(address, 2);
+        let response = client.execute(plain_request(HttpMethod::Get));
+        assert!(handle.join().is_ok());
+        match response {
+            Ok(response) => assert_eq!(response.body, b"ok"),
+            Err(error) => panic!("{error}"),
+        }
+        assert_eq!(attempts.get(), 3);
+    }
+
+    #[test]
+    fn surfaces_final_error_once_retry_budget_is_spent() {
+        let address = SocketAddr::from(([127, 0, 0, 1], 9));
+        let (mut client, attempts) = flaky_client(address, usize::MAX);
+        client.set_max_retries(3);
+
+        match client.execute(plain_request(HttpMethod::Head)) {
+            Err(error) => assert_eq!(error.code, "net.transport.connect_failed"),
+            Ok(_) => panic!("every connect fails"),
+        }
+        assert_eq!(attempts.get(), 4);
+    }
+
+    #[test]
+    fn does_not_retry_post_unless_allowed() {
+        let address = SocketAddr::from(([127, 0, 0, 1], 9));
+        let (mut client, attempts) = flaky_client(address, usize::MAX);
+
+        assert!(client.execute(plain_request(HttpMethod::Post)).is_err());
+        assert_eq!(attempts.get(), 1);
+
+        client.set_retry_non_idempotent(true);
+        assert!(client.execute(plain_request(HttpMethod::Post)).is_err());
+        assert_eq!(attempts.get(), 4);
+    }
+
+    fn read_request_head(stream: &mut TcpStream) -> String {
+        let mut head = Vec::new();
+        let mut byte = [0_u8; 1];
+        while !head.ends_with(b"\r\n\r\n") && stream.read_exact(&mut byte).is_ok() {
+            head.push(byte[0]);
+        }
+        String::from_utf8_lossy(&head).into_owned()
+    }
+
+    fn large_post(port: u16, body_len: usize) -> PreparedRequest {
+        let url = match BrowserUrl::parse(&format!("http://127.0.0.1:{port}/upload")) {
+            Ok(value) => value,
+            Err(error) => panic!("{error}"),
+        };
+        match HttpRequest::builder(HttpMethod::Post, url)
+            .body(vec![b'x'; body_len])
+            .build()
+        {
+            Ok(request) => PreparedRequest { request, tls: None },
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    fn expect_continue_client() -> Http11Client {
+        let mut client = match Http11Client::new(StrictTlsPolicy::default()) {
+            Ok(client) => client,
+            Err(error) => panic!("{error}"),
+        };
+        client.set_expect_continue_threshold(Some(1024));
+        client
+    }
+
+    #[test]
+    fn expect_continue_sends_body_after_interim_response() {
+        let listener = match TcpListener::bind("127.0.0.1:0") {
+            Ok(listener) => listener,
+            Err(error) => panic!("{error}"),
+        };
+        let port = match listener.local_addr() {
+            Ok(address) => address.port(),
+            Err(error) => panic!("{error}"),
+        };
+        let handle = thread::spawn(move || {
+            let Ok((mut stream, _)) = listener.accept() else {
+                return (String::new(), 0);
+            };
+            let head = read_request_head(&mut stream);
+            let _ = stream.write_all(b"HTTP/1.1 100 Continue\r\n\r\n");
+            let mut body = vec![0_u8; 64 * 1024];
+            let received = stream.read_exact(&mut body).map_or(0, |()| body.len());
+            let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok");
+            (head, received)
+        });
+
+        let response = expect_continue_client().execute(large_post(port, 64 * 1024));
+        let (head, received) = handle.join().unwrap_or_default();
+        assert!(head.contains("Expect: 100-continue\r\n"), "{head}");
+        assert_eq!(received, 64 * 1024);
+        match response {
+            Ok(response) => {
+                assert_eq!(response.status.as_u16(), 200);
+                assert_eq!(response.body, b"ok");
+            }
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    #[test]
+    fn expect_continue_withholds_body_on_final_error_status() {
+        let listener = match TcpListener::bind("127.0.0.1:0") {
+            Ok(listener) => listener,
+            Err(error) => panic!("{error}"),
+        };
+        let port = match listener.local_addr() {
+            Ok(address) => address.port(),
+            Err(error) => panic!("{error}"),
+        };
+        let handle = thread::spawn(move || {
+            let Ok((mut stream, _)) = listener.accept() else {
+                return usize::MAX;
+            };
+            let _ = read_request_head(&mut stream);
+            let _ = stream.write_all(
+                b"HTTP/1.1 417 Expectation Failed\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            );
+            let _ = stream.set_read_timeout(Some(Duration::from_millis(500)));
+            let mut rest = Vec::new();
+            let _ = stream.read_to_end(&mut rest);
+            rest.len()
+        });
+
+        let response = expect_continue_client().execute(large_post(port, 64 * 1024));
+        match response {
+            Ok(response) => assert_eq!(response.status.as_u16(), 417),
+            Err(error) => panic!("{error}"),
+        }
+        assert_eq!(handle.join().unwrap_or(usize::MAX), 0);
+    }
 }
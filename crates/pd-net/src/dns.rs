@@ -2,6 +2,7 @@
 
 use pd_core::BrowserError;
 use pd_core::BrowserResult;
+use pd_core::ResultExt;
 use std::net::SocketAddr;
 use std::net::ToSocketAddrs;
 
@@ -19,12 +20,10 @@ impl DnsResolver for SystemDnsResolver {
         let query = format!("{host}:{port}");
         let addresses: Vec<SocketAddr> = query
             .to_socket_addrs()
-            .map_err(|error| {
-                BrowserError::new(
-                    "net.dns.resolve_failed",
-                    format!("failed to resolve `{query}`: {error}"),
-                )
-            })?
+            .context(
+                "net.dns.resolve_failed",
+                format!("failed to resolve `{query}`"),
+            )?
             .collect();
 
         if addresses.is_empty() {
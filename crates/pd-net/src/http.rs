@@ -1,5 +1,6 @@
 //! HTTP request/response contracts.
 
+use crate::tls::TlsSessionInfo;
 use crate::url::BrowserUrl;
 use pd_core::BrowserError;
 use pd_core::BrowserResult;
@@ -193,13 +194,175 @@ impl HttpStatusCode {
     }
 }
 
+/// Byte range reported by a `Content-Range` header on a `206 Partial Content` response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentRange {
+    pub start: u64,
+    /// Inclusive last byte offset.
+    pub end: u64,
+    /// Full resource size, or `None` when the server sent `*`.
+    pub complete_length: Option<u64>,
+}
+
+impl ContentRange {
+    /// Parses `bytes <start>-<end>/<length|*>`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let rest = value.trim().strip_prefix("bytes")?.trim_start();
+        let (range, complete) = rest.split_once('/')?;
+        let (start, end) = range.trim().split_once('-')?;
+        let start = start.trim().parse::<u64>().ok()?;
+        let end = end.trim().parse::<u64>().ok()?;
+        let complete_length = match complete.trim() {
+            "*" => None,
+            length => Some(length.parse::<u64>().ok()?),
+        };
+        if end < start || complete_length.is_some_and(|length| end >= length) {
+            return None;
+        }
+        Some(Self {
+            start,
+            end,
+            complete_length,
+        })
+    }
+
+    /// Number of bytes covered by the range.
+    pub fn byte_count(self) -> u64 {
+        self.end - self.start + 1
+    }
+}
+
+/// Parsed `Content-Disposition` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentDisposition {
+    /// Lowercased type, usually `inline` or `attachment`.
+    pub disposition_type: String,
+    /// Decoded `filename*` when present, else the unquoted `filename`, without directories.
+    pub filename: Option<String>,
+}
+
+impl ContentDisposition {
+    /// Parses `<type>; filename="..."; filename*=<charset>'<lang>'<pct-encoded>`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = split_header_params(value).into_iter();
+        let disposition_type = parts.next()?.trim().to_ascii_lowercase();
+        if disposition_type.is_empty() || !disposition_type.bytes().all(is_token_char) {
+            return None;
+        }
+
+        let mut plain = None;
+        let mut extended = None;
+        for part in parts {
+            let Some((name, value)) = part.split_once('=') else {
+                continue;
+            };
+            match name.trim().to_ascii_lowercase().as_str() {
+                "filename" => plain = Some(unquote_header_value(value.trim())),
+                "filename*" => extended = decode_ext_value(value.trim()),
+                _ => {}
+            }
+        }
+        let filename = extended
+            .or(plain)
+            .and_then(|name| {
+                name.rsplit(['/', '\\'])
+                    .next()
+                    .map(|base| base.trim().to_owned())
+            })
+            .filter(|name| !name.is_empty() && name != "." && name != "..");
+
+        Some(Self {
+            disposition_type,
+            filename,
+        })
+    }
+
+    pub fn is_attachment(&self) -> bool {
+        self.disposition_type == "attachment"
+    }
+}
+
+/// Splits on `;` outside quoted strings.
+fn split_header_params(value: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for (index, byte) in value.bytes().enumerate() {
+        match byte {
+            _ if escaped => escaped = false,
+            b'\\' if in_quotes => escaped = true,
+            b'"' => in_quotes = !in_quotes,
+            b';' if !in_quotes => {
+                parts.push(&value[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&value[start..]);
+    parts
+}
+
+fn unquote_header_value(value: &str) -> String {
+    let Some(inner) = value
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    else {
+        return value.to_owned();
+    };
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => out.extend(chars.next()),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+/// Decodes an RFC 8187 `ext-value`; only UTF-8 and ISO-8859-1 charsets are understood.
+fn decode_ext_value(value: &str) -> Option<String> {
+    let mut fields = value.splitn(3, '\'');
+    let charset = fields.next()?.trim().to_ascii_lowercase();
+    let _language = fields.next()?;
+    let encoded = fields.next()?;
+
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut input = encoded.bytes();
+    while let Some(byte) = input.next() {
+        if byte == b'%' {
+            let high = (input.next()? as char).to_digit(16)?;
+            let low = (input.next()? as char).to_digit(16)?;
+            bytes.push((high * 16 + low) as u8);
+        } else {
+            bytes.push(byte);
+        }
+    }
+
+    match charset.as_str() {
+        "utf-8" => String::from_utf8(bytes).ok(),
+        "iso-8859-1" => Some(bytes.into_iter().map(char::from).collect()),
+        _ => None,
+    }
+}
+
 /// Incoming HTTP response contract.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct HttpResponse {
     pub version: HttpVersion,
     pub status: HttpStatusCode,
+    /// Reason phrase from the status line, verbatim; empty when the server sent none.
+    pub status_text: String,
     pub headers: Vec<Header>,
     pub body: Vec<u8>,
+    /// Non-fatal problems noticed while reading, such as a body shorter than `Content-Length`.
+    pub warnings: Vec<BrowserError>,
+    /// Parsed `Content-Range` of a `206 Partial Content` response.
+    pub content_range: Option<ContentRange>,
+    /// Handshake details of the TLS connection that carried the response.
+    pub tls: Option<TlsSessionInfo>,
 }
 
 fn ensure_singleton_header(headers: &[Header], name: &str) -> BrowserResult<()> {
@@ -255,11 +418,69 @@ fn is_token_char(byte: u8) -> bool {
 
 #[cfg(test)]
 mod tests {
+    use super::ContentDisposition;
+    use super::ContentRange;
     use super::HttpMethod;
     use super::HttpRequest;
     use super::HttpStatusCode;
     use crate::url::BrowserUrl;
 
+    #[test]
+    fn parses_content_range_headers() {
+        assert_eq!(
+            ContentRange::parse("bytes 0-99/1234"),
+            Some(ContentRange {
+                start: 0,
+                end: 99,
+                complete_length: Some(1234),
+            })
+        );
+        assert_eq!(
+            ContentRange::parse("bytes 100-199/*").map(ContentRange::byte_count),
+            Some(100)
+        );
+        assert_eq!(ContentRange::parse("bytes 50-10/100"), None);
+        assert_eq!(ContentRange::parse("bytes 0-100/100"), None);
+        assert_eq!(ContentRange::parse("bytes */100"), None);
+    }
+
+    #[test]
+    fn parses_quoted_content_disposition_filename() {
+        assert_eq!(
+            ContentDisposition::parse(r#"attachment; filename="a.pdf""#),
+            Some(ContentDisposition {
+                disposition_type: "attachment".to_owned(),
+                filename: Some("a.pdf".to_owned()),
+            })
+        );
+        let quoted = ContentDisposition::parse(r#"inline; filename="../x \"q\"; y.txt""#);
+        assert_eq!(
+            quoted.and_then(|disposition| disposition.filename),
+            Some(r#"x "q"; y.txt"#.to_owned())
+        );
+    }
+
+    #[test]
+    fn decodes_extended_content_disposition_filename() {
+        let parsed = ContentDisposition::parse(
+            "attachment; filename=\"fallback.txt\"; filename*=UTF-8''%E2%82%AC.txt",
+        );
+        assert_eq!(
+            parsed.and_then(|disposition| disposition.filename),
+            Some("\u{20ac}.txt".to_owned())
+        );
+    }
+
+    #[test]
+    fn bare_attachment_disposition_has_no_filename() {
+        let Some(parsed) = ContentDisposition::parse("Attachment") else {
+            panic!("expected a disposition");
+        };
+        assert!(parsed.is_attachment());
+        assert_eq!(parsed.filename, None);
+        assert_eq!(ContentDisposition::parse(""), None);
+    }
+
     #[test]
     fn host_header_is_added_automatically() {
         let url = BrowserUrl::parse("https://example.com/path");
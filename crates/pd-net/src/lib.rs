@@ -1,5 +1,6 @@
 //! Networking contracts: URL parsing, HTTP messages, and TLS policy.
 
+pub mod charset;
 pub mod client;
 pub mod dns;
 pub mod http;
@@ -10,30 +11,42 @@ pub mod transport;
 pub mod url;
 
 use client::Http11Client;
+use client::SharedHttp11Client;
 use http::HttpMethod;
 use http::HttpRequest;
+use pd_core::BrowserError;
 use pd_core::BrowserResult;
 use pd_privacy::PrivacyPolicy;
 use pd_security::SecurityPolicy;
 use pd_storage::StorageManager;
+use std::collections::HashMap;
+use std::sync::Arc;
 use tls::StrictTlsPolicy;
 use tls::TlsHandshakeConfig;
 use url::BrowserUrl;
 
+pub use http::ContentDisposition;
+pub use http::ContentRange;
 pub use http::Header;
 pub use http::HttpRequestBuilder;
 pub use http::HttpResponse;
 pub use http::HttpStatusCode;
 pub use http::HttpVersion;
 pub use pool::ConnectionKey;
+pub use tls::TlsSessionInfo;
 pub use tls::TlsVersion;
+pub use tls::TrustAnchorSource;
 pub use tls::TrustStoreMode;
+pub use transport::Proxy;
+pub use transport::ProxyAuth;
+pub use transport::ProxyKind;
 pub use url::Scheme;
 
 const DEFAULT_BROWSER_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/122.0.0.0 Safari/537.36";
 const DEFAULT_ACCEPT_HEADER: &str =
     "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8";
 const DEFAULT_ACCEPT_LANGUAGE: &str = "en-US,en;q=0.9";
+const USER_AGENT_OVERRIDES_PARTITION: &str = "__user_agents";
 
 /// Request prepared by the network layer before transport execution.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -42,6 +55,107 @@ pub struct PreparedRequest {
     pub tls: Option<TlsHandshakeConfig>,
 }
 
+/// Response body decoded to text by [`NetStack::load_text`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadedPage {
+    pub url: BrowserUrl,
+    pub status: HttpStatusCode,
+    pub headers: Vec<Header>,
+    text: String,
+}
+
+impl LoadedPage {
+    /// Body text, decoded as ISO-8859-1 when the `Content-Type` says so and lossy UTF-8 otherwise.
+    pub fn body_text(&self) -> &str {
+        &self.text
+    }
+
+    /// Case-insensitive substring search over [`Self::body_text`].
+    pub fn body_contains(&self, needle: &str) -> bool {
+        self.text.to_lowercase().contains(&needle.to_lowercase())
+    }
+}
+
+/// Per-host `User-Agent` replacements; subdomains inherit a parent host's entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserAgentOverrides {
+    sites: HashMap<String, String>,
+}
+
+impl UserAgentOverrides {
+    /// Reads the overrides persisted in `storage`; ephemeral or rootless storage has none.
+    pub fn load(storage: &StorageManager) -> BrowserResult<Self> {
+        let mut overrides = Self::default();
+        if !persists_user_agent_overrides(storage) {
+            return Ok(overrides);
+        }
+        for site in storage.partition_keys(USER_AGENT_OVERRIDES_PARTITION)? {
+            if let Some(user_agent) =
+                storage.get_partition_value(USER_AGENT_OVERRIDES_PARTITION, &site)?
+            {
+                overrides.sites.insert(site, user_agent);
+            }
+        }
+        Ok(overrides)
+    }
+
+    /// Returns the most specific override covering `host`.
+    pub fn for_host(&self, host: &str) -> Option<&str> {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        self.sites
+            .iter()
+            .filter(|(site, _)| {
+                host == **site
+                    || host
+                        .strip_suffix(site.as_str())
+                        .is_some_and(|prefix| prefix.ends_with('.'))
+            })
+            .max_by_key(|(site, _)| site.len())
+            .map(|(_, user_agent)| user_agent.as_str())
+    }
+
+    /// Sets the `User-Agent` sent to `host` and its subdomains, persisting it when `storage` allows.
+    pub fn set(
+        &mut self,
+        storage: Option<&StorageManager>,
+        host: &str,
+        user_agent: &str,
+    ) -> BrowserResult<()> {
+        let site = host.trim().trim_end_matches('.').to_ascii_lowercase();
+        if site.is_empty() {
+            return Err(BrowserError::new(
+                "net.user_agent.host_empty",
+                "user-agent override needs a host",
+            ));
+        }
+        if user_agent.trim().is_empty() {
+            return Err(BrowserError::new(
+                "net.user_agent.empty",
+                format!("user-agent override for `{site}` is empty"),
+            ));
+        }
+        let header = Header::new("User-Agent", user_agent.trim())?;
+        if let Some(storage) = storage.filter(|storage| persists_user_agent_overrides(storage)) {
+            storage.set_partition_value(USER_AGENT_OVERRIDES_PARTITION, &site, &header.value)?;
+        }
+        self.sites.insert(site, header.value);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, storage: Option<&StorageManager>, host: &str) -> BrowserResult<()> {
+        let site = host.trim().trim_end_matches('.').to_ascii_lowercase();
+        if let Some(storage) = storage.filter(|storage| persists_user_agent_overrides(storage)) {
+            storage.remove_partition_value(USER_AGENT_OVERRIDES_PARTITION, &site)?;
+        }
+        self.sites.remove(&site);
+        Ok(())
+    }
+}
+
+fn persists_user_agent_overrides(storage: &StorageManager) -> bool {
+    !storage.config.ephemeral_mode && storage.persistent_root().is_some()
+}
+
 /// Runtime network stack.
 #[derive(Debug, Clone)]
 pub struct NetStack {
@@ -49,6 +163,8 @@ pub struct NetStack {
     pub security: SecurityPolicy,
     pub storage: StorageManager,
     pub tls_policy: StrictTlsPolicy,
+    /// Shared so embedders can hand every request the same loaded overrides.
+    pub user_agent_overrides: Arc<UserAgentOverrides>,
 }
 
 impl NetStack {
@@ -60,9 +176,17 @@ impl NetStack {
             security,
             storage,
             tls_policy,
+            user_agent_overrides: Arc::default(),
         }
     }
 
+    /// Returns the `User-Agent` for `host`, preferring the most specific override.
+    pub fn user_agent_for_host(&self, host: &str) -> &str {
+        self.user_agent_overrides
+            .for_host(host)
+            .unwrap_or(DEFAULT_BROWSER_USER_AGENT)
+    }
+
     pub fn parse_url(&self, raw_url: &str) -> BrowserResult<BrowserUrl> {
         BrowserUrl::parse(raw_url)
     }
@@ -96,8 +220,9 @@ impl NetStack {
         let url = BrowserUrl::parse(raw_url)?;
         let tls = tls_policy.handshake_config_for(&url)?;
 
+        let user_agent = self.user_agent_for_host(url.host()).to_owned();
         let mut request = HttpRequest::builder(method, url);
-        request = request.header("User-Agent", DEFAULT_BROWSER_USER_AGENT)?;
+        request = request.header("User-Agent", &user_agent)?;
         request = request.header("Accept", DEFAULT_ACCEPT_HEADER)?;
         request = request.header("Accept-Language", DEFAULT_ACCEPT_LANGUAGE)?;
         request = request.header("Accept-Encoding", "gzip, deflate, br")?;
@@ -107,9 +232,12 @@ impl NetStack {
         request = request.header("Sec-Fetch-User", "?1")?;
         request = request.header("Sec-Fetch-Dest", "document")?;
 
-        if self.privacy.block_known_trackers {
+        if self.privacy.send_dnt {
             request = request.header("DNT", "1")?;
         }
+        if self.privacy.send_gpc {
+            request = request.header("Sec-GPC", "1")?;
+        }
 
         Ok(PreparedRequest {
             request: request.build()?,
@@ -117,6 +245,26 @@ impl NetStack {
         })
     }
 
+    /// Fetches `raw_url` once, without following redirects, and decodes the body as text.
+    pub fn load_text(&self, raw_url: &str) -> BrowserResult<LoadedPage> {
+        let prepared = self.prepare_get(raw_url)?;
+        let url = prepared.request.url.clone();
+        let response = self.http11_client()?.execute(prepared)?;
+        let content_type = response
+            .headers
+            .iter()
+            .find(|header| header.name.eq_ignore_ascii_case("content-type"))
+            .map_or("", |header| header.value.as_str());
+        let text = charset::decode_text(&response.body, content_type);
+
+        Ok(LoadedPage {
+            url,
+            status: response.status,
+            headers: response.headers,
+            text,
+        })
+    }
+
     pub fn http11_client(&self) -> BrowserResult<Http11Client> {
         Http11Client::new(self.tls_policy.clone())
     }
@@ -128,6 +276,14 @@ impl NetStack {
         Http11Client::new(tls_policy)
     }
 
+    /// Like [`Self::http11_client_with_tls_policy`], but clones share one connection pool.
+    pub fn shared_http11_client_with_tls_policy(
+        &self,
+        tls_policy: StrictTlsPolicy,
+    ) -> BrowserResult<SharedHttp11Client> {
+        SharedHttp11Client::shared(tls_policy)
+    }
+
     pub fn http11_client_webpki_only(&self) -> BrowserResult<Http11Client> {
         let policy = self
             .tls_policy
@@ -149,10 +305,17 @@ impl NetStack {
 mod tests {
     use super::HttpMethod;
     use super::NetStack;
+    use super::UserAgentOverrides;
+    use super::tls::StrictTlsPolicy;
     use pd_privacy::PrivacyPolicy;
     use pd_security::SecurityPolicy;
     use pd_storage::StorageConfig;
     use pd_storage::StorageManager;
+    use std::io::Read;
+    use std::io::Write;
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::thread;
 
     #[test]
     fn strict_mode_blocks_http_urls() {
@@ -183,4 +346,147 @@ mod tests {
         };
         assert!(prepared.tls.is_some());
     }
+
+    #[test]
+    fn privacy_signal_headers_follow_their_own_flags() {
+        let privacy = PrivacyPolicy {
+            block_known_trackers: true,
+            send_dnt: false,
+            send_gpc: true,
+            ..PrivacyPolicy::default()
+        };
+        let security = SecurityPolicy::default();
+        let storage =
+            StorageManager::new(StorageConfig::default(), privacy.clone(), security.clone());
+        let stack = NetStack::new(privacy, security, storage);
+
+        let prepared = match stack.prepare_request(HttpMethod::Get, "https://example.com/") {
+            Ok(value) => value,
+            Err(error) => panic!("{error}"),
+        };
+        assert_eq!(prepared.request.header("Sec-GPC"), Some("1"));
+        assert_eq!(prepared.request.header("DNT"), None);
+    }
+
+    #[test]
+    fn default_policy_sends_dnt_but_not_gpc() {
+        let privacy = PrivacyPolicy::default();
+        let security = SecurityPolicy::default();
+        let storage =
+            StorageManager::new(StorageConfig::default(), privacy.clone(), security.clone());
+        let stack = NetStack::new(privacy, security, storage);
+
+        let prepared = match stack.prepare_request(HttpMethod::Get, "https://example.com/") {
+            Ok(value) => value,
+            Err(error) => panic!("{error}"),
+        };
+        assert_eq!(prepared.request.header("DNT"), Some("1"));
+        assert_eq!(prepared.request.header("Sec-GPC"), None);
+    }
+
+    fn stack_with_overrides(overrides: &[(&str, &str)]) -> NetStack {
+        let privacy = PrivacyPolicy::default();
+        let security = SecurityPolicy::default();
+        let storage =
+            StorageManager::new(StorageConfig::default(), privacy.clone(), security.clone());
+        let mut user_agents = UserAgentOverrides::default();
+        for (host, user_agent) in overrides {
+            if let Err(error) = user_agents.set(Some(&storage), host, user_agent) {
+                panic!("{error}");
+            }
+        }
+        let mut stack = NetStack::new(privacy, security, storage);
+        stack.user_agent_overrides = Arc::new(user_agents);
+        stack
+    }
+
+    fn user_agent_sent_to(stack: &NetStack, url: &str) -> Option<String> {
+        match stack.prepare_get(url) {
+            Ok(prepared) => prepared.request.header("User-Agent").map(str::to_owned),
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    #[test]
+    fn user_agent_override_applies_to_its_host() {
+        let stack = stack_with_overrides(&[("Example.com", "CompatAgent/1.0")]);
+        assert_eq!(
+            user_agent_sent_to(&stack, "https://example.com/"),
+            Some("CompatAgent/1.0".to_owned())
+        );
+    }
+
+    #[test]
+    fn subdomains_inherit_the_most_specific_parent_override() {
+        let stack = stack_with_overrides(&[
+            ("example.com", "CompatAgent/1.0"),
+            ("app.example.com", "AppAgent/2.0"),
+        ]);
+        assert_eq!(
+            user_agent_sent_to(&stack, "https://www.example.com/"),
+            Some("CompatAgent/1.0".to_owned())
+        );
+        assert_eq!(
+            user_agent_sent_to(&stack, "https://api.app.example.com/"),
+            Some("AppAgent/2.0".to_owned())
+        );
+    }
+
+    #[test]
+    fn non_matching_hosts_keep_the_default_user_agent() {
+        let stack = stack_with_overrides(&[("example.com", "CompatAgent/1.0")]);
+        let default = stack_with_overrides(&[]);
+        assert_eq!(
+            user_agent_sent_to(&stack, "https://notexample.com/"),
+            user_agent_sent_to(&default, "https://notexample.com/")
+        );
+        assert_ne!(
+            user_agent_sent_to(&stack, "https://example.org/"),
+            Some("CompatAgent/1.0".to_owned())
+        );
+    }
+
+    #[test]
+    fn load_text_searches_body_case_insensitively() {
+        let listener = match TcpListener::bind("127.0.0.1:0") {
+            Ok(listener) => listener,
+            Err(error) => panic!("{error}"),
+        };
+        let port = match listener.local_addr() {
+            Ok(address) => address.port(),
+            Err(error) => panic!("{error}"),
+        };
+        let handle = thread::spawn(move || {
+            let Ok((mut stream, _)) = listener.accept() else {
+                return;
+            };
+            let mut head = Vec::new();
+            let mut byte = [0_u8; 1];
+            while !head.ends_with(b"\r\n\r\n") && stream.read_exact(&mut byte).is_ok() {
+                head.push(byte[0]);
+            }
+            let _ = stream.write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=ISO-8859-1\r\n\
+                  Content-Length: 27\r\nConnection: close\r\n\r\n<h1>Hello, Caf\xe9 World!</h1>",
+            );
+        });
+
+        let privacy = PrivacyPolicy::default();
+        let security = SecurityPolicy::default();
+        let storage =
+            StorageManager::new(StorageConfig::default(), privacy.clone(), security.clone());
+        let mut stack = NetStack::new(privacy, security, storage);
+        stack.tls_policy = StrictTlsPolicy::for_security_mode(false);
+
+        let page = match stack.load_text(&format!("http://127.0.0.1:{port}/")) {
+            Ok(page) => page,
+            Err(error) => panic!("{error}"),
+        };
+        let _ = handle.join();
+
+        assert_eq!(page.status.as_u16(), 200);
+        assert_eq!(page.body_text(), "<h1>Hello, Caf\u{e9} World!</h1>");
+        assert!(page.body_contains("hello, CAFÉ world"));
+        assert!(!page.body_contains("goodbye"));
+    }
 }
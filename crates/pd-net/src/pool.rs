@@ -1,10 +1,20 @@
 //! Connection pooling contracts.
 
+use crate::http::Header;
+use crate::tls::TlsSessionInfo;
 use crate::transport::BoxedIoStream;
 use crate::url::BrowserUrl;
 use crate::url::Scheme;
 use std::collections::HashMap;
 use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::time::Duration;
+use std::time::Instant;
+
+/// Idle connections older than this are closed instead of reused.
+pub const DEFAULT_KEEP_ALIVE_TIMEOUT: Duration = Duration::from_secs(60);
 
 /// Logical key used for pooling reusable connections.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -24,6 +34,64 @@ impl ConnectionKey {
     }
 }
 
+/// Retirement limits a server advertised via `Keep-Alive: timeout=, max=`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeepAliveHint {
+    pub timeout: Option<Duration>,
+    pub max_requests: Option<u32>,
+}
+
+impl KeepAliveHint {
+    /// Parses `Keep-Alive` headers; later parameters win and malformed ones are ignored.
+    pub fn from_headers(headers: &[Header]) -> Self {
+        let mut hint = Self::default();
+        for header in headers {
+            if !header.name.eq_ignore_ascii_case("keep-alive") {
+                continue;
+            }
+            for param in header.value.split(',') {
+                let Some((name, value)) = param.split_once('=') else {
+                    continue;
+                };
+                let value = value.trim().trim_matches('"');
+                match name.trim().to_ascii_lowercase().as_str() {
+                    "timeout" => {
+                        if let Ok(seconds) = value.parse::<u64>() {
+                            hint.timeout = Some(Duration::from_secs(seconds));
+                        }
+                    }
+                    "max" => {
+                        if let Ok(max) = value.parse::<u32>() {
+                            hint.max_requests = Some(max);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        hint
+    }
+}
+
+/// A pooled stream plus the reuse bookkeeping needed to retire it.
+pub struct PooledConnection {
+    pub stream: BoxedIoStream,
+    pub requests_served: u32,
+    pub keep_alive: KeepAliveHint,
+    pub tls: Option<TlsSessionInfo>,
+}
+
+impl PooledConnection {
+    pub fn new(stream: BoxedIoStream) -> Self {
+        Self {
+            stream,
+            requests_served: 0,
+            keep_alive: KeepAliveHint::default(),
+            tls: None,
+        }
+    }
+}
+
 /// Pool telemetry contract.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PoolStats {
@@ -33,25 +101,56 @@ pub struct PoolStats {
 
 /// Connection pool contract used by network clients.
 pub trait ConnectionPool {
-    fn checkout(&mut self, key: &ConnectionKey) -> Option<BoxedIoStream>;
-    fn checkin(&mut self, key: ConnectionKey, stream: BoxedIoStream);
+    fn checkout(&mut self, key: &ConnectionKey) -> Option<PooledConnection>;
+    fn checkin(&mut self, key: ConnectionKey, connection: PooledConnection);
     fn clear(&mut self);
     fn stats(&self) -> PoolStats;
 }
 
-/// In-memory idle connection pool with per-origin cap.
+/// In-memory idle connection pool with per-origin cap and keep-alive retirement.
 pub struct InMemoryConnectionPool {
     max_idle_per_key: usize,
-    idle: HashMap<ConnectionKey, VecDeque<BoxedIoStream>>,
+    keep_alive_timeout: Option<Duration>,
+    max_requests_per_connection: Option<u32>,
+    idle: HashMap<ConnectionKey, VecDeque<IdleConnection>>,
+}
+
+struct IdleConnection {
+    connection: PooledConnection,
+    idle_since: Instant,
 }
 
 impl InMemoryConnectionPool {
     pub fn new(max_idle_per_key: usize) -> Self {
         Self {
             max_idle_per_key,
+            keep_alive_timeout: Some(DEFAULT_KEEP_ALIVE_TIMEOUT),
+            max_requests_per_connection: None,
             idle: HashMap::new(),
         }
     }
+
+    /// Closes connections idle for longer than `timeout`; `None` keeps them indefinitely.
+    ///
+    /// A shorter server-advertised `Keep-Alive: timeout=` takes precedence.
+    pub fn set_keep_alive_timeout(&mut self, timeout: Option<Duration>) {
+        self.keep_alive_timeout = timeout;
+    }
+
+    /// Retires a connection once it has served `max` requests; `None` means unlimited.
+    ///
+    /// A lower server-advertised `Keep-Alive: max=` takes precedence.
+    pub fn set_max_requests_per_connection(&mut self, max: Option<u32>) {
+        self.max_requests_per_connection = max;
+    }
+
+    fn request_limit(&self, hint: &KeepAliveHint) -> Option<u32> {
+        min_option(self.max_requests_per_connection, hint.max_requests)
+    }
+
+    fn idle_timeout(&self, hint: &KeepAliveHint) -> Option<Duration> {
+        min_option(self.keep_alive_timeout, hint.timeout)
+    }
 }
 
 impl Default for InMemoryConnectionPool {
@@ -61,24 +160,43 @@ impl Default for InMemoryConnectionPool {
 }
 
 impl ConnectionPool for InMemoryConnectionPool {
-    fn checkout(&mut self, key: &ConnectionKey) -> Option<BoxedIoStream> {
-        let queue = self.idle.get_mut(key)?;
-        let stream = queue.pop_front();
+    fn checkout(&mut self, key: &ConnectionKey) -> Option<PooledConnection> {
+        let mut queue = self.idle.remove(key)?;
+        let mut connection = None;
+        while let Some(idle) = queue.pop_front() {
+            let expired = self
+                .idle_timeout(&idle.connection.keep_alive)
+                .is_some_and(|timeout| idle.idle_since.elapsed() >= timeout);
+            if !expired {
+                connection = Some(idle.connection);
+                break;
+            }
+        }
 
-        if queue.is_empty() {
-            self.idle.remove(key);
+        if !queue.is_empty() {
+            self.idle.insert(key.clone(), queue);
         }
 
-        stream
+        connection
     }
 
-    fn checkin(&mut self, key: ConnectionKey, stream: BoxedIoStream) {
+    fn checkin(&mut self, key: ConnectionKey, connection: PooledConnection) {
+        if self
+            .request_limit(&connection.keep_alive)
+            .is_some_and(|limit| connection.requests_served >= limit)
+        {
+            return;
+        }
+
         let queue = self.idle.entry(key).or_default();
         if queue.len() >= self.max_idle_per_key {
             return;
         }
 
-        queue.push_back(stream);
+        queue.push_back(IdleConnection {
+            connection,
+            idle_since: Instant::now(),
+        });
     }
 
     fn clear(&mut self) {
@@ -94,15 +212,78 @@ impl ConnectionPool for InMemoryConnectionPool {
     }
 }
 
+/// Cloneable handle to one pool, so clients on several threads reuse the same idle connections.
+#[derive(Default)]
+pub struct SharedConnectionPool<P = InMemoryConnectionPool> {
+    inner: Arc<Mutex<P>>,
+}
+
+impl<P> Clone for SharedConnectionPool<P> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<P> SharedConnectionPool<P> {
+    pub fn new(pool: P) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(pool)),
+        }
+    }
+}
+
+impl<P: ConnectionPool> ConnectionPool for SharedConnectionPool<P> {
+    fn checkout(&mut self, key: &ConnectionKey) -> Option<PooledConnection> {
+        self.inner
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .checkout(key)
+    }
+
+    fn checkin(&mut self, key: ConnectionKey, connection: PooledConnection) {
+        self.inner
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .checkin(key, connection);
+    }
+
+    fn clear(&mut self) {
+        self.inner
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    fn stats(&self) -> PoolStats {
+        self.inner
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .stats()
+    }
+}
+
+fn min_option<T: Ord>(left: Option<T>, right: Option<T>) -> Option<T> {
+    match (left, right) {
+        (Some(left), Some(right)) => Some(left.min(right)),
+        (left, right) => left.or(right),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::ConnectionKey;
     use super::ConnectionPool;
     use super::InMemoryConnectionPool;
-    use crate::transport::BoxedIoStream;
+    use super::KeepAliveHint;
+    use super::PooledConnection;
+    use super::SharedConnectionPool;
+    use crate::http::Header;
     use crate::url::BrowserUrl;
     use std::io::Read;
     use std::io::Write;
+    use std::time::Duration;
 
     struct StubStream;
 
@@ -122,8 +303,16 @@ mod tests {
         }
     }
 
-    fn boxed_stub_stream() -> BoxedIoStream {
-        Box::new(StubStream)
+    fn stub_connection() -> PooledConnection {
+        PooledConnection::new(Box::new(StubStream))
+    }
+
+    fn example_key() -> ConnectionKey {
+        ConnectionKey {
+            scheme: crate::url::Scheme::Https,
+            host: "example.com".to_owned(),
+            port: 443,
+        }
     }
 
     #[test]
@@ -136,8 +325,8 @@ mod tests {
         });
 
         let mut pool = InMemoryConnectionPool::new(2);
-        pool.checkin(key.clone(), boxed_stub_stream());
-        pool.checkin(key.clone(), boxed_stub_stream());
+        pool.checkin(key.clone(), stub_connection());
+        pool.checkin(key.clone(), stub_connection());
 
         let stats = pool.stats();
         assert_eq!(stats.keys, 1);
@@ -148,6 +337,17 @@ mod tests {
         assert!(pool.checkout(&key).is_none());
     }
 
+    #[test]
+    fn shared_pool_handles_see_the_same_idle_connections() {
+        let mut first = SharedConnectionPool::new(InMemoryConnectionPool::new(2));
+        let mut second = first.clone();
+
+        first.checkin(example_key(), stub_connection());
+        assert_eq!(second.stats().idle_connections, 1);
+        assert!(second.checkout(&example_key()).is_some());
+        assert!(first.checkout(&example_key()).is_none());
+    }
+
     #[test]
     fn respects_per_key_limit() {
         let mut pool = InMemoryConnectionPool::new(1);
@@ -157,10 +357,68 @@ mod tests {
             port: 443,
         };
 
-        pool.checkin(key.clone(), boxed_stub_stream());
-        pool.checkin(key.clone(), boxed_stub_stream());
+        pool.checkin(key.clone(), stub_connection());
+        pool.checkin(key.clone(), stub_connection());
 
         let stats = pool.stats();
         assert_eq!(stats.idle_connections, 1);
     }
+
+    #[test]
+    fn retires_connection_after_max_requests() {
+        let mut pool = InMemoryConnectionPool::new(4);
+        pool.set_max_requests_per_connection(Some(2));
+        let key = example_key();
+
+        let mut connection = stub_connection();
+        connection.requests_served = 1;
+        pool.checkin(key.clone(), connection);
+        let Some(mut connection) = pool.checkout(&key) else {
+            panic!("connection below the request limit should be pooled");
+        };
+
+        connection.requests_served += 1;
+        pool.checkin(key.clone(), connection);
+        assert_eq!(pool.stats().idle_connections, 0);
+        assert!(pool.checkout(&key).is_none());
+    }
+
+    #[test]
+    fn retires_connection_after_idle_timeout() {
+        let mut pool = InMemoryConnectionPool::new(4);
+        pool.set_keep_alive_timeout(Some(Duration::from_millis(10)));
+        let key = example_key();
+
+        pool.checkin(key.clone(), stub_connection());
+        std::thread::sleep(Duration::from_millis(30));
+
+        assert!(pool.checkout(&key).is_none());
+        assert_eq!(pool.stats().keys, 0);
+    }
+
+    #[test]
+    fn honors_lower_server_advertised_keep_alive_max() {
+        let header = match Header::new("Keep-Alive", "timeout=5, max=1") {
+            Ok(value) => value,
+            Err(error) => panic!("{error}"),
+        };
+        let hint = KeepAliveHint::from_headers(&[header]);
+        assert_eq!(
+            hint,
+            KeepAliveHint {
+                timeout: Some(Duration::from_secs(5)),
+                max_requests: Some(1),
+            }
+        );
+
+        let mut pool = InMemoryConnectionPool::new(4);
+        pool.set_max_requests_per_connection(Some(100));
+        let key = example_key();
+        let mut connection = stub_connection();
+        connection.requests_served = 1;
+        connection.keep_alive = hint;
+        pool.checkin(key.clone(), connection);
+
+        assert!(pool.checkout(&key).is_none());
+    }
 }
@@ -31,6 +31,36 @@ pub enum TrustStoreMode {
     WebPkiAndOs,
 }
 
+/// Root store whose trust anchor a verified certificate chain ends in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrustAnchorSource {
+    /// One of the embedded Mozilla/WebPKI roots.
+    WebPki,
+    /// A root only the operating-system store trusts (enterprise/local CAs).
+    OperatingSystem,
+}
+
+impl TrustAnchorSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::WebPki => "WebPKI roots",
+            Self::OperatingSystem => "OS roots",
+        }
+    }
+}
+
+/// Details of a completed TLS handshake, surfaced for page security UI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsSessionInfo {
+    pub version: TlsVersion,
+    /// Store that validated the server's chain; WebPKI roots are tried first.
+    pub trust_anchor: TrustAnchorSource,
+    /// Whether the server stapled an OCSP response; the response itself is not validated.
+    pub ocsp_stapled: bool,
+    /// Common name of the leaf certificate's subject, when present.
+    pub certificate_subject: Option<String>,
+}
+
 /// TLS handshake requirements for HTTPS requests.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TlsHandshakeConfig {
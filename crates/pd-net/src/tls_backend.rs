@@ -1,15 +1,19 @@
 //! TLS backend adapter contracts and rustls implementation.
 
 use crate::tls::TlsHandshakeConfig;
+use crate::tls::TlsSessionInfo;
 use crate::tls::TrustStoreMode;
 use crate::transport::BoxedIoStream;
 use pd_core::BrowserError;
 use pd_core::BrowserResult;
+use pd_core::ResultExt;
 use std::net::TcpStream;
 
 #[cfg(feature = "tls-rustls")]
 use crate::tls::TlsVersion;
 #[cfg(feature = "tls-rustls")]
+use crate::tls::TrustAnchorSource;
+#[cfg(feature = "tls-rustls")]
 use rustls::DigitallySignedStruct;
 #[cfg(feature = "tls-rustls")]
 use rustls::Error as RustlsError;
@@ -35,6 +39,16 @@ use rustls::pki_types::ServerName;
 use rustls::pki_types::UnixTime;
 #[cfg(feature = "tls-rustls")]
 use std::sync::Arc;
+#[cfg(feature = "tls-rustls")]
+use std::sync::atomic::AtomicBool;
+#[cfg(feature = "tls-rustls")]
+use std::sync::atomic::Ordering;
+
+/// An established TLS stream and what its handshake negotiated.
+pub struct TlsConnection {
+    pub stream: BoxedIoStream,
+    pub session: TlsSessionInfo,
+}
 
 /// Adapter contract for upgrading TCP transport to TLS.
 pub trait TlsBackendAdapter {
@@ -43,7 +57,7 @@ pub trait TlsBackendAdapter {
         stream: TcpStream,
         handshake: &TlsHandshakeConfig,
         tls_policy: &crate::tls::StrictTlsPolicy,
-    ) -> BrowserResult<BoxedIoStream>;
+    ) -> BrowserResult<TlsConnection>;
 }
 
 /// rustls-backed TLS connector.
@@ -57,39 +71,43 @@ impl TlsBackendAdapter for RustlsTlsAdapter {
         mut stream: TcpStream,
         handshake: &TlsHandshakeConfig,
         tls_policy: &crate::tls::StrictTlsPolicy,
-    ) -> BrowserResult<BoxedIoStream> {
+    ) -> BrowserResult<TlsConnection> {
         use rustls::ClientConfig;
         use rustls::ClientConnection;
         use rustls::StreamOwned;
 
         let versions = supported_versions(handshake.minimum_version, handshake.maximum_version)?;
         let provider = Arc::new(rustls::crypto::aws_lc_rs::default_provider());
-        let roots = Arc::new(system_root_store(tls_policy)?);
-        let base_verifier = WebPkiServerVerifier::builder_with_provider(roots, provider.clone())
-            .build()
-            .map_err(|error| {
-                BrowserError::new(
+        let build_verifier = |roots: RootCertStore| {
+            WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+                .build()
+                .context(
                     "net.tls.verifier_build_failed",
-                    format!("failed to build rustls verifier: {error}"),
+                    "failed to build rustls verifier",
                 )
-            })?;
-
-        let verifier: Arc<dyn ServerCertVerifier> = if handshake.require_ocsp_stapling {
-            Arc::new(OcspRequiredVerifier {
-                inner: base_verifier,
-            })
-        } else {
-            base_verifier
+        };
+        let webpki = build_verifier(webpki_root_store())?;
+        let with_os_roots = match tls_policy.trust_store_mode {
+            TrustStoreMode::WebPkiOnly => None,
+            TrustStoreMode::WebPkiAndOs => Some(build_verifier(system_root_store(tls_policy)?)?),
         };
 
+        let ocsp_stapled = Arc::new(AtomicBool::new(false));
+        let os_anchor = Arc::new(AtomicBool::new(false));
+        let verifier: Arc<dyn ServerCertVerifier> = Arc::new(SessionRecordingVerifier {
+            webpki,
+            with_os_roots,
+            require_ocsp_stapling: handshake.require_ocsp_stapling,
+            ocsp_stapled: Arc::clone(&ocsp_stapled),
+            os_anchor: Arc::clone(&os_anchor),
+        });
+
         let mut config = ClientConfig::builder_with_provider(provider)
             .with_protocol_versions(&versions)
-            .map_err(|error| {
-                BrowserError::new(
-                    "net.tls.config_versions_invalid",
-                    format!("failed to configure TLS protocol versions: {error}"),
-                )
-            })?
+            .context(
+                "net.tls.config_versions_invalid",
+                "failed to configure TLS protocol versions",
+            )?
             .dangerous()
             .with_custom_certificate_verifier(verifier)
             .with_no_client_auth();
@@ -100,50 +118,73 @@ impl TlsBackendAdapter for RustlsTlsAdapter {
             .map(|value| value.as_bytes().to_vec())
             .collect();
 
-        let server_name = ServerName::try_from(handshake.server_name.clone()).map_err(|error| {
-            BrowserError::new(
-                "net.tls.server_name_invalid",
-                format!(
-                    "invalid TLS server name `{}`: {error}",
-                    handshake.server_name
-                ),
-            )
-        })?;
-
-        let mut connection =
-            ClientConnection::new(Arc::new(config), server_name).map_err(|error| {
-                BrowserError::new(
-                    "net.tls.connection_init_failed",
-                    format!(
-                        "failed to initialize TLS connection for `{}`: {error}",
-                        handshake.server_name
-                    ),
-                )
-            })?;
-
-        connection.complete_io(&mut stream).map_err(|error| {
-            BrowserError::new(
-                "net.tls.handshake_failed",
-                format!(
-                    "TLS handshake failed for `{}`: {error}",
-                    handshake.server_name
-                ),
-            )
-        })?;
+        let server_name = ServerName::try_from(handshake.server_name.clone()).context(
+            "net.tls.server_name_invalid",
+            format!("invalid TLS server name `{}`", handshake.server_name),
+        )?;
+
+        let mut connection = ClientConnection::new(Arc::new(config), server_name).context(
+            "net.tls.connection_init_failed",
+            format!(
+                "failed to initialize TLS connection for `{}`",
+                handshake.server_name
+            ),
+        )?;
+
+        connection.complete_io(&mut stream).context(
+            "net.tls.handshake_failed",
+            format!("TLS handshake failed for `{}`", handshake.server_name),
+        )?;
+
+        let version = match connection.protocol_version() {
+            Some(rustls::ProtocolVersion::TLSv1_3) => TlsVersion::V1_3,
+            Some(rustls::ProtocolVersion::TLSv1_2) => TlsVersion::V1_2,
+            other => {
+                return Err(BrowserError::new(
+                    "net.tls.version_unknown",
+                    format!("handshake negotiated unsupported protocol {other:?}"),
+                ));
+            }
+        };
+        let session = TlsSessionInfo {
+            version,
+            trust_anchor: if os_anchor.load(Ordering::Relaxed) {
+                TrustAnchorSource::OperatingSystem
+            } else {
+                TrustAnchorSource::WebPki
+            },
+            ocsp_stapled: ocsp_stapled.load(Ordering::Relaxed),
+            certificate_subject: connection
+                .peer_certificates()
+                .and_then(|chain| chain.first())
+                .and_then(|leaf| certificate_common_name(leaf.as_ref())),
+        };
 
         let stream = StreamOwned::new(connection, stream);
-        Ok(Box::new(stream))
+        Ok(TlsConnection {
+            stream: Box::new(stream),
+            session,
+        })
     }
 }
 
+/// Verifies the server chain against WebPKI roots first, then against the OS-merged store
+/// when one is configured, recording which one accepted it and whether OCSP was stapled.
+///
+/// A stapled OCSP response is only checked for presence; its contents are not validated.
 #[cfg(feature = "tls-rustls")]
 #[derive(Debug)]
-struct OcspRequiredVerifier {
-    inner: Arc<WebPkiServerVerifier>,
+struct SessionRecordingVerifier {
+    webpki: Arc<WebPkiServerVerifier>,
+    /// Trusts WebPKI and operating-system roots; `None` in `WebPkiOnly` mode.
+    with_os_roots: Option<Arc<WebPkiServerVerifier>>,
+    require_ocsp_stapling: bool,
+    ocsp_stapled: Arc<AtomicBool>,
+    os_anchor: Arc<AtomicBool>,
 }
 
 #[cfg(feature = "tls-rustls")]
-impl ServerCertVerifier for OcspRequiredVerifier {
+impl ServerCertVerifier for SessionRecordingVerifier {
     fn verify_server_cert(
         &self,
         end_entity: &CertificateDer<'_>,
@@ -152,14 +193,35 @@ impl ServerCertVerifier for OcspRequiredVerifier {
         ocsp_response: &[u8],
         now: UnixTime,
     ) -> Result<ServerCertVerified, RustlsError> {
-        if ocsp_response.is_empty() {
+        self.ocsp_stapled
+            .store(!ocsp_response.is_empty(), Ordering::Relaxed);
+        if self.require_ocsp_stapling && ocsp_response.is_empty() {
             return Err(RustlsError::General(
                 "missing required OCSP stapling response".to_owned(),
             ));
         }
 
-        self.inner
-            .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+        let webpki = self.webpki.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        );
+        match (webpki, &self.with_os_roots) {
+            (Err(_), Some(with_os_roots)) => {
+                let verified = with_os_roots.verify_server_cert(
+                    end_entity,
+                    intermediates,
+                    server_name,
+                    ocsp_response,
+                    now,
+                )?;
+                self.os_anchor.store(true, Ordering::Relaxed);
+                Ok(verified)
+            }
+            (result, _) => result,
+        }
     }
 
     fn verify_tls12_signature(
@@ -168,7 +230,7 @@ impl ServerCertVerifier for OcspRequiredVerifier {
         cert: &CertificateDer<'_>,
         dss: &DigitallySignedStruct,
     ) -> Result<HandshakeSignatureValid, RustlsError> {
-        self.inner.verify_tls12_signature(message, cert, dss)
+        self.webpki.verify_tls12_signature(message, cert, dss)
     }
 
     fn verify_tls13_signature(
@@ -177,18 +239,24 @@ impl ServerCertVerifier for OcspRequiredVerifier {
         cert: &CertificateDer<'_>,
         dss: &DigitallySignedStruct,
     ) -> Result<HandshakeSignatureValid, RustlsError> {
-        self.inner.verify_tls13_signature(message, cert, dss)
+        self.webpki.verify_tls13_signature(message, cert, dss)
     }
 
     fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
-        self.inner.supported_verify_schemes()
+        self.webpki.supported_verify_schemes()
     }
 }
 
 #[cfg(feature = "tls-rustls")]
-fn system_root_store(tls_policy: &crate::tls::StrictTlsPolicy) -> BrowserResult<RootCertStore> {
+fn webpki_root_store() -> RootCertStore {
     let mut roots = RootCertStore::empty();
     roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    roots
+}
+
+#[cfg(feature = "tls-rustls")]
+fn system_root_store(tls_policy: &crate::tls::StrictTlsPolicy) -> BrowserResult<RootCertStore> {
+    let mut roots = webpki_root_store();
 
     if matches!(tls_policy.trust_store_mode, TrustStoreMode::WebPkiAndOs) {
         let native = rustls_native_certs::load_native_certs();
@@ -206,12 +274,10 @@ fn system_root_store(tls_policy: &crate::tls::StrictTlsPolicy) -> BrowserResult<
         }
 
         for cert in native.certs {
-            roots.add(cert).map_err(|error| {
-                BrowserError::new(
-                    "net.tls.os_root_add_failed",
-                    format!("failed to add operating-system root: {error}"),
-                )
-            })?;
+            roots.add(cert).context(
+                "net.tls.os_root_add_failed",
+                "failed to add operating-system root",
+            )?;
         }
     }
 
@@ -225,6 +291,68 @@ fn system_root_store(tls_policy: &crate::tls::StrictTlsPolicy) -> BrowserResult<
     Ok(roots)
 }
 
+/// Extracts the subject `commonName` from a DER-encoded X.509 certificate.
+///
+/// This is a minimal walk over the certificate's outer structure, not a full X.509 parser:
+/// it only reads definite lengths of at most four bytes, returns the first `commonName` of
+/// the subject, decodes its string type as (lossy) UTF-8 whatever the tag says, and neither
+/// consults `subjectAltName` nor validates anything else. Use it for display only.
+#[cfg(feature = "tls-rustls")]
+fn certificate_common_name(certificate: &[u8]) -> Option<String> {
+    const COMMON_NAME_OID: &[u8] = &[0x55, 0x04, 0x03];
+
+    let (certificate, _) = der_element(certificate, 0x30)?;
+    let (tbs, _) = der_element(certificate, 0x30)?;
+    let mut fields = tbs;
+    if fields.first() == Some(&0xA0) {
+        fields = der_element(fields, 0xA0)?.1;
+    }
+    // serialNumber, signature, issuer and validity precede the subject.
+    for tag in [0x02, 0x30, 0x30, 0x30] {
+        fields = der_element(fields, tag)?.1;
+    }
+    let (mut names, _) = der_element(fields, 0x30)?;
+    while !names.is_empty() {
+        let (set, rest) = der_element(names, 0x31)?;
+        names = rest;
+        let (attribute, _) = der_element(set, 0x30)?;
+        let (oid, value) = der_element(attribute, 0x06)?;
+        if oid == COMMON_NAME_OID {
+            let (value, _) = der_element(value, *value.first()?)?;
+            return Some(String::from_utf8_lossy(value).into_owned());
+        }
+    }
+    None
+}
+
+/// Splits one DER element with `tag` off `input` as `(contents, rest)`.
+#[cfg(feature = "tls-rustls")]
+fn der_element(input: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+    let (&actual, input) = input.split_first()?;
+    if actual != tag {
+        return None;
+    }
+    let (&first, mut input) = input.split_first()?;
+    let length = if first < 0x80 {
+        usize::from(first)
+    } else {
+        let count = usize::from(first & 0x7F);
+        if count == 0 || count > 4 || input.len() < count {
+            return None;
+        }
+        let (bytes, rest) = input.split_at(count);
+        input = rest;
+        bytes
+            .iter()
+            .fold(0_usize, |length, byte| (length << 8) | usize::from(*byte))
+    };
+    if input.len() < length {
+        return None;
+    }
+    let (contents, rest) = input.split_at(length);
+    Some((contents, rest))
+}
+
 #[cfg(feature = "tls-rustls")]
 fn to_rustls_version(version: TlsVersion) -> &'static SupportedProtocolVersion {
     match version {
@@ -264,7 +392,7 @@ impl TlsBackendAdapter for RustlsTlsAdapter {
         _stream: TcpStream,
         _handshake: &TlsHandshakeConfig,
         _tls_policy: &crate::tls::StrictTlsPolicy,
-    ) -> BrowserResult<BoxedIoStream> {
+    ) -> BrowserResult<TlsConnection> {
         Err(BrowserError::new(
             "net.tls.backend_unavailable",
             "rustls backend is disabled for this build; enable `pd-net/tls-rustls`",
@@ -274,6 +402,8 @@ impl TlsBackendAdapter for RustlsTlsAdapter {
 
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "tls-rustls")]
+    use super::certificate_common_name;
     use crate::tls::TlsVersion;
 
     #[test]
@@ -282,4 +412,49 @@ mod tests {
         let maximum = TlsVersion::V1_2;
         assert!(minimum > maximum);
     }
+
+    #[cfg(feature = "tls-rustls")]
+    fn der(tag: u8, parts: &[&[u8]]) -> Vec<u8> {
+        let contents = parts.concat();
+        let mut out = vec![tag, u8::try_from(contents.len()).unwrap_or(u8::MAX)];
+        out.extend_from_slice(&contents);
+        out
+    }
+
+    #[cfg(feature = "tls-rustls")]
+    #[test]
+    fn extracts_subject_common_name_from_certificate() {
+        let attribute = |oid: &[u8], value: &[u8]| {
+            der(
+                0x31,
+                &[&der(0x30, &[&der(0x06, &[oid]), &der(0x0C, &[value])])],
+            )
+        };
+        let issuer = der(0x30, &[&attribute(&[0x55, 0x04, 0x03], b"Example CA")]);
+        let subject = der(
+            0x30,
+            &[
+                &attribute(&[0x55, 0x04, 0x0A], b"Example Org"),
+                &attribute(&[0x55, 0x04, 0x03], b"example.test"),
+            ],
+        );
+        let tbs = der(
+            0x30,
+            &[
+                &der(0xA0, &[&der(0x02, &[&[2]])]),
+                &der(0x02, &[&[1]]),
+                &der(0x30, &[]),
+                &issuer,
+                &der(0x30, &[]),
+                &subject,
+            ],
+        );
+        let certificate = der(0x30, &[&tbs, &der(0x30, &[]), &der(0x03, &[&[0]])]);
+
+        assert_eq!(
+            certificate_common_name(&certificate).as_deref(),
+            Some("example.test")
+        );
+        assert_eq!(certificate_common_name(&certificate[..10]), None);
+    }
 }
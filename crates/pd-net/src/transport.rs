@@ -2,15 +2,27 @@
 
 use pd_core::BrowserError;
 use pd_core::BrowserResult;
+use pd_core::ResultExt;
 use std::io::Read;
 use std::io::Write;
+use std::net::IpAddr;
 use std::net::SocketAddr;
 use std::net::TcpStream;
 use std::time::Duration;
 
+const MAX_PROXY_RESPONSE_HEAD_BYTES: usize = 8 * 1024;
+const SOCKS5_VERSION: u8 = 0x05;
+const SOCKS5_NO_AUTH: u8 = 0x00;
+const SOCKS5_USERNAME_PASSWORD: u8 = 0x02;
+const SOCKS5_NO_ACCEPTABLE_METHODS: u8 = 0xff;
+const SOCKS5_CONNECT: u8 = 0x01;
+const SOCKS5_ATYP_IPV4: u8 = 0x01;
+const SOCKS5_ATYP_DOMAIN: u8 = 0x03;
+const SOCKS5_ATYP_IPV6: u8 = 0x04;
+
 /// Trait-object-safe stream used by HTTP client and pool contracts.
-pub trait IoStream: Read + Write {}
-impl<T> IoStream for T where T: Read + Write {}
+pub trait IoStream: Read + Write + Send {}
+impl<T> IoStream for T where T: Read + Write + Send {}
 
 pub type BoxedIoStream = Box<dyn IoStream>;
 
@@ -25,34 +37,475 @@ pub struct TcpTransport;
 
 impl Transport for TcpTransport {
     fn connect(&self, address: SocketAddr, timeout: Duration) -> BrowserResult<TcpStream> {
-        let stream = TcpStream::connect_timeout(&address, timeout).map_err(|error| {
-            BrowserError::new(
-                "net.transport.connect_failed",
-                format!("failed to connect to `{address}`: {error}"),
-            )
-        })?;
+        let stream = TcpStream::connect_timeout(&address, timeout).context(
+            "net.transport.connect_failed",
+            format!("failed to connect to `{address}`"),
+        )?;
 
-        stream.set_nodelay(true).map_err(|error| {
-            BrowserError::new(
-                "net.transport.nodelay_failed",
-                format!("failed to enable TCP_NODELAY for `{address}`: {error}"),
-            )
-        })?;
+        stream.set_nodelay(true).context(
+            "net.transport.nodelay_failed",
+            format!("failed to enable TCP_NODELAY for `{address}`"),
+        )?;
 
-        stream.set_read_timeout(Some(timeout)).map_err(|error| {
-            BrowserError::new(
-                "net.transport.read_timeout_failed",
-                format!("failed to set read timeout for `{address}`: {error}"),
-            )
-        })?;
+        stream.set_read_timeout(Some(timeout)).context(
+            "net.transport.read_timeout_failed",
+            format!("failed to set read timeout for `{address}`"),
+        )?;
 
-        stream.set_write_timeout(Some(timeout)).map_err(|error| {
-            BrowserError::new(
-                "net.transport.write_timeout_failed",
-                format!("failed to set write timeout for `{address}`: {error}"),
-            )
-        })?;
+        stream.set_write_timeout(Some(timeout)).context(
+            "net.transport.write_timeout_failed",
+            format!("failed to set write timeout for `{address}`"),
+        )?;
 
         Ok(stream)
     }
 }
+
+/// Proxy protocol used to reach origin servers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyKind {
+    Http,
+    Socks5,
+}
+
+/// Credentials presented to the proxy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyAuth {
+    pub username: String,
+    pub password: String,
+}
+
+/// Upstream proxy that connections are routed through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proxy {
+    pub kind: ProxyKind,
+    pub host: String,
+    pub port: u16,
+    pub auth: Option<ProxyAuth>,
+}
+
+impl Proxy {
+    pub fn http(host: &str, port: u16) -> Self {
+        Self {
+            kind: ProxyKind::Http,
+            host: host.to_owned(),
+            port,
+            auth: None,
+        }
+    }
+
+    pub fn socks5(host: &str, port: u16) -> Self {
+        Self {
+            kind: ProxyKind::Socks5,
+            host: host.to_owned(),
+            port,
+            auth: None,
+        }
+    }
+
+    pub fn with_auth(mut self, username: &str, password: &str) -> Self {
+        self.auth = Some(ProxyAuth {
+            username: username.to_owned(),
+            password: password.to_owned(),
+        });
+        self
+    }
+
+    /// Plain HTTP through an HTTP proxy is forwarded in absolute form; everything else tunnels.
+    pub fn tunnels(&self, secure: bool) -> bool {
+        secure || self.kind == ProxyKind::Socks5
+    }
+
+    /// `Proxy-Authorization` value for HTTP proxies with credentials.
+    pub fn basic_authorization(&self) -> Option<String> {
+        self.auth.as_ref().map(|auth| {
+            format!(
+                "Basic {}",
+                base64_encode(format!("{}:{}", auth.username, auth.password).as_bytes())
+            )
+        })
+    }
+}
+
+/// Asks an already-connected proxy to open a byte tunnel to `host:port`.
+pub fn establish_proxy_tunnel(
+    stream: &mut dyn IoStream,
+    proxy: &Proxy,
+    host: &str,
+    port: u16,
+) -> BrowserResult<()> {
+    match proxy.kind {
+        ProxyKind::Http => http_connect_tunnel(stream, proxy, host, port),
+        ProxyKind::Socks5 => socks5_connect_tunnel(stream, proxy, host, port),
+    }
+}
+
+fn proxy_error(message: impl Into<String>) -> BrowserError {
+    BrowserError::new("net.proxy_failed", message)
+}
+
+fn http_connect_tunnel(
+    stream: &mut dyn IoStream,
+    proxy: &Proxy,
+    host: &str,
+    port: u16,
+) -> BrowserResult<()> {
+    let authority = if host.contains(':') {
+        format!("[{host}]:{port}")
+    } else {
+        format!("{host}:{port}")
+    };
+    let mut request = format!("CONNECT {authority} HTTP/1.1\r\nHost: {authority}\r\n");
+    if let Some(credentials) = proxy.basic_authorization() {
+        request.push_str(&format!("Proxy-Authorization: {credentials}\r\n"));
+    }
+    request.push_str("\r\n");
+    write_proxy_bytes(stream, request.as_bytes())?;
+
+    // Read one byte at a time so no tunneled bytes are consumed with the head.
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        if head.len() >= MAX_PROXY_RESPONSE_HEAD_BYTES {
+            return Err(proxy_error(format!(
+                "proxy CONNECT response head exceeds {MAX_PROXY_RESPONSE_HEAD_BYTES} bytes"
+            )));
+        }
+        let mut byte = [0_u8; 1];
+        read_proxy_bytes(stream, &mut byte)?;
+        head.push(byte[0]);
+    }
+
+    let head = String::from_utf8_lossy(&head);
+    let status_line = head.lines().next().unwrap_or_default();
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok());
+    match status {
+        Some(code) if (200..300).contains(&code) => Ok(()),
+        Some(code) => Err(proxy_error(format!(
+            "proxy refused CONNECT to `{authority}` with status {code}"
+        ))),
+        None => Err(proxy_error(format!(
+            "proxy sent an invalid CONNECT status line `{status_line}`"
+        ))),
+    }
+}
+
+fn socks5_connect_tunnel(
+    stream: &mut dyn IoStream,
+    proxy: &Proxy,
+    host: &str,
+    port: u16,
+) -> BrowserResult<()> {
+    let greeting: &[u8] = if proxy.auth.is_some() {
+        &[SOCKS5_VERSION, 2, SOCKS5_NO_AUTH, SOCKS5_USERNAME_PASSWORD]
+    } else {
+        &[SOCKS5_VERSION, 1, SOCKS5_NO_AUTH]
+    };
+    write_proxy_bytes(stream, greeting)?;
+
+    let mut choice = [0_u8; 2];
+    read_proxy_bytes(stream, &mut choice)?;
+    if choice[0] != SOCKS5_VERSION {
+        return Err(proxy_error(format!(
+            "SOCKS proxy answered with version {}",
+            choice[0]
+        )));
+    }
+    match (choice[1], proxy.auth.as_ref()) {
+        (SOCKS5_NO_AUTH, _) => {}
+        (SOCKS5_USERNAME_PASSWORD, Some(auth)) => socks5_authenticate(stream, auth)?,
+        (SOCKS5_NO_ACCEPTABLE_METHODS, _) => {
+            return Err(proxy_error(
+                "SOCKS proxy rejected all offered authentication methods",
+            ));
+        }
+        (method, _) => {
+            return Err(proxy_error(format!(
+                "SOCKS proxy selected unsupported authentication method {method}"
+            )));
+        }
+    }
+
+    let mut request = vec![SOCKS5_VERSION, SOCKS5_CONNECT, 0x00];
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(address)) => {
+            request.push(SOCKS5_ATYP_IPV4);
+            request.extend_from_slice(&address.octets());
+        }
+        Ok(IpAddr::V6(address)) => {
+            request.push(SOCKS5_ATYP_IPV6);
+            request.extend_from_slice(&address.octets());
+        }
+        Err(_) => {
+            let length = u8::try_from(host.len())
+                .map_err(|_| proxy_error(format!("host `{host}` is too long for SOCKS5")))?;
+            request.push(SOCKS5_ATYP_DOMAIN);
+            request.push(length);
+            request.extend_from_slice(host.as_bytes());
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+    write_proxy_bytes(stream, &request)?;
+
+    let mut reply = [0_u8; 4];
+    read_proxy_bytes(stream, &mut reply)?;
+    if reply[1] != 0x00 {
+        return Err(proxy_error(format!(
+            "SOCKS proxy could not connect to `{host}:{port}`: {}",
+            socks5_reply_reason(reply[1])
+        )));
+    }
+    let bound_length = match reply[3] {
+        SOCKS5_ATYP_IPV4 => 4,
+        SOCKS5_ATYP_IPV6 => 16,
+        SOCKS5_ATYP_DOMAIN => {
+            let mut length = [0_u8; 1];
+            read_proxy_bytes(stream, &mut length)?;
+            usize::from(length[0])
+        }
+        other => {
+            return Err(proxy_error(format!(
+                "SOCKS proxy replied with unknown address type {other}"
+            )));
+        }
+    };
+    let mut bound = vec![0_u8; bound_length + 2];
+    read_proxy_bytes(stream, &mut bound)
+}
+
+fn socks5_authenticate(stream: &mut dyn IoStream, auth: &ProxyAuth) -> BrowserResult<()> {
+    let username = u8::try_from(auth.username.len())
+        .map_err(|_| proxy_error("SOCKS username exceeds 255 bytes"))?;
+    let password = u8::try_from(auth.password.len())
+        .map_err(|_| proxy_error("SOCKS password exceeds 255 bytes"))?;
+
+    let mut request = vec![0x01, username];
+    request.extend_from_slice(auth.username.as_bytes());
+    request.push(password);
+    request.extend_from_slice(auth.password.as_bytes());
+    write_proxy_bytes(stream, &request)?;
+
+    let mut status = [0_u8; 2];
+    read_proxy_bytes(stream, &mut status)?;
+    if status[1] != 0x00 {
+        return Err(proxy_error("SOCKS proxy rejected the supplied credentials"));
+    }
+    Ok(())
+}
+
+fn socks5_reply_reason(code: u8) -> &'static str {
+    match code {
+        0x01 => "general failure",
+        0x02 => "connection not allowed by ruleset",
+        0x03 => "network unreachable",
+        0x04 => "host unreachable",
+        0x05 => "connection refused",
+        0x06 => "TTL expired",
+        0x07 => "command not supported",
+        0x08 => "address type not supported",
+        _ => "unknown error",
+    }
+}
+
+fn write_proxy_bytes(stream: &mut dyn IoStream, bytes: &[u8]) -> BrowserResult<()> {
+    stream
+        .write_all(bytes)
+        .and_then(|()| stream.flush())
+        .map_err(|error| proxy_error(format!("failed to write to proxy: {error}")))
+}
+
+fn read_proxy_bytes(stream: &mut dyn IoStream, out: &mut [u8]) -> BrowserResult<()> {
+    stream
+        .read_exact(out)
+        .map_err(|error| proxy_error(format!("failed to read from proxy: {error}")))
+}
+
+/// Standard padded base64 (RFC 4648), as used by `Basic` credentials and `data:` URLs.
+pub fn base64_encode(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = chunk.get(1).copied().unwrap_or(0);
+        let b2 = chunk.get(2).copied().unwrap_or(0);
+        let triple = (u32::from(b0) << 16) | (u32::from(b1) << 8) | u32::from(b2);
+        for (index, shift) in [18_u32, 12, 6, 0].into_iter().enumerate() {
+            if index <= chunk.len() {
+                encoded.push(char::from(ALPHABET[((triple >> shift) & 0x3f) as usize]));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Proxy;
+    use super::base64_encode;
+    use super::establish_proxy_tunnel;
+    use std::io::Read;
+    use std::io::Write;
+    use std::net::TcpListener;
+    use std::net::TcpStream;
+    use std::thread;
+
+    fn stub_proxy(
+        handler: impl FnOnce(TcpStream) + Send + 'static,
+    ) -> (u16, thread::JoinHandle<()>) {
+        let listener = match TcpListener::bind("127.0.0.1:0") {
+            Ok(listener) => listener,
+            Err(error) => panic!("{error}"),
+        };
+        let port = match listener.local_addr() {
+            Ok(address) => address.port(),
+            Err(error) => panic!("{error}"),
+        };
+        let handle = thread::spawn(move || {
+            if let Ok((stream, _)) = listener.accept() {
+                handler(stream);
+            }
+        });
+        (port, handle)
+    }
+
+    fn read_head(stream: &mut TcpStream) -> String {
+        let mut head = Vec::new();
+        let mut byte = [0_u8; 1];
+        while !head.ends_with(b"\r\n\r\n") && stream.read_exact(&mut byte).is_ok() {
+            head.push(byte[0]);
+        }
+        String::from_utf8_lossy(&head).into_owned()
+    }
+
+    fn connect(port: u16) -> TcpStream {
+        match TcpStream::connect(("127.0.0.1", port)) {
+            Ok(stream) => stream,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    #[test]
+    fn encodes_basic_credentials() {
+        assert_eq!(base64_encode(b"user:pass"), "dXNlcjpwYXNz");
+        assert_eq!(base64_encode(b"ab"), "YWI=");
+        assert_eq!(base64_encode(b"a"), "YQ==");
+    }
+
+    #[test]
+    fn base64_matches_rfc_4648_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (input, expected) in vectors {
+            assert_eq!(base64_encode(input.as_bytes()), expected);
+        }
+    }
+
+    #[test]
+    fn http_connect_tunnel_forwards_bytes_after_handshake() {
+        let (port, handle) = stub_proxy(|mut stream| {
+            let head = read_head(&mut stream);
+            assert!(head.starts_with("CONNECT example.test:443 HTTP/1.1\r\n"));
+            assert!(head.contains("Proxy-Authorization: Basic dXNlcjpwYXNz\r\n"));
+            let _ = stream.write_all(b"HTTP/1.1 200 Connection established\r\n\r\n");
+            let mut ping = [0_u8; 4];
+            if stream.read_exact(&mut ping).is_ok() && &ping == b"ping" {
+                let _ = stream.write_all(b"pong");
+            }
+        });
+
+        let proxy = Proxy::http("127.0.0.1", port).with_auth("user", "pass");
+        let mut stream = connect(port);
+        assert_eq!(
+            establish_proxy_tunnel(&mut stream, &proxy, "example.test", 443),
+            Ok(())
+        );
+        assert!(stream.write_all(b"ping").is_ok());
+        let mut pong = [0_u8; 4];
+        assert!(stream.read_exact(&mut pong).is_ok());
+        assert_eq!(&pong, b"pong");
+        assert!(handle.join().is_ok());
+    }
+
+    #[test]
+    fn http_connect_refusal_surfaces_proxy_error() {
+        let (port, handle) = stub_proxy(|mut stream| {
+            let _ = read_head(&mut stream);
+            let _ = stream.write_all(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n");
+        });
+
+        let proxy = Proxy::http("127.0.0.1", port);
+        let mut stream = connect(port);
+        let result = establish_proxy_tunnel(&mut stream, &proxy, "example.test", 443);
+        match result {
+            Err(error) => {
+                assert_eq!(error.code, "net.proxy_failed");
+                assert!(error.message.contains("407"));
+            }
+            Ok(()) => panic!("CONNECT refusal must fail"),
+        }
+        assert!(handle.join().is_ok());
+    }
+
+    #[test]
+    fn socks5_handshake_requests_domain_target() {
+        let (port, handle) = stub_proxy(|mut stream| {
+            let mut greeting = [0_u8; 3];
+            assert!(stream.read_exact(&mut greeting).is_ok());
+            assert_eq!(greeting, [0x05, 0x01, 0x00]);
+            let _ = stream.write_all(&[0x05, 0x00]);
+
+            let mut request = [0_u8; 5];
+            assert!(stream.read_exact(&mut request).is_ok());
+            assert_eq!(request, [0x05, 0x01, 0x00, 0x03, 12]);
+            let mut target = [0_u8; 14];
+            assert!(stream.read_exact(&mut target).is_ok());
+            assert_eq!(&target[..12], b"example.test");
+            assert_eq!(&target[12..], &443_u16.to_be_bytes());
+            let _ = stream.write_all(&[0x05, 0x00, 0x00, 0x01, 127, 0, 0, 1, 0x01, 0xbb]);
+        });
+
+        let proxy = Proxy::socks5("127.0.0.1", port);
+        let mut stream = connect(port);
+        assert_eq!(
+            establish_proxy_tunnel(&mut stream, &proxy, "example.test", 443),
+            Ok(())
+        );
+        assert!(handle.join().is_ok());
+    }
+
+    #[test]
+    fn socks5_connect_failure_surfaces_proxy_error() {
+        let (port, handle) = stub_proxy(|mut stream| {
+            let mut greeting = [0_u8; 3];
+            let _ = stream.read_exact(&mut greeting);
+            let _ = stream.write_all(&[0x05, 0x00]);
+            let mut request = [0_u8; 10];
+            let _ = stream.read_exact(&mut request);
+            let _ = stream.write_all(&[0x05, 0x05, 0x00, 0x01, 0, 0, 0, 0, 0, 0]);
+        });
+
+        let proxy = Proxy::socks5("127.0.0.1", port);
+        let mut stream = connect(port);
+        let result = establish_proxy_tunnel(&mut stream, &proxy, "10.0.0.1", 80);
+        match result {
+            Err(error) => {
+                assert_eq!(error.code, "net.proxy_failed");
+                assert!(error.message.contains("connection refused"));
+            }
+            Ok(()) => panic!("SOCKS failure must surface"),
+        }
+        assert!(handle.join().is_ok());
+    }
+}
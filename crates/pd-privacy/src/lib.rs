@@ -9,6 +9,8 @@ const KNOWN_TRACKER_SUFFIXES: &[&str] = &[
     "facebook.com",
 ];
 
+const DEFAULT_TRACKING_PARAMS: &[&str] = &["utm_*", "fbclid", "gclid"];
+
 /// Global privacy policy enabled at startup.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PrivacyPolicy {
@@ -16,6 +18,12 @@ pub struct PrivacyPolicy {
     pub strip_referrer_cross_origin: bool,
     pub block_known_trackers: bool,
     pub fingerprinting_resistance: bool,
+    /// Drops cookie expiry so every cookie is discarded when the browser exits.
+    pub session_cookies_only: bool,
+    /// Sends `DNT: 1` on every request.
+    pub send_dnt: bool,
+    /// Sends the Global Privacy Control signal, `Sec-GPC: 1`. Opt-in, off by default.
+    pub send_gpc: bool,
 }
 
 impl Default for PrivacyPolicy {
@@ -25,6 +33,9 @@ impl Default for PrivacyPolicy {
             strip_referrer_cross_origin: true,
             block_known_trackers: true,
             fingerprinting_resistance: true,
+            session_cookies_only: false,
+            send_dnt: true,
+            send_gpc: false,
         }
     }
 }
@@ -47,9 +58,86 @@ impl PrivacyPolicy {
     }
 }
 
+/// Removes tracking query parameters from navigation URLs.
+///
+/// Deny-list entries match parameter names case-insensitively; a trailing `*` matches any
+/// suffix. Only meant for top-level navigations, since subresources may depend on their query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlParamCleaner {
+    denied: Vec<String>,
+}
+
+impl Default for UrlParamCleaner {
+    fn default() -> Self {
+        Self::new(DEFAULT_TRACKING_PARAMS.iter().copied())
+    }
+}
+
+impl UrlParamCleaner {
+    pub fn new<I, S>(denied: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            denied: denied
+                .into_iter()
+                .map(|name| name.into().to_ascii_lowercase())
+                .collect(),
+        }
+    }
+
+    /// Returns true if a query parameter called `name` is on the deny-list.
+    pub fn is_denied(&self, name: &str) -> bool {
+        let name = name.to_ascii_lowercase();
+        self.denied
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => name == *pattern,
+            })
+    }
+
+    /// Drops denied parameters from an `http(s)` URL, leaving the remaining query and any
+    /// fragment byte-for-byte intact. Other URLs are returned unchanged.
+    pub fn clean(&self, url: &str) -> String {
+        let is_http = ["http://", "https://"].iter().any(|scheme| {
+            url.get(..scheme.len())
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme))
+        });
+        let (before_fragment, fragment) = match url.split_once('#') {
+            Some((head, fragment)) => (head, Some(fragment)),
+            None => (url, None),
+        };
+        let Some((base, query)) = before_fragment.split_once('?').filter(|_| is_http) else {
+            return url.to_owned();
+        };
+
+        let kept: Vec<&str> = query
+            .split('&')
+            .filter(|pair| {
+                let name = pair.split_once('=').map_or(*pair, |(name, _)| name);
+                !pair.is_empty() && !self.is_denied(name)
+            })
+            .collect();
+
+        let mut cleaned = base.to_owned();
+        if !kept.is_empty() {
+            cleaned.push('?');
+            cleaned.push_str(&kept.join("&"));
+        }
+        if let Some(fragment) = fragment {
+            cleaned.push('#');
+            cleaned.push_str(fragment);
+        }
+        cleaned
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::PrivacyPolicy;
+    use super::UrlParamCleaner;
 
     #[test]
     fn blocks_known_tracker_hosts() {
@@ -59,9 +147,50 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn ignores_hosts_when_tracker_blocking_disabled() {
         let mut policy = PrivacyPolicy::default();
         policy.block_known_trackers = false;
         assert!(!policy.should_block_host("doubleclick.net"));
     }
+
+    #[test]
+    fn cleaner_strips_tracking_params_and_keeps_functional_ones() {
+        let cleaner = UrlParamCleaner::default();
+        assert_eq!(
+            cleaner.clean("https://shop.example/item?id=42&utm_source=news&FBCLID=abc&q=a%20b"),
+            "https://shop.example/item?id=42&q=a%20b"
+        );
+        assert_eq!(
+            cleaner.clean("https://example.com/?utm_medium=email&gclid=x"),
+            "https://example.com/"
+        );
+        assert!(!cleaner.is_denied("utm"));
+        assert!(UrlParamCleaner::new(["ref"]).is_denied("REF"));
+    }
+
+    #[test]
+    fn cleaner_leaves_urls_without_tracking_params_unchanged() {
+        let cleaner = UrlParamCleaner::default();
+        for url in [
+            "https://example.com/search?q=rust&page=2",
+            "https://example.com/plain",
+            "data:text/html,<a href=\"?utm_source=x\">",
+        ] {
+            assert_eq!(cleaner.clean(url), url);
+        }
+    }
+
+    #[test]
+    fn cleaner_preserves_fragments() {
+        let cleaner = UrlParamCleaner::default();
+        assert_eq!(
+            cleaner.clean("https://example.com/docs?utm_campaign=x&v=2#section?utm_source=y"),
+            "https://example.com/docs?v=2#section?utm_source=y"
+        );
+        assert_eq!(
+            cleaner.clean("https://example.com/docs?fbclid=1#top"),
+            "https://example.com/docs#top"
+        );
+    }
 }
@@ -2,12 +2,100 @@
 
 use pd_core::BrowserResult;
 
+/// Capability restrictions requested for a sandboxed worker process.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SandboxFlags(u8);
+
+impl SandboxFlags {
+    pub const NO_NETWORK: Self = Self(1 << 0);
+    pub const NO_FILESYSTEM: Self = Self(1 << 1);
+    pub const NO_SPAWN: Self = Self(1 << 2);
+
+    const NAMED: [(Self, &'static str); 3] = [
+        (Self::NO_NETWORK, "no-network"),
+        (Self::NO_FILESYSTEM, "no-filesystem"),
+        (Self::NO_SPAWN, "no-spawn"),
+    ];
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn all() -> Self {
+        Self(Self::NO_NETWORK.0 | Self::NO_FILESYSTEM.0 | Self::NO_SPAWN.0)
+    }
+
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub fn from_bits(bits: u8) -> Option<Self> {
+        if bits & !Self::all().0 != 0 {
+            return None;
+        }
+        Some(Self(bits))
+    }
+
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Parses a comma-separated flag list such as `no-network,no-spawn` (`none` for empty).
+    pub fn parse(input: &str) -> BrowserResult<Self> {
+        let trimmed = input.trim();
+        if trimmed.is_empty() || trimmed.eq_ignore_ascii_case("none") {
+            return Ok(Self::empty());
+        }
+
+        let mut flags = Self::empty();
+        for token in trimmed.split(',').map(str::trim) {
+            let Some((flag, _)) = Self::NAMED
+                .iter()
+                .find(|(_, name)| name.eq_ignore_ascii_case(token))
+            else {
+                return Err(pd_core::BrowserError::new(
+                    "security.sandbox_flag_unknown",
+                    format!("unknown sandbox flag `{token}`"),
+                ));
+            };
+            flags = flags.union(*flag);
+        }
+
+        Ok(flags)
+    }
+
+    /// Serializes flags into the comma-separated form accepted by [`SandboxFlags::parse`].
+    pub fn to_arg_value(self) -> String {
+        if self.is_empty() {
+            return "none".to_owned();
+        }
+
+        Self::NAMED
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| *name)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
 /// Central security policy for process and network hardening.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SecurityPolicy {
     pub enforce_site_isolation: bool,
     pub enforce_strict_tls: bool,
     pub sandbox_renderer: bool,
+    pub sandbox_renderer_flags: SandboxFlags,
+    pub sandbox_network_flags: SandboxFlags,
+    pub sandbox_storage_flags: SandboxFlags,
 }
 
 impl Default for SecurityPolicy {
@@ -16,6 +104,9 @@ impl Default for SecurityPolicy {
             enforce_site_isolation: true,
             enforce_strict_tls: true,
             sandbox_renderer: true,
+            sandbox_renderer_flags: SandboxFlags::all(),
+            sandbox_network_flags: SandboxFlags::NO_FILESYSTEM.union(SandboxFlags::NO_SPAWN),
+            sandbox_storage_flags: SandboxFlags::NO_NETWORK.union(SandboxFlags::NO_SPAWN),
         }
     }
 }
@@ -29,6 +120,84 @@ impl SecurityPolicy {
             ));
         }
 
+        if !self.sandbox_renderer_flags.contains(SandboxFlags::NO_SPAWN) {
+            return Err(pd_core::BrowserError::new(
+                "security.invalid_sandbox_flags",
+                "renderer sandbox must forbid process spawning",
+            ));
+        }
+
+        if self
+            .sandbox_network_flags
+            .contains(SandboxFlags::NO_NETWORK)
+        {
+            return Err(pd_core::BrowserError::new(
+                "security.invalid_sandbox_flags",
+                "network worker cannot run with networking disabled",
+            ));
+        }
+
+        if self
+            .sandbox_storage_flags
+            .contains(SandboxFlags::NO_FILESYSTEM)
+        {
+            return Err(pd_core::BrowserError::new(
+                "security.invalid_sandbox_flags",
+                "storage worker cannot run with filesystem access disabled",
+            ));
+        }
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SandboxFlags;
+    use super::SecurityPolicy;
+
+    #[test]
+    fn sandbox_flags_round_trip_through_arg_value() {
+        let flags = SandboxFlags::NO_NETWORK.union(SandboxFlags::NO_SPAWN);
+        assert_eq!(flags.to_arg_value(), "no-network,no-spawn");
+        assert_eq!(SandboxFlags::parse("no-network,no-spawn").ok(), Some(flags));
+        assert_eq!(
+            SandboxFlags::parse(" NO-SPAWN ").ok(),
+            Some(SandboxFlags::NO_SPAWN)
+        );
+        assert_eq!(SandboxFlags::empty().to_arg_value(), "none");
+        assert_eq!(
+            SandboxFlags::parse("none").ok(),
+            Some(SandboxFlags::empty())
+        );
+        assert_eq!(SandboxFlags::from_bits(flags.bits()), Some(flags));
+        assert_eq!(SandboxFlags::from_bits(0x80), None);
+
+        let error = SandboxFlags::parse("no-network,no-gpu").err();
+        assert_eq!(
+            error.map(|error| error.code),
+            Some("security.sandbox_flag_unknown")
+        );
+    }
+
+    #[test]
+    fn validate_rejects_renderer_without_no_spawn() {
+        assert!(SecurityPolicy::default().validate().is_ok());
+
+        let policy = SecurityPolicy {
+            sandbox_renderer_flags: SandboxFlags::NO_NETWORK.union(SandboxFlags::NO_FILESYSTEM),
+            ..SecurityPolicy::default()
+        };
+        let error = policy.validate().err();
+        assert_eq!(
+            error.map(|error| error.code),
+            Some("security.invalid_sandbox_flags")
+        );
+
+        let policy = SecurityPolicy {
+            sandbox_network_flags: SandboxFlags::all(),
+            ..SecurityPolicy::default()
+        };
+        assert!(policy.validate().is_err());
+    }
+}
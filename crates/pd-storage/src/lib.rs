@@ -2,6 +2,7 @@
 
 use pd_core::BrowserError;
 use pd_core::BrowserResult;
+use pd_core::ResultExt;
 use pd_privacy::PrivacyPolicy;
 use pd_security::SecurityPolicy;
 use std::collections::BTreeMap;
@@ -32,6 +33,7 @@ pub struct StorageManager {
     pub privacy: PrivacyPolicy,
     pub security: SecurityPolicy,
     persistent_root: Option<PathBuf>,
+    dedicated_partitions: Vec<String>,
 }
 
 impl StorageManager {
@@ -41,6 +43,7 @@ impl StorageManager {
             privacy,
             security,
             persistent_root: None,
+            dedicated_partitions: Vec::new(),
         }
     }
 
@@ -49,6 +52,13 @@ impl StorageManager {
         self
     }
 
+    /// Keeps `name` in its own partition even when site partitioning is off, so browser-owned
+    /// data never shares the `global` partition with site storage.
+    pub fn with_dedicated_partition(mut self, name: &str) -> Self {
+        self.dedicated_partitions.push(name.to_owned());
+        self
+    }
+
     pub fn persistent_root(&self) -> Option<&Path> {
         self.persistent_root.as_deref()
     }
@@ -75,6 +85,11 @@ impl StorageManager {
         Ok(map.get(key).cloned())
     }
 
+    pub fn partition_keys(&self, top_level_site: &str) -> BrowserResult<Vec<String>> {
+        let path = self.partition_path(top_level_site)?;
+        Ok(read_partition_map(&path)?.into_keys().collect())
+    }
+
     pub fn remove_partition_value(&self, top_level_site: &str, key: &str) -> BrowserResult<()> {
         let path = self.partition_path(top_level_site)?;
         let mut map = read_partition_map(&path)?;
@@ -82,15 +97,10 @@ impl StorageManager {
 
         if map.is_empty() {
             if path.exists() {
-                fs::remove_file(&path).map_err(|error| {
-                    BrowserError::new(
-                        "storage.partition_remove_failed",
-                        format!(
-                            "failed removing empty partition file `{}`: {error}",
-                            path.display()
-                        ),
-                    )
-                })?;
+                fs::remove_file(&path).context(
+                    "storage.partition_remove_failed",
+                    format!("failed removing empty partition file `{}`", path.display()),
+                )?;
             }
             return Ok(());
         }
@@ -98,7 +108,65 @@ impl StorageManager {
         write_partition_map(&path, &map)
     }
 
+    /// Names the partition that `top_level_site` maps to under the current config.
+    pub fn partition_id(&self, top_level_site: &str) -> String {
+        if self.config.partition_by_top_level_site
+            || self
+                .dedicated_partitions
+                .iter()
+                .any(|name| name == top_level_site)
+        {
+            sanitize_partition_name(top_level_site)
+        } else {
+            "global".to_owned()
+        }
+    }
+
+    /// Lists the ids of partitions that currently hold data, sorted.
+    pub fn list_partitions(&self) -> BrowserResult<Vec<String>> {
+        let directory = self.partitions_directory()?;
+        if !directory.exists() {
+            return Ok(Vec::new());
+        }
+
+        let entries = fs::read_dir(&directory).context(
+            "storage.partition_list_failed",
+            format!(
+                "failed to list partition directory `{}`",
+                directory.display()
+            ),
+        )?;
+        let mut ids = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "kv"))
+            .filter_map(|path| path.file_stem()?.to_str().map(str::to_owned))
+            .collect::<Vec<_>>();
+        ids.sort();
+        Ok(ids)
+    }
+
+    /// Deletes every value in the partition named by a [`StorageManager::partition_id`] result.
+    pub fn clear_partition(&self, partition_id: &str) -> BrowserResult<()> {
+        let path = self
+            .partitions_directory()?
+            .join(format!("{}.kv", sanitize_partition_name(partition_id)));
+        if path.exists() {
+            fs::remove_file(&path).context(
+                "storage.partition_remove_failed",
+                format!("failed removing partition file `{}`", path.display()),
+            )?;
+        }
+        Ok(())
+    }
+
     fn partition_path(&self, top_level_site: &str) -> BrowserResult<PathBuf> {
+        Ok(self
+            .partitions_directory()?
+            .join(format!("{}.kv", self.partition_id(top_level_site))))
+    }
+
+    fn partitions_directory(&self) -> BrowserResult<PathBuf> {
         if self.config.ephemeral_mode {
             return Err(BrowserError::new(
                 "storage.persistence_disabled",
@@ -113,13 +181,7 @@ impl StorageManager {
             )
         })?;
 
-        let partition = if self.config.partition_by_top_level_site {
-            sanitize_partition_name(top_level_site)
-        } else {
-            "global".to_owned()
-        };
-
-        Ok(root.join("partitions").join(format!("{partition}.kv")))
+        Ok(root.join("partitions"))
     }
 }
 
@@ -145,15 +207,10 @@ fn read_partition_map(path: &Path) -> BrowserResult<BTreeMap<String, String>> {
         return Ok(BTreeMap::new());
     }
 
-    let content = fs::read_to_string(path).map_err(|error| {
-        BrowserError::new(
-            "storage.partition_read_failed",
-            format!(
-                "failed to read partition file `{}`: {error}",
-                path.display()
-            ),
-        )
-    })?;
+    let content = fs::read_to_string(path).context(
+        "storage.partition_read_failed",
+        format!("failed to read partition file `{}`", path.display()),
+    )?;
 
     let mut map = BTreeMap::new();
     for (index, line) in content.lines().enumerate() {
@@ -182,15 +239,13 @@ fn read_partition_map(path: &Path) -> BrowserResult<BTreeMap<String, String>> {
 
 fn write_partition_map(path: &Path, map: &BTreeMap<String, String>) -> BrowserResult<()> {
     if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|error| {
-            BrowserError::new(
-                "storage.partition_dir_create_failed",
-                format!(
-                    "failed to create partition directory `{}`: {error}",
-                    parent.display()
-                ),
-            )
-        })?;
+        fs::create_dir_all(parent).context(
+            "storage.partition_dir_create_failed",
+            format!(
+                "failed to create partition directory `{}`",
+                parent.display()
+            ),
+        )?;
     }
 
     let mut encoded = String::new();
@@ -201,15 +256,10 @@ fn write_partition_map(path: &Path, map: &BTreeMap<String, String>) -> BrowserRe
         encoded.push('\n');
     }
 
-    fs::write(path, encoded).map_err(|error| {
-        BrowserError::new(
-            "storage.partition_write_failed",
-            format!(
-                "failed to write partition file `{}`: {error}",
-                path.display()
-            ),
-        )
-    })
+    fs::write(path, encoded).context(
+        "storage.partition_write_failed",
+        format!("failed to write partition file `{}`", path.display()),
+    )
 }
 
 fn encode_hex_string(value: &str) -> String {
@@ -222,7 +272,7 @@ fn encode_hex_string(value: &str) -> String {
 }
 
 fn decode_hex_string(value: &str) -> BrowserResult<String> {
-    if !value.len().is_multiple_of(2) {
+    if value.len() % 2 != 0 {
         return Err(BrowserError::new(
             "storage.partition_hex_invalid",
             "hex field length must be even",
@@ -239,12 +289,10 @@ fn decode_hex_string(value: &str) -> BrowserResult<String> {
         index += 2;
     }
 
-    String::from_utf8(bytes).map_err(|error| {
-        BrowserError::new(
-            "storage.partition_utf8_invalid",
-            format!("partition field is not valid UTF-8: {error}"),
-        )
-    })
+    String::from_utf8(bytes).context(
+        "storage.partition_utf8_invalid",
+        "partition field is not valid UTF-8",
+    )
 }
 
 fn hex_char(value: u8) -> char {
@@ -302,6 +350,83 @@ mod tests {
         let _ = std::fs::remove_dir_all(root);
     }
 
+    #[test]
+    fn partition_keys_lists_stored_keys() {
+        let root = temp_storage_root();
+        let manager = StorageManager::new(
+            StorageConfig::default(),
+            PrivacyPolicy::default(),
+            SecurityPolicy::default(),
+        )
+        .with_persistent_root(root.clone());
+        assert_eq!(manager.partition_keys("example.com"), Ok(Vec::new()));
+
+        assert!(manager.set_partition_value("example.com", "b", "2").is_ok());
+        assert!(manager.set_partition_value("example.com", "a", "1").is_ok());
+        assert_eq!(
+            manager.partition_keys("example.com"),
+            Ok(vec!["a".to_owned(), "b".to_owned()])
+        );
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn lists_and_clears_partitions() {
+        let root = temp_storage_root();
+        let manager = StorageManager::new(
+            StorageConfig::default(),
+            PrivacyPolicy::default(),
+            SecurityPolicy::default(),
+        )
+        .with_persistent_root(root.clone());
+        assert_eq!(manager.list_partitions(), Ok(Vec::new()));
+
+        assert!(manager.set_partition_value("b.example", "k", "v").is_ok());
+        assert!(manager.set_partition_value("A.example", "k", "v").is_ok());
+        assert_eq!(
+            manager.list_partitions(),
+            Ok(vec!["a.example".to_owned(), "b.example".to_owned()])
+        );
+
+        let id = manager.partition_id("A.example");
+        assert!(manager.clear_partition(&id).is_ok());
+        assert_eq!(manager.list_partitions(), Ok(vec!["b.example".to_owned()]));
+        assert_eq!(manager.get_partition_value("A.example", "k"), Ok(None));
+        assert_eq!(
+            manager.get_partition_value("b.example", "k"),
+            Ok(Some("v".to_owned()))
+        );
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn dedicated_partitions_stay_separate_without_site_partitioning() {
+        let root = temp_storage_root();
+        let config = StorageConfig {
+            partition_by_top_level_site: false,
+            ephemeral_mode: false,
+        };
+        let manager =
+            StorageManager::new(config, PrivacyPolicy::default(), SecurityPolicy::default())
+                .with_persistent_root(root.clone())
+                .with_dedicated_partition("__cookies");
+
+        assert_eq!(manager.partition_id("a.example"), "global");
+        assert_eq!(manager.partition_id("__cookies"), "__cookies");
+        assert!(manager.set_partition_value("a.example", "k", "v").is_ok());
+        assert!(
+            manager
+                .set_partition_value("__cookies", "a.example", "sid")
+                .is_ok()
+        );
+        assert!(manager.clear_partition("global").is_ok());
+        assert_eq!(manager.list_partitions(), Ok(vec!["__cookies".to_owned()]));
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
     #[test]
     fn ephemeral_mode_blocks_persistence() {
         let config = StorageConfig {